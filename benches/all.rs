@@ -9,4 +9,5 @@ mod utils;
 mod common;
 mod ray;
 mod bounding_volume;
+mod partitioning;
 mod support_map;
@@ -0,0 +1,75 @@
+use std::rand::{IsaacRng, Rng};
+use test::Bencher;
+use test;
+use na::{Pnt3, Vec3};
+use ncollide::bounding_volume::{AABB, AABB3};
+use ncollide::partitioning::{DBVT, BVT};
+
+/// Boxes of various sizes scattered in a 100 units wide cube.
+fn scattered_aabbs(n: usize) -> Vec<AABB3<f32>> {
+    let mut rng: IsaacRng = IsaacRng::new_unseeded();
+
+    (0 .. n).map(|_| {
+        let center       = rng.gen::<Pnt3<f32>>() * 100.0;
+        let half_extents = rng.gen::<Vec3<f32>>() * 2.0 + Vec3::new(0.01, 0.01, 0.01);
+
+        AABB::new(center - half_extents, center + half_extents)
+    }).collect()
+}
+
+#[bench]
+fn bench_dbvt_insert(bh: &mut Bencher) {
+    let aabbs = scattered_aabbs(1000);
+
+    bh.iter(|| {
+        let mut tree = DBVT::new();
+
+        for (i, aabb) in aabbs.iter().enumerate() {
+            let _ = tree.insert(i, aabb.clone());
+        }
+
+        test::black_box(tree.len());
+    })
+}
+
+#[bench]
+fn bench_dbvt_set_bounding_volume(bh: &mut Bencher) {
+    let aabbs    = scattered_aabbs(1000);
+    let moved    = scattered_aabbs(2000);
+    let mut tree = DBVT::new();
+    let leaves: Vec<_> = aabbs.iter().enumerate().map(|(i, aabb)| tree.insert(i, aabb.clone())).collect();
+    let mut curr = 0;
+
+    bh.iter(|| {
+        for leaf in leaves.iter() {
+            tree.set_bounding_volume(*leaf, moved[curr % moved.len()].clone());
+            curr = curr + 1;
+        }
+    })
+}
+
+#[bench]
+fn bench_dbvt_remove_insert(bh: &mut Bencher) {
+    let aabbs    = scattered_aabbs(1000);
+    let mut tree = DBVT::new();
+    let mut leaves: Vec<_> = aabbs.iter().enumerate().map(|(i, aabb)| tree.insert(i, aabb.clone())).collect();
+
+    bh.iter(|| {
+        for leaf in leaves.iter_mut() {
+            let i = tree.remove(*leaf);
+            *leaf = tree.insert(i, aabbs[i].clone());
+        }
+    })
+}
+
+// Reference for the quality of the dynamic tree: the static BVT built at once.
+#[bench]
+fn bench_bvt_build(bh: &mut Bencher) {
+    let aabbs = scattered_aabbs(1000);
+
+    bh.iter(|| {
+        let leaves = aabbs.iter().map(|aabb| aabb.clone()).enumerate().collect();
+
+        test::black_box(BVT::new_balanced(leaves).depth());
+    })
+}
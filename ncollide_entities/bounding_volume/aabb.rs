@@ -3,7 +3,7 @@
 use std::ops::Neg;
 use na::{Translation, POrd, Translate, Bounded};
 use na;
-use bounding_volume::BoundingVolume;
+use bounding_volume::{BoundingVolume, HasSurfaceArea};
 use math::{Scalar, Point, Vect};
 
 /// Trait of objects that can be bounded by an AABB.
@@ -82,6 +82,39 @@ impl<N, P, V> AABB<P>
     }
 }
 
+#[old_impl_check]
+impl<N, P, V> HasSurfaceArea<N> for AABB<P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn surface_area(&self) -> N {
+        box_surface_area(&(self.maxs - self.mins))
+    }
+}
+
+/// The surface area of a box with the given extents.
+pub fn box_surface_area<N, V>(extents: &V) -> N
+    where N: Scalar,
+          V: Vect<N> {
+    let dim     = na::dim::<V>();
+    let mut res = na::zero::<N>();
+
+    for i in 0 .. dim {
+        let mut face = na::one::<N>();
+
+        for j in 0 .. dim {
+            if j != i {
+                face = face * extents[j];
+            }
+        }
+
+        res = res + face;
+    }
+
+    res * na::cast(2.0f64)
+}
+
 #[old_impl_check]
 impl<N, P, V> BoundingVolume<N> for AABB<P>
     where N: Scalar,
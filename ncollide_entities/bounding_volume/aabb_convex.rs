@@ -1,7 +1,7 @@
 use na::Transform;
 use bounding_volume::{AABB, HasAABB};
 use bounding_volume::aabb_utils;
-use shape::Convex;
+use shape::{Convex, ConvexPolyhedron};
 use math::{Scalar, Point};
 
 #[old_impl_check]
@@ -16,3 +16,16 @@ impl<N, P, V, M> HasAABB<P, M> for Convex<P>
         AABB::new(min, max)
     }
 }
+
+#[old_impl_check]
+impl<N, P, V, M> HasAABB<P, M> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          M: Transform<P> {
+    #[inline]
+    fn aabb(&self, m: &M) -> AABB<P> {
+        let (min, max) = aabb_utils::point_cloud_aabb(m, self.vertices());
+
+        AABB::new(min, max)
+    }
+}
@@ -0,0 +1,23 @@
+use na::{Translate, Translation, Transform, AbsoluteRotate};
+use na;
+use bounding_volume::{AABB, HasAABB};
+use shape::DistanceField;
+use math::{Scalar, Point, Vect};
+
+
+impl<N, P, V, M> HasAABB<P, M> for DistanceField<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P>,
+          M: AbsoluteRotate<V> + Transform<P> + Translation<V> {
+    #[inline]
+    fn aabb(&self, m: &M) -> AABB<P> {
+        let bv              = self.local_aabb();
+        let ls_center       = na::orig::<P>() + bv.translation();
+        let center          = m.transform(&ls_center);
+        let half_extents    = (*bv.maxs() - *bv.mins()) * na::cast::<f64, N>(0.5);
+        let ws_half_extents = m.absolute_rotate(&half_extents);
+
+        AABB::new(center + (-ws_half_extents), center + ws_half_extents)
+    }
+}
@@ -1,8 +1,8 @@
 use na::Translate;
 use bounding_volume::{HasAABB, AABB};
 use math::{Scalar, Point, Vect, Isometry};
-use shape::{Ball, Capsule, Compound, Cone, Convex, Cuboid, Cylinder, TriMesh, Polyline, Plane,
-            Segment, Triangle};
+use shape::{Ball, Capsule, Compound, Cone, Convex, ConvexPolyhedron, Cuboid, Cylinder,
+            HeightField, DistanceField, TriMesh, Polyline, Plane, Segment, Triangle};
 use inspection::Repr;
 
 impl<N, P, V, M> HasAABB<P, M> for Repr<N, P, V, M>
@@ -29,12 +29,21 @@ impl<N, P, V, M> HasAABB<P, M> for Repr<N, P, V, M>
         else if let Some(c) = repr.downcast_ref::<Convex<P>>() {
             c.aabb(m)
         }
+        else if let Some(c) = repr.downcast_ref::<ConvexPolyhedron<P, V>>() {
+            c.aabb(m)
+        }
         else if let Some(c) = repr.downcast_ref::<Cuboid<V>>() {
             c.aabb(m)
         }
         else if let Some(c) = repr.downcast_ref::<Cylinder<N>>() {
             c.aabb(m)
         }
+        else if let Some(h) = repr.downcast_ref::<HeightField<N, V>>() {
+            h.aabb(m)
+        }
+        else if let Some(d) = repr.downcast_ref::<DistanceField<N, P, V>>() {
+            d.aabb(m)
+        }
         else if let Some(t) = repr.downcast_ref::<TriMesh<N, P, V>>() {
             t.aabb(m)
         }
@@ -1,9 +1,9 @@
 //! Bounding sphere.
 
-use na::{Translation, Norm, Transform, Translate};
+use na::{Translation, Norm, Transform, Translate, BaseFloat};
 use na;
 use math::{Scalar, Point, Vect};
-use bounding_volume::BoundingVolume;
+use bounding_volume::{BoundingVolume, HasSurfaceArea};
 
 /// Trait implemented by objects having a bounding sphere.
 pub trait HasBoundingSphere<N, P, M> {
@@ -142,6 +142,23 @@ impl<N, P, V> BoundingVolume<N> for BoundingSphere<N, P>
     }
 }
 
+#[old_impl_check]
+impl<N, P, V> HasSurfaceArea<N> for BoundingSphere<N, P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn surface_area(&self) -> N {
+        let _pi: N = BaseFloat::pi();
+
+        match na::dim::<P>() {
+            2 => _pi * self.radius * na::cast(2.0f64),
+            3 => _pi * self.radius * self.radius * na::cast(4.0f64),
+            _ => panic!("The bounding sphere surface area is only implemented in 2D and 3D.")
+        }
+    }
+}
+
 impl<N, P, V> Translation<V> for BoundingSphere<N, P>
     where N: Scalar,
           P: Point<N, V>,
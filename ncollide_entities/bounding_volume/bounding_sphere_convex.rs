@@ -1,7 +1,7 @@
 use na::Transform;
 use bounding_volume::{BoundingSphere, HasBoundingSphere};
 use bounding_volume;
-use shape::Convex;
+use shape::{Convex, ConvexPolyhedron};
 use math::{Scalar, Point, Vect};
 
 
@@ -18,3 +18,17 @@ impl<N, P, V, M> HasBoundingSphere<N, P, M> for Convex<P>
         BoundingSphere::new(m.transform(&center), radius)
     }
 }
+
+#[old_impl_check]
+impl<N, P, V, M> HasBoundingSphere<N, P, M> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    #[inline]
+    fn bounding_sphere(&self, m: &M) -> BoundingSphere<N, P> {
+        let (center, radius) = bounding_volume::point_cloud_bounding_sphere(self.vertices());
+
+        BoundingSphere::new(m.transform(&center), radius)
+    }
+}
@@ -0,0 +1,21 @@
+use na::Transform;
+use na;
+use bounding_volume::{BoundingSphere, HasBoundingSphere};
+use shape::DistanceField;
+use math::{Scalar, Point, Vect};
+
+
+impl<N, P, V, M> HasBoundingSphere<N, P, M> for DistanceField<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    #[inline]
+    fn bounding_sphere(&self, m: &M) -> BoundingSphere<N, P> {
+        let aabb   = self.local_aabb();
+        let center = aabb.center();
+        let radius = na::dist(aabb.mins(), aabb.maxs()) * na::cast(0.5f64);
+
+        BoundingSphere::new(m.transform(&center), radius)
+    }
+}
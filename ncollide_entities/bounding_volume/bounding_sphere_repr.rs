@@ -1,8 +1,8 @@
 use na::Translate;
 use bounding_volume::{HasBoundingSphere, BoundingSphere};
 use math::{Scalar, Point, Vect, Isometry};
-use shape::{Ball, Capsule, Compound, Cone, Convex, Cuboid, Cylinder, TriMesh, Polyline, Plane,
-            Segment, Triangle};
+use shape::{Ball, Capsule, Compound, Cone, Convex, ConvexPolyhedron, Cuboid, Cylinder,
+            HeightField, DistanceField, TriMesh, Polyline, Plane, Segment, Triangle};
 use inspection::Repr;
 
 
@@ -30,12 +30,21 @@ impl<N, P, V, M> HasBoundingSphere<N, P, M> for Repr<N, P, V, M>
         else if let Some(c) = repr.downcast_ref::<Convex<P>>() {
             c.bounding_sphere(m)
         }
+        else if let Some(c) = repr.downcast_ref::<ConvexPolyhedron<P, V>>() {
+            c.bounding_sphere(m)
+        }
         else if let Some(c) = repr.downcast_ref::<Cuboid<V>>() {
             c.bounding_sphere(m)
         }
         else if let Some(c) = repr.downcast_ref::<Cylinder<N>>() {
             c.bounding_sphere(m)
         }
+        else if let Some(h) = repr.downcast_ref::<HeightField<N, V>>() {
+            h.bounding_sphere(m)
+        }
+        else if let Some(d) = repr.downcast_ref::<DistanceField<N, P, V>>() {
+            d.bounding_sphere(m)
+        }
         else if let Some(t) = repr.downcast_ref::<TriMesh<N, P, V>>() {
             t.bounding_sphere(m)
         }
@@ -34,3 +34,12 @@ pub trait BoundingVolume<N> {
     /// Creates a new, tightened version, of this bounding volume.
     fn tightened(&self, N) -> Self;
 }
+
+/// Trait of bounding volumes having a surface area.
+///
+/// This is used by the construction heuristics of bounding volume trees. In 2D, the surface area
+/// is the perimeter of the bounding volume.
+pub trait HasSurfaceArea<N> {
+    /// The surface area of this bounding volume.
+    fn surface_area(&self) -> N;
+}
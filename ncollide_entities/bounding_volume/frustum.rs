@@ -0,0 +1,293 @@
+//! Convex view frustum.
+
+use na::{Pnt3, Vec3, Pnt4, Mat4};
+use na;
+use utils;
+use bounding_volume::{BoundingVolume, AABB, BoundingSphere};
+use math::{Scalar, Point, Vect};
+
+/// The position of a bounding volume wrt. a frustum.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FrustumClassification {
+    /// The bounding volume is completely inside of the frustum.
+    Inside,
+    /// The bounding volume may intersect the boundary of the frustum.
+    Intersecting,
+    /// The bounding volume is completely outside of the frustum.
+    Outside
+}
+
+/// A convex frustum, i.e., the intersection of a set of half-spaces.
+///
+/// Each half-space is represented by an outward normal `n` and an offset `d` such that a point `x`
+/// is inside of the half-space iff. `dot(n, x) <= d`.
+#[derive(Debug, PartialEq, Clone, RustcEncodable, RustcDecodable)]
+pub struct Frustum<N, P, V> {
+    planes:  Vec<(V, N)>,
+    corners: Vec<P>,
+    aabb:    AABB<P>
+}
+
+impl<N, P, V> Frustum<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// Creates a new frustum from its bounding half-spaces and its corners.
+    ///
+    /// The corners are used to reject bounding volumes which do not intersect the frustum AABB.
+    pub fn new(planes: Vec<(V, N)>, corners: Vec<P>) -> Frustum<N, P, V> {
+        assert!(corners.len() != 0, "A frustum must have at least one corner.");
+
+        let mut mins = corners[0];
+        let mut maxs = corners[0];
+
+        for c in corners[1 ..].iter() {
+            mins = na::inf(&mins, c);
+            maxs = na::sup(&maxs, c);
+        }
+
+        Frustum {
+            planes:  planes,
+            corners: corners,
+            aabb:    AABB::new(mins, maxs)
+        }
+    }
+
+    /// The outward normals and offsets of the half-spaces bounding this frustum.
+    #[inline]
+    pub fn planes(&self) -> &[(V, N)] {
+        &self.planes[..]
+    }
+
+    /// The corners of this frustum.
+    #[inline]
+    pub fn corners(&self) -> &[P] {
+        &self.corners[..]
+    }
+
+    /// The AABB of this frustum.
+    #[inline]
+    pub fn aabb(&self) -> &AABB<P> {
+        &self.aabb
+    }
+
+    /// Tests if a point is inside of this frustum.
+    #[inline]
+    pub fn contains_point(&self, pt: &P) -> bool {
+        self.planes.iter().all(|&(ref n, d)| na::dot(pt.as_vec(), n) <= d)
+    }
+
+    /// Classifies an AABB wrt. this frustum.
+    ///
+    /// This is conservative: an AABB classified as `Intersecting` might actually be outside.
+    pub fn classify_aabb(&self, aabb: &AABB<P>) -> FrustumClassification {
+        if !self.aabb.intersects(aabb) {
+            return FrustumClassification::Outside;
+        }
+
+        let center       = aabb.center();
+        let half_extents = aabb.half_extents();
+        let mut res      = FrustumClassification::Inside;
+
+        for &(ref n, d) in self.planes.iter() {
+            let mut radius = na::zero::<N>();
+
+            for i in 0 .. na::dim::<V>() {
+                radius = radius + na::abs(&n[i]) * half_extents[i];
+            }
+
+            let dist = na::dot(center.as_vec(), n) - d;
+
+            if dist > radius {
+                return FrustumClassification::Outside;
+            }
+            else if dist > -radius {
+                res = FrustumClassification::Intersecting;
+            }
+        }
+
+        res
+    }
+
+    /// Classifies a bounding sphere wrt. this frustum.
+    ///
+    /// This is conservative: a sphere classified as `Intersecting` might actually be outside.
+    pub fn classify_bounding_sphere(&self, bs: &BoundingSphere<N, P>) -> FrustumClassification {
+        let mut res = FrustumClassification::Inside;
+
+        for &(ref n, d) in self.planes.iter() {
+            let dist = na::dot(bs.center().as_vec(), n) - d;
+
+            if dist > bs.radius() {
+                return FrustumClassification::Outside;
+            }
+            else if dist > -bs.radius() {
+                res = FrustumClassification::Intersecting;
+            }
+        }
+
+        res
+    }
+}
+
+impl<N: Scalar> Frustum<N, Pnt3<N>, Vec3<N>> {
+    /// Creates the frustum of a camera given its view-projection matrix.
+    ///
+    /// The frustum is the image of the cube `[-1, 1]^3` of normalized device coordinates by the
+    /// inverse of `view_proj`. Fails if `view_proj` is not invertible.
+    pub fn from_view_projection(view_proj: &Mat4<N>) -> Frustum<N, Pnt3<N>, Vec3<N>> {
+        let inv_view_proj = na::inv(view_proj).expect("The view-projection matrix must be invertible.");
+        let _1: N         = na::one();
+        let mut ndc       = Vec::with_capacity(8);
+
+        // The i-th corner has the coordinates (±1, ±1, ±1) given by the bits of `i`.
+        for i in 0 .. 8usize {
+            let x = if i & 1 == 0 { -_1 } else { _1 };
+            let y = if i & 2 == 0 { -_1 } else { _1 };
+            let z = if i & 4 == 0 { -_1 } else { _1 };
+
+            ndc.push(inv_view_proj * Pnt4::new(x, y, z, _1));
+        }
+
+        let corners: Vec<Pnt3<N>> = utils::project_homogeneous(&mut ndc[..]);
+
+        let mut center = na::orig::<Pnt3<N>>();
+
+        for c in corners.iter() {
+            center = center + *c.as_vec();
+        }
+
+        center = center / na::cast(8.0f64);
+
+        // Left, right, bottom, top, near and far planes, each given by three of their corners.
+        let faces = [ (0, 2, 4), (1, 3, 5), (0, 1, 4), (2, 3, 6), (0, 1, 2), (4, 5, 6) ];
+        let mut planes = Vec::with_capacity(6);
+
+        for &(a, b, c) in faces.iter() {
+            let mut n = na::normalize(&na::cross(&(corners[b] - corners[a]), &(corners[c] - corners[a])));
+
+            // Orient the normal outward.
+            if na::dot(&n, &(center - corners[a])) > na::zero() {
+                n = -n;
+            }
+
+            planes.push((n, na::dot(&n, corners[a].as_vec())));
+        }
+
+        Frustum::new(planes, corners)
+    }
+}
+
+/// Trait of bounding volumes which can be classified wrt. a frustum.
+pub trait FrustumClassifiable<N, P, V> {
+    /// Classifies this bounding volume wrt. `frustum`.
+    fn classify_with_frustum(&self, frustum: &Frustum<N, P, V>) -> FrustumClassification;
+}
+
+impl<N, P, V> FrustumClassifiable<N, P, V> for AABB<P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn classify_with_frustum(&self, frustum: &Frustum<N, P, V>) -> FrustumClassification {
+        frustum.classify_aabb(self)
+    }
+}
+
+impl<N, P, V> FrustumClassifiable<N, P, V> for BoundingSphere<N, P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn classify_with_frustum(&self, frustum: &Frustum<N, P, V>) -> FrustumClassification {
+        frustum.classify_bounding_sphere(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Vec3, Mat4};
+    use na;
+    use bounding_volume::{AABB, BoundingSphere, Frustum, FrustumClassification,
+                          FrustumInterferencesCollector};
+    use partitioning::BVT;
+
+    // The cube [-1, 1]^3.
+    fn unit_frustum() -> Frustum<f64, Pnt3<f64>, Vec3<f64>> {
+        let planes = vec!(
+            (Vec3::x(), 1.0), (-Vec3::x(), 1.0),
+            (Vec3::y(), 1.0), (-Vec3::y(), 1.0),
+            (Vec3::z(), 1.0), (-Vec3::z(), 1.0)
+        );
+        let corners = vec!(Pnt3::new(-1.0, -1.0, -1.0), Pnt3::new(1.0, 1.0, 1.0));
+
+        Frustum::new(planes, corners)
+    }
+
+    fn aabb_at(x: f64, half_extent: f64) -> AABB<Pnt3<f64>> {
+        let he = Vec3::new(half_extent, half_extent, half_extent);
+
+        AABB::new(Pnt3::new(x, 0.0, 0.0) - he, Pnt3::new(x, 0.0, 0.0) + he)
+    }
+
+    #[test]
+    fn test_classify_aabb() {
+        let frustum = unit_frustum();
+
+        assert!(frustum.classify_aabb(&aabb_at(0.0, 0.5)) == FrustumClassification::Inside);
+        assert!(frustum.classify_aabb(&aabb_at(1.0, 0.5)) == FrustumClassification::Intersecting);
+        assert!(frustum.classify_aabb(&aabb_at(3.0, 0.5)) == FrustumClassification::Outside);
+    }
+
+    #[test]
+    fn test_classify_bounding_sphere() {
+        let frustum = unit_frustum();
+
+        let inside       = BoundingSphere::new(Pnt3::new(0.0, 0.0, 0.0), 0.5);
+        let intersecting = BoundingSphere::new(Pnt3::new(0.0, 1.2, 0.0), 0.5);
+        let outside      = BoundingSphere::new(Pnt3::new(0.0, 0.0, -2.0), 0.5);
+
+        assert!(frustum.classify_bounding_sphere(&inside) == FrustumClassification::Inside);
+        assert!(frustum.classify_bounding_sphere(&intersecting) == FrustumClassification::Intersecting);
+        assert!(frustum.classify_bounding_sphere(&outside) == FrustumClassification::Outside);
+    }
+
+    #[test]
+    fn test_from_identity_view_projection() {
+        let frustum = Frustum::from_view_projection(&na::one::<Mat4<f64>>());
+
+        assert!(frustum.planes().len() == 6 && frustum.corners().len() == 8);
+        assert!(frustum.contains_point(&Pnt3::new(0.0, 0.0, 0.0)));
+        assert!(frustum.contains_point(&Pnt3::new(0.9, -0.9, 0.9)));
+        assert!(!frustum.contains_point(&Pnt3::new(1.1, 0.0, 0.0)));
+        assert!(!frustum.contains_point(&Pnt3::new(0.0, 0.0, -1.1)));
+
+        for &(ref n, d) in frustum.planes().iter() {
+            assert!(na::approx_eq(&na::norm(n), &1.0));
+            assert!(na::approx_eq(&d, &1.0));
+        }
+    }
+
+    #[test]
+    fn test_frustum_interferences_collector() {
+        let frustum = unit_frustum();
+        let leaves  = vec!(
+            (0usize, aabb_at(0.0, 0.1)),
+            (1, aabb_at(0.3, 0.1)),
+            (2, aabb_at(1.0, 0.2)),
+            (3, aabb_at(5.0, 0.1)),
+            (4, aabb_at(-7.0, 0.1))
+        );
+        let bvt       = BVT::new_balanced(leaves);
+        let mut found = Vec::new();
+
+        {
+            let mut visitor = FrustumInterferencesCollector::new(&frustum, &mut found);
+            bvt.visit(&mut visitor);
+        }
+
+        found.sort();
+
+        assert!(found == vec!(0, 1, 2));
+    }
+}
@@ -0,0 +1,57 @@
+use bounding_volume::{BoundingVolume, Frustum, FrustumClassification, FrustumClassifiable};
+use partitioning::BVTVisitor;
+
+/// Bounding Volume Tree visitor collecting the leaves which bounding volume is not outside of a
+/// frustum.
+///
+/// Once an internal node is found to be completely inside of the frustum, its descendants are
+/// accepted by a simple containment test instead of being classified wrt. each frustum plane.
+pub struct FrustumInterferencesCollector<'a, N: 'a, P: 'a, V: 'a, B: 'a, BV> {
+    frustum:   &'a Frustum<N, P, V>,
+    collector: &'a mut Vec<B>,
+    inside:    Option<BV>
+}
+
+impl<'a, N, P, V, B, BV> FrustumInterferencesCollector<'a, N, P, V, B, BV> {
+    /// Creates a new `FrustumInterferencesCollector`.
+    #[inline]
+    pub fn new(frustum: &'a Frustum<N, P, V>, buffer: &'a mut Vec<B>)
+               -> FrustumInterferencesCollector<'a, N, P, V, B, BV> {
+        FrustumInterferencesCollector {
+            frustum:   frustum,
+            collector: buffer,
+            inside:    None
+        }
+    }
+
+    fn classify(&mut self, bv: &BV) -> FrustumClassification
+        where BV: BoundingVolume<N> + FrustumClassifiable<N, P, V> + Clone {
+        if let Some(ref inside) = self.inside {
+            if inside.contains(bv) {
+                return FrustumClassification::Inside;
+            }
+        }
+
+        let res = bv.classify_with_frustum(self.frustum);
+
+        self.inside = if res == FrustumClassification::Inside { Some(bv.clone()) } else { None };
+
+        res
+    }
+}
+
+impl<'a, N, P, V, B, BV> BVTVisitor<B, BV> for FrustumInterferencesCollector<'a, N, P, V, B, BV>
+    where B:  Clone,
+          BV: BoundingVolume<N> + FrustumClassifiable<N, P, V> + Clone {
+    #[inline]
+    fn visit_internal(&mut self, bv: &BV) -> bool {
+        self.classify(bv) != FrustumClassification::Outside
+    }
+
+    #[inline]
+    fn visit_leaf(&mut self, b: &B, bv: &BV) {
+        if self.classify(bv) != FrustumClassification::Outside {
+            self.collector.push(b.clone())
+        }
+    }
+}
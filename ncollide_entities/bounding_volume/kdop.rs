@@ -0,0 +1,396 @@
+//! Discrete Oriented Polytope.
+
+use std::sync::Arc;
+use na::{Translation, Bounded};
+use na;
+use bounding_volume::{BoundingVolume, HasSurfaceArea, AABB};
+use bounding_volume::aabb::box_surface_area;
+use math::{Scalar, Point, Vect};
+
+/// A Discrete Oriented Polytope.
+///
+/// A k-DOP is the intersection of `k / 2` slabs. Each slab is the set of points which projection
+/// on a fixed axis lies between a minimum and a maximum value. Every k-DOP built from the same
+/// set of axes can be tested against each other. The axes are shared by all the k-DOPs using them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct KDOP<N, V> {
+    axes: Arc<Vec<V>>,
+    mins: Vec<N>,
+    maxs: Vec<N>
+}
+
+impl<N, V> KDOP<N, V> {
+    /// The normalized axes of the slabs of this k-DOP.
+    #[inline]
+    pub fn axes(&self) -> &Arc<Vec<V>> {
+        &self.axes
+    }
+
+    /// The minimal projections of this k-DOP on each of its axes.
+    #[inline]
+    pub fn mins(&self) -> &[N] {
+        &self.mins[..]
+    }
+
+    /// The maximal projections of this k-DOP on each of its axes.
+    #[inline]
+    pub fn maxs(&self) -> &[N] {
+        &self.maxs[..]
+    }
+
+    /// The number of slabs of this k-DOP, i.e., `k / 2`.
+    #[inline]
+    pub fn nslabs(&self) -> usize {
+        self.mins.len()
+    }
+}
+
+#[old_impl_check]
+impl<N, V> KDOP<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    /// Creates a new k-DOP.
+    ///
+    /// # Arguments:
+    ///   * `axes` - the normalized axes of the slabs. The first axes must be the canonical basis.
+    ///   * `mins` - the minimal projection of the k-DOP on each axis.
+    ///   * `maxs` - the maximal projection of the k-DOP on each axis. Each must be greater than
+    ///   the corresponding element of `mins`.
+    pub fn new(axes: Arc<Vec<V>>, mins: Vec<N>, maxs: Vec<N>) -> KDOP<N, V> {
+        assert!(axes.len() == mins.len() && axes.len() == maxs.len(),
+                "There must be exactly one minimum and one maximum per axis.");
+        assert!(axes.len() >= na::dim::<V>(), "A k-DOP must contain at least the canonical axes.");
+        assert!((0 .. na::dim::<V>()).all(|i| {
+                    na::approx_eq(&axes[i], &na::canonical_basis_element(i).unwrap())
+                }),
+                "The first axes of a k-DOP must be the canonical basis.");
+        assert!(mins.iter().zip(maxs.iter()).all(|(min, max)| *min <= *max),
+                "The minimal projections must be smaller than the maximal ones.");
+
+        KDOP {
+            axes: axes,
+            mins: mins,
+            maxs: maxs
+        }
+    }
+
+    /// Creates a new k-DOP enclosing a set of points.
+    pub fn from_points<P>(axes: Arc<Vec<V>>, pts: &[P]) -> KDOP<N, V>
+        where P: Point<N, V> {
+        assert!(pts.len() != 0, "Cannot compute the k-DOP of an empty set of points.");
+
+        let max: N   = Bounded::max_value();
+        let mut mins = vec![ max; axes.len() ];
+        let mut maxs = vec![ -max; axes.len() ];
+
+        for pt in pts.iter() {
+            for (i, axis) in axes.iter().enumerate() {
+                let proj = na::dot(pt.as_vec(), axis);
+
+                if proj < mins[i] {
+                    mins[i] = proj;
+                }
+
+                if proj > maxs[i] {
+                    maxs[i] = proj;
+                }
+            }
+        }
+
+        KDOP::new(axes, mins, maxs)
+    }
+
+    /// Creates the smallest k-DOP enclosing an AABB.
+    pub fn from_aabb<P>(axes: Arc<Vec<V>>, aabb: &AABB<P>) -> KDOP<N, V>
+        where P: Point<N, V> {
+        let center       = aabb.center();
+        let half_extents = aabb.half_extents();
+        let mut mins     = Vec::with_capacity(axes.len());
+        let mut maxs     = Vec::with_capacity(axes.len());
+
+        for axis in axes.iter() {
+            let proj       = na::dot(center.as_vec(), axis);
+            let mut radius = na::zero::<N>();
+
+            for j in 0 .. na::dim::<V>() {
+                radius = radius + na::abs(&axis[j]) * half_extents[j];
+            }
+
+            mins.push(proj - radius);
+            maxs.push(proj + radius);
+        }
+
+        KDOP::new(axes, mins, maxs)
+    }
+
+    /// The smallest AABB enclosing this k-DOP.
+    pub fn aabb<P>(&self) -> AABB<P>
+        where P: Point<N, V> {
+        let mut mins = na::orig::<P>();
+        let mut maxs = na::orig::<P>();
+
+        for i in 0 .. na::dim::<V>() {
+            mins[i] = self.mins[i];
+            maxs[i] = self.maxs[i];
+        }
+
+        AABB::new(mins, maxs)
+    }
+
+    /// Tests if a point is inside of this k-DOP.
+    pub fn contains_point<P>(&self, pt: &P) -> bool
+        where P: Point<N, V> {
+        self.axes.iter().enumerate().all(|(i, axis)| {
+            let proj = na::dot(pt.as_vec(), axis);
+
+            proj >= self.mins[i] && proj <= self.maxs[i]
+        })
+    }
+
+    #[inline]
+    fn assert_same_axes(&self, other: &KDOP<N, V>) {
+        assert!(&*self.axes as *const Vec<V> == &*other.axes as *const Vec<V> || *self.axes == *other.axes,
+                "Both k-DOPs must share the same axes.");
+    }
+}
+
+/// The normalized axes of the k-DOPs commonly used in 2D and in 3D.
+///
+/// The supported values of `k` are 4 and 8 in 2D, and 6, 14, 18 and 26 in 3D. The canonical
+/// axes are always the first ones.
+pub fn kdop_axes<N, V>(k: usize) -> Arc<Vec<V>>
+    where N: Scalar,
+          V: Vect<N> {
+    let dim      = na::dim::<V>();
+    let mut axes = Vec::with_capacity(k / 2);
+
+    for i in 0 .. dim {
+        axes.push(na::canonical_basis_element(i).unwrap());
+    }
+
+    match (dim, k) {
+        (2, 4) | (3, 6) => { },
+        (2, 8) => {
+            axes.push(direction(&[ 1.0, 1.0 ]));
+            axes.push(direction(&[ 1.0, -1.0 ]));
+        },
+        (3, 14) => push_corner_axes(&mut axes),
+        (3, 18) => push_edge_axes(&mut axes),
+        (3, 26) => {
+            push_corner_axes(&mut axes);
+            push_edge_axes(&mut axes);
+        },
+        _ => panic!("Unsupported k-DOP: {}-DOP in dimension {}.", k, dim)
+    }
+
+    Arc::new(axes)
+}
+
+fn push_corner_axes<N: Scalar, V: Vect<N>>(axes: &mut Vec<V>) {
+    axes.push(direction(&[ 1.0, 1.0, 1.0 ]));
+    axes.push(direction(&[ 1.0, 1.0, -1.0 ]));
+    axes.push(direction(&[ 1.0, -1.0, 1.0 ]));
+    axes.push(direction(&[ -1.0, 1.0, 1.0 ]));
+}
+
+fn push_edge_axes<N: Scalar, V: Vect<N>>(axes: &mut Vec<V>) {
+    axes.push(direction(&[ 1.0, 1.0, 0.0 ]));
+    axes.push(direction(&[ 1.0, 0.0, 1.0 ]));
+    axes.push(direction(&[ 0.0, 1.0, 1.0 ]));
+    axes.push(direction(&[ 1.0, -1.0, 0.0 ]));
+    axes.push(direction(&[ 1.0, 0.0, -1.0 ]));
+    axes.push(direction(&[ 0.0, 1.0, -1.0 ]));
+}
+
+fn direction<N: Scalar, V: Vect<N>>(components: &[f64]) -> V {
+    let mut res = na::zero::<V>();
+
+    for (i, c) in components.iter().enumerate() {
+        res[i] = na::cast(*c);
+    }
+
+    na::normalize(&res)
+}
+
+#[old_impl_check]
+impl<N, V> BoundingVolume<N> for KDOP<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    fn intersects(&self, other: &KDOP<N, V>) -> bool {
+        self.assert_same_axes(other);
+
+        (0 .. self.nslabs()).all(|i| self.mins[i] <= other.maxs[i] && other.mins[i] <= self.maxs[i])
+    }
+
+    fn contains(&self, other: &KDOP<N, V>) -> bool {
+        self.assert_same_axes(other);
+
+        (0 .. self.nslabs()).all(|i| self.mins[i] <= other.mins[i] && other.maxs[i] <= self.maxs[i])
+    }
+
+    fn merge(&mut self, other: &KDOP<N, V>) {
+        self.assert_same_axes(other);
+
+        for i in 0 .. self.nslabs() {
+            self.mins[i] = self.mins[i].min(other.mins[i]);
+            self.maxs[i] = self.maxs[i].max(other.maxs[i]);
+        }
+    }
+
+    #[inline]
+    fn merged(&self, other: &KDOP<N, V>) -> KDOP<N, V> {
+        let mut res = self.clone();
+
+        res.merge(other);
+
+        res
+    }
+
+    fn loosen(&mut self, amount: N) {
+        assert!(amount >= na::zero(), "The loosening margin must be positive.");
+
+        for i in 0 .. self.nslabs() {
+            self.mins[i] = self.mins[i] - amount;
+            self.maxs[i] = self.maxs[i] + amount;
+        }
+    }
+
+    #[inline]
+    fn loosened(&self, amount: N) -> KDOP<N, V> {
+        let mut res = self.clone();
+
+        res.loosen(amount);
+
+        res
+    }
+
+    fn tighten(&mut self, amount: N) {
+        assert!(amount >= na::zero(), "The tightening margin must be positive.");
+
+        for i in 0 .. self.nslabs() {
+            self.mins[i] = self.mins[i] + amount;
+            self.maxs[i] = self.maxs[i] - amount;
+
+            assert!(self.mins[i] <= self.maxs[i], "The tightening margin is too large.");
+        }
+    }
+
+    #[inline]
+    fn tightened(&self, amount: N) -> KDOP<N, V> {
+        let mut res = self.clone();
+
+        res.tighten(amount);
+
+        res
+    }
+}
+
+#[old_impl_check]
+impl<N, V> HasSurfaceArea<N> for KDOP<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    /// The surface area of the AABB enclosing this k-DOP.
+    #[inline]
+    fn surface_area(&self) -> N {
+        let mut extents = na::zero::<V>();
+
+        for i in 0 .. na::dim::<V>() {
+            extents[i] = self.maxs[i] - self.mins[i];
+        }
+
+        box_surface_area(&extents)
+    }
+}
+
+#[old_impl_check]
+impl<N, V> Translation<V> for KDOP<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    /// The center of the AABB enclosing this k-DOP.
+    #[inline]
+    fn translation(&self) -> V {
+        let mut res = na::zero::<V>();
+
+        for i in 0 .. na::dim::<V>() {
+            res[i] = (self.mins[i] + self.maxs[i]) * na::cast(0.5f64);
+        }
+
+        res
+    }
+
+    #[inline]
+    fn inv_translation(&self) -> V {
+        -self.translation()
+    }
+
+    #[inline]
+    fn append_translation_mut(&mut self, dv: &V) {
+        for (i, axis) in self.axes.iter().enumerate() {
+            let shift = na::dot(axis, dv);
+
+            self.mins[i] = self.mins[i] + shift;
+            self.maxs[i] = self.maxs[i] + shift;
+        }
+    }
+
+    #[inline]
+    fn append_translation(&self, dv: &V) -> KDOP<N, V> {
+        let mut res = self.clone();
+
+        res.append_translation_mut(dv);
+
+        res
+    }
+
+    #[inline]
+    fn prepend_translation_mut(&mut self, dv: &V) {
+        self.append_translation_mut(dv)
+    }
+
+    #[inline]
+    fn prepend_translation(&self, dv: &V) -> KDOP<N, V> {
+        self.append_translation(dv)
+    }
+
+    #[inline]
+    fn set_translation(&mut self, v: V) {
+        let center = self.translation();
+
+        self.append_translation_mut(&(v - center))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::{Pnt3, Vec3};
+    use bounding_volume::{BoundingVolume, KDOP, kdop_axes};
+
+    #[test]
+    #[should_panic]
+    fn test_new_requires_canonical_axes() {
+        let axes = Arc::new(vec!(Vec3::y(), Vec3::x(), Vec3::z()));
+        let _: KDOP<f64, Vec3<f64>> = KDOP::new(axes, vec!(0.0, 0.0, 0.0), vec!(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_equal_axes_from_distinct_allocations() {
+        let pts = [ Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(1.0, 1.0, 1.0) ];
+        let a   = KDOP::from_points(kdop_axes::<f64, Vec3<f64>>(14), &pts[..]);
+        let b   = KDOP::from_points(kdop_axes::<f64, Vec3<f64>>(14), &pts[..]);
+
+        assert!(a.intersects(&b) && a.contains(&b));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_different_axes_with_same_count() {
+        let pts  = [ Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(1.0, 1.0, 1.0) ];
+        let a    = KDOP::from_points(kdop_axes::<f64, Vec3<f64>>(14), &pts[..]);
+        let mut axes = (*kdop_axes::<f64, Vec3<f64>>(14)).clone();
+        axes[3] = -axes[3];
+        let b    = KDOP::from_points(Arc::new(axes), &pts[..]);
+
+        let _ = a.intersects(&b);
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+use na::{Transform, Translate};
+use na;
+use support_map::SupportMap;
+use bounding_volume::{HasAABB, KDOP};
+use math::{Scalar, Point, Vect, Isometry};
+use inspection::{Repr, maybe_as_support_map};
+
+
+/// Computes the k-DOP of a support mapped shape.
+pub fn implicit_shape_kdop<N, P, V, M, G: ?Sized>(m: &M, i: &G, axes: &Arc<Vec<V>>) -> KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          G: SupportMap<P, V, M> {
+    let mut mins = Vec::with_capacity(axes.len());
+    let mut maxs = Vec::with_capacity(axes.len());
+
+    for axis in axes.iter() {
+        maxs.push(na::dot(i.support_point(m, axis).as_vec(), axis));
+        mins.push(na::dot(i.support_point(m, &-*axis).as_vec(), axis));
+    }
+
+    KDOP::new(axes.clone(), mins, maxs)
+}
+
+/// Computes the k-DOP of a set of point.
+pub fn point_cloud_kdop<N, P, V, M>(m: &M, pts: &[P], axes: &Arc<Vec<V>>) -> KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let wpts: Vec<P> = pts.iter().map(|pt| na::transform(m, pt)).collect();
+
+    KDOP::from_points(axes.clone(), &wpts[..])
+}
+
+/// Computes the k-DOP of any shape.
+///
+/// The k-DOP is exact for support mapped shapes. Otherwise, this is the k-DOP of the shape’s AABB.
+pub fn repr_kdop<N, P, V, M>(m: &M, g: &Repr<N, P, V, M>, axes: &Arc<Vec<V>>) -> KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P>,
+          M: Isometry<N, P, V> {
+    match maybe_as_support_map::<N, P, V, M, _>(g) {
+        Some(s) => implicit_shape_kdop(m, s, axes),
+        None    => KDOP::from_aabb(axes.clone(), &g.aabb(m))
+    }
+}
@@ -1,18 +1,29 @@
 //! Bounding volumes.
 
 #[doc(inline)]
-pub use bounding_volume::bounding_volume::{HasBoundingVolume, BoundingVolume};
+pub use bounding_volume::bounding_volume::{HasBoundingVolume, BoundingVolume, HasSurfaceArea};
 #[doc(inline)]
 pub use bounding_volume::aabb::{HasAABB, AABB};
 #[doc(inline)]
 pub use bounding_volume::bounding_sphere::{HasBoundingSphere, BoundingSphere};
+#[doc(inline)]
+pub use bounding_volume::obb::{HasOBB, OBB};
+#[doc(inline)]
+pub use bounding_volume::kdop::{KDOP, kdop_axes};
+#[doc(inline)]
+pub use bounding_volume::spacialized_cone::SpacializedCone;
+#[doc(inline)]
+pub use bounding_volume::frustum::{Frustum, FrustumClassification, FrustumClassifiable};
 
 pub use bounding_volume::aabb_utils::{implicit_shape_aabb, point_cloud_aabb};
 pub use bounding_volume::aabb_ball::ball_aabb;
+pub use bounding_volume::obb_shapes::{local_aabb_obb, point_cloud_obb};
+pub use bounding_volume::kdop_utils::{implicit_shape_kdop, point_cloud_kdop, repr_kdop};
 pub use bounding_volume::bounding_sphere_utils::{point_cloud_bounding_sphere_with_center, point_cloud_bounding_sphere};
 pub use bounding_volume::bounding_volume_bvt::BoundingVolumeInterferencesCollector;
+pub use bounding_volume::frustum_bvt::FrustumInterferencesCollector;
 
-use na::{Pnt2, Pnt3};
+use na::{Pnt2, Pnt3, Vec2, Vec3, Mat2, Mat3};
 
 #[doc(hidden)]
 pub mod bounding_volume;
@@ -27,6 +38,8 @@ mod aabb_plane;
 mod aabb_convex;
 mod aabb_compound;
 mod aabb_mesh;
+mod aabb_heightfield;
+mod aabb_distance_field;
 mod aabb_utils;
 mod aabb_repr;
 
@@ -43,9 +56,27 @@ mod bounding_sphere_compound;
 mod bounding_sphere_triangle;
 mod bounding_sphere_segment;
 mod bounding_sphere_mesh;
+mod bounding_sphere_heightfield;
+mod bounding_sphere_distance_field;
 mod bounding_sphere_utils;
 mod bounding_sphere_repr;
 
+#[doc(hidden)]
+pub mod obb;
+mod obb_shapes;
+mod obb_repr;
+
+#[doc(hidden)]
+pub mod kdop;
+mod kdop_utils;
+
+#[doc(hidden)]
+pub mod spacialized_cone;
+
+#[doc(hidden)]
+pub mod frustum;
+mod frustum_bvt;
+
 /*
  *
  * Aliases.
@@ -55,8 +86,18 @@ mod bounding_sphere_repr;
 pub type BoundingSphere2<N> = BoundingSphere<N, Pnt2<N>>;
 /// A 2D AABB.
 pub type AABB2<N> = AABB<Pnt2<N>>;
+/// A 2D OBB.
+pub type OBB2<N> = OBB<Pnt2<N>, Vec2<N>, Mat2<N>>;
+/// A 2D k-DOP.
+pub type KDOP2<N> = KDOP<N, Vec2<N>>;
 
 /// A 3D bounding sphere:
 pub type BoundingSphere3<N> = BoundingSphere<N, Pnt3<N>>;
 /// A 3D AABB.
 pub type AABB3<N> = AABB<Pnt3<N>>;
+/// A 3D OBB.
+pub type OBB3<N> = OBB<Pnt3<N>, Vec3<N>, Mat3<N>>;
+/// A 3D k-DOP.
+pub type KDOP3<N> = KDOP<N, Vec3<N>>;
+/// A 3D frustum.
+pub type Frustum3<N> = Frustum<N, Pnt3<N>, Vec3<N>>;
@@ -0,0 +1,493 @@
+//! Oriented Bounding Box.
+
+use std::ops::Add;
+use std::num::Float;
+use na::{Translation, Transform, Rotate, Col, Outer, EigenQR, Zero, One, Identity};
+use na;
+use bounding_volume::{BoundingVolume, HasBoundingVolume, HasSurfaceArea, AABB};
+use math::{Scalar, Point, Vect};
+use utils;
+
+/// Trait of objects that can be bounded by an OBB.
+pub trait HasOBB<P, V, R, M> {
+    /// The object’s OBB.
+    fn obb(&self, &M) -> OBB<P, V, R>;
+}
+
+/// An Oriented Bounding Box.
+///
+/// The axes of the box are the columns of the orthonormal matrix `axes`.
+#[derive(Debug, PartialEq, Clone, RustcEncodable, RustcDecodable)]
+pub struct OBB<P, V, R> {
+    center:       P,
+    axes:         R,
+    half_extents: V
+}
+
+impl<P, V, R> OBB<P, V, R> {
+    /// The center of this OBB.
+    #[inline]
+    pub fn center(&self) -> &P {
+        &self.center
+    }
+
+    /// The orthonormal matrix which columns are the axes of this OBB.
+    #[inline]
+    pub fn axes(&self) -> &R {
+        &self.axes
+    }
+
+    /// The half extents of this OBB along each of its axes.
+    #[inline]
+    pub fn half_extents(&self) -> &V {
+        &self.half_extents
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R> OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy {
+    /// Creates a new OBB.
+    ///
+    /// # Arguments:
+    ///   * `center` - the center of the box.
+    ///   * `axes` - an orthonormal matrix which columns are the axes of the box.
+    ///   * `half_extents` - the half extents of the box along each of its axes. Each component
+    ///   must be positive.
+    pub fn new(center: P, axes: R, half_extents: V) -> OBB<P, V, R> {
+        assert!(na::partial_le(&na::zero(), &half_extents), "The half extents must be positive.");
+
+        OBB {
+            center:       center,
+            axes:         axes,
+            half_extents: half_extents
+        }
+    }
+
+    /// Creates the OBB with the same extents as an AABB.
+    pub fn from_aabb(aabb: &AABB<P>) -> OBB<P, V, R> {
+        OBB::new(aabb.center(), na::one(), aabb.half_extents())
+    }
+
+    /// The `i`-th axis of this OBB.
+    #[inline]
+    pub fn axis(&self, i: usize) -> V {
+        self.axes.col(i)
+    }
+
+    /// This OBB transformed by `m`.
+    pub fn transformed<M: Transform<P> + Rotate<V>>(&self, m: &M) -> OBB<P, V, R> {
+        let mut axes = self.axes;
+
+        for i in 0 .. na::dim::<P>() {
+            axes.set_col(i, m.rotate(&self.axis(i)));
+        }
+
+        OBB::new(m.transform(&self.center), axes, self.half_extents)
+    }
+
+    /// Expresses a point in the local frame of this OBB.
+    pub fn to_local(&self, pt: &P) -> P {
+        let dpt       = *pt - self.center;
+        let mut local = na::orig::<P>();
+
+        for i in 0 .. na::dim::<P>() {
+            local[i] = na::dot(&dpt, &self.axis(i));
+        }
+
+        local
+    }
+
+    /// Expresses a vector in the local frame of this OBB.
+    pub fn to_local_vec(&self, v: &V) -> V {
+        let mut local = na::zero::<V>();
+
+        for i in 0 .. na::dim::<P>() {
+            local[i] = na::dot(v, &self.axis(i));
+        }
+
+        local
+    }
+
+    /// Expresses a vector of the local frame of this OBB in the global frame.
+    pub fn from_local_vec(&self, v: &V) -> V {
+        let mut res = na::zero::<V>();
+
+        for i in 0 .. na::dim::<P>() {
+            res = res + self.axis(i) * v[i];
+        }
+
+        res
+    }
+
+    /// Expresses a point of the local frame of this OBB in the global frame.
+    #[inline]
+    pub fn from_local(&self, pt: &P) -> P {
+        self.center + self.from_local_vec(pt.as_vec())
+    }
+
+    /// This OBB expressed as an AABB in its local frame.
+    #[inline]
+    pub fn local_aabb(&self) -> AABB<P> {
+        AABB::new(na::orig::<P>() + (-self.half_extents), na::orig::<P>() + self.half_extents)
+    }
+
+    /// The vertices of this OBB.
+    pub fn corners(&self) -> Vec<P> {
+        let dim     = na::dim::<P>();
+        let mut res = Vec::with_capacity(1 << dim);
+
+        for k in 0usize .. 1 << dim {
+            let mut corner = self.center;
+
+            for i in 0 .. dim {
+                if k & (1 << i) == 0 {
+                    corner = corner + self.axis(i) * self.half_extents[i];
+                }
+                else {
+                    corner = corner + self.axis(i) * (-self.half_extents[i]);
+                }
+            }
+
+            res.push(corner);
+        }
+
+        res
+    }
+
+    /// The half-length of the projection of this OBB on `axis`.
+    pub fn projected_radius(&self, axis: &V) -> N {
+        let mut res = na::zero::<N>();
+
+        for i in 0 .. na::dim::<P>() {
+            res = res + na::abs(&na::dot(axis, &self.axis(i))) * self.half_extents[i];
+        }
+
+        res
+    }
+
+    /// The volume of this OBB, up to a constant factor.
+    #[inline]
+    fn scaled_volume(&self) -> N {
+        let mut res = na::one::<N>();
+
+        for i in 0 .. na::dim::<P>() {
+            res = res * self.half_extents[i];
+        }
+
+        res
+    }
+
+    /// Tests if `axis` separates this OBB from `other`.
+    #[inline]
+    fn is_separating_axis(&self, other: &OBB<P, V, R>, axis: &V) -> bool {
+        let d = other.center - self.center;
+
+        na::abs(&na::dot(&d, axis)) > self.projected_radius(axis) + other.projected_radius(axis)
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R> OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Outer<R>,
+          R: Col<V> + EigenQR<N, V> + Add<R, Output = R> + Zero + One + Copy {
+    /// Computes the OBB of a set of points.
+    ///
+    /// The axes of the OBB are the principal axes of the point cloud.
+    pub fn from_points(pts: &[P]) -> OBB<P, V, R> {
+        assert!(pts.len() != 0, "Cannot compute the OBB of an empty set of points.");
+
+        let (cov, center): (R, P) = utils::cov_and_center(pts);
+        let (mut axes, _)         = na::eigen_qr(&cov, &Float::epsilon(), 1000);
+        let dim                   = na::dim::<P>();
+        let mut mins              = na::orig::<P>();
+        let mut maxs              = na::orig::<P>();
+
+        for i in 0 .. dim {
+            let axis = axes.col(i);
+            let axis = if na::is_zero(&na::sqnorm(&axis)) {
+                na::canonical_basis_element(i).unwrap()
+            }
+            else {
+                na::normalize(&axis)
+            };
+
+            axes.set_col(i, axis);
+
+            mins[i] = Float::infinity();
+            maxs[i] = -Float::infinity();
+
+            for pt in pts.iter() {
+                let proj = na::dot(&(*pt - center), &axis);
+
+                if proj < mins[i] {
+                    mins[i] = proj;
+                }
+
+                if proj > maxs[i] {
+                    maxs[i] = proj;
+                }
+            }
+        }
+
+        let mut obb = OBB::new(center, axes, (maxs - mins) * na::cast::<f64, N>(0.5));
+        let shift   = obb.from_local_vec(&na::center(&mins, &maxs).to_vec());
+
+        obb.center = obb.center + shift;
+
+        obb
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R> BoundingVolume<N> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Outer<R>,
+          R: Col<V> + EigenQR<N, V> + Add<R, Output = R> + Zero + One + Copy {
+    fn intersects(&self, other: &OBB<P, V, R>) -> bool {
+        let dim = na::dim::<P>();
+
+        for i in 0 .. dim {
+            if self.is_separating_axis(other, &self.axis(i)) ||
+               self.is_separating_axis(other, &other.axis(i)) {
+                return false;
+            }
+        }
+
+        if dim == 3 {
+            let eps: N = Float::epsilon();
+
+            for i in 0 .. 3 {
+                for j in 0 .. 3 {
+                    let axis = utils::cross3(&self.axis(i), &other.axis(j));
+
+                    if na::sqnorm(&axis) > eps && self.is_separating_axis(other, &axis) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    fn contains(&self, other: &OBB<P, V, R>) -> bool {
+        let tolerance: N = Float::epsilon();
+        let tolerance    = tolerance.sqrt();
+
+        other.corners().iter().all(|corner| {
+            let local = self.to_local(corner);
+
+            (0 .. na::dim::<P>()).all(|i| na::abs(&local[i]) <= self.half_extents[i] + tolerance)
+        })
+    }
+
+    #[inline]
+    fn merge(&mut self, other: &OBB<P, V, R>) {
+        *self = self.merged(other)
+    }
+
+    /// The merged OBB keeps the axes of the largest OBB, and is grown to contain the other one.
+    ///
+    /// This is not the tightest box but it is computed in constant time, which matters when
+    /// OBBs are merged repeatedly, e.g., by a DBVT.
+    fn merged(&self, other: &OBB<P, V, R>) -> OBB<P, V, R> {
+        let (large, small) = if self.scaled_volume() >= other.scaled_volume() { (self, other) }
+                             else { (other, self) };
+
+        let dpos     = small.center - large.center;
+        let mut mins = na::orig::<P>();
+        let mut maxs = na::orig::<P>();
+
+        for i in 0 .. na::dim::<P>() {
+            let axis   = large.axis(i);
+            let proj   = na::dot(&dpos, &axis);
+            let radius = small.projected_radius(&axis);
+
+            mins[i] = -large.half_extents[i];
+            maxs[i] = large.half_extents[i];
+
+            if proj - radius < mins[i] {
+                mins[i] = proj - radius;
+            }
+
+            if proj + radius > maxs[i] {
+                maxs[i] = proj + radius;
+            }
+        }
+
+        let center = large.center + large.from_local_vec(&na::center(&mins, &maxs).to_vec());
+
+        OBB::new(center, large.axes, (maxs - mins) * na::cast::<f64, N>(0.5))
+    }
+
+    #[inline]
+    fn loosen(&mut self, amount: N) {
+        assert!(amount >= na::zero(), "The loosening margin must be positive.");
+
+        for i in 0 .. na::dim::<P>() {
+            self.half_extents[i] = self.half_extents[i] + amount;
+        }
+    }
+
+    #[inline]
+    fn loosened(&self, amount: N) -> OBB<P, V, R> {
+        let mut res = self.clone();
+
+        res.loosen(amount);
+
+        res
+    }
+
+    #[inline]
+    fn tighten(&mut self, amount: N) {
+        assert!(amount >= na::zero(), "The tightening margin must be positive.");
+
+        for i in 0 .. na::dim::<P>() {
+            self.half_extents[i] = self.half_extents[i] - amount;
+        }
+
+        assert!(na::partial_le(&na::zero(), &self.half_extents), "The tightening margin is too large.");
+    }
+
+    #[inline]
+    fn tightened(&self, amount: N) -> OBB<P, V, R> {
+        let mut res = self.clone();
+
+        res.tighten(amount);
+
+        res
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R> HasSurfaceArea<N> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy {
+    #[inline]
+    fn surface_area(&self) -> N {
+        self.local_aabb().surface_area()
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R> Translation<V> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Clone {
+    #[inline]
+    fn translation(&self) -> V {
+        self.center.to_vec()
+    }
+
+    #[inline]
+    fn inv_translation(&self) -> V {
+        -self.translation()
+    }
+
+    #[inline]
+    fn append_translation_mut(&mut self, dv: &V) {
+        self.center = self.center + *dv;
+    }
+
+    #[inline]
+    fn append_translation(&self, dv: &V) -> OBB<P, V, R> {
+        let mut res = self.clone();
+
+        res.append_translation_mut(dv);
+
+        res
+    }
+
+    #[inline]
+    fn prepend_translation_mut(&mut self, dv: &V) {
+        self.append_translation_mut(dv)
+    }
+
+    #[inline]
+    fn prepend_translation(&self, dv: &V) -> OBB<P, V, R> {
+        self.append_translation(dv)
+    }
+
+    #[inline]
+    fn set_translation(&mut self, v: V) {
+        self.center = na::orig::<P>() + v;
+    }
+}
+
+/// The OBB of a shape in its local space.
+impl<P, V, R, G: ?Sized> HasBoundingVolume<OBB<P, V, R>> for G
+    where G: HasOBB<P, V, R, Identity> {
+    #[inline]
+    fn bounding_volume(&self) -> OBB<P, V, R> {
+        self.obb(&Identity::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use na::{Pnt3, Vec3, Mat3, Iso3};
+    use na;
+    use bounding_volume::{BoundingVolume, HasOBB, OBB, OBB3};
+    use shape::Plane;
+
+    #[test]
+    fn test_merged_contains_both() {
+        let a: OBB3<f64> = OBB::new(Pnt3::new(0.0f64, 0.0, 0.0), na::one(), Vec3::new(2.0, 1.0, 1.0));
+        let m = Iso3::new(Vec3::new(3.0f64, 1.0, 0.0), Vec3::new(0.0, 0.0, 0.5));
+        let b: OBB3<f64> = OBB::new(Pnt3::new(0.0f64, 0.0, 0.0), na::one(), Vec3::new(0.5, 0.5, 0.5));
+        let b = b.transformed(&m);
+
+        let ab = a.merged(&b);
+        let ba = b.merged(&a);
+
+        assert!(ab.contains(&a) && ab.contains(&b));
+        assert!(ba.contains(&a) && ba.contains(&b));
+        // The axes of the largest box are kept.
+        assert!(na::approx_eq(ab.axes(), a.axes()));
+    }
+
+    #[test]
+    fn test_from_points() {
+        let pts = [
+            Pnt3::new(-1.0f64, 0.0, 0.0), Pnt3::new(3.0, 0.0, 0.0),
+            Pnt3::new(1.0, 0.5, 0.0), Pnt3::new(1.0, -0.5, 0.0)
+        ];
+
+        let obb: OBB3<f64> = OBB::from_points(&pts);
+
+        assert!(na::approx_eq(obb.center(), &Pnt3::new(1.0, 0.0, 0.0)));
+        assert!(pts.iter().all(|pt| {
+            let local = obb.to_local(pt);
+            (0 .. 3).all(|i| na::abs(&local[i]) <= obb.half_extents()[i] + 1.0e-7)
+        }));
+    }
+
+    #[test]
+    fn test_plane_obb_is_finite() {
+        let plane = Plane::new(Vec3::y());
+        let m     = Iso3::new(Vec3::new(1.0f64, 2.0, 3.0), Vec3::new(0.3, 0.0, 0.0));
+        let obb: OBB3<f64> = plane.obb(&m);
+        let b = OBB::new(Pnt3::new(0.0f64, 0.0, 0.0), na::one::<Mat3<f64>>(), Vec3::new(1.0, 1.0, 1.0));
+
+        let merged = b.merged(&obb);
+
+        for i in 0 .. 3 {
+            assert!(obb.center()[i].is_finite() && merged.center()[i].is_finite());
+            assert!(merged.half_extents()[i].is_finite());
+        }
+
+        assert!(obb.corners().iter().all(|c| (0 .. 3).all(|i| c[i].is_finite())));
+        assert!(obb.intersects(&b));
+    }
+}
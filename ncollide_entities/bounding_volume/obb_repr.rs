@@ -0,0 +1,71 @@
+use std::ops::Add;
+use na::{Translate, Col, Outer, EigenQR, Zero, One};
+use bounding_volume::{HasOBB, OBB};
+use math::{Scalar, Point, Vect, Isometry};
+use shape::{Ball, Capsule, Compound, Cone, Convex, ConvexPolyhedron, Cuboid, Cylinder,
+            HeightField, DistanceField, TriMesh, Polyline, Plane, Segment, Triangle};
+use inspection::Repr;
+
+impl<N, P, V, R, M> HasOBB<P, V, R, M> for Repr<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P> + Outer<R>,
+          R: Col<V> + EigenQR<N, V> + Add<R, Output = R> + Zero + One + Copy,
+          M: Isometry<N, P, V> {
+    #[inline]
+    fn obb(&self, m: &M) -> OBB<P, V, R> {
+        let repr = self.repr();
+
+        if let Some(b) = repr.downcast_ref::<Ball<N>>() {
+            b.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<Capsule<N>>() {
+            c.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<Compound<N, P, V, M>>() {
+            c.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<Cone<N>>() {
+            c.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<Convex<P>>() {
+            c.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<ConvexPolyhedron<P, V>>() {
+            c.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<Cuboid<V>>() {
+            c.obb(m)
+        }
+        else if let Some(c) = repr.downcast_ref::<Cylinder<N>>() {
+            c.obb(m)
+        }
+        else if let Some(h) = repr.downcast_ref::<HeightField<N, V>>() {
+            h.obb(m)
+        }
+        else if let Some(d) = repr.downcast_ref::<DistanceField<N, P, V>>() {
+            d.obb(m)
+        }
+        else if let Some(t) = repr.downcast_ref::<TriMesh<N, P, V>>() {
+            t.obb(m)
+        }
+        else if let Some(p) = repr.downcast_ref::<Polyline<N, P, V>>() {
+            p.obb(m)
+        }
+        else if let Some(p) = repr.downcast_ref::<Plane<V>>() {
+            p.obb(m)
+        }
+        else if let Some(s) = repr.downcast_ref::<Segment<P>>() {
+            s.obb(m)
+        }
+        else if let Some(t) = repr.downcast_ref::<Triangle<P>>() {
+            t.obb(m)
+        }
+        else {
+            /*
+             * XXX: dispatch by custom type.
+             */
+            unimplemented!()
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::ops::Add;
+use na::{Col, Outer, EigenQR, Zero, One, Bounded};
+use na;
+use bounding_volume::{HasAABB, HasOBB, OBB, AABB};
+use shape::{Ball, Capsule, Compound, Cone, Convex, ConvexPolyhedron, Cuboid, Cylinder,
+            HeightField, DistanceField, TriMesh, Polyline, Plane, Segment, Triangle};
+use math::{Scalar, Point, Vect, Isometry};
+
+/// The OBB of a shape, aligned with the axes of its local frame.
+pub fn local_aabb_obb<N, P, V, R, M, G: ?Sized>(m: &M, g: &G) -> OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy,
+          M: Isometry<N, P, V>,
+          G: HasAABB<P, M> {
+    let one: M = na::one();
+
+    OBB::from_aabb(&g.aabb(&one)).transformed(m)
+}
+
+/// The OBB of a set of points, aligned with their principal axes.
+pub fn point_cloud_obb<N, P, V, R, M>(m: &M, pts: &[P]) -> OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Outer<R>,
+          R: Col<V> + EigenQR<N, V> + Add<R, Output = R> + Zero + One + Copy,
+          M: Isometry<N, P, V> {
+    OBB::from_points(pts).transformed(m)
+}
+
+/// The OBB of a plane.
+///
+/// Like its AABB, it is a huge box centered at the origin of the plane local frame. Its extents
+/// are small enough for its corners, and its merge with any other finite OBB, to stay finite.
+#[old_impl_check]
+impl<N, P, V, R, M> HasOBB<P, V, R, M> for Plane<V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy,
+          M: Isometry<N, P, V> {
+    fn obb(&self, m: &M) -> OBB<P, V, R> {
+        let dim      = na::dim::<P>();
+        let max: N   = Bounded::max_value();
+        let extent   = max / na::cast((4 * dim * dim) as f64);
+        let mut maxs = na::orig::<P>();
+
+        for i in 0 .. dim {
+            maxs[i] = extent;
+        }
+
+        OBB::from_aabb(&AABB::new(-maxs, maxs)).transformed(m)
+    }
+}
+
+macro_rules! impl_obb_from_local_aabb(
+    ($t: ty) => {
+        #[old_impl_check]
+        impl<N, P, V, R, M> HasOBB<P, V, R, M> for $t
+            where N:  Scalar,
+                  P:  Point<N, V>,
+                  V:  Vect<N>,
+                  R:  Col<V> + One + Copy,
+                  M:  Isometry<N, P, V>,
+                  $t: HasAABB<P, M> {
+            #[inline]
+            fn obb(&self, m: &M) -> OBB<P, V, R> {
+                local_aabb_obb(m, self)
+            }
+        }
+    }
+);
+
+macro_rules! impl_obb_from_point_cloud(
+    ($t: ty, $points: ident) => {
+        #[old_impl_check]
+        impl<N, P, V, R, M> HasOBB<P, V, R, M> for $t
+            where N: Scalar,
+                  P: Point<N, V>,
+                  V: Vect<N> + Outer<R>,
+                  R: Col<V> + EigenQR<N, V> + Add<R, Output = R> + Zero + One + Copy,
+                  M: Isometry<N, P, V> {
+            #[inline]
+            fn obb(&self, m: &M) -> OBB<P, V, R> {
+                point_cloud_obb(m, &self.$points()[..])
+            }
+        }
+    }
+);
+
+impl_obb_from_local_aabb!(Ball<N>);
+impl_obb_from_local_aabb!(Capsule<N>);
+impl_obb_from_local_aabb!(Compound<N, P, V, M>);
+impl_obb_from_local_aabb!(Cone<N>);
+impl_obb_from_local_aabb!(Cuboid<V>);
+impl_obb_from_local_aabb!(Cylinder<N>);
+impl_obb_from_local_aabb!(HeightField<N, V>);
+impl_obb_from_local_aabb!(DistanceField<N, P, V>);
+impl_obb_from_local_aabb!(Segment<P>);
+impl_obb_from_local_aabb!(Triangle<P>);
+
+impl_obb_from_point_cloud!(Convex<P>, points);
+impl_obb_from_point_cloud!(ConvexPolyhedron<P, V>, vertices);
+impl_obb_from_point_cloud!(TriMesh<N, P, V>, vertices);
+impl_obb_from_point_cloud!(Polyline<N, P, V>, vertices);
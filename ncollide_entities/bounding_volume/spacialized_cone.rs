@@ -1,28 +1,34 @@
-use na::{Translation, Norm, RotationMatrix, BaseFloat};
+//! Normal cone with a bounding sphere.
+
+use na::{Translation, Translate, Transform, Rotate, BaseFloat};
 use na;
-use math::{N, Vect, Matrix};
-use bounding_volume::{BoundingVolume, BoundingSphere};
+use bounding_volume::{BoundingVolume, HasSurfaceArea, BoundingSphere};
 use math::{Scalar, Point, Vect};
 
 // FIXME: make a structure 'cone' ?
 #[derive(Debug, PartialEq, Clone, RustcEncodable, RustcDecodable)]
 /// A normal cone with a bounding sphere.
-pub struct SpacializedCone {
-    sphere:  BoundingSphere,
-    axis:    Vect,
+///
+/// The cone contains the normals of a set of surface elements, while the sphere bounds them.
+pub struct SpacializedCone<N, P, V> {
+    sphere:  BoundingSphere<N, P>,
+    axis:    V,
     hangle:  N,
 }
 
-impl SpacializedCone {
+impl<N, P, V> SpacializedCone<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
     /// Creates a new spacialized cone with a given bounding sphere, axis, and half-angle.
-    pub fn new(sphere: BoundingSphere, axis: Vect, hangle: N) -> SpacializedCone {
+    pub fn new(sphere: BoundingSphere<N, P>, axis: V, hangle: N) -> SpacializedCone<N, P, V> {
         let axis = na::normalize(&axis);
 
         unsafe { SpacializedCone::new_normalized(sphere, axis, hangle) }
     }
 
     /// Creates a new spacialized cone with a given bounding sphere, unit axis, and half-angle.
-    pub unsafe fn new_normalized(sphere: BoundingSphere, axis: Vect, hangle: N) -> SpacializedCone {
+    pub unsafe fn new_normalized(sphere: BoundingSphere<N, P>, axis: V, hangle: N) -> SpacializedCone<N, P, V> {
         SpacializedCone {
             sphere:  sphere,
             axis:    axis,
@@ -32,13 +38,13 @@ impl SpacializedCone {
 
     /// The bounding sphere of this spacialized cone.
     #[inline]
-    pub fn sphere<'a>(&'a self) -> &'a BoundingSphere {
+    pub fn sphere<'a>(&'a self) -> &'a BoundingSphere<N, P> {
         &self.sphere
     }
 
     /// This cone axis.
     #[inline]
-    pub fn axis<'a>(&'a self) -> &'a Vect {
+    pub fn axis<'a>(&'a self) -> &'a V {
         &self.axis
     }
 
@@ -49,7 +55,7 @@ impl SpacializedCone {
     }
 
     /// Transforms the spacialized cone by `m`.
-    pub fn transform_by(&self, m: &Matrix) -> SpacializedCone {
+    pub fn transform_by<M: Transform<P> + Rotate<V>>(&self, m: &M) -> SpacializedCone<N, P, V> {
         unsafe {
             let sphere = self.sphere.transform_by(m);
             let axis   = na::rotate(m, &self.axis);
@@ -60,21 +66,65 @@ impl SpacializedCone {
     // FIXME: create a Cone bounding volume and move this method to it.
     /// Tests whether the given direction is inside of the cone.
     #[inline]
-    pub fn contains_direction(&self, dir: &Vect) -> bool {
-        let angle = na::dot(&self.axis, dir);
-        let angle = na::clamp(angle, -na::one::<N>(), na::one()).acos();
+    pub fn contains_direction(&self, dir: &V) -> bool {
+        angle_between(&self.axis, dir) <= self.hangle
+    }
+
+    /// Whether the surface bounded by this cone may fold back on itself.
+    ///
+    /// A connected surface which normals all lie within a cone of half-angle smaller than `pi / 2`
+    /// cannot intersect itself.
+    #[inline]
+    pub fn may_fold_back(&self) -> bool {
+        let _pi: N = BaseFloat::pi();
+
+        self.hangle >= _pi * na::cast(0.5f64)
+    }
+
+    /// Merges the normal cone of `other` with this normal cone, leaving the sphere untouched.
+    fn merge_cone(&mut self, other: &SpacializedCone<N, P, V>) {
+        let _pi: N = BaseFloat::pi();
+        let alpha  = angle_between(&self.axis, &other.axis);
+
+        if alpha + other.hangle <= self.hangle {
+            // `other` is already inside of this cone.
+            return;
+        }
+
+        if alpha + self.hangle <= other.hangle {
+            // This cone is inside of `other`.
+            self.axis   = other.axis;
+            self.hangle = other.hangle;
+
+            return;
+        }
+
+        let hangle = (self.hangle + other.hangle + alpha) * na::cast(0.5f64);
+
+        // Direction orthogonal to `self.axis`, toward `other.axis`, in the plane they span.
+        let mut perp = other.axis - self.axis * na::dot(&self.axis, &other.axis);
 
-        angle <= self.hangle
+        if hangle >= _pi || na::is_zero(&perp.normalize()) {
+            // This happens if alpha ~= 0 or alpha ~= pi.
+            self.hangle = na::clamp(hangle, na::zero(), _pi);
+        }
+        else {
+            // Rotate the axis toward `other.axis` so that both cones are enclosed.
+            let dangle  = hangle - self.hangle;
+            self.axis   = na::normalize(&(self.axis * dangle.cos() + perp * dangle.sin()));
+            self.hangle = hangle;
+        }
     }
 }
 
-#[cfg(not(feature = "4d"))]
-impl BoundingVolume for SpacializedCone {
+impl<N, P, V> BoundingVolume<N> for SpacializedCone<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P> {
     #[inline]
-    fn intersects(&self, other: &SpacializedCone) -> bool {
+    fn intersects(&self, other: &SpacializedCone<N, P, V>) -> bool {
         if self.sphere.intersects(&other.sphere) {
-            let dangle = na::dot(&self.axis, &(-other.axis));
-            let dangle = na::clamp(dangle, -na::one::<N>(), na::one()).acos();
+            let dangle = angle_between(&self.axis, &(-other.axis));
             let angsum = self.hangle + other.hangle;
 
             dangle <= angsum
@@ -85,111 +135,148 @@ impl BoundingVolume for SpacializedCone {
     }
 
     #[inline]
-    fn contains(&self, other: &SpacializedCone) -> bool {
-        if self.sphere.contains(&other.sphere) {
-            panic!("Not yet implemented.")
-        }
-        else {
-            false
-        }
+    fn contains(&self, other: &SpacializedCone<N, P, V>) -> bool {
+        self.sphere.contains(&other.sphere) &&
+        angle_between(&self.axis, &other.axis) + other.hangle <= self.hangle
     }
 
     #[inline]
-    fn merge(&mut self, other: &SpacializedCone) {
+    fn merge(&mut self, other: &SpacializedCone<N, P, V>) {
         self.sphere.merge(&other.sphere);
-
-        // merge the cone
-        let alpha = na::clamp(na::dot(&self.axis, &other.axis), -na::one::<N>(), na::one()).acos();
-
-        let mut rot_axis = na::cross(&self.axis, &other.axis);
-        if !na::is_zero(&rot_axis.normalize()) {
-            let dangle = (alpha - self.hangle + other.hangle) * na::cast(0.5f64);
-            let rot    = na::append_rotation(&na::one::<RotationMatrix>(), &(rot_axis * dangle));
-
-            self.axis    = rot * self.axis;
-            self.hangle  = na::clamp((self.hangle + other.hangle + alpha) * na::cast(0.5f64), na::zero(), BaseFloat::pi());
-        }
-        else {
-            // This happens if alpha ~= 0 or alpha ~= pi.
-            if alpha > na::one() { // NOTE: 1.0 is just a randomly chosen number in-between 0 and pi.
-                // alpha ~= pi
-                self.hangle = alpha;
-            }
-            else {
-                // alpha ~= 0, do nothing.
-            }
-        }
+        self.merge_cone(other);
     }
 
     #[inline]
-    fn merged(&self, other: &SpacializedCone) -> SpacializedCone {
+    fn merged(&self, other: &SpacializedCone<N, P, V>) -> SpacializedCone<N, P, V> {
         let mut res = self.clone();
 
         res.merge(other);
 
         res
     }
+
+    #[inline]
+    fn loosen(&mut self, amount: N) {
+        self.sphere.loosen(amount)
+    }
+
+    #[inline]
+    fn loosened(&self, amount: N) -> SpacializedCone<N, P, V> {
+        unsafe { SpacializedCone::new_normalized(self.sphere.loosened(amount), self.axis, self.hangle) }
+    }
+
+    #[inline]
+    fn tighten(&mut self, amount: N) {
+        self.sphere.tighten(amount)
+    }
+
+    #[inline]
+    fn tightened(&self, amount: N) -> SpacializedCone<N, P, V> {
+        unsafe { SpacializedCone::new_normalized(self.sphere.tightened(amount), self.axis, self.hangle) }
+    }
+}
+
+impl<N, P, V> HasSurfaceArea<N> for SpacializedCone<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn surface_area(&self) -> N {
+        self.sphere.surface_area()
+    }
 }
 
-impl Translation<Vect> for SpacializedCone {
+impl<N, P, V> Translation<V> for SpacializedCone<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
     #[inline]
-    fn translation(&self) -> Vect {
-        self.sphere.center().as_vec().clone()
+    fn translation(&self) -> V {
+        self.sphere.translation()
     }
 
     #[inline]
-    fn inv_translation(&self) -> Vect {
+    fn inv_translation(&self) -> V {
         -self.sphere.translation()
     }
 
     #[inline]
-    fn append_translation(&mut self, dv: &Vect) {
-        self.sphere.append_translation(dv);
+    fn append_translation_mut(&mut self, dv: &V) {
+        self.sphere.append_translation_mut(dv);
     }
 
     #[inline]
-    fn append_translation_cpy(sc: &SpacializedCone, dv: &Vect) -> SpacializedCone {
-        SpacializedCone::new(
-            Translation::append_translation_cpy(&sc.sphere, dv),
-            sc.axis.clone(),
-            sc.hangle.clone())
+    fn append_translation(&self, dv: &V) -> SpacializedCone<N, P, V> {
+        unsafe { SpacializedCone::new_normalized(self.sphere.append_translation(dv), self.axis, self.hangle) }
     }
 
     #[inline]
-    fn prepend_translation(&mut self, dv: &Vect) {
-        self.sphere.append_translation(dv)
+    fn prepend_translation_mut(&mut self, dv: &V) {
+        self.sphere.append_translation_mut(dv)
     }
 
     #[inline]
-    fn prepend_translation_cpy(sc: &SpacializedCone, dv: &Vect) -> SpacializedCone {
-        Translation::append_translation_cpy(sc, dv)
+    fn prepend_translation(&self, dv: &V) -> SpacializedCone<N, P, V> {
+        self.append_translation(dv)
     }
 
     #[inline]
-    fn set_translation(&mut self, v: Vect) {
+    fn set_translation(&mut self, v: V) {
         self.sphere.set_translation(v)
     }
 }
 
+/// The angle between two unit vectors.
+#[inline]
+fn angle_between<N: Scalar, V: Vect<N>>(a: &V, b: &V) -> N {
+    na::clamp(na::dot(a, b), -na::one::<N>(), na::one()).acos()
+}
+
 #[cfg(test)]
 mod test {
-    use na::Vec3;
+    use na::{Pnt3, Vec3, BaseFloat};
     use na;
     use super::SpacializedCone;
     use bounding_volume::{BoundingVolume, BoundingSphere};
 
 
     #[test]
-    #[cfg(feature = "3d")]
     fn test_merge_vee() {
-        let sp   = BoundingSphere::new(na::orig(), na::one());
-        let pi: N = BaseFloat::pi();
-        let pi_12 = pi / na::cast(12.0f64);
-        let a    = SpacializedCone::new(sp.clone(), Vec3::new(1.0, 1.0, 0.0), pi_12);
-        let b    = SpacializedCone::new(sp.clone(), Vec3::new(-1.0, 1.0, 0.0), pi_12);
+        let sp: BoundingSphere<f64, Pnt3<f64>> = BoundingSphere::new(na::orig(), na::one());
+        let pi: f64 = BaseFloat::pi();
+        let pi_12   = pi / na::cast(12.0f64);
+        let a       = SpacializedCone::new(sp.clone(), Vec3::new(1.0, 1.0, 0.0), pi_12);
+        let b       = SpacializedCone::new(sp.clone(), Vec3::new(-1.0, 1.0, 0.0), pi_12);
 
         let ab = a.merged(&b);
 
         assert!(na::approx_eq(&ab.hangle, &(pi_12 * na::cast(4.0f64))))
     }
+
+    #[test]
+    fn test_merge_contained_cone() {
+        let sp: BoundingSphere<f64, Pnt3<f64>> = BoundingSphere::new(na::orig(), na::one());
+        let pi: f64 = BaseFloat::pi();
+        let a       = SpacializedCone::new(sp.clone(), Vec3::new(0.0, 1.0, 0.0), pi / 4.0);
+        let b       = SpacializedCone::new(sp.clone(), Vec3::new(0.1, 1.0, 0.0), pi / 36.0);
+
+        let ab = a.merged(&b);
+        let ba = b.merged(&a);
+
+        assert!(na::approx_eq(&ab.hangle, &(pi / 4.0)) && na::approx_eq(&ab.axis, &Vec3::y()));
+        assert!(na::approx_eq(&ba.hangle, &(pi / 4.0)) && na::approx_eq(&ba.axis, &Vec3::y()));
+    }
+
+    #[test]
+    fn test_may_fold_back() {
+        let sp: BoundingSphere<f64, Pnt3<f64>> = BoundingSphere::new(na::orig(), na::one());
+        let pi: f64 = BaseFloat::pi();
+        let a       = SpacializedCone::new(sp.clone(), Vec3::new(1.0, 1.0, 0.0), pi / 8.0);
+        let b       = SpacializedCone::new(sp.clone(), Vec3::new(-1.0, 1.0, 0.0), pi / 8.0);
+        let c       = SpacializedCone::new(sp.clone(), Vec3::new(0.0, -1.0, 0.0), 0.0);
+
+        assert!(!a.may_fold_back());
+        assert!(!a.merged(&b).may_fold_back());
+        assert!(a.merged(&b).merged(&c).may_fold_back());
+    }
 }
@@ -1,7 +1,7 @@
 use std::mem;
 use std::any::TypeId;
 use math::{Scalar, Point, Vect, Isometry};
-use shape::{Compound, TriMesh, Polyline, CompositeShape};
+use shape::{Compound, TriMesh, Polyline, HeightField, CompositeShape};
 use inspection::{Repr, ReprDesc};
 
 /// Gets the id associated with the `CompositeShape` trait.
@@ -51,3 +51,4 @@ macro_rules! impl_composite_shape_repr(
 impl_composite_shape_repr!(Compound<N, P, V, M>);
 impl_composite_shape_repr!(TriMesh<N, P, V>);
 impl_composite_shape_repr!(Polyline<N, P, V>);
+impl_composite_shape_repr!(HeightField<N, V>);
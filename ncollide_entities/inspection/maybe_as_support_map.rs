@@ -1,7 +1,7 @@
 use std::mem;
 use std::any::TypeId;
 use math::{Scalar, Point, Vect, Isometry};
-use shape::{Ball, Capsule, Cone, Convex, Cuboid, Cylinder, Segment, Triangle};
+use shape::{Ball, Capsule, Cone, Convex, ConvexPolyhedron, Cuboid, Cylinder, Segment, Triangle};
 use support_map::SupportMap;
 use inspection::{Repr, ReprDesc};
 
@@ -53,6 +53,7 @@ impl_support_map_repr!(Ball<N>);
 impl_support_map_repr!(Capsule<N>);
 impl_support_map_repr!(Cone<N>);
 impl_support_map_repr!(Convex<P>);
+impl_support_map_repr!(ConvexPolyhedron<P, V>);
 impl_support_map_repr!(Cuboid<V>);
 impl_support_map_repr!(Cylinder<N>);
 impl_support_map_repr!(Segment<P>);
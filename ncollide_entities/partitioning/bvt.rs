@@ -1,11 +1,13 @@
 //! A read-only Bounding Volume Tree.
 
+use std::usize;
 use test::stats::Stats;
 use std::collections::BinaryHeap;
 use na::{Translation, Bounded};
 use na;
 use partitioning::{BVTVisitor, BVTTVisitor, BVTCostFn};
-use bounding_volume::BoundingVolume;
+use partitioning::k_best_first_search::{self, NodeContent};
+use bounding_volume::{BoundingVolume, HasSurfaceArea};
 use utils::data::ref_with_cost::RefWithCost;
 use math::{Scalar, Vect};
 
@@ -54,7 +56,7 @@ impl<B, BV> BVT<B, BV> {
     }
 
     /// Visit this tree using… a visitor!
-    pub fn visit<Vis: BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
+    pub fn visit<Vis: ?Sized + BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
         match self.tree {
             Some(ref t) => t.visit(visitor),
             None        => { }
@@ -69,12 +71,27 @@ impl<B, BV> BVT<B, BV> {
         }
     }
 
+    /// Visits the bounding volume traversal tree implicitely formed with itself.
+    ///
+    /// This visits the same pairs of distinct leaves as `self.visit_bvtt(self, visitor)`, but each
+    /// unordered pair only once. Before the pairs of leaves of a subtree are visited, `visit_self`
+    /// is called with this subtree. If it returns `false`, the pairs of leaves of this subtree are
+    /// skipped, while the pairs made of one of its leaves and another leaf are still visited.
+    pub fn visit_self_bvtt<Vis, F>(&self, visitor: &mut Vis, visit_self: &mut F)
+        where Vis: BVTTVisitor<B, BV>,
+              F:   FnMut(&BVTNode<B, BV>) -> bool {
+        match self.tree {
+            Some(ref t) => t.visit_self_bvtt(visitor, visit_self),
+            None        => { }
+        }
+    }
+
     // FIXME: internalize the type parameter R using associated types.
     // FIXME: really return a ref to B ?
     /// Performs a best-fist-search on the tree.
     ///
     /// Returns the content of the best leaf nound, and a result of user-defined type.
-    pub fn best_first_search<'a, N, BFS, R>(&'a self, algorithm: &mut BFS) -> Option<(&'a B, R)>
+    pub fn best_first_search<'a, N, BFS: ?Sized, R>(&'a self, algorithm: &mut BFS) -> Option<(&'a B, R)>
         where N:   Scalar,
               BFS: BVTCostFn<N, B, BV, R> {
         match self.tree {
@@ -83,6 +100,36 @@ impl<B, BV> BVT<B, BV> {
         }
     }
 
+    /// Performs a best-first search on the tree to find the `k` leaves with the smallest cost.
+    ///
+    /// Leaves with a cost greater than `max_cost` are ignored. The leaves found are appended to
+    /// `out` by increasing cost, together with their cost and a result of user-defined type.
+    pub fn k_best_first_search<'a, N, BFS: ?Sized, R>(&'a self,
+                                                      k:         usize,
+                                                      max_cost:  N,
+                                                      algorithm: &mut BFS,
+                                                      out:       &mut Vec<(&'a B, N, R)>)
+        where N:   Scalar,
+              BFS: BVTCostFn<N, B, BV, R> {
+        match self.tree {
+            Some(ref t) => t.k_best_first_search(k, max_cost, algorithm, out),
+            None        => { }
+        }
+    }
+
+    /// Finds every leaf with a cost smaller than `max_cost`.
+    ///
+    /// The leaves found are appended to `out` by increasing cost, together with their cost and a
+    /// result of user-defined type.
+    pub fn max_cost_search<'a, N, BFS: ?Sized, R>(&'a self,
+                                                  max_cost:  N,
+                                                  algorithm: &mut BFS,
+                                                  out:       &mut Vec<(&'a B, N, R)>)
+        where N:   Scalar,
+              BFS: BVTCostFn<N, B, BV, R> {
+        self.k_best_first_search(usize::MAX, max_cost, algorithm, out)
+    }
+
     /// Reference to the bounding volume of the tree root.
     pub fn root_bounding_volume<'r>(&'r self) -> Option<&'r BV> {
         match self.tree {
@@ -116,6 +163,47 @@ impl<N, V, B, BV> BVT<B, BV>
     }
 }
 
+#[old_impl_check]
+impl<N, B, BV> BVT<B, BV>
+    where N:  Scalar,
+          BV: BoundingVolume<N> {
+    /// Recomputes the bounding volumes of this tree, keeping its topology unchanged.
+    ///
+    /// The bounding volume of each leaf is given by `leaf_bv`. The bounding volumes of the internal
+    /// nodes are then updated bottom-up.
+    pub fn refit<F: FnMut(&B) -> BV>(&mut self, leaf_bv: &mut F) {
+        match self.tree {
+            Some(ref mut t) => t.refit(leaf_bv),
+            None            => { }
+        }
+    }
+}
+
+#[old_impl_check]
+impl<N, B, BV> BVT<B, BV>
+    where N:  Scalar,
+          BV: HasSurfaceArea<N> {
+    /// The Surface Area Heuristic cost of this tree.
+    ///
+    /// This is the sum of the surface areas of the internal nodes divided by the surface area of
+    /// the root. A lower cost means a faster traversal.
+    pub fn sah_cost(&self) -> N {
+        match self.tree {
+            Some(ref t) => {
+                let root_area = t.bounding_volume().surface_area();
+
+                if na::is_zero(&root_area) {
+                    na::zero()
+                }
+                else {
+                    t.internal_surface_area() / root_area
+                }
+            },
+            None => na::zero()
+        }
+    }
+}
+
 impl<B, BV> BVTNode<B, BV> {
     /// The bounding volume of this node.
     #[inline]
@@ -126,7 +214,34 @@ impl<B, BV> BVTNode<B, BV> {
         }
     }
 
-    fn visit<Vis: BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
+    fn refit<N, F: FnMut(&B) -> BV>(&mut self, leaf_bv: &mut F)
+        where N:  Scalar,
+              BV: BoundingVolume<N> {
+        match *self {
+            BVTNode::Internal(ref mut bv, ref mut left, ref mut right) => {
+                left.refit(leaf_bv);
+                right.refit(leaf_bv);
+
+                *bv = left.bounding_volume().merged(right.bounding_volume());
+            },
+            BVTNode::Leaf(ref mut bv, ref b) => {
+                *bv = leaf_bv(b);
+            }
+        }
+    }
+
+    fn internal_surface_area<N>(&self) -> N
+        where N:  Scalar,
+              BV: HasSurfaceArea<N> {
+        match *self {
+            BVTNode::Internal(ref bv, ref left, ref right) => {
+                bv.surface_area() + left.internal_surface_area() + right.internal_surface_area()
+            },
+            BVTNode::Leaf(_, _) => na::zero()
+        }
+    }
+
+    fn visit<Vis: ?Sized + BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
         match *self {
             BVTNode::Internal(ref bv, ref left, ref right) => {
                 if visitor.visit_internal(bv) {
@@ -168,7 +283,33 @@ impl<B, BV> BVTNode<B, BV> {
         }
     }
 
-    fn best_first_search<'a, N, BFS, R>(&'a self, algorithm: &mut BFS) -> Option<(&'a B, R)>
+    /// Appends the objects of the leaves of this subtree to `out`.
+    pub fn leaves<'a>(&'a self, out: &mut Vec<&'a B>) {
+        match *self {
+            BVTNode::Internal(_, ref left, ref right) => {
+                left.leaves(out);
+                right.leaves(out);
+            },
+            BVTNode::Leaf(_, ref b) => out.push(b)
+        }
+    }
+
+    fn visit_self_bvtt<Vis, F>(&self, visitor: &mut Vis, visit_self: &mut F)
+        where Vis: BVTTVisitor<B, BV>,
+              F:   FnMut(&BVTNode<B, BV>) -> bool {
+        match *self {
+            BVTNode::Internal(_, ref left, ref right) => {
+                if visit_self(self) {
+                    left.visit_self_bvtt(visitor, visit_self);
+                    right.visit_self_bvtt(visitor, visit_self);
+                    left.visit_bvtt(&**right, visitor);
+                }
+            },
+            BVTNode::Leaf(_, _) => { }
+        }
+    }
+
+    fn best_first_search<'a, N, BFS: ?Sized, R>(&'a self, algorithm: &mut BFS) -> Option<(&'a B, R)>
         where N:   Scalar,
               BFS: BVTCostFn<N, B, BV, R> {
         let mut queue: BinaryHeap<RefWithCost<'a, N, BVTNode<B, BV>>> = BinaryHeap::new();
@@ -227,6 +368,24 @@ impl<B, BV> BVTNode<B, BV> {
         result
     }
 
+    fn k_best_first_search<'a, N, BFS: ?Sized, R>(&'a self,
+                                                  k:         usize,
+                                                  max_cost:  N,
+                                                  algorithm: &mut BFS,
+                                                  out:       &mut Vec<(&'a B, N, R)>)
+        where N:   Scalar,
+              BFS: BVTCostFn<N, B, BV, R> {
+        let bv = |node: &'a BVTNode<B, BV>| node.bounding_volume();
+        let content = |node: &'a BVTNode<B, BV>| {
+            match *node {
+                BVTNode::Internal(_, ref left, ref right) => NodeContent::Internal(&**left, &**right),
+                BVTNode::Leaf(_, ref b)                   => NodeContent::Leaf(b)
+            }
+        };
+
+        k_best_first_search::k_best_first_search(self, k, max_cost, algorithm, &bv, &content, out)
+    }
+
     fn depth(&self) -> usize {
         match *self {
             BVTNode::Internal(_, ref left, ref right) => 1 + na::max(left.depth(), right.depth()),
@@ -332,3 +491,68 @@ fn __new_with_partitioner<B, BV, F: FnMut(usize, Vec<(B, BV)>) -> (BV, BinaryPar
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use na::Pnt3;
+    use bounding_volume::AABB;
+    use partitioning::{BVT, BVTTVisitor};
+
+    // Collects every pair of leaves visited.
+    struct PairCollector {
+        pairs: Vec<(usize, usize)>
+    }
+
+    impl BVTTVisitor<usize, AABB<Pnt3<f64>>> for PairCollector {
+        fn visit_internal_internal(&mut self, _: &AABB<Pnt3<f64>>, _: &AABB<Pnt3<f64>>) -> bool {
+            true
+        }
+
+        fn visit_leaf_leaf(&mut self, b1: &usize, _: &AABB<Pnt3<f64>>, b2: &usize, _: &AABB<Pnt3<f64>>) {
+            self.pairs.push(if *b1 < *b2 { (*b1, *b2) } else { (*b2, *b1) })
+        }
+
+        fn visit_internal_leaf(&mut self, _: &AABB<Pnt3<f64>>, _: &usize, _: &AABB<Pnt3<f64>>) -> bool {
+            true
+        }
+
+        fn visit_leaf_internal(&mut self, _: &usize, _: &AABB<Pnt3<f64>>, _: &AABB<Pnt3<f64>>) -> bool {
+            true
+        }
+    }
+
+    fn tree(n: usize) -> BVT<usize, AABB<Pnt3<f64>>> {
+        BVT::new_balanced((0 .. n).map(|i| {
+            let x = i as f64;
+            (i, AABB::new(Pnt3::new(x, 0.0, 0.0), Pnt3::new(x + 1.0, 1.0, 1.0)))
+        }).collect())
+    }
+
+    #[test]
+    fn test_visit_self_bvtt_visits_each_pair_once() {
+        let bvt           = tree(9);
+        let mut collector = PairCollector { pairs: Vec::new() };
+
+        bvt.visit_self_bvtt(&mut collector, &mut |_| true);
+
+        let mut pairs = collector.pairs.clone();
+        pairs.sort();
+        pairs.dedup();
+
+        assert!(collector.pairs.len() == 9 * 8 / 2);
+        assert!(pairs.len() == collector.pairs.len());
+    }
+
+    #[test]
+    fn test_visit_self_bvtt_pruning() {
+        let bvt           = tree(8);
+        let mut collector = PairCollector { pairs: Vec::new() };
+
+        // Skip the pairs of leaves of every subtree but the root.
+        let mut is_root = true;
+        bvt.visit_self_bvtt(&mut collector, &mut |_| { let res = is_root; is_root = false; res });
+
+        // Only the pairs made of one leaf of each half of the tree remain.
+        assert!(collector.pairs.len() == 4 * 4);
+    }
+}
@@ -0,0 +1,352 @@
+//! Construction functions for the BVT based on the Surface Area Heuristic.
+
+use std::cmp::Ordering;
+use na::{Translation, Bounded};
+use na;
+use partitioning::BinaryPartition;
+use bounding_volume::{BoundingVolume, HasSurfaceArea};
+use math::{Scalar, Vect};
+
+/// The default number of bins used by `binned_sah_partitioner`.
+pub const DEFAULT_NBINS: usize = 16;
+
+/// Construction function for a SAH-optimized BVT to be used with `BVT::new_with_partitioner`.
+///
+/// The leaves are sorted along each axis wrt. the center of their bounding volume, and the split
+/// minimizing the Surface Area Heuristic is selected among every possible positions. This gives
+/// high-quality trees but is significantly slower than `binned_sah_partitioner`.
+pub fn sah_partitioner<N, V, B, BV>(_: usize, leaves: Vec<(B, BV)>) -> (BV, BinaryPartition<B, BV>)
+    where N:  Scalar,
+          V:  Vect<N>,
+          BV: Translation<V> + BoundingVolume<N> + HasSurfaceArea<N> + Clone {
+    if leaves.len() == 0 {
+        panic!("Cannot build a tree without leaves.");
+    }
+    else if leaves.len() == 1 {
+        let (b, bv) = leaves.into_iter().next().unwrap();
+        return (bv, BinaryPartition::Part(b))
+    }
+
+    let mut leaves      = leaves;
+    let nleaves         = leaves.len();
+    let mut right_areas = vec![ na::zero::<N>(); nleaves ];
+    let mut best_cost   = Bounded::max_value();
+    let mut best_axis   = 0;
+    let mut best_split  = nleaves / 2;
+
+    for axis in 0 .. na::dim::<V>() {
+        sort_along_axis(&mut leaves[..], axis);
+
+        // Area of the bounding volume of the leaves on the right of each split position.
+        let mut right_bv = leaves[nleaves - 1].1.clone();
+
+        for i in (1 .. nleaves).rev() {
+            right_bv.merge(&leaves[i].1);
+            right_areas[i] = right_bv.surface_area();
+        }
+
+        let mut left_bv = leaves[0].1.clone();
+
+        for i in 1 .. nleaves {
+            let cost = sah_cost(left_bv.surface_area(), i, right_areas[i], nleaves - i);
+
+            if cost < best_cost {
+                best_cost  = cost;
+                best_axis  = axis;
+                best_split = i;
+            }
+
+            left_bv.merge(&leaves[i].1);
+        }
+    }
+
+    sort_along_axis(&mut leaves[..], best_axis);
+
+    split_at(leaves, best_split)
+}
+
+/// Construction function for a SAH-optimized BVT to be used with `BVT::new_with_partitioner`.
+///
+/// This is a binned approximation of `sah_partitioner` using `DEFAULT_NBINS` bins.
+pub fn binned_sah_partitioner<N, V, B, BV>(depth: usize, leaves: Vec<(B, BV)>) -> (BV, BinaryPartition<B, BV>)
+    where N:  Scalar,
+          V:  Vect<N>,
+          BV: Translation<V> + BoundingVolume<N> + HasSurfaceArea<N> + Clone {
+    binned_sah_partitioner_with_nbins(DEFAULT_NBINS, depth, leaves)
+}
+
+/// Construction function for a SAH-optimized BVT to be used with `BVT::new_with_partitioner`.
+///
+/// The leaves are dispatched into `nbins` bins of equal width along each axis, wrt. the center of
+/// their bounding volume. The split minimizing the Surface Area Heuristic is then selected among
+/// the bin boundaries only. This is much faster than `sah_partitioner` for large sets of leaves.
+pub fn binned_sah_partitioner_with_nbins<N, V, B, BV>(nbins:  usize,
+                                                      _:      usize,
+                                                      leaves: Vec<(B, BV)>)
+                                                      -> (BV, BinaryPartition<B, BV>)
+    where N:  Scalar,
+          V:  Vect<N>,
+          BV: Translation<V> + BoundingVolume<N> + HasSurfaceArea<N> + Clone {
+    assert!(nbins >= 2, "The binned SAH requires at least two bins.");
+
+    if leaves.len() == 0 {
+        panic!("Cannot build a tree without leaves.");
+    }
+    else if leaves.len() == 1 {
+        let (b, bv) = leaves.into_iter().next().unwrap();
+        return (bv, BinaryPartition::Part(b))
+    }
+
+    let nleaves         = leaves.len();
+    let centers: Vec<V> = leaves.iter().map(|l| l.1.translation()).collect();
+    let mut cmin        = centers[0];
+    let mut cmax        = centers[0];
+
+    for c in centers.iter() {
+        cmin = na::inf(&cmin, c);
+        cmax = na::sup(&cmax, c);
+    }
+
+    let mut best_cost  = Bounded::max_value();
+    let mut best_axis  = None;
+    let mut best_bin   = 0;
+    let mut right_area = vec![ na::zero::<N>(); nbins ];
+    let mut right_num  = vec![ 0; nbins ];
+
+    for axis in 0 .. na::dim::<V>() {
+        let extent = cmax[axis] - cmin[axis];
+
+        if extent <= na::zero() {
+            continue; // All the centers are on the same plane.
+        }
+
+        let mut bins: Vec<Option<BV>> = vec![ None; nbins ];
+        let mut counts                = vec![ 0; nbins ];
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            let bin_id = bin_index(centers[i][axis] - cmin[axis], extent, nbins);
+
+            counts[bin_id] += 1;
+
+            if bins[bin_id].is_none() {
+                bins[bin_id] = Some(leaf.1.clone());
+            }
+            else {
+                bins[bin_id].as_mut().unwrap().merge(&leaf.1);
+            }
+        }
+
+        // Area and number of leaves on the right of each bin boundary.
+        let mut right_bv: Option<BV> = None;
+        let mut num                  = 0;
+
+        for b in (1 .. nbins).rev() {
+            merge_optional(&mut right_bv, &bins[b]);
+            num          += counts[b];
+            right_num[b]  = num;
+            right_area[b] = right_bv.as_ref().map(|bv| bv.surface_area()).unwrap_or(na::zero());
+        }
+
+        let mut left_bv: Option<BV> = None;
+        let mut left_num            = 0;
+
+        for b in 1 .. nbins {
+            merge_optional(&mut left_bv, &bins[b - 1]);
+            left_num += counts[b - 1];
+
+            if left_num != 0 && right_num[b] != 0 {
+                let left_area = left_bv.as_ref().unwrap().surface_area();
+                let cost      = sah_cost(left_area, left_num, right_area[b], right_num[b]);
+
+                if cost < best_cost {
+                    best_cost = cost;
+                    best_axis = Some(axis);
+                    best_bin  = b;
+                }
+            }
+        }
+    }
+
+    match best_axis {
+        Some(axis) => {
+            let extent    = cmax[axis] - cmin[axis];
+            let mut bv    = leaves[0].1.clone();
+            let mut left  = Vec::new();
+            let mut right = Vec::new();
+
+            for (i, leaf) in leaves.into_iter().enumerate() {
+                bv.merge(&leaf.1);
+
+                if bin_index(centers[i][axis] - cmin[axis], extent, nbins) < best_bin {
+                    left.push(leaf);
+                }
+                else {
+                    right.push(leaf);
+                }
+            }
+
+            (bv, BinaryPartition::Parts(left, right))
+        },
+        None => {
+            // All the centers coincide: no split is better than another one.
+            split_at(leaves, nleaves / 2)
+        }
+    }
+}
+
+/// The SAH cost of a split, up to a constant factor.
+#[inline]
+fn sah_cost<N: Scalar>(left_area: N, left_num: usize, right_area: N, right_num: usize) -> N {
+    left_area * na::cast(left_num as f64) + right_area * na::cast(right_num as f64)
+}
+
+/// The index of the bin containing a center at `offset` from the smallest center.
+#[inline]
+fn bin_index<N: Scalar>(offset: N, extent: N, nbins: usize) -> usize {
+    let id = na::cast::<N, f64>(offset / extent * na::cast(nbins as f64)) as usize;
+
+    if id >= nbins { nbins - 1 } else { id }
+}
+
+#[inline]
+fn merge_optional<N, BV>(acc: &mut Option<BV>, bv: &Option<BV>)
+    where BV: BoundingVolume<N> + Clone {
+    if let Some(ref bv) = *bv {
+        if acc.is_none() {
+            *acc = Some(bv.clone());
+        }
+        else {
+            acc.as_mut().unwrap().merge(bv);
+        }
+    }
+}
+
+fn sort_along_axis<N, V, B, BV>(leaves: &mut [(B, BV)], axis: usize)
+    where N:  Scalar,
+          V:  Vect<N>,
+          BV: Translation<V> {
+    leaves.sort_by(|a, b| {
+        a.1.translation()[axis].partial_cmp(&b.1.translation()[axis]).unwrap_or(Ordering::Equal)
+    })
+}
+
+fn split_at<N, B, BV>(leaves: Vec<(B, BV)>, split: usize) -> (BV, BinaryPartition<B, BV>)
+    where BV: BoundingVolume<N> + Clone {
+    let mut bv    = leaves[0].1.clone();
+    let mut left  = Vec::with_capacity(split);
+    let mut right = Vec::with_capacity(leaves.len() - split);
+
+    for (i, leaf) in leaves.into_iter().enumerate() {
+        bv.merge(&leaf.1);
+
+        if i < split {
+            left.push(leaf);
+        }
+        else {
+            right.push(leaf);
+        }
+    }
+
+    (bv, BinaryPartition::Parts(left, right))
+}
+
+#[cfg(test)]
+mod test {
+    use na::Pnt3;
+    use partitioning::{BVT, BinaryPartition, BoundingVolumeInterferencesCollector, sah_partitioner,
+                       binned_sah_partitioner};
+    use bounding_volume::AABB;
+
+    // Six small boxes around the origin and two far on the `x` axis: a median split would cut the
+    // first cluster while the SAH isolates both clusters.
+    fn uneven_clusters() -> Vec<(usize, AABB<Pnt3<f64>>)> {
+        let mut res = Vec::new();
+
+        for i in 0usize .. 6 {
+            let x = i as f64 * 0.1;
+            res.push((i, AABB::new(Pnt3::new(x, 0.0, 0.0), Pnt3::new(x + 0.1, 1.0, 1.0))));
+        }
+
+        for i in 6usize .. 8 {
+            let x = 100.0 + i as f64 * 0.1;
+            res.push((i, AABB::new(Pnt3::new(x, 0.0, 0.0), Pnt3::new(x + 0.1, 1.0, 1.0))));
+        }
+
+        res
+    }
+
+    fn assert_separates_clusters(part: BinaryPartition<usize, AABB<Pnt3<f64>>>) {
+        match part {
+            BinaryPartition::Parts(left, right) => {
+                let (small, large) = if left.len() < right.len() { (left, right) } else { (right, left) };
+
+                assert!(small.len() == 2 && large.len() == 6);
+                assert!(small.iter().all(|l| l.0 >= 6));
+                assert!(large.iter().all(|l| l.0 < 6));
+            },
+            BinaryPartition::Part(_) => panic!("Several leaves must be split.")
+        }
+    }
+
+    #[test]
+    fn test_sah_separates_uneven_clusters() {
+        let (bv, part) = sah_partitioner(0, uneven_clusters());
+
+        assert!(*bv.mins() == Pnt3::new(0.0, 0.0, 0.0));
+        assert_separates_clusters(part);
+    }
+
+    #[test]
+    fn test_binned_sah_separates_uneven_clusters() {
+        let (_, part) = binned_sah_partitioner(0, uneven_clusters());
+
+        assert_separates_clusters(part);
+    }
+
+    #[test]
+    fn test_binned_sah_coincident_centers() {
+        let leaves: Vec<_> = (0usize .. 4).map(|i| {
+            let r = (i + 1) as f64;
+            (i, AABB::new(Pnt3::new(-r, -r, -r), Pnt3::new(r, r, r)))
+        }).collect();
+
+        match binned_sah_partitioner(0, leaves).1 {
+            BinaryPartition::Parts(left, right) => assert!(left.len() == 2 && right.len() == 2),
+            BinaryPartition::Part(_) => panic!("Several leaves must be split.")
+        }
+    }
+
+    #[test]
+    fn test_single_leaf() {
+        let leaf = (42usize, AABB::new(Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(1.0, 1.0, 1.0)));
+
+        match sah_partitioner(0, vec!(leaf.clone())).1 {
+            BinaryPartition::Part(b) => assert!(b == 42),
+            BinaryPartition::Parts(_, _) => panic!("A single leaf must not be split.")
+        }
+
+        match binned_sah_partitioner(0, vec!(leaf)).1 {
+            BinaryPartition::Part(b) => assert!(b == 42),
+            BinaryPartition::Parts(_, _) => panic!("A single leaf must not be split.")
+        }
+    }
+
+    #[test]
+    fn test_sah_trees_find_every_leaf() {
+        let sah    = BVT::new_with_partitioner(uneven_clusters(), &mut sah_partitioner);
+        let binned = BVT::new_with_partitioner(uneven_clusters(), &mut binned_sah_partitioner);
+
+        for tree in [ sah, binned ].iter() {
+            for (i, bv) in uneven_clusters().into_iter() {
+                let mut found = Vec::new();
+
+                {
+                    let mut visitor = BoundingVolumeInterferencesCollector::new(&bv, &mut found);
+                    tree.visit(&mut visitor);
+                }
+
+                assert!(found.contains(&i));
+            }
+        }
+    }
+}
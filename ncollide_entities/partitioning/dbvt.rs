@@ -1,468 +1,521 @@
 //! A Dynamic Bounding Volume Tree.
 
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::ptr;
+use std::usize;
 use std::mem;
-use utils::data::owned_allocation_cache::OwnedAllocationCache;
-use na::{FloatVec, Translation};
 use na;
-use bounding_volume::BoundingVolume;
+use bounding_volume::{BoundingVolume, HasSurfaceArea};
 use partitioning::bvt_visitor::BVTVisitor;
-use math::{Scalar, Point};
+use partitioning::bvt_cost_fn::BVTCostFn;
+use partitioning::k_best_first_search::{self, NodeContent};
+use math::Scalar;
 
 
-#[derive(RustcEncodable, RustcDecodable)]
-enum UpdateState {
-    NeedsShrink,
-    UpToDate
+/// Index of the missing nodes, e.g., the parent of the root.
+const NULL: usize = usize::MAX;
+
+/// The identifier of a leaf of a Dynamic Bounding Volume Tree.
+///
+/// It remains valid until the leaf is removed from its tree. The identifier of a removed leaf is
+/// never valid again, even if its node is recycled by a later insertion.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
+pub struct DBVTLeafId(usize, usize);
+
+impl DBVTLeafId {
+    /// The index of this leaf on the node arena of its tree.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+enum DBVTNodeState<B> {
+    /// An internal node with the indices of its two children.
+    Internal(usize, usize),
+    /// A leaf with its user-defined object.
+    Leaf(B),
+    /// A node on the free list.
+    Free
 }
 
-type Cache<P, B, BV> = OwnedAllocationCache<DBVTInternal<P, B, BV>>;
+/// Node of the Dynamic Bounding Volume Tree.
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+struct DBVTNode<B, BV> {
+    /// The bounding volume of this node. It always encloses both its children bounding volumes.
+    bounding_volume: BV,
+    /// The index of this node parent.
+    parent:          usize,
+    /// The number of times this node has been released.
+    generation:      usize,
+    state:           DBVTNodeState<B>
+}
 
 /// A Dynamic Bounding Volume Tree.
-pub struct DBVT<P, B, BV> {
-    cache: Cache<P, B, BV>,
-    tree:  Option<DBVTNode<P, B, BV>>,
+///
+/// The nodes are stored on an arena and refer to each other by index. The nodes of removed leaves
+/// are recycled by the next insertions.
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct DBVT<B, BV> {
+    nodes: Vec<DBVTNode<B, BV>>,
+    free:  Vec<usize>,
+    root:  usize,
     len:   usize
 }
 
-impl<P, B, BV> DBVT<P, B, BV> {
+impl<B, BV> DBVT<B, BV> {
     /// Creates a new Dynamic Bounding Volume Tree.
-    pub fn new() -> DBVT<P, B, BV> {
+    pub fn new() -> DBVT<B, BV> {
         DBVT {
-            cache: OwnedAllocationCache::new(),
-            tree:  None,
+            nodes: Vec::new(),
+            free:  Vec::new(),
+            root:  NULL,
             len:   0
         }
     }
-}
-
-#[old_impl_check]
-impl<N, P, V, B, BV> DBVT<P, B, BV>
-    where N: Scalar,
-          P:  Point<N, V>,
-          V:  FloatVec<N>,
-          BV: 'static + BoundingVolume<N> + Translation<V> + Clone,
-          B:  'static + Clone {
-    /// Removes a leaf from the tree. Fails if the tree is empty.
-    pub fn remove(&mut self, leaf: &mut Rc<RefCell<DBVTLeaf<P, B, BV>>>) {
-        let self_tree = self.tree.take().expect("This tree was empty.");
 
-        let mut bleaf = (*leaf).borrow_mut();
-        self.tree = bleaf.unlink(&mut self.cache, self_tree);
-        self.len  = self.len - 1;
+    /// The number of leaves of this tree.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
     }
 
-    // FIXME: it feels strange that this method takes (B, BV) in this order while the leaves
-    // constructor takes (BV, B)…
-    /// Creates, inserts, and returns a new leaf with the given content.
-    pub fn insert_new(&mut self, b: B, bv: BV) -> Rc<RefCell<DBVTLeaf<P, B, BV>>> {
-        let leaf = Rc::new(RefCell::new(DBVTLeaf::new(bv, b)));
-
-        self.insert(leaf.clone());
-
-        leaf
+    /// Whether this tree has no leaf.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    /// Inserts a leaf to the tree.
-    pub fn insert(&mut self, leaf: Rc<RefCell<DBVTLeaf<P, B, BV>>>) {
-        let mut self_tree = None;
-        mem::swap(&mut self_tree, &mut self.tree);
+    /// Whether `leaf` identifies a leaf of this tree.
+    #[inline]
+    pub fn contains(&self, leaf: DBVTLeafId) -> bool {
+        leaf.0 < self.nodes.len() && self.nodes[leaf.0].generation == leaf.1 && self.is_leaf(leaf.0)
+    }
 
-        self.tree = match self_tree {
-            None    => {
-                leaf.borrow_mut().parent = DBVTLeafState::Root;
-                Some(DBVTNode::Leaf(leaf))
-            },
-            Some(t) => Some(DBVTNode::Internal(t.insert(&mut self.cache, leaf)))
-        };
+    /// The object attached to a leaf.
+    #[inline]
+    pub fn object(&self, leaf: DBVTLeafId) -> &B {
+        assert!(self.contains(leaf), "Invalid DBVT leaf identifier.");
 
-        self.len = self.len + 1;
+        match self.nodes[leaf.0].state {
+            DBVTNodeState::Leaf(ref b) => b,
+            _ => panic!("Invalid DBVT leaf identifier.")
+        }
     }
 
-    /// Visit this tree using… a visitor!
-    pub fn visit<Vis: BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
-        match self.tree {
-            Some(ref t) => t.visit(visitor),
-            None        => { }
+    /// A mutable reference to the object attached to a leaf.
+    #[inline]
+    pub fn object_mut(&mut self, leaf: DBVTLeafId) -> &mut B {
+        assert!(self.contains(leaf), "Invalid DBVT leaf identifier.");
+
+        match self.nodes[leaf.0].state {
+            DBVTNodeState::Leaf(ref mut b) => b,
+            _ => panic!("Invalid DBVT leaf identifier.")
         }
     }
-}
 
-/// Node of the Dynamic Bounding Volume Tree.
-enum DBVTNode<P, B, BV> {
-    Internal(Box<DBVTInternal<P, B, BV>>),
-    Leaf(Rc<RefCell<DBVTLeaf<P, B, BV>>>),
-    Invalid
-}
+    /// The bounding volume of a leaf.
+    #[inline]
+    pub fn bounding_volume(&self, leaf: DBVTLeafId) -> &BV {
+        assert!(self.contains(leaf), "Invalid DBVT leaf identifier.");
 
-/// Internal node of a DBVT. An internal node always has two children.
-struct DBVTInternal<P, B, BV> {
-    /// The bounding volume of this node. It always encloses both its children bounding volumes.
-    bounding_volume: BV,
-    /// The center of this node bounding volume.
-    center:          P,
-    /// This node left child.
-    left:            DBVTNode<P, B, BV>,
-    /// This node right child.
-    right:           DBVTNode<P, B, BV>,
-    /// This node parent.
-    parent:          *mut DBVTInternal<P, B, BV>,
-
-    state:           UpdateState
-}
+        &self.nodes[leaf.0].bounding_volume
+    }
 
-#[old_impl_check]
-impl<N, P: Point<N, V>, V, BV: Translation<V>, B> DBVTInternal<P, B, BV> {
-    /// Creates a new internal node.
-    fn new(bounding_volume: BV,
-           parent:          *mut DBVTInternal<P, B, BV>,
-           left:            DBVTNode<P, B, BV>,
-           right:           DBVTNode<P, B, BV>)
-           -> DBVTInternal<P, B, BV> {
-        DBVTInternal {
-            center:          na::orig::<P>() + bounding_volume.translation(),
-            bounding_volume: bounding_volume,
-            left:            left,
-            right:           right,
-            parent:          parent,
-            state:           UpdateState::UpToDate
+    /// Visit this tree using… a visitor!
+    pub fn visit<Vis: ?Sized + BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
+        if self.root != NULL {
+            self.visit_node(self.root, visitor)
         }
     }
-}
 
-#[allow(raw_pointer_derive)]
-#[derive(Clone)]
-/// State of a leaf.
-enum DBVTLeafState<P, B, BV> {
-    /// This leaf is the root of a tree.
-    Root,
-    /// This leaf is the right child of another node.
-    RightChildOf(*mut DBVTInternal<P, B, BV>),
-    /// This leaf is the left child of another node.
-    LeftChildOf(*mut DBVTInternal<P, B, BV>),
-    /// This leaf is detached from any tree.
-    Detached
-}
+    fn visit_node<Vis: ?Sized + BVTVisitor<B, BV>>(&self, i: usize, visitor: &mut Vis) {
+        let node = &self.nodes[i];
 
-impl<P, B, BV> DBVTLeafState<P, B, BV> {
-    /// Indicates whether this leaf is the root.
-    #[inline]
-    pub fn is_root(&self) -> bool {
-        match *self {
-            DBVTLeafState::Root => true,
-            _ => false
+        match node.state {
+            DBVTNodeState::Internal(left, right) => {
+                if visitor.visit_internal(&node.bounding_volume) {
+                    self.visit_node(left, visitor);
+                    self.visit_node(right, visitor);
+                }
+            },
+            DBVTNodeState::Leaf(ref b) => visitor.visit_leaf(b, &node.bounding_volume),
+            DBVTNodeState::Free        => unreachable!()
         }
     }
 
-    /// Indicates whether this leaf is detached.
     #[inline]
-    pub fn is_detached(&self) -> bool {
-        match *self {
-            DBVTLeafState::Detached => true,
-            _ => false
+    fn is_leaf(&self, i: usize) -> bool {
+        match self.nodes[i].state {
+            DBVTNodeState::Leaf(_) => true,
+            _                      => false
         }
     }
 
-    /// Returns a pointer to this leaf parent and `true` if it is the left child.
     #[inline]
-    fn unwrap(self) -> (bool, *mut DBVTInternal<P, B, BV>) {
-        match self {
-            DBVTLeafState::RightChildOf(p) => (false, p),
-            DBVTLeafState::LeftChildOf(p)  => (true, p),
-            _ => panic!("Attempting to unwrap a root or detached node.")
+    fn children(&self, i: usize) -> Option<(usize, usize)> {
+        match self.nodes[i].state {
+            DBVTNodeState::Internal(left, right) => Some((left, right)),
+            _                                    => None
         }
     }
-}
 
-/// Leaf of a Dynamic Bounding Volume Tree.
-#[derive(Clone)]
-pub struct DBVTLeaf<P, B, BV> {
-    /// The bounding volume of this node.
-    pub bounding_volume: BV,
-    /// The center of this node bounding volume.
-    pub center:          P,
-    /// An user-defined object.
-    pub object:          B,
-    /// This node parent.
-    parent:              DBVTLeafState<P, B, BV>
-}
+    fn alloc(&mut self, node: DBVTNode<B, BV>) -> usize {
+        let mut node = node;
 
-impl<P, B, BV> DBVTNode<P, B, BV> {
-    fn take_internal(self) -> Box<DBVTInternal<P, B, BV>> {
-        match self {
-            DBVTNode::Internal(i) => i,
-            _ => panic!("DBVT internal error: this is not an internal node.")
+        match self.free.pop() {
+            Some(i) => {
+                node.generation = self.nodes[i].generation;
+                self.nodes[i]   = node;
+                i
+            },
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
         }
     }
 
-    fn invalidate(&mut self) -> DBVTNode<P, B, BV> {
-        let mut res = DBVTNode::Invalid;
+    fn release(&mut self, i: usize) -> DBVTNodeState<B> {
+        self.free.push(i);
+        self.nodes[i].generation = self.nodes[i].generation + 1;
 
-        mem::swap(&mut res, self);
+        mem::replace(&mut self.nodes[i].state, DBVTNodeState::Free)
+    }
 
-        res
+    /// Replaces the child `old` of the internal node `parent` by `new`.
+    fn set_child(&mut self, parent: usize, old: usize, new: usize) {
+        match self.nodes[parent].state {
+            DBVTNodeState::Internal(ref mut left, ref mut right) => {
+                if *left == old {
+                    *left = new
+                }
+                else {
+                    *right = new
+                }
+            },
+            _ => unreachable!()
+        }
     }
-}
 
-impl<P, B, BV> DBVTInternal<P, B, BV> {
-    fn is_right_internal_node(&self, r: &mut DBVTInternal<P, B, BV>) -> bool
-    {
-        match self.right {
-            DBVTNode::Internal(ref i) => &**i as *const DBVTInternal<P, B, BV> == &*r as *const DBVTInternal<P, B, BV>,
-            _ => false
+    /// Replaces the child `old` of `parent` by `new`, and updates the parent of `new`.
+    ///
+    /// If `parent` is `NULL`, `new` becomes the root.
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if parent == NULL {
+            self.root = new;
+        }
+        else {
+            self.set_child(parent, old, new);
         }
+
+        self.nodes[new].parent = parent;
     }
 }
 
 #[old_impl_check]
-impl<N, P: Point<N, V>, V, B: 'static, BV: Translation<V> + 'static> DBVTLeaf<P, B, BV> {
-    /// Creates a new leaf.
-    pub fn new(bounding_volume: BV, object: B) -> DBVTLeaf<P, B, BV> {
-        DBVTLeaf {
-            center:          na::orig::<P>() + bounding_volume.translation(),
-            bounding_volume: bounding_volume,
-            object:          object,
-            parent:          DBVTLeafState::Detached
-        }
+impl<N, B, BV> DBVT<B, BV>
+    where N:  Scalar,
+          BV: BoundingVolume<N> + HasSurfaceArea<N> + Clone {
+    /// Creates and inserts a new leaf with the given content and returns its identifier.
+    pub fn insert(&mut self, b: B, bv: BV) -> DBVTLeafId {
+        let leaf = self.alloc(DBVTNode {
+            bounding_volume: bv,
+            parent:          NULL,
+            generation:      0,
+            state:           DBVTNodeState::Leaf(b)
+        });
+
+        self.insert_leaf(leaf);
+        self.len = self.len + 1;
+
+        DBVTLeafId(leaf, self.nodes[leaf].generation)
     }
 
-    /// Tests if this node is the root.
-    pub fn is_root(&self) -> bool {
-        self.parent.is_root()
+    /// Removes a leaf from the tree and returns its content.
+    pub fn remove(&mut self, leaf: DBVTLeafId) -> B {
+        assert!(self.contains(leaf), "Invalid DBVT leaf identifier.");
+
+        self.detach_leaf(leaf.0);
+        self.len = self.len - 1;
+
+        match self.release(leaf.0) {
+            DBVTNodeState::Leaf(b) => b,
+            _                      => unreachable!()
+        }
     }
 
-    /// Tests if this node has no parent.
-    pub fn is_detached(&self) -> bool {
-        self.parent.is_detached()
+    /// Modifies the bounding volume of a leaf.
+    ///
+    /// The leaf is re-inserted on the tree and keeps the same identifier.
+    pub fn set_bounding_volume(&mut self, leaf: DBVTLeafId, bv: BV) {
+        assert!(self.contains(leaf), "Invalid DBVT leaf identifier.");
+
+        self.detach_leaf(leaf.0);
+        self.nodes[leaf.0].bounding_volume = bv;
+        self.insert_leaf(leaf.0);
     }
 
-    /// Removes this leaf from the tree.
+    /// Performs a best-first search on the tree to find the `k` objects with the smallest cost.
     ///
-    /// Returns the new root of the tree.
+    /// Objects with a cost greater than `max_cost` are ignored. The objects found are appended to
+    /// `out` by increasing cost, together with their cost and a result of user-defined type.
+    pub fn k_best_first_search<'a, BFS, R>(&'a self,
+                                           k:         usize,
+                                           max_cost:  N,
+                                           algorithm: &mut BFS,
+                                           out:       &mut Vec<(B, N, R)>)
+        where BFS: BVTCostFn<N, B, BV, R>,
+              B:   Clone {
+        if self.root == NULL {
+            return;
+        }
+
+        let nodes   = &self.nodes[..];
+        let bv      = |node: &'a DBVTNode<B, BV>| &node.bounding_volume;
+        let content = |node: &'a DBVTNode<B, BV>| {
+            match node.state {
+                DBVTNodeState::Internal(left, right) => NodeContent::Internal(&nodes[left], &nodes[right]),
+                DBVTNodeState::Leaf(ref b)           => NodeContent::Leaf(b),
+                DBVTNodeState::Free                  => unreachable!()
+            }
+        };
+        let mut found = Vec::new();
+
+        k_best_first_search::k_best_first_search(&self.nodes[self.root], k, max_cost, algorithm,
+                                                 &bv, &content, &mut found);
+
+        out.extend(found.into_iter().map(|(b, cost, res)| (b.clone(), cost, res)))
+    }
+
+    /// Finds every object with a cost smaller than `max_cost`.
     ///
-    /// # Arguments:
-    /// * `curr_root`: current root of the tree.
-    fn unlink(&mut self,
-              cache:     &mut Cache<P, B, BV>,
-              curr_root: DBVTNode<P, B, BV>) -> Option<DBVTNode<P, B, BV>> {
-        if !self.is_root() {
-            let (is_left, p) = mem::replace(&mut self.parent, DBVTLeafState::Detached).unwrap();
-
-            let pp           = unsafe { (*p).parent };
-            let parent_left  = unsafe { (*p).left.invalidate() };
-            let parent_right = unsafe { (*p).right.invalidate() };
-
-            let mut other = if is_left { parent_right } else { parent_left };
-
-            if !pp.is_null() {
-                let is_p_right_to_pp = unsafe { (*pp).is_right_internal_node(&mut *p) };
-                // we are far away from the root
-                unsafe {
-                    match other {
-                        DBVTNode::Internal(ref mut i) => i.parent = pp,
-                        DBVTNode::Leaf(ref mut l)     => {
-                            (**l).borrow_mut().parent =
-                                if is_p_right_to_pp { DBVTLeafState::RightChildOf(pp) }
-                                else { DBVTLeafState::LeftChildOf(pp) }
-                        },
-                        DBVTNode::Invalid => unreachable!()
-                    }
+    /// The objects found are appended to `out` by increasing cost, together with their cost and a
+    /// result of user-defined type.
+    pub fn max_cost_search<BFS, R>(&self, max_cost: N, algorithm: &mut BFS, out: &mut Vec<(B, N, R)>)
+        where BFS: BVTCostFn<N, B, BV, R>,
+              B:   Clone {
+        self.k_best_first_search(usize::MAX, max_cost, algorithm, out)
+    }
 
-                    if is_p_right_to_pp {
-                        mem::swap(&mut (*pp).right, &mut other);
-                        // NOTE: the children have already been invalidated before
-                        cache.retain(other.take_internal())
-                    }
-                    else {
-                        mem::swap(&mut (*pp).left, &mut other);
-                        // NOTE: the children have already been invalidated before
-                        cache.retain(other.take_internal())
-                    }
+    /// Inserts on the tree a leaf node which is already on the arena.
+    fn insert_leaf(&mut self, leaf: usize) {
+        if self.root == NULL {
+            self.root = leaf;
+            self.nodes[leaf].parent = NULL;
 
-                    (*pp).state = UpdateState::NeedsShrink;
-                }
+            return;
+        }
 
-                Some(curr_root)
-            }
-            else {
-                // the root changes to the other child
-                match other {
-                    DBVTNode::Internal(ref mut i) => i.parent = ptr::null_mut(),
-                    DBVTNode::Leaf(ref l)         => (**l).borrow_mut().parent = DBVTLeafState::Root,
-                    DBVTNode::Invalid             => unreachable!()
-                }
+        let sibling    = self.find_best_sibling(leaf);
+        let old_parent = self.nodes[sibling].parent;
+        let bv         = self.nodes[sibling].bounding_volume.merged(&self.nodes[leaf].bounding_volume);
+        let new_parent = self.alloc(DBVTNode {
+            bounding_volume: bv,
+            parent:          old_parent,
+            generation:      0,
+            state:           DBVTNodeState::Internal(sibling, leaf)
+        });
+
+        self.replace_child(old_parent, sibling, new_parent);
+        self.nodes[sibling].parent = new_parent;
+        self.nodes[leaf].parent    = new_parent;
+
+        self.refit_ancestors(old_parent);
+    }
+
+    /// Removes a leaf from the tree structure without releasing its node.
+    fn detach_leaf(&mut self, leaf: usize) {
+        if leaf == self.root {
+            self.root = NULL;
+
+            return;
+        }
 
-                Some(other)
+        let parent        = self.nodes[leaf].parent;
+        let grand_parent  = self.nodes[parent].parent;
+        let (left, right) = self.children(parent).unwrap();
+        let sibling       = if left == leaf { right } else { left };
+
+        self.replace_child(grand_parent, parent, sibling);
+        let _ = self.release(parent);
+        self.nodes[leaf].parent = NULL;
+
+        self.refit_ancestors(grand_parent);
+    }
+
+    /// Finds the node which, paired with `leaf`, increases the least the surface area of the tree.
+    fn find_best_sibling(&self, leaf: usize) -> usize {
+        let leaf_bv  = &self.nodes[leaf].bounding_volume;
+        let _2: N    = na::cast(2.0f64);
+        let mut curr = self.root;
+
+        loop {
+            match self.children(curr) {
+                Some((left, right)) => {
+                    let area          = self.nodes[curr].bounding_volume.surface_area();
+                    let combined_area = self.nodes[curr].bounding_volume.merged(leaf_bv).surface_area();
+
+                    // Cost of creating a new parent for this node and the new leaf.
+                    let cost = combined_area * _2;
+                    // Minimum cost of pushing the leaf further down the tree.
+                    let inheritance_cost = (combined_area - area) * _2;
+
+                    let left_cost  = self.descent_cost(left, leaf_bv) + inheritance_cost;
+                    let right_cost = self.descent_cost(right, leaf_bv) + inheritance_cost;
+
+                    if cost < left_cost && cost < right_cost {
+                        return curr;
+                    }
+
+                    curr = if left_cost < right_cost { left } else { right };
+                },
+                None => return curr
             }
         }
-        else {
-            self.parent = DBVTLeafState::Detached;
+    }
 
-            // the tree becomes empty
-            None
+    /// The cost of descending into `node` to insert a leaf with the bounding volume `leaf_bv`.
+    fn descent_cost(&self, node: usize, leaf_bv: &BV) -> N {
+        let bv     = &self.nodes[node].bounding_volume;
+        let merged = bv.merged(leaf_bv).surface_area();
+
+        if self.is_leaf(node) {
+            merged
+        }
+        else {
+            merged - bv.surface_area()
         }
     }
-}
 
-#[old_impl_check]
-impl<N, P, V, BV, B> DBVTNode<P, B, BV>
-    where N: Scalar,
-          P:  Point<N, V>,
-          V:  FloatVec<N>,
-          BV: 'static + BoundingVolume<N>,
-          B: 'static {
-    fn sqdist_to(&self, to: &P) -> N {
-        match *self {
-            DBVTNode::Internal(ref i) => na::sqdist(&i.center, to),
-            DBVTNode::Leaf(ref l)     => {
-                let bl = l.borrow();
-                na::sqdist(&bl.center, to)
-            },
-            DBVTNode::Invalid => unreachable!()
+    /// Refits and rotates every node from `start` to the root.
+    fn refit_ancestors(&mut self, start: usize) {
+        let mut curr = start;
+
+        while curr != NULL {
+            self.refit(curr);
+            self.rotate(curr);
+
+            curr = self.nodes[curr].parent;
         }
     }
-}
 
-#[old_impl_check]
-impl<N, P, V, B, BV> DBVTInternal<P, B, BV>
-    where N: Scalar,
-          P:  Point<N, V>,
-          V:  FloatVec<N>,
-          BV: 'static + Translation<V> + BoundingVolume<N>,
-          B:  'static {
-    fn is_closest_to_left(&self, pt: &P) -> bool {
-        self.right.sqdist_to(pt) > self.left.sqdist_to(pt)
+    fn refit(&mut self, node: usize) {
+        let (left, right) = self.children(node).unwrap();
+        let bv            = self.nodes[left].bounding_volume.merged(&self.nodes[right].bounding_volume);
+
+        self.nodes[node].bounding_volume = bv;
     }
-}
 
-#[old_impl_check]
-impl<N, P, V, BV, B> DBVTNode<P, B, BV>
-    where N: Scalar,
-          P:  Point<N, V>,
-          V:  FloatVec<N>,
-          BV: 'static + Translation<V> + BoundingVolume<N>,
-          B:  'static {
-    /// Inserts a new leaf on this tree.
-    fn insert(self,
-              cache:     &mut Cache<P, B, BV>,
-              to_insert: Rc<RefCell<DBVTLeaf<P, B, BV>>>)
-              -> Box<DBVTInternal<P, B, BV>> {
-        assert!(to_insert.borrow().is_detached(), "Cannot insert the same node twice.");
-
-        let mut bto_insert = (*to_insert).borrow_mut();
-        let pto_insert     = &mut *bto_insert;
-
-        match self {
-            DBVTNode::Internal(i) => {
-                /*
-                 * NOTE: the insersion is done with unsafe pointers.
-                 * This is so because using &mut references dont seem to be possible since we have
-                 * to take successive references to nodes contents.
-                 */
-
-                let mut mut_internal = i;
-                let mut parent       = &mut *mut_internal as *mut DBVTInternal<P, B, BV>;
-
-                unsafe {
-                    (*parent).bounding_volume.merge(&pto_insert.bounding_volume);
-
-                    // iteratively go to the leaves
-                    let mut curr;
-                    let mut left;
-
-                    if (*parent).is_closest_to_left(&pto_insert.center) {
-                        curr = &mut (*parent).left as *mut DBVTNode<P, B, BV>;
-                        left = true;
-                    }
-                    else {
-                        curr = &mut (*parent).right as *mut DBVTNode<P, B, BV>;
-                        left = false;
-                    }
+    /// Swaps a child of `node` with a grandchild if it decreases the surface area of the tree.
+    fn rotate(&mut self, node: usize) {
+        let (left, right) = self.children(node).unwrap();
+        let mut best_gain = na::zero::<N>();
+        let mut best      = None;
 
-                    loop {
-                        match *curr {
-                            DBVTNode::Internal(ref mut ci) => {
-                                // FIXME: we could avoid the systematic merge
-                                ci.bounding_volume.merge(&pto_insert.bounding_volume);
-
-                                if ci.is_closest_to_left(&pto_insert.center) { // FIXME
-                                    curr = &mut ci.left as *mut DBVTNode<P, B, BV>;
-                                    left = true;
-                                }
-                                else {
-                                    curr = &mut ci.right as *mut DBVTNode<P, B, BV>;
-                                    left = false;
-                                }
-
-                                parent = &mut **ci as *mut DBVTInternal<P, B, BV>;
-                            },
-                            DBVTNode::Leaf(ref l) => {
-                                let mut bl       = (**l).borrow_mut();
-                                let     pl       = &mut *bl;
-                                let mut internal = cache.alloc(DBVTInternal::new(
-                                    pl.bounding_volume.merged(&pto_insert.bounding_volume),
-                                    parent,
-                                    DBVTNode::Leaf(l.clone()),
-                                    DBVTNode::Leaf(to_insert.clone())));
-
-                                pl.parent = DBVTLeafState::LeftChildOf(&mut *internal as *mut DBVTInternal<P, B, BV>);
-                                pto_insert.parent =
-                                    DBVTLeafState::RightChildOf(&mut *internal as *mut DBVTInternal<P, B, BV>);
-
-                                if left {
-                                    (*parent).left = DBVTNode::Internal(internal)
-                                }
-                                else {
-                                    (*parent).right = DBVTNode::Internal(internal)
-                                }
-
-                                break;
-                            },
-                            DBVTNode::Invalid => unreachable!()
-                        }
+        for &(uncle, child) in [ (left, right), (right, left) ].iter() {
+            if let Some((a, b)) = self.children(child) {
+                let child_area = self.nodes[child].bounding_volume.surface_area();
+
+                // Swapping `uncle` with `grandchild` turns `child` into `uncle` + `other`.
+                for &(grandchild, other) in [ (a, b), (b, a) ].iter() {
+                    let bv   = self.nodes[uncle].bounding_volume.merged(&self.nodes[other].bounding_volume);
+                    let gain = child_area - bv.surface_area();
+
+                    if gain > best_gain {
+                        best_gain = gain;
+                        best      = Some((uncle, grandchild, child));
                     }
                 }
+            }
+        }
 
-                mut_internal
-            },
-            DBVTNode::Leaf(l) => {
-                let     cl = l.clone();
-                let mut bl = (*cl).borrow_mut();
-                let     pl = &mut *bl;
-
-                // create the root
-                let mut root = cache.alloc(DBVTInternal::new(
-                    pl.bounding_volume.merged(&pto_insert.bounding_volume),
-                    ptr::null_mut(),
-                    DBVTNode::Leaf(l),
-                    DBVTNode::Leaf(to_insert.clone())));
-
-                pl.parent = DBVTLeafState::LeftChildOf(&mut *root as *mut DBVTInternal<P, B, BV>);
-                pto_insert.parent = DBVTLeafState::RightChildOf(&mut *root as *mut DBVTInternal<P, B, BV>);
-
-                root
-            },
-            DBVTNode::Invalid => unreachable!()
+        if let Some((uncle, grandchild, child)) = best {
+            self.set_child(node, uncle, grandchild);
+            self.set_child(child, grandchild, uncle);
+            self.nodes[grandchild].parent = node;
+            self.nodes[uncle].parent      = child;
+
+            self.refit(child);
         }
     }
+}
 
-    fn visit<Vis: BVTVisitor<B, BV>>(&self, visitor: &mut Vis) {
-        match *self {
-            DBVTNode::Internal(ref i) => {
-                if visitor.visit_internal(&i.bounding_volume) {
-                    i.left.visit(visitor);
-                    i.right.visit(visitor);
-                }
-            },
-            DBVTNode::Leaf(ref l) => {
-                let bl = l.borrow();
-                visitor.visit_leaf(&bl.object, &bl.bounding_volume)
-            },
-            DBVTNode::Invalid => unreachable!()
+#[cfg(test)]
+mod test {
+    use na::Pnt3;
+    use bounding_volume::AABB;
+    use partitioning::{BoundingVolumeInterferencesCollector, DBVT};
+
+    fn unit_aabb(x: f64) -> AABB<Pnt3<f64>> {
+        AABB::new(Pnt3::new(x, 0.0, 0.0), Pnt3::new(x + 1.0, 1.0, 1.0))
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {
+    }
+
+    #[test]
+    fn test_send_sync() {
+        // Fails to compile if the tree stops being shareable across threads.
+        assert_send_sync::<DBVT<usize, AABB<Pnt3<f64>>>>();
+    }
+
+    #[test]
+    fn test_stale_leaf_id() {
+        let mut tree = DBVT::new();
+        let a        = tree.insert(0usize, unit_aabb(0.0));
+        let _        = tree.insert(1usize, unit_aabb(5.0));
+
+        assert!(tree.remove(a) == 0);
+        assert!(!tree.contains(a));
+
+        // The node of `a` is recycled but its old identifier must remain invalid.
+        let c = tree.insert(2usize, unit_aabb(10.0));
+
+        assert!(c.index() == a.index());
+        assert!(tree.contains(c));
+        assert!(!tree.contains(a));
+        assert!(*tree.object(c) == 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remove_stale_leaf_id() {
+        let mut tree = DBVT::new();
+        let a        = tree.insert(0usize, unit_aabb(0.0));
+        let _        = tree.insert(1usize, unit_aabb(5.0));
+
+        let _ = tree.remove(a);
+        let _ = tree.insert(2usize, unit_aabb(10.0));
+        let _ = tree.remove(a);
+    }
+
+    #[test]
+    fn test_insert_remove_visit() {
+        let mut tree   = DBVT::new();
+        let mut leaves = Vec::new();
+
+        for i in 0usize .. 32 {
+            leaves.push(tree.insert(i, unit_aabb((i * 2) as f64)));
+        }
+
+        for i in (0usize .. 32).filter(|i| i % 3 == 0) {
+            assert!(tree.remove(leaves[i]) == i);
         }
+
+        tree.set_bounding_volume(leaves[1], unit_aabb(100.0));
+
+        for i in 0usize .. 32 {
+            let mut found = Vec::new();
+            let aabb      = if i == 1 { unit_aabb(100.0) } else { unit_aabb((i * 2) as f64) };
+
+            {
+                let mut visitor = BoundingVolumeInterferencesCollector::new(&aabb, &mut found);
+                tree.visit(&mut visitor);
+            }
+
+            assert!(found.contains(&i) == (i % 3 != 0));
+        }
+
+        assert!(tree.len() == 32 - 11);
     }
 }
-
-// XXX: Drop should be implemented to invalidate the leaves parents when the tree is dropped.
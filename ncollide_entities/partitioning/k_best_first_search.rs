@@ -0,0 +1,238 @@
+//! Best-first search of the `k` leaves with the smallest cost, shared by the `BVT` and the `DBVT`.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use utils::data::ref_with_cost::RefWithCost;
+use partitioning::BVTCostFn;
+use math::Scalar;
+
+/// The content of a tree node, as seen by `k_best_first_search`.
+pub enum NodeContent<'a, B: 'a, T: 'a> {
+    /// An internal node with its two children.
+    Internal(&'a T, &'a T),
+    /// A leaf with its user-defined object.
+    Leaf(&'a B)
+}
+
+/// A leaf found by the search, ordered by cost.
+struct Candidate<'a, N, B: 'a, R> {
+    cost:   N,
+    b:      &'a B,
+    result: R
+}
+
+impl<'a, N: PartialEq, B, R> PartialEq for Candidate<'a, N, B, R> {
+    #[inline]
+    fn eq(&self, other: &Candidate<'a, N, B, R>) -> bool {
+        self.cost.eq(&other.cost)
+    }
+}
+
+impl<'a, N: PartialEq, B, R> Eq for Candidate<'a, N, B, R> {
+}
+
+impl<'a, N: PartialOrd, B, R> PartialOrd for Candidate<'a, N, B, R> {
+    #[inline]
+    fn partial_cmp(&self, other: &Candidate<'a, N, B, R>) -> Option<Ordering> {
+        self.cost.partial_cmp(&other.cost)
+    }
+}
+
+impl<'a, N: PartialOrd, B, R> Ord for Candidate<'a, N, B, R> {
+    #[inline]
+    fn cmp(&self, other: &Candidate<'a, N, B, R>) -> Ordering {
+        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Finds the `k` leaves with the smallest cost, ignoring those with a cost greater than `max_cost`.
+///
+/// The tree is described by its `root`, the bounding volume `bv` of each node, and the `content`
+/// of each node. The leaves found are appended to `out` by increasing cost.
+pub fn k_best_first_search<'a, N, B, BV, T, BFS: ?Sized, R, FBV, FC>(root:      &'a T,
+                                                                     k:         usize,
+                                                                     max_cost:  N,
+                                                                     algorithm: &mut BFS,
+                                                                     bv:        &FBV,
+                                                                     content:   &FC,
+                                                                     out:       &mut Vec<(&'a B, N, R)>)
+    where N:   Scalar,
+          BFS: BVTCostFn<N, B, BV, R>,
+          FBV: Fn(&'a T) -> &'a BV,
+          FC:  Fn(&'a T) -> NodeContent<'a, B, T> {
+    if k == 0 {
+        return;
+    }
+
+    let mut queue: BinaryHeap<RefWithCost<'a, N, T>> = BinaryHeap::new();
+    // Max-heap of the best leaves found so far: its top is the worst of them.
+    let mut found: BinaryHeap<Candidate<'a, N, B, R>> = BinaryHeap::new();
+
+    match algorithm.compute_bv_cost(bv(root)) {
+        Some(cost) => {
+            if cost <= max_cost {
+                queue.push(RefWithCost::new(root, -cost))
+            }
+        },
+        None => return
+    }
+
+    loop {
+        // The cost a leaf must not exceed to be part of the solution.
+        let worst_cost = if found.len() == k { found.peek().unwrap().cost } else { max_cost };
+
+        match queue.pop() {
+            Some(node) => {
+                if -node.cost > worst_cost || (found.len() == k && -node.cost == worst_cost) {
+                    break; // solution found.
+                }
+
+                match content(node.object) {
+                    NodeContent::Internal(left, right) => {
+                        for child in [ left, right ].iter() {
+                            match algorithm.compute_bv_cost(bv(*child)) {
+                                Some(cost) => {
+                                    if cost <= worst_cost {
+                                        queue.push(RefWithCost::new(*child, -cost))
+                                    }
+                                },
+                                None => { }
+                            }
+                        }
+                    },
+                    NodeContent::Leaf(b) => {
+                        match algorithm.compute_b_cost(b) {
+                            Some((candidate_cost, candidate_result)) => {
+                                if candidate_cost <= worst_cost &&
+                                   !(found.len() == k && candidate_cost == worst_cost) {
+                                    found.push(Candidate {
+                                        cost:   candidate_cost,
+                                        b:      b,
+                                        result: candidate_result
+                                    });
+
+                                    if found.len() > k {
+                                        let _ = found.pop();
+                                    }
+                                }
+                            },
+                            None => { }
+                        }
+                    }
+                }
+            },
+            None => break
+        }
+    }
+
+    out.extend(found.into_sorted_vec().into_iter().map(|c| (c.b, c.cost, c.result)))
+}
+
+#[cfg(test)]
+mod test {
+    use na::Pnt3;
+    use bounding_volume::AABB;
+    use partitioning::{BVT, DBVT, BVTCostFn};
+
+    // Distance along the `x` axis between `x` and the boxes. Leaves count their evaluations.
+    struct XDistanceCostFn {
+        x:          f64,
+        boxes:      Vec<AABB<Pnt3<f64>>>,
+        leaf_evals: usize
+    }
+
+    impl XDistanceCostFn {
+        fn dist(&self, aabb: &AABB<Pnt3<f64>>) -> f64 {
+            if self.x < aabb.mins().x { aabb.mins().x - self.x }
+            else if self.x > aabb.maxs().x { self.x - aabb.maxs().x }
+            else { 0.0 }
+        }
+    }
+
+    impl BVTCostFn<f64, usize, AABB<Pnt3<f64>>, usize> for XDistanceCostFn {
+        fn compute_bv_cost(&mut self, bv: &AABB<Pnt3<f64>>) -> Option<f64> {
+            Some(self.dist(bv))
+        }
+
+        fn compute_b_cost(&mut self, b: &usize) -> Option<(f64, usize)> {
+            self.leaf_evals = self.leaf_evals + 1;
+
+            let d = self.dist(&self.boxes[*b]);
+
+            Some((d, *b * 10))
+        }
+    }
+
+    fn boxes() -> Vec<AABB<Pnt3<f64>>> {
+        (0usize .. 64).map(|i| {
+            let x = (i * 2) as f64;
+            AABB::new(Pnt3::new(x, 0.0, 0.0), Pnt3::new(x + 1.0, 1.0, 1.0))
+        }).collect()
+    }
+
+    fn cost_fn(x: f64) -> XDistanceCostFn {
+        XDistanceCostFn { x: x, boxes: boxes(), leaf_evals: 0 }
+    }
+
+    #[test]
+    fn test_bvt_k_best_first_search() {
+        let bvt       = BVT::new_balanced(boxes().into_iter().enumerate().collect());
+        let mut costs = cost_fn(20.5);
+        let mut found = Vec::new();
+
+        bvt.k_best_first_search(3, 100.0, &mut costs, &mut found);
+
+        assert!(found.len() == 3);
+        assert!(*found[0].0 == 10 && found[0].1 == 0.0 && found[0].2 == 100);
+        assert!(found[1].1 == 1.5 && found[2].1 == 1.5);
+        // The search must not evaluate every leaf.
+        assert!(costs.leaf_evals < 64);
+    }
+
+    #[test]
+    fn test_dbvt_matches_bvt() {
+        let bvt      = BVT::new_balanced(boxes().into_iter().enumerate().collect());
+        let mut dbvt = DBVT::new();
+
+        for (i, aabb) in boxes().into_iter().enumerate() {
+            let _ = dbvt.insert(i, aabb);
+        }
+
+        for k in [ 1usize, 5, 64, 100 ].iter() {
+            let mut bvt_found  = Vec::new();
+            let mut dbvt_found = Vec::new();
+
+            bvt.k_best_first_search(*k, 1000.0, &mut cost_fn(37.2), &mut bvt_found);
+            dbvt.k_best_first_search(*k, 1000.0, &mut cost_fn(37.2), &mut dbvt_found);
+
+            assert!(bvt_found.len() == dbvt_found.len());
+            assert!(bvt_found.len() == if *k > 64 { 64 } else { *k });
+
+            for (a, b) in bvt_found.iter().zip(dbvt_found.iter()) {
+                assert!(a.1 == b.1);
+            }
+
+            for w in bvt_found.windows(2) {
+                assert!(w[0].1 <= w[1].1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_cost_search() {
+        let bvt       = BVT::new_balanced(boxes().into_iter().enumerate().collect());
+        let mut found = Vec::new();
+
+        bvt.max_cost_search(5.0, &mut cost_fn(64.5), &mut found);
+
+        let mut ids: Vec<usize> = found.iter().map(|f| *f.0).collect();
+        ids.sort();
+
+        // Boxes 30 to 34 span [60, 69].
+        assert!(ids == vec!(30, 31, 32, 33, 34));
+
+        for w in found.windows(2) {
+            assert!(w[0].1 <= w[1].1);
+        }
+    }
+}
@@ -1,8 +1,10 @@
 //! Spatial partitioning tools.
 
-pub use partitioning::dbvt::{DBVT, DBVTLeaf};
+pub use partitioning::dbvt::{DBVT, DBVTLeafId};
 pub use partitioning::bvt::{BVT, median_partitioner, median_partitioner_with_centers,
                             BinaryPartition, BVTNode};
+pub use partitioning::bvt_sah::{sah_partitioner, binned_sah_partitioner,
+                                binned_sah_partitioner_with_nbins, DEFAULT_NBINS};
 #[doc(inline)]
 pub use partitioning::bvt_visitor::{BVTVisitor, BoundingVolumeInterferencesCollector};
 #[doc(inline)]
@@ -12,6 +14,8 @@ pub use partitioning::bvt_cost_fn::BVTCostFn;
 
 mod dbvt;
 mod bvt;
+mod bvt_sah;
+mod k_best_first_search;
 
 #[doc(hidden)]
 pub mod bvt_visitor;
@@ -2,9 +2,12 @@
 
 use std::sync::Arc;
 use std::marker::PhantomData;
-use na::{Translate, Identity, Pnt2};
-use partitioning::BVT;
-use bounding_volume::{HasAABB, AABB};
+use na::{Translate, Translation, Identity, Pnt2};
+use na;
+use partitioning::{BVT, BinaryPartition, median_partitioner};
+use bounding_volume::{HasAABB, AABB, KDOP, BoundingVolume};
+use bounding_volume;
+use support_map::SupportMap;
 use math::{Scalar, Point, Vect};
 
 
@@ -17,7 +20,9 @@ pub trait BaseMeshElement<I, P> {
 /// A mesh generic wrt. the contained mesh elements characterized by vertices.
 pub struct BaseMesh<N, P, V, I, E> {
     bvt:      BVT<usize, AABB<P>>,
+    bvt_cost: N,
     bvs:      Vec<AABB<P>>,
+    kdop_bvt: Option<BVT<usize, KDOP<N, V>>>,
     vertices: Arc<Vec<P>>,
     indices:  Arc<Vec<I>>,
     uvs:      Option<Arc<Vec<Pnt2<N>>>>,
@@ -28,11 +33,13 @@ pub struct BaseMesh<N, P, V, I, E> {
 impl<N, P, V, I, E> Clone for BaseMesh<N, P, V, I, E>
     where N: Clone,
           P: Send + Sync + Clone,
-          V: Send + Sync {
+          V: Send + Sync + Clone {
     fn clone(&self) -> BaseMesh<N, P, V, I, E> {
         BaseMesh {
             bvt:      self.bvt.clone(),
+            bvt_cost: self.bvt_cost.clone(),
             bvs:      self.bvs.clone(),
+            kdop_bvt: self.kdop_bvt.clone(),
             vertices: self.vertices.clone(),
             indices:  self.indices.clone(),
             uvs:      self.uvs.clone(),
@@ -53,6 +60,19 @@ impl<N, P, V, I, E> BaseMesh<N, P, V, I, E>
                uvs:      Option<Arc<Vec<Pnt2<N>>>>,
                normals:  Option<Arc<Vec<V>>>) // a loosening margin for the BVT.
                -> BaseMesh<N, P, V, I, E> {
+        BaseMesh::new_with_partitioner(vertices, indices, uvs, normals, &mut median_partitioner)
+    }
+
+    /// Builds a new mesh which BVT is constructed by a user-defined partitioner.
+    ///
+    /// See `BVT::new_with_partitioner` for details about the `partitioner`.
+    pub fn new_with_partitioner<F>(vertices:    Arc<Vec<P>>,
+                                   indices:     Arc<Vec<I>>,
+                                   uvs:         Option<Arc<Vec<Pnt2<N>>>>,
+                                   normals:     Option<Arc<Vec<V>>>,
+                                   partitioner: &mut F)
+                                   -> BaseMesh<N, P, V, I, E>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
         for uvs in uvs.iter() {
             assert!(uvs.len() == vertices.len());
         }
@@ -74,11 +94,14 @@ impl<N, P, V, I, E> BaseMesh<N, P, V, I, E>
             }
         }
 
-        let bvt = BVT::new_balanced(leaves);
+        let bvt      = BVT::new_with_partitioner(leaves, partitioner);
+        let bvt_cost = bvt.sah_cost();
 
         BaseMesh {
             bvt:      bvt,
+            bvt_cost: bvt_cost,
             bvs:      bvs,
+            kdop_bvt: None,
             vertices: vertices,
             indices:  indices,
             uvs:      uvs,
@@ -86,6 +109,165 @@ impl<N, P, V, I, E> BaseMesh<N, P, V, I, E>
             elt:      PhantomData
         }
     }
+
+    /// Moves the vertices of this mesh and refits its BVT.
+    ///
+    /// If the BVT has to be rebuilt, the median partitioner is used. See
+    /// `set_vertices_with_partitioner` for details.
+    #[inline]
+    pub fn set_vertices(&mut self,
+                        vertices:          Arc<Vec<P>>,
+                        normals:           Option<Arc<Vec<V>>>,
+                        rebuild_threshold: Option<N>)
+                        -> bool
+        where E: SupportMap<P, V, Identity> {
+        self.set_vertices_with_partitioner(vertices, normals, rebuild_threshold, &mut median_partitioner)
+    }
+
+    /// Moves the vertices of this mesh and refits its BVT.
+    ///
+    /// The topology of the mesh is not modified: `vertices` must contain as many vertices as the
+    /// current ones. The BVT bounding volumes are updated bottom-up without changing its structure.
+    /// If `rebuild_threshold` is set and the refitted BVT is more than `rebuild_threshold` times
+    /// as costly as it was when it was built, it is rebuilt with `partitioner` instead. This
+    /// should be the partitioner the mesh was built with. Returns `true` if the BVT has been
+    /// rebuilt.
+    ///
+    /// The k-DOP BVT, if any, is refitted as well, and rebuilt along with the AABB one.
+    pub fn set_vertices_with_partitioner<F>(&mut self,
+                                            vertices:          Arc<Vec<P>>,
+                                            normals:           Option<Arc<Vec<V>>>,
+                                            rebuild_threshold: Option<N>,
+                                            partitioner:       &mut F)
+                                            -> bool
+        where E: SupportMap<P, V, Identity>,
+              F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        assert!(vertices.len() == self.vertices.len(),
+                "The number of vertices of a mesh cannot be modified.");
+
+        self.vertices = vertices;
+        self.normals  = normals;
+
+        {
+            let vs: &[P] = self.vertices.as_slice();
+
+            for (i, is) in self.indices.iter().enumerate() {
+                let element: E = BaseMeshElement::new_with_vertices_and_indices(vs, is);
+
+                self.bvs[i] = element.aabb(&Identity::new());
+            }
+        }
+
+        {
+            let bvs = &self.bvs;
+
+            self.bvt.refit(&mut |i| bvs[*i].clone());
+        }
+
+        match rebuild_threshold {
+            Some(threshold) if self.bvt_degradation() > threshold => {
+                self.rebuild_bvt_with_partitioner(partitioner);
+
+                true
+            },
+            _ => {
+                self.refit_kdop_bvt();
+
+                false
+            }
+        }
+    }
+
+    /// Rebuilds the BVT of this mesh from scratch with a user-defined partitioner.
+    ///
+    /// The k-DOP BVT, if any, is rebuilt too.
+    pub fn rebuild_bvt_with_partitioner<F>(&mut self, partitioner: &mut F)
+        where E: SupportMap<P, V, Identity>,
+              F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        let leaves = self.bvs.iter().map(|bv| bv.clone()).enumerate().collect();
+
+        self.bvt      = BVT::new_with_partitioner(leaves, partitioner);
+        self.bvt_cost = self.bvt.sah_cost();
+
+        let axes = self.kdop_axes();
+
+        if let Some(axes) = axes {
+            self.build_kdop_bvt(axes)
+        }
+    }
+
+    /// The ratio between the current cost of the BVT and its cost when it was built.
+    ///
+    /// This grows when the vertices are moved with `set_vertices`, as the BVT is only refitted.
+    #[inline]
+    pub fn bvt_degradation(&self) -> N {
+        if na::is_zero(&self.bvt_cost) {
+            na::one()
+        }
+        else {
+            self.bvt.sah_cost() / self.bvt_cost
+        }
+    }
+}
+
+impl<N, P, V, I, E> BaseMesh<N, P, V, I, E>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          E: BaseMeshElement<I, P> {
+    /// Builds a balanced BVT of the mesh elements, bounded by a custom bounding volume.
+    ///
+    /// The bounding volume of each element is computed by `bounding_volume`. The leaves of the
+    /// BVT are the element indices.
+    pub fn custom_bvt<BV, F>(&self, bounding_volume: &mut F) -> BVT<usize, BV>
+        where BV: Translation<V> + BoundingVolume<N> + Clone,
+              F:  FnMut(&E) -> BV {
+        let vs: &[P]   = self.vertices.as_slice();
+        let mut leaves = Vec::with_capacity(self.indices.len());
+
+        for (i, is) in self.indices.iter().enumerate() {
+            let element: E = BaseMeshElement::new_with_vertices_and_indices(vs, is);
+
+            leaves.push((i, bounding_volume(&element)));
+        }
+
+        BVT::new_balanced(leaves)
+    }
+
+    /// Builds the k-DOP BVT of this mesh, with the given k-DOP `axes`.
+    ///
+    /// Once built, ray casts and point queries traverse this BVT instead of the AABB one. It is
+    /// kept up to date by `set_vertices`.
+    pub fn build_kdop_bvt(&mut self, axes: Arc<Vec<V>>)
+        where E: SupportMap<P, V, Identity> {
+        let id = Identity::new();
+
+        self.kdop_bvt = Some(self.custom_bvt(&mut |e: &E| bounding_volume::implicit_shape_kdop(&id, e, &axes)));
+    }
+
+    fn refit_kdop_bvt(&mut self)
+        where E: SupportMap<P, V, Identity> {
+        let axes = match self.kdop_axes() {
+            Some(axes) => axes,
+            None       => return
+        };
+
+        let id       = Identity::new();
+        let vs: &[P] = self.vertices.as_slice();
+        let is: &[I] = self.indices.as_slice();
+
+        if let Some(ref mut bvt) = self.kdop_bvt {
+            bvt.refit(&mut |i| {
+                let element: E = BaseMeshElement::new_with_vertices_and_indices(vs, &is[*i]);
+
+                bounding_volume::implicit_shape_kdop(&id, &element, &axes)
+            })
+        }
+    }
+
+    fn kdop_axes(&self) -> Option<Arc<Vec<V>>> {
+        self.kdop_bvt.as_ref().and_then(|bvt| bvt.root_bounding_volume()).map(|bv| bv.axes().clone())
+    }
 }
 
 impl<N, P, V, I, E> BaseMesh<N, P, V, I, E> {
@@ -124,6 +306,12 @@ impl<N, P, V, I, E> BaseMesh<N, P, V, I, E> {
     pub fn bvt(&self) -> &BVT<usize, AABB<P>> {
         &self.bvt
     }
+
+    /// The k-DOP BVT used by ray casts and point queries, if it has been built.
+    #[inline]
+    pub fn kdop_bvt(&self) -> Option<&BVT<usize, KDOP<N, V>>> {
+        self.kdop_bvt.as_ref()
+    }
 }
 
 impl<N, P: Send + Sync, V, I, E: BaseMeshElement<I, P>> BaseMesh<N, P, V, I, E> {
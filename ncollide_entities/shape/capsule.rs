@@ -1,7 +1,9 @@
 //! Support mapping based Capsule shape.
 
 use std::num::Float;
-use math::Scalar;
+use na;
+use shape::Segment;
+use math::{Scalar, Point, Vect};
 
 /// SupportMap description of a capsule shape with its principal axis aligned with the `y` axis.
 #[derive(PartialEq, Debug, Clone, RustcEncodable, RustcDecodable)]
@@ -37,4 +39,18 @@ impl<N> Capsule<N>
     pub fn radius(&self) -> N {
         self.radius.clone()
     }
+
+    /// The segment forming the principal axis of this capsule.
+    #[inline]
+    pub fn segment<P, V>(&self) -> Segment<P>
+        where P: Point<N, V>,
+              V: Vect<N> {
+        let mut a = na::orig::<P>();
+        let mut b = na::orig::<P>();
+
+        a[1] = -self.half_height;
+        b[1] = self.half_height;
+
+        Segment::new(a, b)
+    }
 }
@@ -1,9 +1,9 @@
 use std::ops::Mul;
 use na;
 use math::{Scalar, Point, Vect, Isometry};
-use partitioning::BVT;
+use partitioning::{BVT, BVTVisitor, BVTCostFn};
 use bounding_volume::AABB;
-use shape::{Compound, TriMesh, Polyline};
+use shape::{Compound, TriMesh, Polyline, HeightField};
 use inspection::Repr;
 
 /// Trait implemented by shapes composed of multiple simpler shapes.
@@ -17,7 +17,21 @@ pub trait CompositeShape<N, P, V, M> {
     /// shape.
     fn map_transformed_part_at(&self, m: &M, usize, &mut FnMut(&M, &Repr<N, P, V, M>));
 
-    // FIXME: the following two methods really are not generic enough.
+    // FIXME: the following methods really are not generic enough.
+    /// Traverses the acceleration structure of the concave shape with a visitor.
+    fn visit(&self, visitor: &mut BVTVisitor<usize, AABB<P>>);
+
+    /// Performs a best-first search on the acceleration structure of the concave shape.
+    ///
+    /// Returns the index of the best sub-shape, and its cost.
+    fn best_first_search(&self, algorithm: &mut BVTCostFn<N, usize, AABB<P>, N>) -> Option<(usize, N)>
+        where N: Scalar;
+}
+
+/// Trait implemented by composite shapes storing the AABBs of their parts in a BVT.
+///
+/// Shapes like `HeightField`, which use another acceleration structure, do not implement it.
+pub trait BVTCompositeShape<N, P, V, M>: CompositeShape<N, P, V, M> {
     /// Gets the AABB of the shape identified by the index `i`.
     fn aabb_at(&self, i: usize) -> &AABB<P>;
     /// Gets the acceleration structure of the concave shape.
@@ -40,6 +54,20 @@ impl<N, P, V, M> CompositeShape<N, P, V, M> for Compound<N, P, V, M>
         f(&(*m * elt.0), &**elt.1)
     }
 
+    #[inline]
+    fn visit(&self, visitor: &mut BVTVisitor<usize, AABB<P>>) {
+        self.bvt().visit(visitor)
+    }
+
+    #[inline]
+    fn best_first_search(&self, algorithm: &mut BVTCostFn<N, usize, AABB<P>, N>) -> Option<(usize, N)>
+        where N: Scalar {
+        self.bvt().best_first_search(algorithm).map(|(i, res)| (*i, res))
+    }
+}
+
+impl<N, P, V, M> BVTCompositeShape<N, P, V, M> for Compound<N, P, V, M>
+    where M: Copy + Mul<M, Output = M> {
     #[inline]
     fn aabb_at(&self, i: usize) -> &AABB<P> {
         &self.bounding_volumes()[i]
@@ -70,6 +98,23 @@ impl<N, P, V, M> CompositeShape<N, P, V, M> for TriMesh<N, P, V>
         f(m, &element)
     }
 
+    #[inline]
+    fn visit(&self, visitor: &mut BVTVisitor<usize, AABB<P>>) {
+        self.bvt().visit(visitor)
+    }
+
+    #[inline]
+    fn best_first_search(&self, algorithm: &mut BVTCostFn<N, usize, AABB<P>, N>) -> Option<(usize, N)>
+        where N: Scalar {
+        self.bvt().best_first_search(algorithm).map(|(i, res)| (*i, res))
+    }
+}
+
+impl<N, P, V, M> BVTCompositeShape<N, P, V, M> for TriMesh<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V> {
     #[inline]
     fn aabb_at(&self, i: usize) -> &AABB<P> {
         &self.bounding_volumes()[i]
@@ -100,6 +145,23 @@ impl<N, P, V, M> CompositeShape<N, P, V, M> for Polyline<N, P, V>
         f(m, &element)
     }
 
+    #[inline]
+    fn visit(&self, visitor: &mut BVTVisitor<usize, AABB<P>>) {
+        self.bvt().visit(visitor)
+    }
+
+    #[inline]
+    fn best_first_search(&self, algorithm: &mut BVTCostFn<N, usize, AABB<P>, N>) -> Option<(usize, N)>
+        where N: Scalar {
+        self.bvt().best_first_search(algorithm).map(|(i, res)| (*i, res))
+    }
+}
+
+impl<N, P, V, M> BVTCompositeShape<N, P, V, M> for Polyline<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V> {
     #[inline]
     fn aabb_at(&self, i: usize) -> &AABB<P> {
         &self.bounding_volumes()[i]
@@ -110,3 +172,41 @@ impl<N, P, V, M> CompositeShape<N, P, V, M> for Polyline<N, P, V>
         self.bvt()
     }
 }
+
+impl<N, P, V, M> CompositeShape<N, P, V, M> for HeightField<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V> {
+    #[inline(always)]
+    fn map_part_at(&self, i: usize, f: &mut FnMut(&M, &Repr<N, P, V, M>)) {
+        let one: M = na::one();
+
+        self.map_transformed_part_at(&one, i, f)
+    }
+
+    #[inline(always)]
+    fn map_transformed_part_at(&self, m: &M, i: usize, f: &mut FnMut(&M, &Repr<N, P, V, M>)) {
+        if na::dim::<P>() == 2 {
+            let element = self.segment_at::<P>(i);
+
+            f(m, &element)
+        }
+        else {
+            let element = self.triangle_at::<P>(i);
+
+            f(m, &element)
+        }
+    }
+
+    #[inline]
+    fn visit(&self, visitor: &mut BVTVisitor<usize, AABB<P>>) {
+        HeightField::visit(self, visitor)
+    }
+
+    #[inline]
+    fn best_first_search(&self, algorithm: &mut BVTCostFn<N, usize, AABB<P>, N>) -> Option<(usize, N)>
+        where N: Scalar {
+        HeightField::best_first_search(self, algorithm)
+    }
+}
@@ -3,10 +3,11 @@
 //!
 
 use std::sync::Arc;
-use na::Translate;
+use na::{Translate, Translation};
 use na;
-use bounding_volume::{HasAABB, AABB, BoundingVolume};
-use partitioning::BVT;
+use bounding_volume::{HasAABB, AABB, KDOP, BoundingVolume};
+use bounding_volume;
+use partitioning::{BVT, BinaryPartition, median_partitioner};
 use math::{Scalar, Point, Vect, Isometry};
 use inspection::Repr;
 
@@ -16,9 +17,10 @@ use inspection::Repr;
 /// the main way of creating a concave shape from convex parts. Each parts can have its own
 /// delta transformation to shift or rotate it with regard to the other shapes.
 pub struct Compound<N, P, V, M> {
-    shapes:  Vec<(M, Arc<Box<Repr<N, P, V, M>>>)>,
-    bvt:     BVT<usize, AABB<P>>,
-    bvs:     Vec<AABB<P>>
+    shapes:   Vec<(M, Arc<Box<Repr<N, P, V, M>>>)>,
+    bvt:      BVT<usize, AABB<P>>,
+    bvs:      Vec<AABB<P>>,
+    kdop_bvt: Option<BVT<usize, KDOP<N, V>>>
 }
 
 impl<N, P, V, M> Clone for Compound<N, P, V, M>
@@ -28,9 +30,10 @@ impl<N, P, V, M> Clone for Compound<N, P, V, M>
           M: Clone {
     fn clone(&self) -> Compound<N, P, V, M> {
         Compound {
-            shapes: self.shapes.clone(),
-            bvt:    self.bvt.clone(),
-            bvs:    self.bvs.clone()
+            shapes:   self.shapes.clone(),
+            bvt:      self.bvt.clone(),
+            bvs:      self.bvs.clone(),
+            kdop_bvt: self.kdop_bvt.clone()
         }
     }
 }
@@ -42,6 +45,16 @@ impl<N, P, V, M> Compound<N, P, V, M>
           M: Isometry<N, P, V> {
     /// Builds a new compound shape.
     pub fn new(shapes: Vec<(M, Arc<Box<Repr<N, P, V, M>>>)>) -> Compound<N, P, V, M> {
+        Compound::new_with_partitioner(shapes, &mut median_partitioner)
+    }
+
+    /// Builds a new compound shape which BVT is constructed by a user-defined partitioner.
+    ///
+    /// See `BVT::new_with_partitioner` for details about the `partitioner`.
+    pub fn new_with_partitioner<F>(shapes:      Vec<(M, Arc<Box<Repr<N, P, V, M>>>)>,
+                                   partitioner: &mut F)
+                                   -> Compound<N, P, V, M>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
         let mut bvs    = Vec::new();
         let mut leaves = Vec::new();
 
@@ -53,14 +66,29 @@ impl<N, P, V, M> Compound<N, P, V, M>
             leaves.push((i, bv));
         }
 
-        let bvt = BVT::new_balanced(leaves);
+        let bvt = BVT::new_with_partitioner(leaves, partitioner);
 
         Compound {
-            shapes:  shapes,
-            bvt:     bvt,
-            bvs:     bvs
+            shapes:   shapes,
+            bvt:      bvt,
+            bvs:      bvs,
+            kdop_bvt: None
         }
     }
+
+    /// Builds a new compound shape with an additional k-DOP BVT.
+    ///
+    /// Ray casts and point queries traverse the k-DOP BVT instead of the AABB one. `axes` are the
+    /// axes of the k-DOPs, see `bounding_volume::kdop_axes`.
+    pub fn new_with_kdop(shapes: Vec<(M, Arc<Box<Repr<N, P, V, M>>>)>, axes: Arc<Vec<V>>)
+                         -> Compound<N, P, V, M> {
+        let mut res = Compound::new(shapes);
+        let bvt     = res.custom_bvt(&mut |delta, shape| bounding_volume::repr_kdop(delta, shape, &axes));
+
+        res.kdop_bvt = Some(bvt);
+
+        res
+    }
 }
 
 impl<N, P, V, M> Compound<N, P, V, M> {
@@ -76,6 +104,12 @@ impl<N, P, V, M> Compound<N, P, V, M> {
         &self.bvt
     }
 
+    /// The k-DOP BVT used by ray casts and point queries, if this compound has been built with one.
+    #[inline]
+    pub fn kdop_bvt(&self) -> Option<&BVT<usize, KDOP<N, V>>> {
+        self.kdop_bvt.as_ref()
+    }
+
     /// The shapes bounding volumes.
     #[inline]
     pub fn bounding_volumes(&self) -> &[AABB<P>] {
@@ -88,3 +122,22 @@ impl<N, P, V, M> Compound<N, P, V, M> {
         &self.bvs[i]
     }
 }
+
+impl<N, P, V, M> Compound<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// Builds a balanced BVT of the shapes of this compound, bounded by a custom bounding volume.
+    ///
+    /// The bounding volume of each shape is computed by `bounding_volume` from the shape and its
+    /// delta transformation. The leaves of the BVT are the shape indices.
+    pub fn custom_bvt<BV, F>(&self, bounding_volume: &mut F) -> BVT<usize, BV>
+        where BV: Translation<V> + BoundingVolume<N> + Clone,
+              F:  FnMut(&M, &Repr<N, P, V, M>) -> BV {
+        let leaves = self.shapes.iter().enumerate().map(|(i, &(ref delta, ref shape))| {
+            (i, bounding_volume(delta, &***shape))
+        }).collect();
+
+        BVT::new_balanced(leaves)
+    }
+}
@@ -0,0 +1,241 @@
+//! Convex polyhedron with explicit topology.
+
+use std::collections::HashMap;
+use std::num::Float;
+use na;
+use math::{Scalar, Point, Vect};
+use utils;
+
+#[derive(PartialEq, Debug, Clone, RustcEncodable, RustcDecodable)]
+/// A convex polyhedron with explicit vertices, faces, edges and face normals.
+///
+/// In 3D, each face is a convex polygon which vertices are given counterclockwise when seen from
+/// outside of the polyhedron. In 2D, each face is a segment and the polyhedron has no edge.
+pub struct ConvexPolyhedron<P, V> {
+    vertices:   Vec<P>,
+    faces:      Vec<Vec<usize>>,
+    normals:    Vec<V>,
+    edges:      Vec<(usize, usize)>,
+    edge_faces: Vec<(usize, usize)>
+}
+
+#[old_impl_check]
+impl<N, P, V> ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// Creates a convex polyhedron from its vertices, faces and outward face normals.
+    ///
+    /// The edges and their adjacent faces are deduced from the faces. Use
+    /// `transformation::convex_polyhedron3` or `transformation::convex_polyhedron2` to build the
+    /// convex polyhedron of an arbitrary set of points.
+    ///
+    /// Panics if a normal does not point outward, if the polyhedron is not convex, or if a 3D face
+    /// is not wound counterclockwise around its normal.
+    pub fn new(vertices: Vec<P>, faces: Vec<Vec<usize>>, normals: Vec<V>) -> ConvexPolyhedron<P, V> {
+        assert!(faces.len() == normals.len(), "There must be exactly one normal per face.");
+        assert!(faces.iter().all(|f| f.len() >= 2 && f.iter().all(|i| *i < vertices.len())),
+                "Invalid face indices.");
+        assert!(is_convex(&vertices[..], &faces[..], &normals[..]),
+                "The polyhedron is not convex or has normals not pointing outward.");
+        assert!(is_counterclockwise(&vertices[..], &faces[..], &normals[..]),
+                "The faces are not wound counterclockwise around their normals.");
+
+        let mut edges:      Vec<(usize, usize)> = Vec::new();
+        let mut edge_faces: Vec<(usize, usize)> = Vec::new();
+
+        if faces.iter().all(|f| f.len() >= 3) {
+            let mut edge_ids = HashMap::new();
+
+            for (fid, face) in faces.iter().enumerate() {
+                for i in 0 .. face.len() {
+                    let a   = face[i];
+                    let b   = face[(i + 1) % face.len()];
+                    let key = if a < b { (a, b) } else { (b, a) };
+
+                    match edge_ids.get(&key).cloned() {
+                        Some(eid) => {
+                            edge_faces[eid].1 = fid
+                        },
+                        None => {
+                            let _ = edge_ids.insert(key, edges.len());
+                            edges.push(key);
+                            edge_faces.push((fid, fid))
+                        }
+                    }
+                }
+            }
+        }
+
+        ConvexPolyhedron {
+            vertices:   vertices,
+            faces:      faces,
+            normals:    normals,
+            edges:      edges,
+            edge_faces: edge_faces
+        }
+    }
+
+}
+
+impl<P, V> ConvexPolyhedron<P, V> {
+    /// The vertices of this polyhedron.
+    #[inline]
+    pub fn vertices(&self) -> &[P] {
+        &self.vertices[..]
+    }
+
+    /// The faces of this polyhedron, as indices of their vertices.
+    #[inline]
+    pub fn faces(&self) -> &[Vec<usize>] {
+        &self.faces[..]
+    }
+
+    /// The outward normals of the faces of this polyhedron.
+    #[inline]
+    pub fn normals(&self) -> &[V] {
+        &self.normals[..]
+    }
+
+    /// The edges of this polyhedron, as indices of their vertices.
+    ///
+    /// This is empty in 2D.
+    #[inline]
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges[..]
+    }
+
+    /// The indices of the two faces adjacent to each edge.
+    #[inline]
+    pub fn edge_faces(&self) -> &[(usize, usize)] {
+        &self.edge_faces[..]
+    }
+}
+
+/// Tests that every vertex lies behind the plane of every face, up to a tolerance relative to the
+/// polyhedron size.
+fn is_convex<N, P, V>(vertices: &[P], faces: &[Vec<usize>], normals: &[V]) -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    if vertices.is_empty() {
+        return true;
+    }
+
+    let mut diameter = na::zero::<N>();
+
+    for v in vertices.iter() {
+        let d = na::dist(&vertices[0], v);
+
+        if d > diameter {
+            diameter = d;
+        }
+    }
+
+    let eps: N = Float::epsilon();
+    let tol    = diameter * eps.sqrt();
+
+    faces.iter().zip(normals.iter()).all(|(face, n)| {
+        let orig = vertices[face[0]];
+
+        vertices.iter().all(|v| na::dot(n, &(*v - orig)) <= tol) &&
+        face.iter().all(|i| na::abs(&na::dot(n, &(vertices[*i] - orig))) <= tol)
+    })
+}
+
+/// Tests that each 3D face is wound counterclockwise when seen from the tip of its normal.
+fn is_counterclockwise<N, P, V>(vertices: &[P], faces: &[Vec<usize>], normals: &[V]) -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    if na::dim::<P>() != 3 {
+        return true;
+    }
+
+    faces.iter().zip(normals.iter()).all(|(face, n)| {
+        if face.len() < 3 {
+            return true;
+        }
+
+        // Newell's method gives the area-weighted normal of the face.
+        let orig     = vertices[face[0]];
+        let mut area = na::zero::<V>();
+
+        for i in 1 .. face.len() - 1 {
+            area = area + utils::cross3(&(vertices[face[i]] - orig), &(vertices[face[i + 1]] - orig));
+        }
+
+        na::dot(&area, n) > na::zero()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Vec3};
+    use super::ConvexPolyhedron;
+
+    fn cube_faces() -> (Vec<Pnt3<f64>>, Vec<Vec<usize>>, Vec<Vec3<f64>>) {
+        let mut vertices = Vec::new();
+
+        for i in 0usize .. 8 {
+            let x = if i & 1 == 0 { -1.0 } else { 1.0 };
+            let y = if i & 2 == 0 { -1.0 } else { 1.0 };
+            let z = if i & 4 == 0 { -1.0 } else { 1.0 };
+
+            vertices.push(Pnt3::new(x, y, z));
+        }
+
+        let faces = vec![
+            vec![ 0, 4, 6, 2 ], vec![ 1, 3, 7, 5 ],
+            vec![ 0, 1, 5, 4 ], vec![ 2, 6, 7, 3 ],
+            vec![ 0, 2, 3, 1 ], vec![ 4, 5, 7, 6 ]
+        ];
+
+        let normals = vec![
+            -Vec3::x(), Vec3::x(),
+            -Vec3::y(), Vec3::y(),
+            -Vec3::z(), Vec3::z()
+        ];
+
+        (vertices, faces, normals)
+    }
+
+    #[test]
+    fn test_cube_topology() {
+        let (vertices, faces, normals) = cube_faces();
+        let cube = ConvexPolyhedron::new(vertices, faces, normals);
+
+        assert!(cube.edges().len() == 12);
+        assert!(cube.edge_faces().iter().all(|&(a, b)| a != b));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inward_normal() {
+        let (vertices, faces, mut normals) = cube_faces();
+
+        normals[0] = -normals[0];
+
+        let _ = ConvexPolyhedron::new(vertices, faces, normals);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_clockwise_face() {
+        let (vertices, mut faces, normals) = cube_faces();
+
+        faces[3].reverse();
+
+        let _ = ConvexPolyhedron::new(vertices, faces, normals);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_convex() {
+        let (mut vertices, faces, normals) = cube_faces();
+
+        vertices[7] = Pnt3::new(0.2, 0.2, 0.2);
+
+        let _ = ConvexPolyhedron::new(vertices, faces, normals);
+    }
+}
@@ -0,0 +1,200 @@
+//! Regular grid of signed distances.
+
+use std::any::{Any, TypeId};
+use std::mem;
+use na;
+use bounding_volume::AABB;
+use inspection::{Repr, ReprDesc};
+use math::{Scalar, Point, Vect};
+
+#[derive(PartialEq, Debug, Clone, RustcEncodable, RustcDecodable)]
+/// A regular 2D or 3D grid of signed distances, i.e., a sampled signed distance field.
+///
+/// The sample with the multi-index `(i0, i1, i2)` is located at `mins + (i0, i1, i2) * spacing`.
+/// Samples are stored with the first index varying the fastest. A sample is negative if it lies
+/// inside of the sampled shape.
+///
+/// Contacts, distances, and times of impact are only computed against support-mapped shapes, or
+/// against the parts of composite shapes. Other pairs, e.g. two distance fields, are not supported.
+pub struct DistanceField<N, P, V> {
+    values:     Vec<N>,
+    resolution: Vec<usize>,
+    mins:       P,
+    spacing:    V
+}
+
+impl<N, P, V> DistanceField<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// Creates a new distance field from its samples.
+    ///
+    /// `resolution` gives the number of samples along each axis, `mins` the position of the first
+    /// sample, and `spacing` the distance between two consecutive samples along each axis.
+    pub fn new(values: Vec<N>, resolution: Vec<usize>, mins: P, spacing: V) -> DistanceField<N, P, V> {
+        assert!(resolution.len() == na::dim::<P>(), "The resolution must be given for each axis.");
+
+        let mut nsamples = 1;
+
+        for i in 0 .. na::dim::<P>() {
+            assert!(resolution[i] >= 2, "A distance field must have at least two samples per axis.");
+            assert!(spacing[i] > na::zero(), "The distance field spacing must be positive.");
+
+            nsamples = nsamples * resolution[i];
+        }
+
+        assert!(values.len() == nsamples, "The number of values must match the resolution.");
+
+        DistanceField {
+            values:     values,
+            resolution: resolution,
+            mins:       mins,
+            spacing:    spacing
+        }
+    }
+
+    /// The position of the last sample of this distance field.
+    #[inline]
+    pub fn maxs(&self) -> P {
+        let mut res = self.mins;
+
+        for i in 0 .. na::dim::<P>() {
+            res[i] = res[i] + self.spacing[i] * na::cast((self.resolution[i] - 1) as f64);
+        }
+
+        res
+    }
+
+    /// The local-space AABB of the sampled domain.
+    #[inline]
+    pub fn local_aabb(&self) -> AABB<P> {
+        AABB::new(self.mins, self.maxs())
+    }
+
+    /// The sample with the given multi-index.
+    #[inline]
+    pub fn value_at_index(&self, index: &[usize]) -> N {
+        self.values[self.linear_index(index)]
+    }
+
+    /// The signed distance at the given point, computed by multilinear interpolation.
+    ///
+    /// Points outside of the sampled domain are first projected on it. The distance between the
+    /// point and its projection is then added to the interpolated value. This is exact as long as
+    /// the sampled shape lies inside of the domain.
+    pub fn value_at(&self, pt: &P) -> N {
+        let mut base = [ 0usize; 3 ];
+        let mut t    = [ na::zero::<N>(); 3 ];
+        let mut out  = na::zero::<N>();
+        let dim      = na::dim::<P>();
+
+        for i in 0 .. dim {
+            let ncells  = self.resolution[i] - 1;
+            let extent  = self.spacing[i] * na::cast(ncells as f64);
+            let offset  = pt[i] - self.mins[i];
+            let clamped = na::clamp(offset, na::zero(), extent);
+
+            out = out + (offset - clamped) * (offset - clamped);
+
+            let coord = clamped / self.spacing[i];
+            let cell  = na::cast::<N, f64>(coord) as usize;
+
+            base[i] = if cell >= ncells { ncells - 1 } else { cell };
+            t[i]    = coord - na::cast(base[i] as f64);
+        }
+
+        let mut res   = na::zero::<N>();
+        let mut index = [ 0usize; 3 ];
+
+        // Iterate through the corners of the cell, one bit of `corner` per axis.
+        for corner in 0 .. 1usize << dim {
+            let mut weight = na::one::<N>();
+
+            for i in 0 .. dim {
+                if corner & (1 << i) == 0 {
+                    index[i] = base[i];
+                    weight   = weight * (na::one::<N>() - t[i]);
+                }
+                else {
+                    index[i] = base[i] + 1;
+                    weight   = weight * t[i];
+                }
+            }
+
+            res = res + self.value_at_index(&index[.. dim]) * weight;
+        }
+
+        res + out.sqrt()
+    }
+
+    /// The gradient of the signed distance at the given point, computed by central differences.
+    pub fn gradient_at(&self, pt: &P) -> V {
+        let mut res = na::zero::<V>();
+        let half: N = na::cast(0.5f64);
+
+        for i in 0 .. na::dim::<P>() {
+            let h      = self.spacing[i] * half;
+            let mut p1 = *pt;
+            let mut p2 = *pt;
+
+            p1[i] = p1[i] - h;
+            p2[i] = p2[i] + h;
+
+            res[i] = (self.value_at(&p2) - self.value_at(&p1)) / (h + h);
+        }
+
+        res
+    }
+
+    fn linear_index(&self, index: &[usize]) -> usize {
+        let mut res = 0;
+
+        for i in (0 .. na::dim::<P>()).rev() {
+            res = res * self.resolution[i] + index[i];
+        }
+
+        res
+    }
+}
+
+impl<N, P, V> DistanceField<N, P, V> {
+    /// The samples of this distance field, the first index varying the fastest.
+    #[inline]
+    pub fn values(&self) -> &[N] {
+        &self.values[..]
+    }
+
+    /// The number of samples along each axis.
+    #[inline]
+    pub fn resolution(&self) -> &[usize] {
+        &self.resolution[..]
+    }
+
+    /// The position of the first sample of this distance field.
+    #[inline]
+    pub fn mins(&self) -> &P {
+        &self.mins
+    }
+
+    /// The distance between two consecutive samples along each axis.
+    #[inline]
+    pub fn spacing(&self) -> &V {
+        &self.spacing
+    }
+}
+
+impl<N, P, V, M> Repr<N, P, V, M> for DistanceField<N, P, V>
+    where N: 'static + Send + Sync,
+          P: 'static + Send + Sync,
+          V: 'static + Send + Sync {
+    #[inline(always)]
+    fn repr(&self) -> ReprDesc {
+        unsafe {
+            ReprDesc::new(
+                TypeId::of::<DistanceField<N, P, V>>(),
+                TypeId::of::<&Any>(),
+                mem::transmute(self as &Any)
+            )
+        }
+    }
+}
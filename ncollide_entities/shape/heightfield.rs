@@ -0,0 +1,529 @@
+//! Heightfield shape for terrains.
+
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::collections::BinaryHeap;
+use na::Bounded;
+use na;
+use bounding_volume::AABB;
+use partitioning::{BVTVisitor, BVTCostFn};
+use shape::{Segment, Triangle};
+use math::{Scalar, Point, Vect};
+
+#[derive(PartialEq, Debug, Clone, RustcEncodable, RustcDecodable)]
+/// A regular grid of heights, typically used to represent terrains.
+///
+/// The heights are stored row-major and are scaled by the `y` component of the scale. In 3D, the
+/// rows are distributed along the `z` axis and the columns along the `x` axis. In 2D, the
+/// heightfield has a single row. The heightfield is centered at the origin and its horizontal
+/// extents are given by the other components of its scale.
+///
+/// Each cell of the grid is made of one segment in 2D, and of two triangles in 3D. Those parts are
+/// never stored explicitly and no bounding volume tree is built: the grid itself is used as an
+/// acceleration structure.
+pub struct HeightField<N, V> {
+    heights:    Vec<N>,
+    nrows:      usize,
+    ncols:      usize,
+    scale:      V,
+    min_height: N,
+    max_height: N
+}
+
+impl<N, V> HeightField<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    /// Creates a new heightfield from its row-major heights and its scale.
+    ///
+    /// In 2D, `nrows` must be 1.
+    pub fn new(heights: Vec<N>, nrows: usize, ncols: usize, scale: V) -> HeightField<N, V> {
+        assert!(heights.len() == nrows * ncols, "The number of heights must be `nrows * ncols`.");
+        assert!(ncols >= 2, "A heightfield must have at least two columns.");
+        assert!(if na::dim::<V>() == 2 { nrows == 1 } else { na::dim::<V>() == 3 && nrows >= 2 },
+                "A heightfield must have exactly one row in 2D and at least two rows in 3D.");
+
+        for i in 0 .. na::dim::<V>() {
+            assert!(scale[i] > na::zero(), "The heightfield scale must be positive.");
+        }
+
+        let mut min_height = heights[0];
+        let mut max_height = heights[0];
+
+        for h in heights.iter() {
+            if *h < min_height {
+                min_height = *h;
+            }
+
+            if *h > max_height {
+                max_height = *h;
+            }
+        }
+
+        HeightField {
+            heights:    heights,
+            nrows:      nrows,
+            ncols:      ncols,
+            scale:      scale,
+            min_height: min_height,
+            max_height: max_height
+        }
+    }
+}
+
+impl<N, V> HeightField<N, V> {
+    /// The row-major unscaled heights of this heightfield.
+    #[inline]
+    pub fn heights(&self) -> &[N] {
+        &self.heights[..]
+    }
+
+    /// The number of rows of heights.
+    #[inline]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// The number of columns of heights.
+    #[inline]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The scale of this heightfield.
+    #[inline]
+    pub fn scale(&self) -> &V {
+        &self.scale
+    }
+
+    /// The number of rows of cells.
+    #[inline]
+    pub fn ncell_rows(&self) -> usize {
+        if self.nrows == 1 { 1 } else { self.nrows - 1 }
+    }
+
+    /// The number of columns of cells.
+    #[inline]
+    pub fn ncell_cols(&self) -> usize {
+        self.ncols - 1
+    }
+
+    /// The number of parts (segments in 2D, triangles in 3D) of this heightfield.
+    #[inline]
+    pub fn num_parts(&self) -> usize {
+        self.ncell_rows() * self.ncell_cols() * self.parts_per_cell()
+    }
+
+    #[inline]
+    fn parts_per_cell(&self) -> usize {
+        if self.nrows == 1 { 1 } else { 2 }
+    }
+}
+
+impl<N, V> HeightField<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    /// The unscaled height at the given row and column.
+    #[inline]
+    pub fn height_at(&self, i: usize, j: usize) -> N {
+        self.heights[i * self.ncols + j]
+    }
+
+    /// The scaled vertex of the grid at the given row and column.
+    pub fn point_at<P: Point<N, V>>(&self, i: usize, j: usize) -> P {
+        let mut pt = na::orig::<P>();
+
+        pt[0] = self.column_coordinate(j);
+        pt[1] = self.height_at(i, j) * self.scale[1];
+
+        if na::dim::<P>() == 3 {
+            pt[2] = self.row_coordinate(i);
+        }
+
+        pt
+    }
+
+    /// The segment with the given part index.
+    ///
+    /// Only valid in 2D.
+    #[inline]
+    pub fn segment_at<P: Point<N, V>>(&self, i: usize) -> Segment<P> {
+        Segment::new(self.point_at(0, i), self.point_at(0, i + 1))
+    }
+
+    /// The triangle with the given part index.
+    ///
+    /// Only valid in 3D. The triangles are oriented toward the `y` axis.
+    pub fn triangle_at<P: Point<N, V>>(&self, i: usize) -> Triangle<P> {
+        let cell = i / 2;
+        let row  = cell / self.ncell_cols();
+        let col  = cell % self.ncell_cols();
+
+        let p00 = self.point_at(row, col);
+        let p11 = self.point_at(row + 1, col + 1);
+
+        if i % 2 == 0 {
+            Triangle::new(p00, self.point_at(row + 1, col), p11)
+        }
+        else {
+            Triangle::new(p00, p11, self.point_at(row, col + 1))
+        }
+    }
+
+    /// The AABB of the part with the given index.
+    pub fn part_aabb<P: Point<N, V>>(&self, i: usize) -> AABB<P> {
+        if self.nrows == 1 {
+            let s = self.segment_at::<P>(i);
+
+            AABB::new(na::inf(s.a(), s.b()), na::sup(s.a(), s.b()))
+        }
+        else {
+            let t = self.triangle_at::<P>(i);
+
+            AABB::new(na::inf(&na::inf(t.a(), t.b()), t.c()), na::sup(&na::sup(t.a(), t.b()), t.c()))
+        }
+    }
+
+    /// The local-space AABB of the whole heightfield.
+    #[inline]
+    pub fn local_aabb<P: Point<N, V>>(&self) -> AABB<P> {
+        self.cells_aabb(0, self.ncell_rows(), 0, self.ncell_cols())
+    }
+
+    /// The cell containing the horizontal projection of `pt`, if any.
+    ///
+    /// Returns the row and the column of the cell.
+    pub fn cell_at_point<P: Point<N, V>>(&self, pt: &P) -> Option<(usize, usize)> {
+        let half: N = na::cast(0.5f64);
+
+        for k in 0 .. na::dim::<P>() {
+            if k != 1 && na::abs(&pt[k]) > self.scale[k] * half {
+                return None;
+            }
+        }
+
+        Some(self.clamped_cell_at_point(pt))
+    }
+
+    /// The cell closest to the horizontal projection of `pt`.
+    ///
+    /// Returns the row and the column of the cell.
+    pub fn clamped_cell_at_point<P: Point<N, V>>(&self, pt: &P) -> (usize, usize) {
+        let col = clamped_cell_index(pt[0] - self.column_coordinate(0), self.cell_width(), self.ncell_cols());
+
+        if self.nrows == 1 {
+            (0, col)
+        }
+        else {
+            let row = clamped_cell_index(pt[2] - self.row_coordinate(0), self.cell_depth(), self.ncell_rows());
+
+            (row, col)
+        }
+    }
+
+    /// The scaled height of the surface at the horizontal projection of `pt`, if it lies on the
+    /// grid.
+    pub fn surface_height_at_point<P: Point<N, V>>(&self, pt: &P) -> Option<N> {
+        let (i, j) = match self.cell_at_point(pt) {
+            Some(cell) => cell,
+            None       => return None
+        };
+
+        let sy  = self.scale[1];
+        let u   = (pt[0] - self.column_coordinate(j)) / self.cell_width();
+        let h00 = self.height_at(i, j) * sy;
+        let h01 = self.height_at(i, j + 1) * sy;
+
+        if self.nrows == 1 {
+            return Some(h00 + (h01 - h00) * u);
+        }
+
+        let v   = (pt[2] - self.row_coordinate(i)) / self.cell_depth();
+        let h10 = self.height_at(i + 1, j) * sy;
+        let h11 = self.height_at(i + 1, j + 1) * sy;
+
+        // See `triangle_at` for the way each cell is split.
+        if v >= u {
+            Some(h00 + (h10 - h00) * v + (h11 - h10) * u)
+        }
+        else {
+            Some(h00 + (h01 - h00) * u + (h11 - h01) * v)
+        }
+    }
+
+    /// The indices of the parts of the cell at the given row and column.
+    #[inline]
+    pub fn cell_parts(&self, i: usize, j: usize) -> Range<usize> {
+        let first = (i * self.ncell_cols() + j) * self.parts_per_cell();
+
+        first .. first + self.parts_per_cell()
+    }
+
+    /// Visits the parts of this heightfield, using the grid as an implicit hierarchy.
+    ///
+    /// The bounding volume of a range of cells is the box spanned by the cells horizontally and by
+    /// the extremal heights of the whole heightfield vertically.
+    pub fn visit<P, Vis: ?Sized>(&self, visitor: &mut Vis)
+        where P:   Point<N, V>,
+              Vis: BVTVisitor<usize, AABB<P>> {
+        self.visit_cells(0, self.ncell_rows(), 0, self.ncell_cols(), visitor)
+    }
+
+    fn visit_cells<P, Vis: ?Sized>(&self, i0: usize, i1: usize, j0: usize, j1: usize, visitor: &mut Vis)
+        where P:   Point<N, V>,
+              Vis: BVTVisitor<usize, AABB<P>> {
+        if i1 - i0 == 1 && j1 - j0 == 1 {
+            for part in self.cell_parts(i0, j0) {
+                visitor.visit_leaf(&part, &self.part_aabb(part));
+            }
+        }
+        else if visitor.visit_internal(&self.cells_aabb(i0, i1, j0, j1)) {
+            if i1 - i0 > j1 - j0 {
+                let mid = (i0 + i1) / 2;
+                self.visit_cells(i0, mid, j0, j1, visitor);
+                self.visit_cells(mid, i1, j0, j1, visitor);
+            }
+            else {
+                let mid = (j0 + j1) / 2;
+                self.visit_cells(i0, i1, j0, mid, visitor);
+                self.visit_cells(i0, i1, mid, j1, visitor);
+            }
+        }
+    }
+
+    /// Performs a best-first search on the parts of this heightfield, using the grid as an implicit
+    /// hierarchy.
+    ///
+    /// Returns the index of the best part, and a result of user-defined type.
+    pub fn best_first_search<P, BFS: ?Sized, R>(&self, algorithm: &mut BFS) -> Option<(usize, R)>
+        where P:   Point<N, V>,
+              BFS: BVTCostFn<N, usize, AABB<P>, R> {
+        let mut queue: BinaryHeap<WeightedCells<N>> = BinaryHeap::new();
+        let mut best_cost = Bounded::max_value();
+        let mut result    = None;
+
+        let root = (0, self.ncell_rows(), 0, self.ncell_cols());
+
+        match algorithm.compute_bv_cost(&self.cells_aabb(root.0, root.1, root.2, root.3)) {
+            Some(cost) => queue.push(WeightedCells { cells: root, cost: -cost }),
+            None       => return None
+        }
+
+        loop {
+            match queue.pop() {
+                Some(node) => {
+                    if -node.cost >= best_cost {
+                        break; // solution found.
+                    }
+
+                    let (i0, i1, j0, j1) = node.cells;
+
+                    if i1 - i0 == 1 && j1 - j0 == 1 {
+                        for part in self.cell_parts(i0, j0) {
+                            match algorithm.compute_bv_cost(&self.part_aabb(part)) {
+                                Some(cost) if cost < best_cost => { },
+                                _ => continue
+                            }
+
+                            match algorithm.compute_b_cost(&part) {
+                                Some((candidate_cost, candidate_result)) => {
+                                    if candidate_cost < best_cost {
+                                        best_cost = candidate_cost;
+                                        result    = Some((part, candidate_result));
+                                    }
+                                },
+                                None => { }
+                            }
+                        }
+                    }
+                    else {
+                        let children = if i1 - i0 > j1 - j0 {
+                            let mid = (i0 + i1) / 2;
+                            [ (i0, mid, j0, j1), (mid, i1, j0, j1) ]
+                        }
+                        else {
+                            let mid = (j0 + j1) / 2;
+                            [ (i0, i1, j0, mid), (i0, i1, mid, j1) ]
+                        };
+
+                        for cells in children.iter() {
+                            let aabb = self.cells_aabb(cells.0, cells.1, cells.2, cells.3);
+
+                            match algorithm.compute_bv_cost(&aabb) {
+                                Some(cost) => {
+                                    if cost < best_cost {
+                                        queue.push(WeightedCells { cells: *cells, cost: -cost })
+                                    }
+                                },
+                                None => { }
+                            }
+                        }
+                    }
+                },
+                None => break
+            }
+        }
+
+        result
+    }
+
+    /// The AABB of the cells with rows in `[i0, i1[` and columns in `[j0, j1[`.
+    fn cells_aabb<P: Point<N, V>>(&self, i0: usize, i1: usize, j0: usize, j1: usize) -> AABB<P> {
+        let mut mins = na::orig::<P>();
+        let mut maxs = na::orig::<P>();
+
+        mins[0] = self.column_coordinate(j0);
+        maxs[0] = self.column_coordinate(j1);
+        mins[1] = self.min_height * self.scale[1];
+        maxs[1] = self.max_height * self.scale[1];
+
+        if na::dim::<P>() == 3 {
+            mins[2] = self.row_coordinate(i0);
+            maxs[2] = self.row_coordinate(i1);
+        }
+
+        AABB::new(mins, maxs)
+    }
+
+    /// The `x` coordinate of the vertices of the `j`-th column.
+    #[inline]
+    pub fn column_coordinate(&self, j: usize) -> N {
+        let half: N = na::cast(0.5f64);
+        (na::cast::<f64, N>(j as f64) / na::cast((self.ncols - 1) as f64) - half) * self.scale[0]
+    }
+
+    /// The `z` coordinate of the vertices of the `i`-th row.
+    ///
+    /// Only valid in 3D.
+    #[inline]
+    pub fn row_coordinate(&self, i: usize) -> N {
+        let half: N = na::cast(0.5f64);
+        (na::cast::<f64, N>(i as f64) / na::cast((self.nrows - 1) as f64) - half) * self.scale[2]
+    }
+
+    /// The extent of a cell along the `x` axis.
+    #[inline]
+    pub fn cell_width(&self) -> N {
+        self.scale[0] / na::cast((self.ncols - 1) as f64)
+    }
+
+    /// The extent of a cell along the `z` axis.
+    ///
+    /// Only valid in 3D.
+    #[inline]
+    pub fn cell_depth(&self) -> N {
+        self.scale[2] / na::cast((self.nrows - 1) as f64)
+    }
+}
+
+fn clamped_cell_index<N: Scalar>(offset: N, cell_size: N, ncells: usize) -> usize {
+    if offset <= na::zero() {
+        0
+    }
+    else {
+        let id = na::cast::<N, f64>(offset / cell_size) as usize;
+
+        if id >= ncells { ncells - 1 } else { id }
+    }
+}
+
+/// A range of cells packed with a cost value.
+struct WeightedCells<N> {
+    cells: (usize, usize, usize, usize),
+    cost:  N
+}
+
+impl<N: PartialEq> PartialEq for WeightedCells<N> {
+    #[inline]
+    fn eq(&self, other: &WeightedCells<N>) -> bool {
+        self.cost.eq(&other.cost)
+    }
+}
+
+impl<N: PartialEq> Eq for WeightedCells<N> {
+}
+
+impl<N: PartialOrd> PartialOrd for WeightedCells<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &WeightedCells<N>) -> Option<Ordering> {
+        self.cost.partial_cmp(&other.cost)
+    }
+}
+
+impl<N: PartialOrd> Ord for WeightedCells<N> {
+    #[inline]
+    fn cmp(&self, other: &WeightedCells<N>) -> Ordering {
+        if self.cost < other.cost {
+            Ordering::Less
+        }
+        else if self.cost > other.cost {
+            Ordering::Greater
+        }
+        else {
+            Ordering::Equal
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Vec3};
+    use bounding_volume::{AABB, BoundingVolume};
+    use partitioning::BoundingVolumeInterferencesCollector;
+    use shape::{CompositeShape, HeightField};
+
+    fn heightfield() -> HeightField<f64, Vec3<f64>> {
+        let heights = vec![
+            0.0, 1.0, 0.0,
+            1.0, 2.0, 1.0,
+            0.0, 1.0, 0.0
+        ];
+
+        HeightField::new(heights, 3, 3, Vec3::new(2.0, 1.0, 2.0))
+    }
+
+    #[test]
+    fn test_cell_parts() {
+        let h = heightfield();
+
+        assert!(h.num_parts() == 8);
+        assert!(h.cell_parts(0, 0) == (0 .. 2));
+        assert!(h.cell_parts(1, 1) == (6 .. 8));
+    }
+
+    #[test]
+    fn test_visit_all_parts() {
+        let h    = heightfield();
+        let aabb = AABB::new(Pnt3::new(-10.0, -10.0, -10.0), Pnt3::new(10.0, 10.0, 10.0));
+        let mut found = Vec::new();
+
+        {
+            let mut visitor = BoundingVolumeInterferencesCollector::new(&aabb, &mut found);
+            h.visit::<Pnt3<f64>, _>(&mut visitor);
+        }
+
+        found.sort();
+
+        assert!(found == (0 .. 8).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_visit_matches_part_aabbs() {
+        let h    = heightfield();
+        let aabb = AABB::new(Pnt3::new(0.1, -1.0, 0.1), Pnt3::new(0.4, 3.0, 0.4));
+        let mut found = Vec::new();
+
+        {
+            let mut visitor = BoundingVolumeInterferencesCollector::new(&aabb, &mut found);
+            CompositeShape::<f64, Pnt3<f64>, Vec3<f64>, ::na::Iso3<f64>>::visit(&h, &mut visitor);
+        }
+
+        found.sort();
+
+        let expected: Vec<usize> = (0 .. h.num_parts()).filter(|i| {
+            aabb.intersects(&h.part_aabb::<Pnt3<f64>>(*i))
+        }).collect();
+
+        assert!(!found.is_empty());
+        assert!(found == expected);
+    }
+}
@@ -7,16 +7,19 @@ pub use shape::capsule::Capsule;
 pub use shape::cone::Cone;
 pub use shape::cylinder::Cylinder;
 pub use shape::convex::Convex;
+pub use shape::convex_polyhedron::ConvexPolyhedron;
 pub use shape::minkowski_sum::{MinkowskiSum, AnnotatedMinkowskiSum, AnnotatedPoint};
 pub use shape::reflection::Reflection;
 pub use shape::compound::Compound;
 pub use shape::base_mesh::{BaseMesh, BaseMeshElement};
-pub use shape::trimesh::TriMesh;
+pub use shape::trimesh::{TriMesh, TriMeshPseudoNormals};
 pub use shape::polyline::Polyline;
+pub use shape::heightfield::HeightField;
+pub use shape::distance_field::DistanceField;
 pub use shape::segment::Segment;
 pub use shape::triangle::Triangle;
 pub use shape::torus::Torus;
-pub use shape::composite_shape::CompositeShape;
+pub use shape::composite_shape::{CompositeShape, BVTCompositeShape};
 
 use na::{Pnt2, Pnt3, Vec2, Vec3, Iso2, Iso3};
 
@@ -28,6 +31,8 @@ mod triangle;
 mod base_mesh;
 mod trimesh;
 mod polyline;
+mod heightfield;
+mod distance_field;
 mod ball;
 mod capsule;
 mod cone;
@@ -36,6 +41,7 @@ mod reflection;
 mod torus;
 mod compound;
 mod convex;
+mod convex_polyhedron;
 mod composite_shape;
 
 /*
@@ -50,9 +56,12 @@ mod composite_shape;
 #[doc = "A 2D cone."] pub type Cone2<N> = Cone<N>;
 #[doc = "A 2D cylinder."] pub type Cylinder2<N> = Cylinder<N>;
 #[doc = "A 2D convex polytope."] pub type Convex2<N> = Convex<Pnt2<N>>;
+#[doc = "A 2D convex polygon with explicit topology."] pub type ConvexPolyhedron2<N> = ConvexPolyhedron<Pnt2<N>, Vec2<N>>;
 #[doc = "A 2D segment."] pub type Segment2<N> = Segment<Pnt2<N>>;
 #[doc = "A 2D triangle."] pub type Triangle2<N> = Triangle<Pnt2<N>>;
 #[doc = "A 2D polyline."] pub type Polyline2<N> = Polyline<N, Pnt2<N>, Vec2<N>>;
+#[doc = "A 2D heightfield."] pub type HeightField2<N> = HeightField<N, Vec2<N>>;
+#[doc = "A 2D distance field."] pub type DistanceField2<N> = DistanceField<N, Pnt2<N>, Vec2<N>>;
 #[doc = "A 2D compound shape."] pub type Compound2<N> = Compound<N, Pnt2<N>, Vec2<N>, Iso2<N>>;
 
 #[doc = "A 3D ball."] pub type Ball3<N> = Ball<N>;
@@ -62,8 +71,11 @@ mod composite_shape;
 #[doc = "A 3D cone."] pub type Cone3<N> = Cone<N>;
 #[doc = "A 3D cylinder."] pub type Cylinder3<N> = Cylinder<N>;
 #[doc = "A 3D convex polytope."] pub type Convex3<N> = Convex<Pnt3<N>>;
+#[doc = "A 3D convex polyhedron with explicit topology."] pub type ConvexPolyhedron3<N> = ConvexPolyhedron<Pnt3<N>, Vec3<N>>;
 #[doc = "A 3D segment."] pub type Segment3<N> = Segment<Pnt3<N>>;
 #[doc = "A 3D triangle."] pub type Triangle3<N> = Triangle<Pnt3<N>>;
 #[doc = "A 3D polyline."] pub type Polyline3<N> = Polyline<N, Pnt3<N>, Vec3<N>>;
 #[doc = "A 3D triangle mesh."] pub type TriMesh3<N> = TriMesh<N, Pnt3<N>, Vec3<N>>;
+#[doc = "A 3D heightfield."] pub type HeightField3<N> = HeightField<N, Vec3<N>>;
+#[doc = "A 3D distance field."] pub type DistanceField3<N> = DistanceField<N, Pnt3<N>, Vec3<N>>;
 #[doc = "A 3D compound shape."] pub type Compound3<N> = Compound<N, Pnt3<N>, Vec3<N>, Iso3<N>>;
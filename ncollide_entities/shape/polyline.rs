@@ -2,9 +2,9 @@
 
 use std::mem;
 use std::sync::Arc;
-use na::{Translate, Dim, Pnt2};
-use partitioning::BVT;
-use bounding_volume::AABB;
+use na::{Translate, Translation, Dim, Pnt2};
+use partitioning::{BVT, BinaryPartition, median_partitioner};
+use bounding_volume::{AABB, KDOP, BoundingVolume};
 use shape::{Segment, BaseMesh};
 use math::{Scalar, Point, Vect};
 
@@ -36,6 +36,76 @@ impl<N, P, V> Polyline<N, P, V>
             mesh: BaseMesh::new(vertices, indices, uvs, normals)
         }
     }
+
+    /// Builds a new mesh which BVT is constructed by a user-defined partitioner.
+    ///
+    /// See `BVT::new_with_partitioner` for details about the `partitioner`.
+    pub fn new_with_partitioner<F>(vertices:    Arc<Vec<P>>,
+                                   indices:     Arc<Vec<Pnt2<usize>>>,
+                                   uvs:         Option<Arc<Vec<Pnt2<N>>>>,
+                                   normals:     Option<Arc<Vec<V>>>,
+                                   partitioner: &mut F)
+                                   -> Polyline<N, P, V>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        Polyline {
+            mesh: BaseMesh::new_with_partitioner(vertices, indices, uvs, normals, partitioner)
+        }
+    }
+
+    /// Builds a new mesh with an additional k-DOP BVT.
+    ///
+    /// Ray casts and point queries traverse the k-DOP BVT instead of the AABB one. `axes` are the
+    /// axes of the k-DOPs, see `bounding_volume::kdop_axes`.
+    pub fn new_with_kdop(vertices: Arc<Vec<P>>,
+                         indices:  Arc<Vec<Pnt2<usize>>>,
+                         uvs:      Option<Arc<Vec<Pnt2<N>>>>,
+                         normals:  Option<Arc<Vec<V>>>,
+                         axes:     Arc<Vec<V>>)
+                         -> Polyline<N, P, V> {
+        let mut res = Polyline::new(vertices, indices, uvs, normals);
+
+        res.mesh.build_kdop_bvt(axes);
+
+        res
+    }
+
+    /// Moves the vertices of this mesh and refits its BVT.
+    ///
+    /// See `BaseMesh::set_vertices` for details.
+    #[inline]
+    pub fn set_vertices(&mut self,
+                        vertices:          Arc<Vec<P>>,
+                        normals:           Option<Arc<Vec<V>>>,
+                        rebuild_threshold: Option<N>)
+                        -> bool {
+        self.set_vertices_with_partitioner(vertices, normals, rebuild_threshold, &mut median_partitioner)
+    }
+
+    /// Moves the vertices of this mesh and refits its BVT, rebuilding it with `partitioner` if needed.
+    ///
+    /// See `BaseMesh::set_vertices_with_partitioner` for details.
+    pub fn set_vertices_with_partitioner<F>(&mut self,
+                                            vertices:          Arc<Vec<P>>,
+                                            normals:           Option<Arc<Vec<V>>>,
+                                            rebuild_threshold: Option<N>,
+                                            partitioner:       &mut F)
+                                            -> bool
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        self.mesh.set_vertices_with_partitioner(vertices, normals, rebuild_threshold, partitioner)
+    }
+
+    /// Rebuilds the BVT of this mesh from scratch with a user-defined partitioner.
+    #[inline]
+    pub fn rebuild_bvt_with_partitioner<F>(&mut self, partitioner: &mut F)
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        self.mesh.rebuild_bvt_with_partitioner(partitioner)
+    }
+
+    /// The ratio between the current cost of the BVT and its cost when it was built.
+    #[inline]
+    pub fn bvt_degradation(&self) -> N {
+        self.mesh.bvt_degradation()
+    }
 }
 
 impl<N, P, V> Polyline<N, P, V> {
@@ -80,6 +150,12 @@ impl<N, P, V> Polyline<N, P, V> {
     pub fn bvt(&self) -> &BVT<usize, AABB<P>> {
         self.mesh.bvt()
     }
+
+    /// The k-DOP BVT used by ray casts and point queries, if this mesh has been built with one.
+    #[inline]
+    pub fn kdop_bvt(&self) -> Option<&BVT<usize, KDOP<N, V>>> {
+        self.mesh.kdop_bvt()
+    }
 }
 
 impl<N, P: Send + Sync + Copy + Dim, V> Polyline<N, P, V> {
@@ -89,3 +165,16 @@ impl<N, P: Send + Sync + Copy + Dim, V> Polyline<N, P, V> {
         self.mesh.element_at(i)
     }
 }
+
+impl<N, P, V> Polyline<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// Builds a balanced BVT of the elements of this mesh, bounded by a custom bounding volume.
+    #[inline]
+    pub fn custom_bvt<BV, F>(&self, bounding_volume: &mut F) -> BVT<usize, BV>
+        where BV: Translation<V> + BoundingVolume<N> + Clone,
+              F:  FnMut(&Segment<P>) -> BV {
+        self.mesh.custom_bvt(bounding_volume)
+    }
+}
@@ -1,15 +1,22 @@
 //! 2d line strip, 3d triangle mesh, and nd subsimplex mesh.
 
 use std::sync::Arc;
-use na::{Translate, Dim, Pnt2, Pnt3};
-use partitioning::BVT;
-use bounding_volume::AABB;
+use std::collections::HashMap;
+use na::{Translate, Translation, Cross, Dim, Pnt2, Pnt3, BaseFloat};
+use na;
+use partitioning::{BVT, BinaryPartition, median_partitioner};
+use bounding_volume::{AABB, KDOP, BoundingVolume, BoundingSphere, SpacializedCone};
+use bounding_volume;
 use shape::{Triangle, BaseMesh};
+use utils;
 use math::{Scalar, Point, Vect};
 
 /// Shape commonly known as a 2d line strip or a 3d triangle mesh.
 pub struct TriMesh<N, P, V> {
-    mesh: BaseMesh<N, P, V, Pnt3<usize>, Triangle<P>>
+    mesh:           BaseMesh<N, P, V, Pnt3<usize>, Triangle<P>>,
+    closed:         bool,
+    watertight:     bool,
+    pseudo_normals: Option<Arc<TriMeshPseudoNormals<V>>>
 }
 
 impl<N, P, V> Clone for TriMesh<N, P, V>
@@ -17,7 +24,12 @@ impl<N, P, V> Clone for TriMesh<N, P, V>
           P: Send + Sync + Clone,
           V: Send + Sync {
     fn clone(&self) -> TriMesh<N, P, V> {
-        self.clone()
+        TriMesh {
+            mesh:           self.mesh.clone(),
+            closed:         self.closed,
+            watertight:     self.watertight,
+            pseudo_normals: self.pseudo_normals.clone()
+        }
     }
 }
 
@@ -32,9 +44,159 @@ impl<N, P, V> TriMesh<N, P, V>
                normals:  Option<Arc<Vec<V>>>) // a loosening margin for the BVT.
                -> TriMesh<N, P, V> {
         TriMesh {
-            mesh: BaseMesh::new(vertices, indices, uvs, normals)
+            mesh:           BaseMesh::new(vertices, indices, uvs, normals),
+            closed:         false,
+            watertight:     false,
+            pseudo_normals: None
+        }
+    }
+
+    /// Builds a new mesh flagged as closed.
+    ///
+    /// The mesh must be watertight, i.e., each edge must be shared by exactly two triangles. Point
+    /// queries will then consider that the mesh bounds a solid volume. In 3d, the pseudo-normals
+    /// used by signed distance queries are computed as well. Panics if the mesh is not watertight.
+    pub fn new_closed(vertices: Arc<Vec<P>>,
+                      indices:  Arc<Vec<Pnt3<usize>>>,
+                      uvs:      Option<Arc<Vec<Pnt2<N>>>>,
+                      normals:  Option<Arc<Vec<V>>>)
+                      -> TriMesh<N, P, V> {
+        TriMesh::new_closed_with_partitioner(vertices, indices, uvs, normals, &mut median_partitioner)
+    }
+
+    /// Builds a new mesh flagged as closed which BVT is constructed by a user-defined partitioner.
+    ///
+    /// See `TriMesh::new_closed` and `BVT::new_with_partitioner` for details.
+    pub fn new_closed_with_partitioner<F>(vertices:    Arc<Vec<P>>,
+                                          indices:     Arc<Vec<Pnt3<usize>>>,
+                                          uvs:         Option<Arc<Vec<Pnt2<N>>>>,
+                                          normals:     Option<Arc<Vec<V>>>,
+                                          partitioner: &mut F)
+                                          -> TriMesh<N, P, V>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        assert!(is_watertight(&indices[..]), "A mesh flagged as closed must be watertight.");
+
+        TriMesh::new_closed_mesh(vertices, indices, uvs, normals, partitioner, true)
+    }
+
+    /// Builds a new mesh flagged as closed, even if it is not watertight.
+    ///
+    /// This is meant for meshes which bound a solid volume despite a few defects, e.g., duplicate
+    /// or missing triangles. If the mesh is not watertight, point queries fall back on ray parity
+    /// tests instead of pseudo-normals. Those are slower, and meaningless if the mesh does not
+    /// bound a volume.
+    pub fn new_closed_lenient<F>(vertices:    Arc<Vec<P>>,
+                                 indices:     Arc<Vec<Pnt3<usize>>>,
+                                 uvs:         Option<Arc<Vec<Pnt2<N>>>>,
+                                 normals:     Option<Arc<Vec<V>>>,
+                                 partitioner: &mut F)
+                                 -> TriMesh<N, P, V>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        let watertight = is_watertight(&indices[..]);
+
+        TriMesh::new_closed_mesh(vertices, indices, uvs, normals, partitioner, watertight)
+    }
+
+    fn new_closed_mesh<F>(vertices:    Arc<Vec<P>>,
+                          indices:     Arc<Vec<Pnt3<usize>>>,
+                          uvs:         Option<Arc<Vec<Pnt2<N>>>>,
+                          normals:     Option<Arc<Vec<V>>>,
+                          partitioner: &mut F,
+                          watertight:  bool)
+                          -> TriMesh<N, P, V>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        let pseudo_normals = if na::dim::<P>() == 3 {
+            Some(Arc::new(TriMeshPseudoNormals::new(&vertices[..], &indices[..])))
+        }
+        else {
+            None
+        };
+
+        TriMesh {
+            mesh:           BaseMesh::new_with_partitioner(vertices, indices, uvs, normals, partitioner),
+            closed:         true,
+            watertight:     watertight,
+            pseudo_normals: pseudo_normals
+        }
+    }
+
+    /// Builds a new mesh which BVT is constructed by a user-defined partitioner.
+    ///
+    /// See `BVT::new_with_partitioner` for details about the `partitioner`.
+    pub fn new_with_partitioner<F>(vertices:    Arc<Vec<P>>,
+                                   indices:     Arc<Vec<Pnt3<usize>>>,
+                                   uvs:         Option<Arc<Vec<Pnt2<N>>>>,
+                                   normals:     Option<Arc<Vec<V>>>,
+                                   partitioner: &mut F)
+                                   -> TriMesh<N, P, V>
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        TriMesh {
+            mesh:           BaseMesh::new_with_partitioner(vertices, indices, uvs, normals, partitioner),
+            closed:         false,
+            watertight:     false,
+            pseudo_normals: None
         }
     }
+
+    /// Builds a new mesh with an additional k-DOP BVT.
+    ///
+    /// Ray casts and point queries traverse the k-DOP BVT instead of the AABB one. `axes` are the
+    /// axes of the k-DOPs, see `bounding_volume::kdop_axes`.
+    pub fn new_with_kdop(vertices: Arc<Vec<P>>,
+                         indices:  Arc<Vec<Pnt3<usize>>>,
+                         uvs:      Option<Arc<Vec<Pnt2<N>>>>,
+                         normals:  Option<Arc<Vec<V>>>,
+                         axes:     Arc<Vec<V>>)
+                         -> TriMesh<N, P, V> {
+        let mut res = TriMesh::new(vertices, indices, uvs, normals);
+
+        res.mesh.build_kdop_bvt(axes);
+
+        res
+    }
+
+    /// Moves the vertices of this mesh and refits its BVT.
+    ///
+    /// See `BaseMesh::set_vertices` for details.
+    #[inline]
+    pub fn set_vertices(&mut self,
+                        vertices:          Arc<Vec<P>>,
+                        normals:           Option<Arc<Vec<V>>>,
+                        rebuild_threshold: Option<N>)
+                        -> bool {
+        self.set_vertices_with_partitioner(vertices, normals, rebuild_threshold, &mut median_partitioner)
+    }
+
+    /// Moves the vertices of this mesh and refits its BVT, rebuilding it with `partitioner` if needed.
+    ///
+    /// See `BaseMesh::set_vertices_with_partitioner` for details.
+    pub fn set_vertices_with_partitioner<F>(&mut self,
+                                            vertices:          Arc<Vec<P>>,
+                                            normals:           Option<Arc<Vec<V>>>,
+                                            rebuild_threshold: Option<N>,
+                                            partitioner:       &mut F)
+                                            -> bool
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        if self.pseudo_normals.is_some() {
+            let pseudo_normals  = TriMeshPseudoNormals::new(&vertices[..], &self.indices()[..]);
+            self.pseudo_normals = Some(Arc::new(pseudo_normals));
+        }
+
+        self.mesh.set_vertices_with_partitioner(vertices, normals, rebuild_threshold, partitioner)
+    }
+
+    /// Rebuilds the BVT of this mesh from scratch with a user-defined partitioner.
+    #[inline]
+    pub fn rebuild_bvt_with_partitioner<F>(&mut self, partitioner: &mut F)
+        where F: FnMut(usize, Vec<(usize, AABB<P>)>) -> (AABB<P>, BinaryPartition<usize, AABB<P>>) {
+        self.mesh.rebuild_bvt_with_partitioner(partitioner)
+    }
+
+    /// The ratio between the current cost of the BVT and its cost when it was built.
+    #[inline]
+    pub fn bvt_degradation(&self) -> N {
+        self.mesh.bvt_degradation()
+    }
 }
 
 impl<N, P, V> TriMesh<N, P, V> {
@@ -44,6 +206,27 @@ impl<N, P, V> TriMesh<N, P, V> {
         &self.mesh
     }
 
+    /// Whether this mesh has been flagged as closed at construction.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Whether this mesh has been flagged as closed and each of its edges is shared by exactly two
+    /// triangles.
+    #[inline]
+    pub fn is_watertight(&self) -> bool {
+        self.watertight
+    }
+
+    /// The pseudo-normals of this mesh.
+    ///
+    /// Those are available only for 3d meshes flagged as closed at construction.
+    #[inline]
+    pub fn pseudo_normals(&self) -> Option<&TriMeshPseudoNormals<V>> {
+        self.pseudo_normals.as_ref().map(|pn| &**pn)
+    }
+
     /// The vertices of this mesh.
     #[inline]
     pub fn vertices(&self) -> &Arc<Vec<P>> {
@@ -79,6 +262,12 @@ impl<N, P, V> TriMesh<N, P, V> {
     pub fn bvt(&self) -> &BVT<usize, AABB<P>> {
         self.mesh.bvt()
     }
+
+    /// The k-DOP BVT used by ray casts and point queries, if this mesh has been built with one.
+    #[inline]
+    pub fn kdop_bvt(&self) -> Option<&BVT<usize, KDOP<N, V>>> {
+        self.mesh.kdop_bvt()
+    }
 }
 
 impl<N, P: Send + Sync + Copy + Dim, V> TriMesh<N, P, V> {
@@ -88,3 +277,306 @@ impl<N, P: Send + Sync + Copy + Dim, V> TriMesh<N, P, V> {
         self.mesh.element_at(i)
     }
 }
+
+impl<N, P, V> TriMesh<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// Builds a balanced BVT of the elements of this mesh, bounded by a custom bounding volume.
+    #[inline]
+    pub fn custom_bvt<BV, F>(&self, bounding_volume: &mut F) -> BVT<usize, BV>
+        where BV: Translation<V> + BoundingVolume<N> + Clone,
+              F:  FnMut(&Triangle<P>) -> BV {
+        self.mesh.custom_bvt(bounding_volume)
+    }
+}
+
+impl<N, P, V> TriMesh<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P> + Cross<Output = V> {
+    /// Builds a balanced BVT of the triangles of this mesh, bounded by normal cones.
+    ///
+    /// Each triangle is bounded by its bounding sphere and the cone containing only its normal.
+    /// Degenerate triangles have a normal cone containing every direction, around an arbitrary unit
+    /// axis. A connected patch which normal cone satisfies `!cone.may_fold_back()` cannot fold back
+    /// on itself.
+    pub fn normal_cone_bvt(&self) -> BVT<usize, SpacializedCone<N, P, V>> {
+        self.custom_bvt(&mut |t: &Triangle<P>| {
+            let pts        = [ *t.a(), *t.b(), *t.c() ];
+            let (c, r)     = bounding_volume::point_cloud_bounding_sphere(&pts[..]);
+            let sphere     = BoundingSphere::new(c, r);
+            let mut normal = na::cross(&(*t.b() - *t.a()), &(*t.c() - *t.a()));
+
+            if na::is_zero(&normal.normalize()) {
+                let axis = na::canonical_basis_element(0).unwrap();
+
+                unsafe { SpacializedCone::new_normalized(sphere, axis, BaseFloat::pi()) }
+            }
+            else {
+                unsafe { SpacializedCone::new_normalized(sphere, normal, na::zero()) }
+            }
+        })
+    }
+}
+
+/// Angle-weighted pseudo-normals of a closed 3d triangle mesh.
+///
+/// Let `proj` be the projection of a point `p` on the mesh, and `n` the pseudo-normal of the
+/// feature (vertex, edge, or face) containing `proj`. Then `p` is outside of the mesh iff.
+/// `dot(p - proj, n) >= 0`.
+pub struct TriMeshPseudoNormals<V> {
+    vertices: Vec<V>,
+    edges:    Vec<V>,
+    faces:    Vec<V>
+}
+
+impl<V> TriMeshPseudoNormals<V> {
+    /// Computes the pseudo-normals of a closed 3d triangle mesh.
+    ///
+    /// The pseudo-normal of a vertex is the sum of the normals of its adjacent faces, weighted by
+    /// their angle at this vertex. The pseudo-normal of an edge is the sum of the normals of its
+    /// two adjacent faces.
+    pub fn new<N, P>(vertices: &[P], indices: &[Pnt3<usize>]) -> TriMeshPseudoNormals<V>
+        where N: Scalar,
+              P: Point<N, V>,
+              V: Vect<N> {
+        assert!(na::dim::<P>() == 3, "Pseudo-normals are only defined in 3d.");
+
+        let mut vnormals = vec![ na::zero::<V>(); vertices.len() ];
+        let mut enormals = HashMap::new();
+        let mut fnormals = Vec::with_capacity(indices.len());
+
+        for idx in indices.iter() {
+            let pts        = [ vertices[idx.x], vertices[idx.y], vertices[idx.z] ];
+            let mut normal = utils::cross3(&(pts[1] - pts[0]), &(pts[2] - pts[0]));
+
+            if na::is_zero(&normal.normalize()) {
+                normal = na::zero();
+            }
+
+            for i in 0 .. 3 {
+                let e1 = pts[(i + 1) % 3] - pts[i];
+                let e2 = pts[(i + 2) % 3] - pts[i];
+
+                if !na::is_zero(&na::sqnorm(&e1)) && !na::is_zero(&na::sqnorm(&e2)) {
+                    let cos = na::dot(&na::normalize(&e1), &na::normalize(&e2));
+                    let ang = na::clamp(cos, -na::one::<N>(), na::one()).acos();
+
+                    vnormals[idx[i]] = vnormals[idx[i]] + normal * ang;
+                }
+
+                let key = edge_key(idx[i], idx[(i + 1) % 3]);
+                let sum = enormals.get(&key).map(|n: &V| *n).unwrap_or(na::zero());
+
+                let _ = enormals.insert(key, sum + normal);
+            }
+
+            fnormals.push(normal);
+        }
+
+        for n in vnormals.iter_mut() {
+            let _ = n.normalize();
+        }
+
+        let mut edges = Vec::with_capacity(indices.len() * 3);
+
+        for idx in indices.iter() {
+            for i in 0 .. 3 {
+                let mut n = *enormals.get(&edge_key(idx[i], idx[(i + 1) % 3])).unwrap();
+                let _     = n.normalize();
+
+                edges.push(n);
+            }
+        }
+
+        TriMeshPseudoNormals {
+            vertices: vnormals,
+            edges:    edges,
+            faces:    fnormals
+        }
+    }
+
+    /// The pseudo-normal of the i-th vertex.
+    #[inline]
+    pub fn vertex_normal(&self, i: usize) -> &V {
+        &self.vertices[i]
+    }
+
+    /// The pseudo-normal of the j-th edge (`ab`, `bc`, or `ca`) of the i-th triangle.
+    #[inline]
+    pub fn edge_normal(&self, i: usize, j: usize) -> &V {
+        &self.edges[i * 3 + j]
+    }
+
+    /// The normal of the i-th triangle.
+    #[inline]
+    pub fn face_normal(&self, i: usize) -> &V {
+        &self.faces[i]
+    }
+}
+
+#[inline]
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// Tests if each edge of a triangle mesh is shared by exactly two triangles.
+fn is_watertight(indices: &[Pnt3<usize>]) -> bool {
+    let mut counts = HashMap::new();
+
+    for idx in indices.iter() {
+        for i in 0 .. 3 {
+            let key   = edge_key(idx[i], idx[(i + 1) % 3]);
+            let count = counts.get(&key).map(|c: &usize| *c).unwrap_or(0);
+
+            let _ = counts.insert(key, count + 1);
+        }
+    }
+
+    counts.values().all(|c| *c == 2)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::{Pnt3, Vec3};
+    use na;
+    use partitioning::{sah_partitioner, median_partitioner};
+    use shape::{TriMesh, TriMesh3};
+
+    // Sixteen disjoint triangles, the i-th one being placed at `x = 2 * slot(i)`.
+    fn strip_vertices<F: Fn(usize) -> usize>(slot: F) -> Vec<Pnt3<f64>> {
+        let mut res = Vec::new();
+
+        for i in 0usize .. 16 {
+            let x = (slot(i) * 2) as f64;
+
+            res.push(Pnt3::new(x, 0.0, 0.0));
+            res.push(Pnt3::new(x + 1.0, 0.0, 0.0));
+            res.push(Pnt3::new(x, 1.0, 0.0));
+        }
+
+        res
+    }
+
+    fn strip() -> TriMesh3<f64> {
+        let indices = (0usize .. 16).map(|i| Pnt3::new(3 * i, 3 * i + 1, 3 * i + 2)).collect();
+
+        TriMesh::new(Arc::new(strip_vertices(|i| i)), Arc::new(indices), None, None)
+    }
+
+    #[test]
+    fn test_set_vertices_refits() {
+        let mut mesh = strip();
+        let moved    = strip_vertices(|i| i).iter().map(|p| *p + Vec3::new(0.0, 10.0, 0.0)).collect();
+
+        assert!(!mesh.set_vertices(Arc::new(moved), None, Some(1.5)));
+
+        let root = mesh.bvt().root_bounding_volume().unwrap();
+
+        assert!(na::approx_eq(root.mins(), &Pnt3::new(0.0, 10.0, 0.0)));
+        assert!(na::approx_eq(root.maxs(), &Pnt3::new(31.0, 11.0, 0.0)));
+        assert!(na::approx_eq(&mesh.bvt_degradation(), &1.0));
+    }
+
+    #[test]
+    fn test_set_vertices_rebuilds_degraded_bvt() {
+        let mut mesh = strip();
+
+        // Without threshold the tree is only refitted, even if its quality drops.
+        assert!(!mesh.set_vertices(Arc::new(strip_vertices(|i| (i * 7) % 16)), None, None));
+        assert!(mesh.bvt_degradation() > 1.5);
+
+        let mut mesh = strip();
+
+        assert!(mesh.set_vertices(Arc::new(strip_vertices(|i| (i * 7) % 16)), None, Some(1.5)));
+        assert!(na::approx_eq(&mesh.bvt_degradation(), &1.0));
+    }
+
+    #[test]
+    fn test_set_vertices_rebuilds_with_the_given_partitioner() {
+        let mut mesh   = strip();
+        let mut ncalls = 0usize;
+        let rebuilt    = mesh.set_vertices_with_partitioner(
+            Arc::new(strip_vertices(|i| (i * 7) % 16)), None, Some(1.5),
+            &mut |depth, leaves| { ncalls += 1; sah_partitioner(depth, leaves) });
+
+        assert!(rebuilt);
+        assert!(ncalls != 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_vertices_rejects_topology_changes() {
+        let mut mesh = strip();
+        let mut vs   = strip_vertices(|i| i);
+        let _ = vs.pop();
+
+        let _ = mesh.set_vertices(Arc::new(vs), None, None);
+    }
+
+    #[test]
+    fn test_normal_cone_bvt_of_flat_mesh() {
+        let mesh = strip();
+        let bvt  = mesh.normal_cone_bvt();
+        let root = bvt.root_bounding_volume().unwrap();
+
+        assert!(na::approx_eq(&root.hangle(), &0.0));
+        assert!(na::approx_eq(root.axis(), &Vec3::z()));
+        assert!(!root.may_fold_back());
+    }
+
+    #[test]
+    fn test_normal_cone_bvt_of_closed_mesh() {
+        let vertices = vec!(Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(1.0, 0.0, 0.0),
+                            Pnt3::new(0.0, 1.0, 0.0), Pnt3::new(0.0, 0.0, 1.0));
+        let indices  = vec!(Pnt3::new(0usize, 2, 1), Pnt3::new(0, 1, 3), Pnt3::new(0, 3, 2),
+                            Pnt3::new(1, 2, 3));
+        let mesh     = TriMesh::new(Arc::new(vertices), Arc::new(indices), None, None);
+        let bvt      = mesh.normal_cone_bvt();
+
+        assert!(bvt.root_bounding_volume().unwrap().may_fold_back());
+    }
+
+    #[test]
+    fn test_normal_cone_of_degenerate_triangle() {
+        let vertices = vec!(Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(1.0, 0.0, 0.0),
+                            Pnt3::new(2.0, 0.0, 0.0));
+        let mesh     = TriMesh::new(Arc::new(vertices), Arc::new(vec!(Pnt3::new(0usize, 1, 2))), None, None);
+        let bvt      = mesh.normal_cone_bvt();
+        let cone     = bvt.root_bounding_volume().unwrap();
+
+        assert!(na::approx_eq(&na::norm(cone.axis()), &1.0));
+        assert!(cone.may_fold_back());
+        assert!(cone.contains_direction(&Vec3::y()) && cone.contains_direction(&-Vec3::x()));
+    }
+
+    #[test]
+    fn test_new_closed_with_partitioner() {
+        let vertices = vec!(Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(1.0, 0.0, 0.0),
+                            Pnt3::new(0.0, 1.0, 0.0), Pnt3::new(0.0, 0.0, 1.0));
+        let indices  = vec!(Pnt3::new(0usize, 2, 1), Pnt3::new(0, 1, 3), Pnt3::new(0, 3, 2),
+                            Pnt3::new(1, 2, 3));
+        let mesh     = TriMesh::new_closed_with_partitioner(Arc::new(vertices), Arc::new(indices), None, None,
+                                                            &mut sah_partitioner);
+
+        assert!(mesh.is_closed() && mesh.is_watertight());
+        assert!(mesh.pseudo_normals().is_some());
+    }
+
+    #[test]
+    fn test_new_closed_lenient_accepts_open_mesh() {
+        let strip = strip();
+        let mesh  = TriMesh::new_closed_lenient(strip.vertices().clone(), strip.indices().clone(), None, None,
+                                                &mut median_partitioner);
+
+        assert!(mesh.is_closed() && !mesh.is_watertight());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_closed_rejects_open_mesh() {
+        let _ = TriMesh::new_closed(strip().vertices().clone(), strip().indices().clone(), None, None);
+    }
+}
@@ -1,7 +1,7 @@
 use na::{Transform, Rotate};
 use support_map::{SupportMap, PreferedSamplingDirections};
 use support_map;
-use shape::Convex;
+use shape::{Convex, ConvexPolyhedron};
 use math::{Scalar, Point, Vect};
 
 
@@ -26,3 +26,29 @@ impl<P, V, M> PreferedSamplingDirections<V, M> for Convex<P> {
     fn sample(&self, _: &M, _: &mut FnMut(V)) {
     }
 }
+
+#[old_impl_check]
+impl<N, P, V, M> SupportMap<P, V, M> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+    #[inline]
+    fn support_point(&self, m: &M, dir: &V) -> P {
+        let local_dir = m.inv_rotate(dir);
+
+        let best_pt = support_map::point_cloud_support_point(&local_dir, self.vertices());
+
+        m.transform(&best_pt)
+    }
+}
+
+impl<P, V: Clone, M> PreferedSamplingDirections<V, M> for ConvexPolyhedron<P, V>
+    where M: Rotate<V> {
+    #[inline(always)]
+    fn sample(&self, transform: &M, f: &mut FnMut(V)) {
+        for n in self.normals().iter() {
+            f(transform.rotate(n))
+        }
+    }
+}
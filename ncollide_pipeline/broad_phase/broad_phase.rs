@@ -1,7 +1,10 @@
+use entities::partitioning::{BVTCostFn, BVTVisitor};
 use queries::ray::Ray;
 
 /// Trait all broad phase must implement.
-pub trait BroadPhase<P, V, BV, T> {
+///
+/// `N` is the scalar type of the costs used by best-first searches.
+pub trait BroadPhase<N, P, V, BV, T> {
     /// Tells the broad phase to add an element during the next update.
     fn defered_add(&mut self, uid: usize, bv: BV, data: T);
 
@@ -14,6 +17,19 @@ pub trait BroadPhase<P, V, BV, T> {
     /// Updates the object additions, removals, and interferences detection.
     fn update(&mut self, allow_proximity: &mut FnMut(&T, &T) -> bool, proximity_handler: &mut FnMut(&T, &T, bool));
 
+    /// Number of interferences detected by this broad phase.
+    ///
+    /// The default implementation returns zero.
+    fn num_interferences(&self) -> usize {
+        0
+    }
+
+    /// Visits the bounding volumes of the acceleration structures of this broad phase.
+    ///
+    /// The default implementation visits nothing.
+    fn visit(&self, _: &mut BVTVisitor<T, BV>) {
+    }
+
     /*
      * FIXME: the following are not flexible enough.
      */
@@ -26,4 +42,14 @@ pub trait BroadPhase<P, V, BV, T> {
 
     /// Collects every object which might contain a given point.
     fn interferences_with_point<'a>(&'a self, point: &P, out: &mut Vec<&'a T>);
+
+    /// Collects the `k` objects with the smallest cost, sorted by increasing cost.
+    ///
+    /// Objects with a cost greater than `max_cost` are ignored. The cost of a bounding volume must
+    /// be a lower bound of the cost of any object it contains.
+    fn k_best_first_search<'a>(&'a self,
+                               k:        usize,
+                               max_cost: N,
+                               cost_fn:  &mut BVTCostFn<N, T, BV, ()>,
+                               out:      &mut Vec<(&'a T, N)>);
 }
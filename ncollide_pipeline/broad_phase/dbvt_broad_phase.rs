@@ -1,23 +1,69 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::marker::PhantomData;
 use na::Translation;
 use na;
 use utils::data::uid_remap::{UidRemap, FastKey};
 use utils::data::pair::{Pair, PairTWHash};
 use utils::data::hash_map::HashMap;
 use math::{Scalar, Point, Vect};
-use entities::bounding_volume::{HasBoundingVolume, BoundingVolume, BoundingVolumeInterferencesCollector};
-use entities::partitioning::{DBVT, DBVTLeaf};
+use entities::bounding_volume::{BoundingVolume, HasSurfaceArea, BoundingVolumeInterferencesCollector};
+use entities::partitioning::{DBVT, DBVTLeafId, BVTCostFn, BVTVisitor};
 use queries::ray::{Ray, LocalRayCast, RayInterferencesCollector};
 use queries::point::{LocalPointQuery, PointInterferencesCollector};
 use broad_phase::BroadPhase;
 
-struct DBVTBroadPhaseProxy<P, BV, T> {
+struct DBVTBroadPhaseProxy<T> {
     data:   T,
-    leaf:   Rc<RefCell<DBVTLeaf<P, FastKey, BV>>>,
+    leaf:   Option<DBVTLeafId>, // `None` => not on any tree.
     active: isize // Negative => removed.
 }
 
+/// Cost function forwarding the costs of the proxies to a cost function on the proxies data.
+struct ProxyCostFn<'a, 'b, N: 'b, BV: 'b, T: 'a> {
+    proxies: &'a UidRemap<DBVTBroadPhaseProxy<T>>,
+    cost_fn: &'b mut (BVTCostFn<N, T, BV, ()> + 'b)
+}
+
+impl<'a, 'b, N, BV, T> BVTCostFn<N, FastKey, BV, ()> for ProxyCostFn<'a, 'b, N, BV, T> {
+    #[inline]
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        self.cost_fn.compute_bv_cost(bv)
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, proxy_key: &FastKey) -> Option<(N, ())> {
+        let proxy = &self.proxies[*proxy_key];
+
+        if proxy.active < 0 {
+            None // Removed.
+        }
+        else {
+            self.cost_fn.compute_b_cost(&proxy.data)
+        }
+    }
+}
+
+/// Visitor forwarding the visit of the proxies to a visitor of the proxies data.
+struct ProxyVisitor<'a, 'b, BV: 'b, T: 'a> {
+    proxies: &'a UidRemap<DBVTBroadPhaseProxy<T>>,
+    visitor: &'b mut (BVTVisitor<T, BV> + 'b)
+}
+
+impl<'a, 'b, BV, T> BVTVisitor<FastKey, BV> for ProxyVisitor<'a, 'b, BV, T> {
+    #[inline]
+    fn visit_internal(&mut self, bv: &BV) -> bool {
+        self.visitor.visit_internal(bv)
+    }
+
+    #[inline]
+    fn visit_leaf(&mut self, proxy_key: &FastKey, bv: &BV) {
+        let proxy = &self.proxies[*proxy_key];
+
+        if proxy.active >= 0 {
+            self.visitor.visit_leaf(&proxy.data, bv)
+        }
+    }
+}
+
 const DEACTIVATION_THRESHOLD: isize = 100;
 const REMOVE_FROM_TREE: isize = -1;
 const REMOVE_FROM_STREE: isize = -2;
@@ -28,9 +74,9 @@ const REMOVE_FROM_STREE: isize = -2;
 /// It uses two separate trees: one for static objects and which is never updated, and one for
 /// moving objects.
 pub struct DBVTBroadPhase<N, P, BV, T> {
-    proxies:    UidRemap<DBVTBroadPhaseProxy<P, BV, T>>,
-    tree:       DBVT<P, FastKey, BV>, // DBVT for moving objects.
-    stree:      DBVT<P, FastKey, BV>, // DBVT for static objects.
+    proxies:    UidRemap<DBVTBroadPhaseProxy<T>>,
+    tree:       DBVT<FastKey, BV>, // DBVT for moving objects.
+    stree:      DBVT<FastKey, BV>, // DBVT for static objects.
     pairs:      HashMap<Pair, (), PairTWHash>, // Pairs detected (FIXME: use a Vec instead?)
     margin:     N, // The margin added to each bounding volume.
     update_off: usize, // Incremental pairs removal index.
@@ -39,6 +85,7 @@ pub struct DBVTBroadPhase<N, P, BV, T> {
     // Just to avoid dynamic allocations.
     collector:  Vec<FastKey>,
     to_update:  Vec<(FastKey, BV)>,
+    point_type: PhantomData<P>
 }
 
 #[old_impl_check]
@@ -46,7 +93,7 @@ impl<N, P, V, BV, T> DBVTBroadPhase<N, P, BV, T>
     where N:  Scalar,
           P:  Point<N, V>,
           V:  Vect<N>,
-          BV: 'static + BoundingVolume<N> + Translation<V> + Clone {
+          BV: 'static + BoundingVolume<N> + HasSurfaceArea<N> + Translation<V> + Clone {
     /// Creates a new broad phase based on a Dynamic Bounding Volume Tree.
     pub fn new(margin: N, small_keys: bool)
                -> DBVTBroadPhase<N, P, BV, T> {
@@ -59,7 +106,8 @@ impl<N, P, V, BV, T> DBVTBroadPhase<N, P, BV, T>
             purge_all:  false,
             collector:  Vec::new(),
             to_update:  Vec::new(),
-            margin:     margin
+            margin:     margin,
+            point_type: PhantomData
         }
     }
 
@@ -68,26 +116,36 @@ impl<N, P, V, BV, T> DBVTBroadPhase<N, P, BV, T>
     pub fn num_interferences(&self) -> usize {
         self.pairs.len()
     }
+
+    /// The bounding volume of the leaf of a proxy, if it is on one of the trees.
+    fn leaf_bounding_volume(&self, proxy: &DBVTBroadPhaseProxy<T>) -> Option<&BV> {
+        proxy.leaf.map(|leaf| {
+            if proxy.active == 0 || proxy.active == REMOVE_FROM_STREE {
+                self.stree.bounding_volume(leaf)
+            }
+            else {
+                self.tree.bounding_volume(leaf)
+            }
+        })
+    }
 }
 
-impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
+impl<N, P, V, BV, T> BroadPhase<N, P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
     where N:  Scalar,
           P:  Point<N, V>,
           V:  Vect<N>,
-          BV: 'static + BoundingVolume<N> + Translation<V> + LocalRayCast<N, P, V> + LocalPointQuery<N, P> + Clone {
+          BV: 'static + BoundingVolume<N> + HasSurfaceArea<N> + Translation<V> + LocalRayCast<N, P, V> +
+              LocalPointQuery<N, P> + Clone {
     #[inline]
     fn defered_add(&mut self, uid: usize, bv: BV, data: T) {
-        let lbv = bv.loosened(self.margin.clone());
-        let leaf: DBVTLeaf<P, FastKey, BV> = DBVTLeaf::new(lbv.clone(), FastKey::new_invalid());
-        let leaf = Rc::new(RefCell::new(leaf));
+        let lbv   = bv.loosened(self.margin.clone());
         let proxy = DBVTBroadPhaseProxy {
             data:   data,
-            leaf:   leaf.clone(),
+            leaf:   None,
             active: DEACTIVATION_THRESHOLD
         };
 
         let (proxy_key, _) = self.proxies.insert(uid, proxy);
-        leaf.borrow_mut().object = proxy_key.clone();
         self.to_update.push((proxy_key, lbv));
     }
 
@@ -117,75 +175,70 @@ impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
         /*
          * Remove all the outdated nodes.
          */
-        for &(ref id, ref bv) in self.to_update.iter().rev() {
+        for &(ref id, _) in self.to_update.iter() {
             // FIXME: the `None` case may actually happen if the object is updated, then
             // removed, then the broad phase is updated.
             let proxy = self.proxies.get_fast_mut(id).expect("The proxy was not valid.");
 
             // If the activation number is < than the threshold then the leaf has not been removed
             // yet.
-            if proxy.active == 0 {
-                proxy.leaf.borrow_mut().bounding_volume = bv.clone();
-                self.stree.remove(&mut proxy.leaf);
-                proxy.active = DEACTIVATION_THRESHOLD;
-            }
-            else if proxy.active == REMOVE_FROM_TREE {
-                self.tree.remove(&mut proxy.leaf);
-            }
-            else if proxy.active == REMOVE_FROM_STREE {
-                self.stree.remove(&mut proxy.leaf);
-            }
-            else if proxy.active < DEACTIVATION_THRESHOLD {
-                proxy.leaf.borrow_mut().bounding_volume = bv.clone();
-                self.tree.remove(&mut proxy.leaf);
-                proxy.active = DEACTIVATION_THRESHOLD;
+            if let Some(leaf) = proxy.leaf {
+                if proxy.active == 0 || proxy.active == REMOVE_FROM_STREE {
+                    let _ = self.stree.remove(leaf);
+                    proxy.leaf = None;
+                }
+                else if proxy.active == REMOVE_FROM_TREE || proxy.active < DEACTIVATION_THRESHOLD {
+                    let _ = self.tree.remove(leaf);
+                    proxy.leaf = None;
+                }
+
+                if proxy.active >= 0 {
+                    proxy.active = DEACTIVATION_THRESHOLD;
+                }
             }
         }
 
         /*
          * Re-insert outdated nodes one by one and collect interferences at the same time.
          */
-        for &(ref proxy_key1, _) in self.to_update.iter().rev() {
-            let proxy1 = &self.proxies[*proxy_key1];
+        for &(ref proxy_key1, ref bv1) in self.to_update.iter().rev() {
+            {
+                let proxy1 = &self.proxies[*proxy_key1];
 
-            if !proxy1.leaf.borrow().is_detached() {
-                continue;
-            }
+                // Already re-inserted with a more recent bounding volume, or removed.
+                if proxy1.leaf.is_some() || proxy1.active < 0 {
+                    continue;
+                }
 
-            if proxy1.active < 0 {
-                continue;
-            }
+                {
+                    let mut visitor = BoundingVolumeInterferencesCollector::new(bv1, &mut self.collector);
 
-            {
-                let node1 = proxy1.leaf.borrow();
-                let mut visitor = BoundingVolumeInterferencesCollector::new(
-                    &node1.bounding_volume,
-                    &mut self.collector);
-
-                self.tree.visit(&mut visitor);
-                self.stree.visit(&mut visitor);
-            }
+                    self.tree.visit(&mut visitor);
+                    self.stree.visit(&mut visitor);
+                }
 
-            // Event generation.
-            for proxy_key2 in self.collector.iter() {
-                let proxy2 = &self.proxies[*proxy_key2];
-                let filtered_out = proxy2.active < 0 || !allow_proximity(&proxy1.data, &proxy2.data);
+                // Event generation.
+                for proxy_key2 in self.collector.iter() {
+                    let proxy2 = &self.proxies[*proxy_key2];
+                    let filtered_out = proxy2.active < 0 || !allow_proximity(&proxy1.data, &proxy2.data);
 
-                if !filtered_out {
-                    let mut trigger = false;
+                    if !filtered_out {
+                        let mut trigger = false;
 
-                    let _ = self.pairs.find_or_insert_lazy(
-                        Pair::new(*proxy_key1, *proxy_key2),
-                        || { trigger = true; Some(()) });
+                        let _ = self.pairs.find_or_insert_lazy(
+                            Pair::new(*proxy_key1, *proxy_key2),
+                            || { trigger = true; Some(()) });
 
-                    if trigger {
-                        handler(&proxy1.data, &proxy2.data, true)
+                        if trigger {
+                            handler(&proxy1.data, &proxy2.data, true)
+                        }
                     }
                 }
             }
 
             self.collector.clear();
-            self.tree.insert(proxy1.leaf.clone());
+            let leaf = self.tree.insert(*proxy_key1, bv1.clone());
+            self.proxies.get_fast_mut(proxy_key1).unwrap().leaf = Some(leaf);
         }
 
         /*
@@ -194,8 +247,12 @@ impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
         for (_, proxy) in self.proxies.iter_mut() {
             if proxy.active == 1 {
                 proxy.active = 0;
-                self.tree.remove(&mut proxy.leaf);
-                self.stree.insert(proxy.leaf.clone());
+
+                if let Some(leaf) = proxy.leaf {
+                    let bv  = self.tree.bounding_volume(leaf).clone();
+                    let key = self.tree.remove(leaf);
+                    proxy.leaf = Some(self.stree.insert(key, bv));
+                }
             }
             else if proxy.active > 1 {
                 proxy.active = proxy.active - 1;
@@ -230,14 +287,16 @@ impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
                     let proxy1 = &self.proxies[ids.first];
                     let proxy2 = &self.proxies[ids.second];
 
-                    let bv1 = &proxy1.leaf.borrow().bounding_volume;
-                    let bv2 = &proxy2.leaf.borrow().bounding_volume;
-
                     let filtered_out = proxy1.active < 0 ||
                                        proxy2.active < 0 ||
                                        !allow_proximity(&proxy1.data, &proxy2.data);
 
-                    if filtered_out || !bv1.intersects(bv2) {
+                    let intersect = match (self.leaf_bounding_volume(proxy1), self.leaf_bounding_volume(proxy2)) {
+                        (Some(bv1), Some(bv2)) => bv1.intersects(bv2),
+                        _                      => false
+                    };
+
+                    if filtered_out || !intersect {
                         handler(&proxy1.data, &proxy2.data, false);
 
                         true
@@ -263,15 +322,30 @@ impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
             let proxy = self.proxies.get_fast_mut(&proxy_key).unwrap();
 
             if proxy.active >= 0 {
-                let needs_update = !proxy.leaf.borrow().bounding_volume.contains(&bounding_volume);
+                let leaf = match proxy.leaf {
+                    Some(leaf) => leaf,
+                    None       => {
+                        // Not inserted yet: the bounding volume on the update list is outdated.
+                        self.to_update.push((proxy_key, bounding_volume.loosened(self.margin)));
+                        return;
+                    }
+                };
+
+                let needs_update = if proxy.active == 0 {
+                    !self.stree.bounding_volume(leaf).contains(&bounding_volume)
+                }
+                else {
+                    !self.tree.bounding_volume(leaf).contains(&bounding_volume)
+                };
 
                 if needs_update {
                     self.to_update.push((proxy_key, bounding_volume.loosened(self.margin)));
                 }
                 else if proxy.active != DEACTIVATION_THRESHOLD { // If == the object might already be on the update list.
                     if proxy.active == 0 {
-                        self.stree.remove(&mut proxy.leaf);
-                        self.tree.insert(proxy.leaf.clone());
+                        let bv  = self.stree.bounding_volume(leaf).clone();
+                        let key = self.stree.remove(leaf);
+                        proxy.leaf = Some(self.tree.insert(key, bv));
                     }
 
                     proxy.active = DEACTIVATION_THRESHOLD - 1;
@@ -280,6 +354,21 @@ impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
         }
     }
 
+    #[inline]
+    fn num_interferences(&self) -> usize {
+        self.pairs.len()
+    }
+
+    fn visit(&self, visitor: &mut BVTVisitor<T, BV>) {
+        let mut proxy_visitor = ProxyVisitor {
+            proxies: &self.proxies,
+            visitor: visitor
+        };
+
+        self.tree.visit(&mut proxy_visitor);
+        self.stree.visit(&mut proxy_visitor);
+    }
+
     fn interferences_with_bounding_volume<'a>(&'a self, bv: &BV, out: &mut Vec<&'a T>) {
         let mut collector = Vec::new();
 
@@ -324,4 +413,30 @@ impl<N, P, V, BV, T> BroadPhase<P, V, BV, T> for DBVTBroadPhase<N, P, BV, T>
             out.push(&self.proxies[l].data)
         }
     }
+
+    fn k_best_first_search<'a>(&'a self,
+                               k:        usize,
+                               max_cost: N,
+                               cost_fn:  &mut BVTCostFn<N, T, BV, ()>,
+                               out:      &mut Vec<(&'a T, N)>) {
+        let mut found = Vec::new();
+
+        {
+            let mut proxy_cost_fn = ProxyCostFn {
+                proxies: &self.proxies,
+                cost_fn: cost_fn
+            };
+
+            self.tree.k_best_first_search(k, max_cost, &mut proxy_cost_fn, &mut found);
+            self.stree.k_best_first_search(k, max_cost, &mut proxy_cost_fn, &mut found);
+        }
+
+        // Merge the results of both trees.
+        found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        found.truncate(k);
+
+        for (proxy_key, cost, _) in found.into_iter() {
+            out.push((&self.proxies[proxy_key].data, cost))
+        }
+    }
 }
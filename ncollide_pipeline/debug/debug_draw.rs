@@ -0,0 +1,297 @@
+use na::{Pnt2, Pnt3, Vec2, Vec3, Iso2, Iso3, Translate, Rotate, Transform, Cross, Rotation};
+use na;
+use math::{Scalar, Point, Vect, Isometry};
+use utils::data::uid_remap::FastKey;
+use entities::inspection::Repr;
+use entities::shape::{Ball2, Capsule2, Cone2, Cuboid2, Cylinder2, Convex2, ConvexPolyhedron2, Segment2, Triangle2,
+                      Polyline2, HeightField2, Compound2, Plane2};
+use entities::shape::{Ball3, Capsule3, Cone3, Cuboid3, Cylinder3, Convex3, ConvexPolyhedron3, Segment3, Triangle3,
+                      Polyline3, TriMesh3, HeightField3, Compound3, Plane3};
+use entities::bounding_volume::{AABB, HasAABB};
+use entities::partitioning::BVTVisitor;
+use procedural;
+use transformation::{ToTriMesh, ToPolyline};
+use transformation;
+use world::CollisionWorld;
+use debug::{DebugRenderer, DebugElement};
+
+/// Number of subdivisions used to discretize the curved shapes.
+const NSUBDIVS: u32 = 16;
+
+/// Trait implemented by collision worlds able to emit their debug geometry.
+pub trait DebugDraw<N, P> {
+    /// Emits the debug geometry of this collision world.
+    ///
+    /// This includes the shape of each collision object at its position, the bounding volumes of
+    /// the broad phase hierarchy, and each contact point with its normal scaled by `normal_length`.
+    fn debug_draw(&self, normal_length: N, renderer: &mut DebugRenderer<P>);
+}
+
+impl<N: Scalar, T> DebugDraw<N, Pnt2<N>> for CollisionWorld<N, Pnt2<N>, Vec2<N>, Iso2<N>, T> {
+    fn debug_draw(&self, normal_length: N, renderer: &mut DebugRenderer<Pnt2<N>>) {
+        for co in self.collision_objects() {
+            draw_shape2(&co.position, &**co.shape, renderer);
+        }
+
+        draw_broad_phase_and_contacts(self, normal_length, renderer)
+    }
+}
+
+impl<N: Scalar, T> DebugDraw<N, Pnt3<N>> for CollisionWorld<N, Pnt3<N>, Vec3<N>, Iso3<N>, T> {
+    fn debug_draw(&self, normal_length: N, renderer: &mut DebugRenderer<Pnt3<N>>) {
+        for co in self.collision_objects() {
+            draw_shape3(&co.position, &**co.shape, renderer);
+        }
+
+        draw_broad_phase_and_contacts(self, normal_length, renderer)
+    }
+}
+
+fn draw_broad_phase_and_contacts<N, P, V, AV, M, T>(world:         &CollisionWorld<N, P, V, M, T>,
+                                                    normal_length: N,
+                                                    renderer:      &mut DebugRenderer<P>)
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N> + Translate<P> + Cross<Output = AV>,
+          AV: Vect<N>,
+          M:  Isometry<N, P, V> + Rotation<AV> {
+    {
+        let mut visitor = AABBDrawer { renderer: &mut *renderer };
+
+        world.broad_phase().visit(&mut visitor);
+    }
+
+    world.contacts(|_, _, c| {
+        renderer.draw_point(&c.world1, DebugElement::ContactPoint);
+        renderer.draw_point(&c.world2, DebugElement::ContactPoint);
+        renderer.draw_line(&c.world1, &(c.world1 + c.normal * normal_length), DebugElement::ContactNormal);
+    });
+}
+
+/// Visitor drawing the bounding volumes of the broad phase.
+struct AABBDrawer<'a, P: 'a> {
+    renderer: &'a mut (DebugRenderer<P> + 'a)
+}
+
+#[old_impl_check]
+impl<'a, N, P, V> BVTVisitor<FastKey, AABB<P>> for AABBDrawer<'a, P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn visit_internal(&mut self, aabb: &AABB<P>) -> bool {
+        draw_aabb(aabb, DebugElement::BroadPhaseNode, &mut *self.renderer);
+
+        true
+    }
+
+    #[inline]
+    fn visit_leaf(&mut self, _: &FastKey, aabb: &AABB<P>) {
+        draw_aabb(aabb, DebugElement::BroadPhaseLeaf, &mut *self.renderer)
+    }
+}
+
+/// Draws the edges of an AABB.
+fn draw_aabb<N, P, V>(aabb: &AABB<P>, element: DebugElement, renderer: &mut DebugRenderer<P>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let dim                = na::dim::<P>();
+    let num_corners: usize = 1 << dim;
+
+    for i in 0 .. num_corners {
+        for j in 0 .. dim {
+            let bit: usize = 1 << j;
+
+            if i & bit == 0 {
+                renderer.draw_line(&aabb_corner(aabb, i), &aabb_corner(aabb, i | bit), element)
+            }
+        }
+    }
+}
+
+/// The corner of an AABB which coordinates are maximal for each bit set on `id`.
+fn aabb_corner<N, P, V>(aabb: &AABB<P>, id: usize) -> P
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut res = aabb.mins().clone();
+
+    for i in 0 .. na::dim::<P>() {
+        if id & (1 << i) != 0 {
+            res[i] = aabb.maxs()[i];
+        }
+    }
+
+    res
+}
+
+fn draw_shape2<N: Scalar>(m:        &Iso2<N>,
+                          shape:    &Repr<N, Pnt2<N>, Vec2<N>, Iso2<N>>,
+                          renderer: &mut DebugRenderer<Pnt2<N>>) {
+    let repr = shape.repr();
+
+    if let Some(b) = repr.downcast_ref::<Ball2<N>>() {
+        draw_polyline(b.to_polyline(NSUBDIVS), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Capsule2<N>>() {
+        draw_polyline(c.to_polyline(NSUBDIVS), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Cone2<N>>() {
+        draw_polyline(c.to_polyline(()), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Cuboid2<N>>() {
+        draw_polyline(c.to_polyline(()), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Cylinder2<N>>() {
+        draw_polyline(c.to_polyline(()), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Convex2<N>>() {
+        draw_polyline(transformation::convex_hull2(c.points()), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<ConvexPolyhedron2<N>>() {
+        let vs = c.vertices();
+
+        for f in c.faces().iter() {
+            renderer.draw_line(&m.transform(&vs[f[0]]), &m.transform(&vs[f[1]]), DebugElement::Shape)
+        }
+    }
+    else if let Some(t) = repr.downcast_ref::<Triangle2<N>>() {
+        draw_polyline(t.to_polyline(()), m, renderer)
+    }
+    else if let Some(s) = repr.downcast_ref::<Segment2<N>>() {
+        renderer.draw_line(&m.transform(s.a()), &m.transform(s.b()), DebugElement::Shape)
+    }
+    else if let Some(p) = repr.downcast_ref::<Polyline2<N>>() {
+        let vs = p.vertices();
+
+        for i in p.indices().iter() {
+            renderer.draw_line(&m.transform(&vs[i.x]), &m.transform(&vs[i.y]), DebugElement::Shape)
+        }
+    }
+    else if let Some(h) = repr.downcast_ref::<HeightField2<N>>() {
+        for i in 0 .. h.num_parts() {
+            let s: Segment2<N> = h.segment_at(i);
+            renderer.draw_line(&m.transform(s.a()), &m.transform(s.b()), DebugElement::Shape)
+        }
+    }
+    else if let Some(c) = repr.downcast_ref::<Compound2<N>>() {
+        for &(ref dm, ref s) in c.shapes().iter() {
+            draw_shape2(&(*m * *dm), &***s, renderer)
+        }
+    }
+    else if let Some(p) = repr.downcast_ref::<Plane2<N>>() {
+        // Planes are infinite: only draw their normal.
+        let orig = m.translate(&na::orig());
+        renderer.draw_line(&orig, &(orig + m.rotate(p.normal())), DebugElement::Shape)
+    }
+    else {
+        draw_aabb(&shape.aabb(m), DebugElement::Shape, renderer)
+    }
+}
+
+fn draw_shape3<N: Scalar>(m:        &Iso3<N>,
+                          shape:    &Repr<N, Pnt3<N>, Vec3<N>, Iso3<N>>,
+                          renderer: &mut DebugRenderer<Pnt3<N>>) {
+    let repr = shape.repr();
+
+    if let Some(b) = repr.downcast_ref::<Ball3<N>>() {
+        draw_trimesh(b.to_trimesh((NSUBDIVS, NSUBDIVS)), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Capsule3<N>>() {
+        draw_trimesh(c.to_trimesh((NSUBDIVS, NSUBDIVS)), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Cone3<N>>() {
+        draw_trimesh(c.to_trimesh(NSUBDIVS), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Cuboid3<N>>() {
+        draw_trimesh(c.to_trimesh(()), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Cylinder3<N>>() {
+        draw_trimesh(c.to_trimesh(NSUBDIVS), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<Convex3<N>>() {
+        draw_trimesh(transformation::convex_hull3(c.points()), m, renderer)
+    }
+    else if let Some(c) = repr.downcast_ref::<ConvexPolyhedron3<N>>() {
+        let vs = c.vertices();
+
+        for f in c.faces().iter() {
+            let a = m.transform(&vs[f[0]]);
+
+            for i in 1 .. f.len() - 1 {
+                renderer.draw_triangle(&a, &m.transform(&vs[f[i]]), &m.transform(&vs[f[i + 1]]),
+                                       DebugElement::Shape)
+            }
+        }
+    }
+    else if let Some(t) = repr.downcast_ref::<Triangle3<N>>() {
+        draw_trimesh(t.to_trimesh(()), m, renderer)
+    }
+    else if let Some(t) = repr.downcast_ref::<TriMesh3<N>>() {
+        draw_trimesh(t.to_trimesh(()), m, renderer)
+    }
+    else if let Some(s) = repr.downcast_ref::<Segment3<N>>() {
+        renderer.draw_line(&m.transform(s.a()), &m.transform(s.b()), DebugElement::Shape)
+    }
+    else if let Some(p) = repr.downcast_ref::<Polyline3<N>>() {
+        let vs = p.vertices();
+
+        for i in p.indices().iter() {
+            renderer.draw_line(&m.transform(&vs[i.x]), &m.transform(&vs[i.y]), DebugElement::Shape)
+        }
+    }
+    else if let Some(h) = repr.downcast_ref::<HeightField3<N>>() {
+        for i in 0 .. h.num_parts() {
+            let t: Triangle3<N> = h.triangle_at(i);
+            renderer.draw_triangle(&m.transform(t.a()), &m.transform(t.b()), &m.transform(t.c()),
+                                   DebugElement::Shape)
+        }
+    }
+    else if let Some(c) = repr.downcast_ref::<Compound3<N>>() {
+        for &(ref dm, ref s) in c.shapes().iter() {
+            draw_shape3(&(*m * *dm), &***s, renderer)
+        }
+    }
+    else if let Some(p) = repr.downcast_ref::<Plane3<N>>() {
+        // Planes are infinite: only draw their normal.
+        let orig = m.translate(&na::orig());
+        renderer.draw_line(&orig, &(orig + m.rotate(p.normal())), DebugElement::Shape)
+    }
+    else {
+        draw_aabb(&shape.aabb(m), DebugElement::Shape, renderer)
+    }
+}
+
+/// Draws the segments of a closed polyline.
+fn draw_polyline<N: Scalar>(mut polyline: procedural::Polyline2<N>,
+                            m:            &Iso2<N>,
+                            renderer:     &mut DebugRenderer<Pnt2<N>>) {
+    polyline.transform_by(m);
+
+    let coords = &polyline.coords[..];
+
+    if coords.len() == 2 {
+        renderer.draw_line(&coords[0], &coords[1], DebugElement::Shape)
+    }
+    else {
+        for i in 0 .. coords.len() {
+            renderer.draw_line(&coords[i], &coords[(i + 1) % coords.len()], DebugElement::Shape)
+        }
+    }
+}
+
+/// Draws the triangles of a triangle mesh.
+fn draw_trimesh<N: Scalar>(mut mesh: procedural::TriMesh3<N>,
+                           m:        &Iso3<N>,
+                           renderer: &mut DebugRenderer<Pnt3<N>>) {
+    mesh.transform_by(m);
+    mesh.unify_index_buffer();
+
+    let coords = mesh.coords;
+
+    for t in mesh.indices.unwrap_unified().iter() {
+        renderer.draw_triangle(&coords[t.x as usize], &coords[t.y as usize], &coords[t.z as usize],
+                               DebugElement::Shape)
+    }
+}
@@ -0,0 +1,26 @@
+/// The kind of geometric element emitted during the debug rendering of a collision world.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DebugElement {
+    /// The geometry of a collision object.
+    Shape,
+    /// The bounding volume of a leaf of the broad phase.
+    BroadPhaseLeaf,
+    /// The bounding volume of an internal node of the broad phase.
+    BroadPhaseNode,
+    /// A contact point.
+    ContactPoint,
+    /// A contact normal.
+    ContactNormal
+}
+
+/// Trait implemented by the renderers of the debug geometry of a collision world.
+pub trait DebugRenderer<P> {
+    /// Draws a line segment.
+    fn draw_line(&mut self, a: &P, b: &P, element: DebugElement);
+
+    /// Draws a triangle.
+    fn draw_triangle(&mut self, a: &P, b: &P, c: &P, element: DebugElement);
+
+    /// Draws a point.
+    fn draw_point(&mut self, p: &P, element: DebugElement);
+}
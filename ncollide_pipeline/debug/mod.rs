@@ -0,0 +1,11 @@
+//! Debug rendering of the geometry seen by the collision detection pipeline.
+
+pub use self::debug_renderer::{DebugRenderer, DebugElement};
+pub use self::debug_draw::DebugDraw;
+pub use self::obj_renderer::ObjRenderer;
+pub use self::svg_renderer::SvgRenderer;
+
+mod debug_renderer;
+mod debug_draw;
+mod obj_renderer;
+mod svg_renderer;
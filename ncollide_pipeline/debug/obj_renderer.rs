@@ -0,0 +1,133 @@
+use na;
+use math::{Scalar, Point, Vect};
+use debug::{DebugRenderer, DebugElement};
+
+/// A debug renderer collecting the debug geometry to be exported as a Wavefront OBJ file.
+///
+/// Each kind of debug element is exported as a separate group.
+pub struct ObjRenderer<P> {
+    lines:     Vec<(P, P, DebugElement)>,
+    triangles: Vec<(P, P, P, DebugElement)>,
+    points:    Vec<(P, DebugElement)>
+}
+
+impl<P> ObjRenderer<P> {
+    /// Creates a new renderer with no geometry.
+    pub fn new() -> ObjRenderer<P> {
+        ObjRenderer {
+            lines:     Vec::new(),
+            triangles: Vec::new(),
+            points:    Vec::new()
+        }
+    }
+
+    /// Removes all the geometry collected by this renderer.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.triangles.clear();
+        self.points.clear();
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V> ObjRenderer<P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /// The content of the Wavefront OBJ file containing the geometry collected by this renderer.
+    ///
+    /// 2D points are exported on the `z = 0` plane.
+    pub fn to_obj(&self) -> String {
+        let elements = [ DebugElement::Shape, DebugElement::BroadPhaseLeaf, DebugElement::BroadPhaseNode,
+                         DebugElement::ContactPoint, DebugElement::ContactNormal ];
+        let mut res  = String::new();
+
+        // NOTE: OBJ negative indices are relative to the last vertex written.
+        for element in elements.iter() {
+            res.push_str(format!("g {}\n", element_name(*element)).as_slice());
+
+            for &(ref a, ref b, _) in self.lines.iter().filter(|l| l.2 == *element) {
+                push_vertex(&mut res, a);
+                push_vertex(&mut res, b);
+                res.push_str("l -2 -1\n");
+            }
+
+            for &(ref a, ref b, ref c, _) in self.triangles.iter().filter(|t| t.3 == *element) {
+                push_vertex(&mut res, a);
+                push_vertex(&mut res, b);
+                push_vertex(&mut res, c);
+                res.push_str("f -3 -2 -1\n");
+            }
+
+            for &(ref a, _) in self.points.iter().filter(|p| p.1 == *element) {
+                push_vertex(&mut res, a);
+                res.push_str("p -1\n");
+            }
+        }
+
+        res
+    }
+}
+
+impl<P: Clone> DebugRenderer<P> for ObjRenderer<P> {
+    #[inline]
+    fn draw_line(&mut self, a: &P, b: &P, element: DebugElement) {
+        self.lines.push((a.clone(), b.clone(), element))
+    }
+
+    #[inline]
+    fn draw_triangle(&mut self, a: &P, b: &P, c: &P, element: DebugElement) {
+        self.triangles.push((a.clone(), b.clone(), c.clone(), element))
+    }
+
+    #[inline]
+    fn draw_point(&mut self, p: &P, element: DebugElement) {
+        self.points.push((p.clone(), element))
+    }
+}
+
+fn push_vertex<N, P, V>(out: &mut String, p: &P)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    out.push_str("v");
+
+    for i in 0 .. 3 {
+        if i < na::dim::<P>() {
+            out.push_str(format!(" {}", na::cast::<N, f64>(p[i])).as_slice())
+        }
+        else {
+            out.push_str(" 0")
+        }
+    }
+
+    out.push_str("\n")
+}
+
+fn element_name(element: DebugElement) -> &'static str {
+    match element {
+        DebugElement::Shape          => "shapes",
+        DebugElement::BroadPhaseLeaf => "broad_phase_leaves",
+        DebugElement::BroadPhaseNode => "broad_phase_nodes",
+        DebugElement::ContactPoint   => "contact_points",
+        DebugElement::ContactNormal  => "contact_normals"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::Pnt3;
+    use debug::{DebugRenderer, DebugElement};
+    use super::ObjRenderer;
+
+    #[test]
+    fn test_obj_plain_numbers() {
+        let mut renderer = ObjRenderer::new();
+
+        renderer.draw_line(&Pnt3::new(1.5f64, 0.0, -2.0), &Pnt3::new(0.25, 1.0, 3.0), DebugElement::Shape);
+
+        let obj = renderer.to_obj();
+
+        assert!(obj.contains("g shapes\nv 1.5 0 -2\nv 0.25 1 3\nl -2 -1\n"));
+    }
+}
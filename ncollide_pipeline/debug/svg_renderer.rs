@@ -0,0 +1,151 @@
+use na::Pnt2;
+use na;
+use math::Scalar;
+use debug::{DebugRenderer, DebugElement};
+
+/// A debug renderer collecting 2D debug geometry to be exported as a SVG file.
+pub struct SvgRenderer<N> {
+    lines:     Vec<(Pnt2<N>, Pnt2<N>, DebugElement)>,
+    triangles: Vec<(Pnt2<N>, Pnt2<N>, Pnt2<N>, DebugElement)>,
+    points:    Vec<(Pnt2<N>, DebugElement)>
+}
+
+impl<N> SvgRenderer<N> {
+    /// Creates a new renderer with no geometry.
+    pub fn new() -> SvgRenderer<N> {
+        SvgRenderer {
+            lines:     Vec::new(),
+            triangles: Vec::new(),
+            points:    Vec::new()
+        }
+    }
+
+    /// Removes all the geometry collected by this renderer.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.triangles.clear();
+        self.points.clear();
+    }
+}
+
+impl<N: Scalar> SvgRenderer<N> {
+    /// The content of the SVG file containing the geometry collected by this renderer.
+    ///
+    /// The view box is fitted to the collected geometry, and the `y` axis points upward.
+    pub fn to_svg(&self) -> String {
+        let mut mins = Pnt2::new(na::zero::<N>(), na::zero());
+        let mut maxs = Pnt2::new(na::zero::<N>(), na::zero());
+        let mut first = true;
+
+        {
+            let mut enlarge = |p: &Pnt2<N>| {
+                if first {
+                    mins  = *p;
+                    maxs  = *p;
+                    first = false;
+                }
+                else {
+                    mins = na::inf(&mins, p);
+                    maxs = na::sup(&maxs, p);
+                }
+            };
+
+            for &(ref a, ref b, _) in self.lines.iter() {
+                enlarge(a);
+                enlarge(b);
+            }
+
+            for &(ref a, ref b, ref c, _) in self.triangles.iter() {
+                enlarge(a);
+                enlarge(b);
+                enlarge(c);
+            }
+
+            for &(ref a, _) in self.points.iter() {
+                enlarge(a);
+            }
+        }
+
+        let extents = maxs - mins;
+        let margin  = na::max(extents.x, extents.y) * na::cast(0.05f64) + na::cast(1.0e-3f64);
+        let radius  = margin * na::cast(0.1f64);
+        let mut res = String::new();
+
+        // The `y` axis is flipped by the group transform.
+        res.push_str(format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+                             num(mins.x - margin), num(-maxs.y - margin),
+                             num(extents.x + margin + margin), num(extents.y + margin + margin)).as_slice());
+        res.push_str("<g transform=\"scale(1, -1)\" fill=\"none\" stroke-width=\"1\">\n");
+
+        for &(ref a, ref b, ref c, element) in self.triangles.iter() {
+            res.push_str(format!("<polygon points=\"{},{} {},{} {},{}\" stroke=\"{}\" vector-effect=\"non-scaling-stroke\"/>\n",
+                                 num(a.x), num(a.y), num(b.x), num(b.y), num(c.x), num(c.y),
+                                 element_color(element)).as_slice());
+        }
+
+        for &(ref a, ref b, element) in self.lines.iter() {
+            res.push_str(format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" vector-effect=\"non-scaling-stroke\"/>\n",
+                                 num(a.x), num(a.y), num(b.x), num(b.y), element_color(element)).as_slice());
+        }
+
+        for &(ref a, element) in self.points.iter() {
+            res.push_str(format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                                 num(a.x), num(a.y), num(radius), element_color(element)).as_slice());
+        }
+
+        res.push_str("</g>\n</svg>\n");
+
+        res
+    }
+}
+
+impl<N: Scalar> DebugRenderer<Pnt2<N>> for SvgRenderer<N> {
+    #[inline]
+    fn draw_line(&mut self, a: &Pnt2<N>, b: &Pnt2<N>, element: DebugElement) {
+        self.lines.push((*a, *b, element))
+    }
+
+    #[inline]
+    fn draw_triangle(&mut self, a: &Pnt2<N>, b: &Pnt2<N>, c: &Pnt2<N>, element: DebugElement) {
+        self.triangles.push((*a, *b, *c, element))
+    }
+
+    #[inline]
+    fn draw_point(&mut self, p: &Pnt2<N>, element: DebugElement) {
+        self.points.push((*p, element))
+    }
+}
+
+/// Converts a coordinate to a plain number for the SVG output.
+#[inline]
+fn num<N: Scalar>(x: N) -> f64 {
+    na::cast(x)
+}
+
+fn element_color(element: DebugElement) -> &'static str {
+    match element {
+        DebugElement::Shape          => "black",
+        DebugElement::BroadPhaseLeaf => "blue",
+        DebugElement::BroadPhaseNode => "lightgray",
+        DebugElement::ContactPoint   => "red",
+        DebugElement::ContactNormal  => "green"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::Pnt2;
+    use debug::{DebugRenderer, DebugElement};
+    use super::SvgRenderer;
+
+    #[test]
+    fn test_svg_plain_numbers() {
+        let mut renderer = SvgRenderer::new();
+
+        renderer.draw_line(&Pnt2::new(1.5f64, 0.0), &Pnt2::new(-2.0, 4.0), DebugElement::Shape);
+
+        let svg = renderer.to_svg();
+
+        assert!(svg.contains("<line x1=\"1.5\" y1=\"0\" x2=\"-2\" y2=\"4\" stroke=\"black\""));
+    }
+}
@@ -16,13 +16,18 @@
 #![doc(html_root_url = "http://ncollide.org/doc")]
 
 extern crate test; // To compute the median.
+extern crate time;
 extern crate "rustc-serialize" as rustc_serialize;
 extern crate "nalgebra" as na;
 extern crate "ncollide_math" as math;
 extern crate "ncollide_utils" as utils;
 extern crate "ncollide_entities" as entities;
 extern crate "ncollide_queries" as queries;
+extern crate "ncollide_procedural" as procedural;
+extern crate "ncollide_transformation" as transformation;
 
 pub mod broad_phase;
 pub mod narrow_phase;
 pub mod world;
+pub mod debug;
+pub mod spatial_index;
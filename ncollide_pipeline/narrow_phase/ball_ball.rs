@@ -83,4 +83,9 @@ impl<N, P, V, M> CollisionDetector<N, P, V, M> for BallBall<N, P, V, M>
             None        => ()
         }
     }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "BallBall"
+    }
 }
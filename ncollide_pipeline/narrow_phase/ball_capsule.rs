@@ -0,0 +1,180 @@
+use std::marker::PhantomData;
+use na::{Transform, Translate};
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::{Ball, Capsule};
+use entities::inspection::Repr;
+use queries::geometry::Contact;
+use queries::geometry::contacts_internal;
+use narrow_phase::{CollisionDetector, CollisionDispatcher};
+
+
+/// Collision detector between a ball and a capsule.
+pub struct BallCapsule<N, P, V, M> {
+    prediction: N,
+    contact:    Option<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for BallCapsule<N, P, V, M> {
+    fn clone(&self) -> BallCapsule<N, P, V, M> {
+        BallCapsule {
+            prediction: self.prediction.clone(),
+            contact:    self.contact.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> BallCapsule<N, P, V, M> {
+    /// Creates a new persistent collision detector between a ball and a capsule.
+    #[inline]
+    pub fn new(prediction: N) -> BallCapsule<N, P, V, M> {
+        BallCapsule {
+            prediction: prediction,
+            contact:    None,
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for BallCapsule<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<Ball<N>>(), rb.downcast_ref::<Capsule<N>>()) {
+            self.contact = contacts_internal::ball_against_capsule(&ma.translate(&na::orig()), a, mb, b, self.prediction);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        match self.contact {
+            None    => 0,
+            Some(_) => 1
+        }
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        match self.contact {
+            Some(ref c) => out_colls.push(c.clone()),
+            None        => ()
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "BallCapsule"
+    }
+}
+
+/// Collision detector between a capsule and a ball.
+pub struct CapsuleBall<N, P, V, M> {
+    prediction: N,
+    contact:    Option<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for CapsuleBall<N, P, V, M> {
+    fn clone(&self) -> CapsuleBall<N, P, V, M> {
+        CapsuleBall {
+            prediction: self.prediction.clone(),
+            contact:    self.contact.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CapsuleBall<N, P, V, M> {
+    /// Creates a new persistent collision detector between a capsule and a ball.
+    #[inline]
+    pub fn new(prediction: N) -> CapsuleBall<N, P, V, M> {
+        CapsuleBall {
+            prediction: prediction,
+            contact:    None,
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for CapsuleBall<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<Capsule<N>>(), rb.downcast_ref::<Ball<N>>()) {
+            self.contact = contacts_internal::capsule_against_ball(ma, a, &mb.translate(&na::orig()), b, self.prediction);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        match self.contact {
+            None    => 0,
+            Some(_) => 1
+        }
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        match self.contact {
+            Some(ref c) => out_colls.push(c.clone()),
+            None        => ()
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "CapsuleBall"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Vec3};
+    use super::{BallCapsule, CapsuleBall};
+
+    // A transformation type that does not implement `Clone`.
+    struct NotClone;
+
+    #[test]
+    fn test_clone_without_clone_transform() {
+        let bc: BallCapsule<f64, Pnt3<f64>, Vec3<f64>, NotClone> = BallCapsule::new(0.1);
+        let cb: CapsuleBall<f64, Pnt3<f64>, Vec3<f64>, NotClone> = CapsuleBall::new(0.1);
+
+        let _ = bc.clone();
+        let _ = cb.clone();
+    }
+}
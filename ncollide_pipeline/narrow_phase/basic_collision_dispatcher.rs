@@ -1,15 +1,25 @@
 use na::{Translate, Cross, Rotation};
+use na;
 use math::{Scalar, Point, Vect, Isometry};
 use entities::inspection;
 use entities::inspection::ReprDesc;
-use entities::shape::{Ball, Plane};
+use entities::shape::{Ball, Capsule, Cuboid, ConvexPolyhedron, Plane, DistanceField};
 use queries::geometry::algorithms::johnson_simplex::JohnsonSimplex;
 use narrow_phase::{
     CollisionDispatcher,
     CollisionAlgorithm,
     BallBall,
+    CuboidCuboid,
+    CapsuleCapsule,
+    BallCapsule,
+    CapsuleBall,
+    PlaneCapsule,
+    CapsulePlane,
+    ConvexPolyhedronConvexPolyhedron,
     PlaneSupportMap,
     SupportMapPlane,
+    DistanceFieldSupportMap,
+    SupportMapDistanceField,
     SupportMapSupportMap,
     CompositeShapeRepr,
     ReprCompositeShape,
@@ -37,12 +47,39 @@ impl<N, P, V, AV, M> CollisionDispatcher<N, P, V, M> for BasicCollisionDispatche
           AV: Vect<N>,
           M:  Isometry<N, P, V> + Rotation<AV> {
     fn get_collision_algorithm(&self, a: &ReprDesc, b: &ReprDesc) -> Option<CollisionAlgorithm<N, P, V, M>> {
-        let a_is_ball = a.downcast_ref::<Ball<N>>().is_some();
-        let b_is_ball = b.downcast_ref::<Ball<N>>().is_some();
+        let a_is_ball    = a.downcast_ref::<Ball<N>>().is_some();
+        let b_is_ball    = b.downcast_ref::<Ball<N>>().is_some();
+        let a_is_capsule = a.downcast_ref::<Capsule<N>>().is_some();
+        let b_is_capsule = b.downcast_ref::<Capsule<N>>().is_some();
 
         if a_is_ball && b_is_ball {
             Some(Box::new(BallBall::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
         }
+        else if a_is_capsule && b_is_capsule {
+            Some(Box::new(CapsuleCapsule::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
+        }
+        else if a_is_ball && b_is_capsule {
+            Some(Box::new(BallCapsule::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
+        }
+        else if a_is_capsule && b_is_ball {
+            Some(Box::new(CapsuleBall::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
+        }
+        else if a_is_capsule && b.downcast_ref::<Plane<V>>().is_some() {
+            Some(Box::new(CapsulePlane::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
+        }
+        else if a.downcast_ref::<Plane<V>>().is_some() && b_is_capsule {
+            Some(Box::new(PlaneCapsule::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
+        }
+        else if na::dim::<P>() == 3 &&
+                a.downcast_ref::<Cuboid<V>>().is_some() &&
+                b.downcast_ref::<Cuboid<V>>().is_some() {
+            Some(Box::new(CuboidCuboid::new(self.prediction)) as CollisionAlgorithm<N, P, V, M>)
+        }
+        else if a.downcast_ref::<ConvexPolyhedron<P, V>>().is_some() &&
+                b.downcast_ref::<ConvexPolyhedron<P, V>>().is_some() {
+            let detector = ConvexPolyhedronConvexPolyhedron::new(self.prediction);
+            Some(Box::new(detector) as CollisionAlgorithm<N, P, V, M>)
+        }
         else if a.downcast_ref::<Plane<V>>().is_some() &&
                 inspection::maybe_repr_desc_as_support_map::<P, V, M>(*b).is_some() {
             let wo_manifold = PlaneSupportMap::new(self.prediction);
@@ -67,6 +104,30 @@ impl<N, P, V, AV, M> CollisionDispatcher<N, P, V, M> for BasicCollisionDispatche
                 Some(Box::new(wo_manifold) as CollisionAlgorithm<N, P, V, M>)
             }
         }
+        else if a.downcast_ref::<DistanceField<N, P, V>>().is_some() &&
+                inspection::maybe_repr_desc_as_support_map::<P, V, M>(*b).is_some() {
+            let wo_manifold = DistanceFieldSupportMap::new(self.prediction);
+
+            if !b_is_ball {
+                let manifold = OneShotContactManifoldGenerator::new(self.prediction, wo_manifold);
+                Some(Box::new(manifold) as CollisionAlgorithm<N, P, V, M>)
+            }
+            else {
+                Some(Box::new(wo_manifold) as CollisionAlgorithm<N, P, V, M>)
+            }
+        }
+        else if b.downcast_ref::<DistanceField<N, P, V>>().is_some() &&
+                inspection::maybe_repr_desc_as_support_map::<P, V, M>(*a).is_some() {
+            let wo_manifold = SupportMapDistanceField::new(self.prediction);
+
+            if !a_is_ball {
+                let manifold = OneShotContactManifoldGenerator::new(self.prediction, wo_manifold);
+                Some(Box::new(manifold) as CollisionAlgorithm<N, P, V, M>)
+            }
+            else {
+                Some(Box::new(wo_manifold) as CollisionAlgorithm<N, P, V, M>)
+            }
+        }
         else if inspection::maybe_repr_desc_as_support_map::<P, V, M>(*a).is_some() &&
                 inspection::maybe_repr_desc_as_support_map::<P, V, M>(*b).is_some() {
             let simplex = JohnsonSimplex::new_w_tls();
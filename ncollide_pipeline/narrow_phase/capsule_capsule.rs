@@ -0,0 +1,84 @@
+use std::marker::PhantomData;
+use na::{Transform, Translate, Rotate};
+use math::{Scalar, Point, Vect};
+use entities::shape::{Capsule};
+use entities::inspection::Repr;
+use queries::geometry::Contact;
+use queries::geometry::contacts_internal;
+use narrow_phase::{CollisionDetector, CollisionDispatcher};
+
+
+/// Collision detector between two capsules.
+///
+/// It computes the closest points of the capsule axis analytically and generates two contacts
+/// when the capsules are parallel.
+pub struct CapsuleCapsule<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for CapsuleCapsule<N, P, V, M> {
+    fn clone(&self) -> CapsuleCapsule<N, P, V, M> {
+        CapsuleCapsule {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CapsuleCapsule<N, P, V, M> {
+    /// Creates a new persistent collision detector between two capsules.
+    #[inline]
+    pub fn new(prediction: N) -> CapsuleCapsule<N, P, V, M> {
+        CapsuleCapsule {
+            prediction: prediction,
+            contacts:   Vec::with_capacity(2),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for CapsuleCapsule<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> + Rotate<V> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<Capsule<N>>(), rb.downcast_ref::<Capsule<N>>()) {
+            self.contacts.clear();
+
+            contacts_internal::capsule_against_capsule(ma, a, mb, b, self.prediction, &mut self.contacts);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "CapsuleCapsule"
+    }
+}
@@ -22,6 +22,13 @@ pub trait CollisionDetector<N, P, V, M> {
 
     /// Collects the collisions detected during the last update.
     fn colls(&self, &mut Vec<Contact<N, P, V>>);
+
+    /// The name of this collision detection algorithm.
+    ///
+    /// It is used to group the algorithms of the pipeline statistics.
+    fn name(&self) -> &'static str {
+        "unnamed"
+    }
 }
 
 pub type CollisionAlgorithm<N, P, V, M> = Box<CollisionDetector<N, P, V, M> + 'static>;
@@ -50,7 +50,7 @@ impl<N, P, V, M> CompositeShapeRepr<N, P, V, M>
 
         {
             let mut visitor = BoundingVolumeInterferencesCollector::new(&ls_aabb2, &mut self.interferences);
-            g1.bvt().visit(&mut visitor);
+            g1.visit(&mut visitor);
         }
 
         for i in self.interferences.iter() {
@@ -76,12 +76,13 @@ impl<N, P, V, M> CompositeShapeRepr<N, P, V, M>
             }
         }
 
-        self.interferences.clear();
+        // Update all collisions. The parts which AABB intersects `ls_aabb2` are exactly those found
+        // by the visitor, so there is no need to ask the composite shape for their AABBs again.
+        self.interferences.sort();
 
-        // Update all collisions
         for detector in self.sub_detectors.elements_mut().iter_mut() {
             let key = detector.key;
-            if ls_aabb2.intersects(g1.aabb_at(key)) {
+            if self.interferences.binary_search(&key).is_ok() {
                 g1.map_transformed_part_at(m1, key, &mut |m1, g1| {
                     if swap {
                         assert!(detector.value.update(dispatcher, m2, g2, m1, g1), "The shape was no longer valid.");
@@ -102,6 +103,7 @@ impl<N, P, V, M> CompositeShapeRepr<N, P, V, M>
             self.sub_detectors.remove(i);
         }
 
+        self.interferences.clear();
         self.to_delete.clear();
     }
 }
@@ -157,6 +159,10 @@ impl<N, P, V, M> CollisionDetector<N, P, V, M> for CompositeShapeRepr<N, P, V, M
             detector.value.colls(out);
         }
     }
+
+    fn name(&self) -> &'static str {
+        "CompositeShapeRepr"
+    }
 }
 
 impl<N, P, V, M> CollisionDetector<N, P, V, M> for ReprCompositeShape<N, P, V, M>
@@ -188,4 +194,8 @@ impl<N, P, V, M> CollisionDetector<N, P, V, M> for ReprCompositeShape<N, P, V, M
     fn colls(&self, out: &mut Vec<Contact<N, P, V>>) {
         self.sub_detector.colls(out)
     }
+
+    fn name(&self) -> &'static str {
+        "ReprCompositeShape"
+    }
 }
@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+use na::{Rotate, Transform};
+use math::{Scalar, Point, Vect};
+use entities::shape::ConvexPolyhedron;
+use entities::inspection::Repr;
+use queries::geometry::Contact;
+use queries::geometry::contacts_internal;
+use narrow_phase::{CollisionDetector, CollisionDispatcher};
+
+
+/// Collision detector between two convex polyhedra.
+///
+/// It uses the separating axis theorem and clips the incident face against the reference face to
+/// generate a full contact manifold in a single pass.
+pub struct ConvexPolyhedronConvexPolyhedron<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for ConvexPolyhedronConvexPolyhedron<N, P, V, M> {
+    fn clone(&self) -> ConvexPolyhedronConvexPolyhedron<N, P, V, M> {
+        ConvexPolyhedronConvexPolyhedron {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> ConvexPolyhedronConvexPolyhedron<N, P, V, M> {
+    /// Creates a new persistent collision detector between two convex polyhedra.
+    #[inline]
+    pub fn new(prediction: N) -> ConvexPolyhedronConvexPolyhedron<N, P, V, M> {
+        ConvexPolyhedronConvexPolyhedron {
+            prediction: prediction,
+            contacts:   Vec::with_capacity(4),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for ConvexPolyhedronConvexPolyhedron<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<ConvexPolyhedron<P, V>>(),
+                                     rb.downcast_ref::<ConvexPolyhedron<P, V>>()) {
+            self.contacts.clear();
+
+            let _ = contacts_internal::convex_polyhedron_against_convex_polyhedron(
+                ma, a, mb, b, self.prediction, &mut self.contacts);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "ConvexPolyhedronConvexPolyhedron"
+    }
+}
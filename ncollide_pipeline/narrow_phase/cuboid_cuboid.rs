@@ -0,0 +1,84 @@
+use std::marker::PhantomData;
+use na::{Rotate, Translate};
+use math::{Scalar, Point, Vect};
+use entities::shape::Cuboid;
+use entities::inspection::Repr;
+use queries::geometry::Contact;
+use queries::geometry::contacts_internal;
+use narrow_phase::{CollisionDetector, CollisionDispatcher};
+
+
+/// Collision detector between two 3D cuboids.
+///
+/// It uses the separating axis theorem and clips the incident face against the reference face to
+/// generate a full contact manifold in a single pass.
+pub struct CuboidCuboid<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for CuboidCuboid<N, P, V, M> {
+    fn clone(&self) -> CuboidCuboid<N, P, V, M> {
+        CuboidCuboid {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CuboidCuboid<N, P, V, M> {
+    /// Creates a new persistent collision detector between two cuboids.
+    #[inline]
+    pub fn new(prediction: N) -> CuboidCuboid<N, P, V, M> {
+        CuboidCuboid {
+            prediction: prediction,
+            contacts:   Vec::with_capacity(4),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for CuboidCuboid<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Rotate<V> + Translate<P> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<Cuboid<V>>(), rb.downcast_ref::<Cuboid<V>>()) {
+            self.contacts.clear();
+
+            let _ = contacts_internal::cuboid_against_cuboid(ma, a, mb, b, self.prediction, &mut self.contacts);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "CuboidCuboid"
+    }
+}
@@ -0,0 +1,184 @@
+use std::marker::PhantomData;
+use math::{Scalar, Point, Vect, Isometry};
+use entities::shape::DistanceField;
+use entities::inspection;
+use entities::inspection::Repr;
+use queries::geometry::Contact;
+use queries::geometry::contacts_internal;
+use narrow_phase::{CollisionDetector, CollisionDispatcher};
+
+
+/// Collision detector between a distance field and a shape implementing the `SupportMap` trait.
+///
+/// This detector generates one contact point per local minimum of the signed distance found on the
+/// support-mapped shape.
+pub struct DistanceFieldSupportMap<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for DistanceFieldSupportMap<N, P, V, M> {
+    fn clone(&self) -> DistanceFieldSupportMap<N, P, V, M> {
+        DistanceFieldSupportMap {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> DistanceFieldSupportMap<N, P, V, M> {
+    /// Creates a new persistent collision detector between a distance field and a shape with a
+    /// support mapping function.
+    #[inline]
+    pub fn new(prediction: N) -> DistanceFieldSupportMap<N, P, V, M> {
+        DistanceFieldSupportMap {
+            prediction: prediction,
+            contacts:   Vec::new(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+/// Collision detector between a distance field and a shape implementing the `SupportMap` trait.
+///
+/// This detector generates one contact point per local minimum of the signed distance found on the
+/// support-mapped shape.
+pub struct SupportMapDistanceField<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for SupportMapDistanceField<N, P, V, M> {
+    fn clone(&self) -> SupportMapDistanceField<N, P, V, M> {
+        SupportMapDistanceField {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> SupportMapDistanceField<N, P, V, M> {
+    /// Creates a new persistent collision detector between a distance field and a shape with a
+    /// support mapping function.
+    #[inline]
+    pub fn new(prediction: N) -> SupportMapDistanceField<N, P, V, M> {
+        SupportMapDistanceField {
+            prediction: prediction,
+            contacts:   Vec::new(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M>
+for DistanceFieldSupportMap<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V> {
+    #[inline]
+    fn update(&mut self,
+              _:     &CollisionDispatcher<N, P, V, M>, 
+              ma:    &M,
+              field: &Repr<N, P, V, M>,
+              mb:    &M,
+              b:     &Repr<N, P, V, M>)
+              -> bool {
+        let rf = field.repr();
+
+        if let (Some(f), Some(sm)) =
+            (rf.downcast_ref::<DistanceField<N, P, V>>(), inspection::maybe_as_support_map(b)) {
+                self.contacts.clear();
+
+                let _ = contacts_internal::distance_field_against_support_map_manifold(
+                    ma, f, mb, sm, self.prediction, &mut self.contacts);
+
+                true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "DistanceFieldSupportMap"
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for SupportMapDistanceField<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V> {
+    #[inline]
+    fn update(&mut self,
+              _:     &CollisionDispatcher<N, P, V, M>, 
+              ma:    &M,
+              a:     &Repr<N, P, V, M>,
+              mb:    &M,
+              field: &Repr<N, P, V, M>)
+              -> bool {
+        let rf = field.repr();
+
+        if let (Some(sm), Some(f)) =
+            (inspection::maybe_as_support_map(a), rf.downcast_ref::<DistanceField<N, P, V>>()) {
+                self.contacts.clear();
+
+                let _ = contacts_internal::support_map_against_distance_field_manifold(
+                    ma, sm, mb, f, self.prediction, &mut self.contacts);
+
+                true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "SupportMapDistanceField"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Vec3};
+    use super::{DistanceFieldSupportMap, SupportMapDistanceField};
+
+    // A transformation type that does not implement `Clone`.
+    struct NotClone;
+
+    #[test]
+    fn test_clone_without_clone_transform() {
+        let fs: DistanceFieldSupportMap<f64, Pnt3<f64>, Vec3<f64>, NotClone> = DistanceFieldSupportMap::new(0.1);
+        let sf: SupportMapDistanceField<f64, Pnt3<f64>, Vec3<f64>, NotClone> = SupportMapDistanceField::new(0.1);
+
+        let _ = fs.clone();
+        let _ = sf.clone();
+    }
+}
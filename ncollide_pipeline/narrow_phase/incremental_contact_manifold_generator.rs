@@ -192,6 +192,11 @@ impl<N, P, V, M, CD> CollisionDetector<N, P, V, M> for IncrementalContactManifol
             out_colls.push(c.contact.clone())
         }
     }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "IncrementalContactManifoldGenerator"
+    }
 }
 
 fn add_reduce_by_variance<N, P, V, M>(pts: &mut [ContactWLocals<N, P, V>], to_add: Contact<N, P, V>, m1: &M, m2: &M)
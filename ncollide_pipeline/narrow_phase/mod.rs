@@ -2,7 +2,13 @@
 #[doc(inline)]
 pub use self::collision_detector::{CollisionDetector, CollisionDispatcher, CollisionAlgorithm};
 pub use self::ball_ball::BallBall;
+pub use self::cuboid_cuboid::CuboidCuboid;
+pub use self::capsule_capsule::CapsuleCapsule;
+pub use self::ball_capsule::{BallCapsule, CapsuleBall};
+pub use self::plane_capsule::{PlaneCapsule, CapsulePlane};
+pub use self::convex_polyhedron_convex_polyhedron::ConvexPolyhedronConvexPolyhedron;
 pub use self::plane_support_map::{PlaneSupportMap, SupportMapPlane};
+pub use self::distance_field_support_map::{DistanceFieldSupportMap, SupportMapDistanceField};
 pub use self::support_map_support_map::SupportMapSupportMap;
 pub use self::incremental_contact_manifold_generator::IncrementalContactManifoldGenerator;
 pub use self::one_shot_contact_manifold_generator::OneShotContactManifoldGenerator;
@@ -18,7 +24,13 @@ pub mod collision_detector;
 #[doc(hidden)]
 pub mod contact_signal;
 mod ball_ball;
+mod cuboid_cuboid;
+mod capsule_capsule;
+mod ball_capsule;
+mod plane_capsule;
+mod convex_polyhedron_convex_polyhedron;
 mod plane_support_map;
+mod distance_field_support_map;
 mod support_map_support_map;
 mod incremental_contact_manifold_generator;
 mod one_shot_contact_manifold_generator;
@@ -86,4 +86,9 @@ impl<N, P, V, AV, M, CD> CollisionDetector<N, P, V, M> for OneShotContactManifol
     fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
         self.sub_detector.colls(out_colls)
     }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "OneShotContactManifoldGenerator"
+    }
 }
@@ -0,0 +1,157 @@
+use std::marker::PhantomData;
+use na::{Transform, Translate, Rotate};
+use math::{Scalar, Point, Vect};
+use entities::shape::{Capsule, Plane};
+use entities::inspection::Repr;
+use queries::geometry::Contact;
+use queries::geometry::contacts_internal;
+use narrow_phase::{CollisionDetector, CollisionDispatcher};
+
+
+/// Collision detector between a plane and a capsule.
+///
+/// It generates one contact for each extremity of the capsule axis close to the plane.
+pub struct PlaneCapsule<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for PlaneCapsule<N, P, V, M> {
+    fn clone(&self) -> PlaneCapsule<N, P, V, M> {
+        PlaneCapsule {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> PlaneCapsule<N, P, V, M> {
+    /// Creates a new persistent collision detector between a plane and a capsule.
+    #[inline]
+    pub fn new(prediction: N) -> PlaneCapsule<N, P, V, M> {
+        PlaneCapsule {
+            prediction: prediction,
+            contacts:   Vec::with_capacity(2),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for PlaneCapsule<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> + Rotate<V> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<Plane<V>>(), rb.downcast_ref::<Capsule<N>>()) {
+            self.contacts.clear();
+
+            contacts_internal::plane_against_capsule(ma, a, mb, b, self.prediction, &mut self.contacts);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "PlaneCapsule"
+    }
+}
+
+/// Collision detector between a capsule and a plane.
+///
+/// It generates one contact for each extremity of the capsule axis close to the plane.
+pub struct CapsulePlane<N, P, V, M> {
+    prediction: N,
+    contacts:   Vec<Contact<N, P, V>>,
+    mat_type:   PhantomData<M> // FIXME: can we avoid this (using a generalized where clause ?)
+}
+
+impl<N: Clone, P: Clone, V: Clone, M> Clone for CapsulePlane<N, P, V, M> {
+    fn clone(&self) -> CapsulePlane<N, P, V, M> {
+        CapsulePlane {
+            prediction: self.prediction.clone(),
+            contacts:   self.contacts.clone(),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CapsulePlane<N, P, V, M> {
+    /// Creates a new persistent collision detector between a capsule and a plane.
+    #[inline]
+    pub fn new(prediction: N) -> CapsulePlane<N, P, V, M> {
+        CapsulePlane {
+            prediction: prediction,
+            contacts:   Vec::with_capacity(2),
+            mat_type:   PhantomData
+        }
+    }
+}
+
+impl<N, P, V, M> CollisionDetector<N, P, V, M> for CapsulePlane<N, P, V, M>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> + Rotate<V> {
+    fn update(&mut self,
+              _:  &CollisionDispatcher<N, P, V, M>,
+              ma: &M,
+              a:  &Repr<N, P, V, M>,
+              mb: &M,
+              b:  &Repr<N, P, V, M>)
+              -> bool {
+        let ra = a.repr();
+        let rb = b.repr();
+
+        if let (Some(a), Some(b)) = (ra.downcast_ref::<Capsule<N>>(), rb.downcast_ref::<Plane<V>>()) {
+            self.contacts.clear();
+
+            contacts_internal::capsule_against_plane(ma, a, mb, b, self.prediction, &mut self.contacts);
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    #[inline]
+    fn num_colls(&self) -> usize {
+        self.contacts.len()
+    }
+
+    #[inline]
+    fn colls(&self, out_colls: &mut Vec<Contact<N, P, V>>) {
+        out_colls.extend(self.contacts.iter().cloned())
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "CapsulePlane"
+    }
+}
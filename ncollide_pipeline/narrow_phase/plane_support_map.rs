@@ -99,6 +99,11 @@ for PlaneSupportMap<N, P, V, M>
             None        => ()
         }
     }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "PlaneSupportMap"
+    }
 }
 
 impl<N, P, V, M> CollisionDetector<N, P, V, M> for SupportMapPlane<N, P, V, M>
@@ -142,4 +147,9 @@ impl<N, P, V, M> CollisionDetector<N, P, V, M> for SupportMapPlane<N, P, V, M>
             None        => ()
         }
     }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "SupportMapPlane"
+    }
 }
@@ -94,4 +94,9 @@ impl<N, P, V, M, S> CollisionDetector<N, P, V, M> for SupportMapSupportMap<N, P,
             _ => ()
         }
     }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "SupportMapSupportMap"
+    }
 }
@@ -0,0 +1,5 @@
+//! General-purpose spatial index of objects with a bounding volume.
+
+pub use self::spatial_index::{SpatialIndex, SpatialIndexHandle, SpatialIndexQuery};
+
+mod spatial_index;
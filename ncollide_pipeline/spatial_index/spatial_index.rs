@@ -0,0 +1,452 @@
+use std::vec::IntoIter;
+use na::Bounded;
+use math::Scalar;
+use entities::bounding_volume::{BoundingVolume, HasSurfaceArea, BoundingVolumeInterferencesCollector, Frustum,
+                                FrustumClassifiable, FrustumInterferencesCollector};
+use entities::partitioning::{DBVT, DBVTLeafId, BVTVisitor, BVTCostFn};
+use queries::ray::{Ray, LocalRayCast, RayInterferencesCollector};
+use queries::point::{LocalPointQuery, PointInterferencesCollector};
+
+/// The handle of an object stored on a `SpatialIndex`.
+///
+/// A handle is valid until its object is removed. It is never valid again afterward, even if the
+/// slot of its object is reused by a new object.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
+pub struct SpatialIndexHandle(usize, usize);
+
+impl SpatialIndexHandle {
+    /// The index of the slot of the object identified by this handle.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+struct SpatialIndexEntry<BV, T> {
+    data: T,
+    // The bounding volume given by the user, before loosening.
+    bv:   BV,
+    leaf: DBVTLeafId
+}
+
+/// A spatial index of user-defined objects with an attached bounding volume.
+///
+/// This is a general-purpose wrapper of a `DBVT`. The bounding volume stored for each object is
+/// enlarged by a margin so that small motions do not require any update of the tree. Therefore,
+/// queries are conservative: they may return objects whose actual bounding volume lies at most
+/// `margin` away from the query. Nearest neighbor queries are exact though.
+pub struct SpatialIndex<N, BV, T> {
+    tree:        DBVT<usize, BV>,
+    objects:     Vec<Option<SpatialIndexEntry<BV, T>>>,
+    generations: Vec<usize>,
+    free:        Vec<usize>,
+    margin:      N
+}
+
+#[old_impl_check]
+impl<N, BV, T> SpatialIndex<N, BV, T>
+    where N:  Scalar,
+          BV: BoundingVolume<N> + HasSurfaceArea<N> + Clone {
+    /// Creates a new empty spatial index.
+    ///
+    /// Each bounding volume will be loosened by `margin` when inserted on the index.
+    pub fn new(margin: N) -> SpatialIndex<N, BV, T> {
+        SpatialIndex {
+            tree:        DBVT::new(),
+            objects:     Vec::new(),
+            generations: Vec::new(),
+            free:        Vec::new(),
+            margin:      margin
+        }
+    }
+
+    /// The number of objects on this index.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Whether this index contains no object.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Whether `handle` identifies an object of this index.
+    #[inline]
+    pub fn contains(&self, handle: SpatialIndexHandle) -> bool {
+        self.entry(handle).is_some()
+    }
+
+    /// A reference to the object identified by `handle`.
+    #[inline]
+    pub fn get(&self, handle: SpatialIndexHandle) -> Option<&T> {
+        self.entry(handle).map(|entry| &entry.data)
+    }
+
+    /// A mutable reference to the object identified by `handle`.
+    #[inline]
+    pub fn get_mut(&mut self, handle: SpatialIndexHandle) -> Option<&mut T> {
+        if handle.0 >= self.generations.len() || self.generations[handle.0] != handle.1 {
+            return None;
+        }
+
+        match self.objects[handle.0] {
+            Some(ref mut entry) => Some(&mut entry.data),
+            None                => None
+        }
+    }
+
+    /// The loosened bounding volume of the object identified by `handle`.
+    #[inline]
+    pub fn bounding_volume(&self, handle: SpatialIndexHandle) -> Option<&BV> {
+        self.entry(handle).map(|entry| self.tree.bounding_volume(entry.leaf))
+    }
+
+    /// The bounding volume of the object identified by `handle`, as given by the user.
+    #[inline]
+    pub fn tight_bounding_volume(&self, handle: SpatialIndexHandle) -> Option<&BV> {
+        self.entry(handle).map(|entry| &entry.bv)
+    }
+
+    /// Inserts an object with its bounding volume, and returns its handle.
+    pub fn insert(&mut self, bv: BV, data: T) -> SpatialIndexHandle {
+        let id = match self.free.pop() {
+            Some(id) => id,
+            None     => {
+                self.objects.push(None);
+                self.generations.push(0);
+                self.objects.len() - 1
+            }
+        };
+
+        let leaf = self.tree.insert(id, bv.loosened(self.margin));
+
+        self.objects[id] = Some(SpatialIndexEntry {
+            data: data,
+            bv:   bv,
+            leaf: leaf
+        });
+
+        SpatialIndexHandle(id, self.generations[id])
+    }
+
+    /// Sets the bounding volume of the object identified by `handle`.
+    ///
+    /// The tree is only modified if `bv` is not contained by the current loosened bounding
+    /// volume of the object. Fails if `handle` is not a valid handle.
+    pub fn update(&mut self, handle: SpatialIndexHandle, bv: BV) {
+        assert!(self.contains(handle), "Invalid spatial index handle.");
+
+        let entry = self.objects[handle.0].as_mut().unwrap();
+
+        if !self.tree.bounding_volume(entry.leaf).contains(&bv) {
+            self.tree.set_bounding_volume(entry.leaf, bv.loosened(self.margin))
+        }
+
+        entry.bv = bv;
+    }
+
+    /// Removes the object identified by `handle` and returns it.
+    ///
+    /// Returns `None` if `handle` is not a valid handle.
+    pub fn remove(&mut self, handle: SpatialIndexHandle) -> Option<T> {
+        if !self.contains(handle) {
+            return None;
+        }
+
+        let entry = self.objects[handle.0].take().unwrap();
+
+        let _ = self.tree.remove(entry.leaf);
+        self.generations[handle.0] = self.generations[handle.0] + 1;
+        self.free.push(handle.0);
+
+        Some(entry.data)
+    }
+
+    /// Iterates through every object of this index.
+    pub fn iter<'a>(&'a self) -> SpatialIndexQuery<'a, BV, T> {
+        let ids: Vec<usize> = (0 .. self.objects.len()).filter(|i| self.objects[*i].is_some()).collect();
+
+        self.query_result(ids)
+    }
+
+    /// Iterates through the objects which bounding volume intersects `bv`.
+    pub fn interferences_with_bounding_volume<'a>(&'a self, bv: &BV) -> SpatialIndexQuery<'a, BV, T> {
+        let mut ids = Vec::new();
+
+        {
+            let mut visitor = BoundingVolumeInterferencesCollector::new(bv, &mut ids);
+            self.tree.visit(&mut visitor);
+        }
+
+        self.query_result(ids)
+    }
+
+    /// Iterates through the objects which bounding volume intersects a ray.
+    pub fn interferences_with_ray<'a, P, V>(&'a self, ray: &Ray<P, V>) -> SpatialIndexQuery<'a, BV, T>
+        where BV: LocalRayCast<N, P, V> {
+        let mut ids = Vec::new();
+
+        {
+            let mut visitor = RayInterferencesCollector::new(ray, &mut ids);
+            self.tree.visit(&mut visitor);
+        }
+
+        self.query_result(ids)
+    }
+
+    /// Iterates through the objects which bounding volume contains a point.
+    pub fn interferences_with_point<'a, P>(&'a self, point: &P) -> SpatialIndexQuery<'a, BV, T>
+        where BV: LocalPointQuery<N, P> {
+        let mut ids = Vec::new();
+
+        {
+            let mut visitor = PointInterferencesCollector::new(point, &mut ids);
+            self.tree.visit(&mut visitor);
+        }
+
+        self.query_result(ids)
+    }
+
+    /// Iterates through the objects which bounding volume intersects the ball with the given
+    /// `center` and `radius`.
+    pub fn interferences_with_ball<'a, P>(&'a self, center: &P, radius: N) -> SpatialIndexQuery<'a, BV, T>
+        where BV: LocalPointQuery<N, P> {
+        self.interferences_with(|bv: &BV| bv.distance_to_point(center) <= radius)
+    }
+
+    /// Iterates through the objects which bounding volume is not outside of a frustum.
+    pub fn interferences_with_frustum<'a, P, V>(&'a self, frustum: &Frustum<N, P, V>) -> SpatialIndexQuery<'a, BV, T>
+        where BV: FrustumClassifiable<N, P, V> {
+        let mut ids = Vec::new();
+
+        {
+            let mut visitor = FrustumInterferencesCollector::new(frustum, &mut ids);
+            self.tree.visit(&mut visitor);
+        }
+
+        self.query_result(ids)
+    }
+
+    /// Iterates through the objects which bounding volume passes a user-defined test.
+    ///
+    /// The test must be conservative: if it fails for a bounding volume, it must also fail for
+    /// every bounding volume it contains.
+    pub fn interferences_with<'a, F>(&'a self, test: F) -> SpatialIndexQuery<'a, BV, T>
+        where F: FnMut(&BV) -> bool {
+        let mut ids = Vec::new();
+
+        {
+            let mut visitor = PredicateCollector {
+                test:      test,
+                collector: &mut ids
+            };
+
+            self.tree.visit(&mut visitor);
+        }
+
+        self.query_result(ids)
+    }
+
+    /// Iterates through the `k` objects which bounding volume is the closest to a point.
+    ///
+    /// The objects are sorted by increasing distance, and returned with this distance. Objects
+    /// further than `max_dist` are ignored.
+    pub fn nearest_neighbors<'a, P>(&'a self, point: &P, k: usize, max_dist: Option<N>)
+                                    -> IntoIter<(SpatialIndexHandle, &'a T, N)>
+        where BV: LocalPointQuery<N, P> {
+        let mut found = Vec::new();
+
+        {
+            let mut cost_fn = TightBVDistanceCostFn {
+                objects: &self.objects[..],
+                point:   point
+            };
+
+            let max_dist = max_dist.unwrap_or(Bounded::max_value());
+            self.tree.k_best_first_search(k, max_dist, &mut cost_fn, &mut found);
+        }
+
+        let res: Vec<(SpatialIndexHandle, &'a T, N)> = found.into_iter().map(|(id, dist, _)| {
+            (SpatialIndexHandle(id, self.generations[id]), &self.objects[id].as_ref().unwrap().data, dist)
+        }).collect();
+
+        res.into_iter()
+    }
+
+    /// The object which bounding volume is the closest to a point, with its distance.
+    pub fn nearest_neighbor<'a, P>(&'a self, point: &P, max_dist: Option<N>)
+                                   -> Option<(SpatialIndexHandle, &'a T, N)>
+        where BV: LocalPointQuery<N, P> {
+        self.nearest_neighbors(point, 1, max_dist).next()
+    }
+
+    fn query_result<'a>(&'a self, ids: Vec<usize>) -> SpatialIndexQuery<'a, BV, T> {
+        SpatialIndexQuery {
+            objects:     &self.objects[..],
+            generations: &self.generations[..],
+            ids:         ids.into_iter()
+        }
+    }
+
+    #[inline]
+    fn entry(&self, handle: SpatialIndexHandle) -> Option<&SpatialIndexEntry<BV, T>> {
+        if handle.0 >= self.generations.len() || self.generations[handle.0] != handle.1 {
+            None
+        }
+        else {
+            self.objects[handle.0].as_ref()
+        }
+    }
+}
+
+/// An iterator through the result of a query on a `SpatialIndex`.
+pub struct SpatialIndexQuery<'a, BV: 'a, T: 'a> {
+    objects:     &'a [Option<SpatialIndexEntry<BV, T>>],
+    generations: &'a [usize],
+    ids:         IntoIter<usize>
+}
+
+impl<'a, BV, T> Iterator for SpatialIndexQuery<'a, BV, T> {
+    type Item = (SpatialIndexHandle, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(SpatialIndexHandle, &'a T)> {
+        self.ids.next().map(|id| {
+            (SpatialIndexHandle(id, self.generations[id]), &self.objects[id].as_ref().unwrap().data)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+/// Visitor collecting the leaves which bounding volume passes a user-defined test.
+struct PredicateCollector<'a, F> {
+    test:      F,
+    collector: &'a mut Vec<usize>
+}
+
+impl<'a, BV, F> BVTVisitor<usize, BV> for PredicateCollector<'a, F>
+    where F: FnMut(&BV) -> bool {
+    #[inline]
+    fn visit_internal(&mut self, bv: &BV) -> bool {
+        (self.test)(bv)
+    }
+
+    #[inline]
+    fn visit_leaf(&mut self, b: &usize, bv: &BV) {
+        if (self.test)(bv) {
+            self.collector.push(*b)
+        }
+    }
+}
+
+/// Cost function computing the distance between a point and the tight bounding volumes of the
+/// index.
+///
+/// The loosened bounding volumes of the tree only give lower bounds of the distances.
+struct TightBVDistanceCostFn<'a, BV: 'a, T: 'a, P: 'a> {
+    objects: &'a [Option<SpatialIndexEntry<BV, T>>],
+    point:   &'a P
+}
+
+impl<'a, N, BV, T, P> BVTCostFn<N, usize, BV, ()> for TightBVDistanceCostFn<'a, BV, T, P>
+    where N:  Scalar,
+          BV: LocalPointQuery<N, P> {
+    #[inline]
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        Some(bv.distance_to_point(self.point))
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, id: &usize) -> Option<(N, ())> {
+        let bv = &self.objects[*id].as_ref().unwrap().bv;
+
+        Some((bv.distance_to_point(self.point), ()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Mat4};
+    use na;
+    use entities::bounding_volume::{AABB, Frustum};
+    use super::SpatialIndex;
+
+    fn cube_at(x: f64) -> AABB<Pnt3<f64>> {
+        AABB::new(Pnt3::new(x, -0.5, -0.5), Pnt3::new(x + 1.0, 0.5, 0.5))
+    }
+
+    #[test]
+    fn test_nearest_neighbors_use_tight_bounding_volumes() {
+        let mut index = SpatialIndex::new(0.5f64);
+
+        // Both objects lie within the margin of the query point.
+        let _ = index.insert(cube_at(0.4), 'b');
+        let _ = index.insert(cube_at(0.3), 'a');
+
+        let found: Vec<_> = index.nearest_neighbors(&Pnt3::new(0.0, 0.0, 0.0), 2, None).collect();
+
+        assert!(found.len() == 2);
+        assert!(*found[0].1 == 'a' && *found[1].1 == 'b');
+        assert!(na::approx_eq(&found[0].2, &0.3));
+        assert!(na::approx_eq(&found[1].2, &0.4));
+
+        assert!(index.nearest_neighbor(&Pnt3::new(0.0, 0.0, 0.0), Some(0.2)).is_none());
+    }
+
+    #[test]
+    fn test_nearest_neighbor_after_update() {
+        let mut index = SpatialIndex::new(0.5f64);
+        let a         = index.insert(cube_at(1.0), 'a');
+        let _         = index.insert(cube_at(1.2), 'b');
+
+        // Still within the loosened bounding volume, but further than `b`.
+        index.update(a, cube_at(1.4));
+
+        let (_, data, dist) = index.nearest_neighbor(&Pnt3::new(0.0, 0.0, 0.0), None).unwrap();
+
+        assert!(*data == 'b');
+        assert!(na::approx_eq(&dist, &1.2));
+        assert!(na::approx_eq(index.tight_bounding_volume(a).unwrap().mins(), &Pnt3::new(1.4, -0.5, -0.5)));
+    }
+
+    #[test]
+    fn test_stale_handle() {
+        let mut index = SpatialIndex::new(0.1f64);
+        let a         = index.insert(cube_at(0.0), 'a');
+
+        assert!(index.remove(a) == Some('a'));
+
+        let b = index.insert(cube_at(3.0), 'b');
+
+        assert!(a.index() == b.index());
+        assert!(!index.contains(a) && index.contains(b));
+        assert!(index.get(a).is_none() && index.get_mut(a).is_none());
+        assert!(index.remove(a).is_none());
+        assert!(index.get(b) == Some(&'b'));
+
+        let found: Vec<_> = index.iter().map(|(handle, _)| handle).collect();
+
+        assert!(found == vec!(b));
+    }
+
+    #[test]
+    fn test_interferences_with_frustum() {
+        let mut index = SpatialIndex::new(0.1f64);
+        let _         = index.insert(cube_at(-0.5), 'a');
+        let _         = index.insert(cube_at(0.8), 'b');
+        let _         = index.insert(cube_at(3.0), 'c');
+
+        // The cube [-1, 1]^3.
+        let frustum = Frustum::from_view_projection(&na::one::<Mat4<f64>>());
+        let mut found: Vec<char> = index.interferences_with_frustum(&frustum).map(|(_, data)| *data).collect();
+        found.sort();
+
+        assert!(found == vec!('a', 'b'));
+    }
+}
@@ -0,0 +1,360 @@
+use na::{Translate, Cross, Rotation, Translation};
+use na;
+use math::{Scalar, Point, Vect, Isometry};
+use entities::inspection::Repr;
+use world::{CollisionWorld, CollisionGroups};
+
+/// Default maximum number of sliding (and depenetration) iterations performed by each motion.
+const DEFAULT_MAX_ITERATIONS: usize = 4;
+
+/// A kinematic character controller.
+///
+/// It resolves the desired displacement of a character by sliding it along the obstacles it hits.
+/// It also handles steps up small ledges, slope limits and depenetration from the initial
+/// overlaps.
+pub struct CharacterController<N, V> {
+    up:             V,
+    max_slope:      N,
+    step_height:    N,
+    skin_width:     N,
+    max_iterations: usize
+}
+
+/// The result of the motion of a character.
+pub struct CharacterMotion<V, M> {
+    /// The final position of the character.
+    pub position:     M,
+    /// The displacement actually performed by the character.
+    pub displacement: V,
+    /// Whether the character hit at least one obstacle.
+    pub collided:     bool,
+    /// Whether the character stands on a walkable surface at the end of the motion.
+    pub on_ground:    bool
+}
+
+impl<N, V> CharacterController<N, V>
+    where N: Scalar,
+          V: Vect<N> {
+    /// Creates a new kinematic character controller.
+    ///
+    /// # Arguments:
+    /// * `up`          - the up direction. It does not need to be normalized.
+    /// * `max_slope`   - the angle (in radians) between `up` and the normal of the steepest
+    /// walkable surface.
+    /// * `step_height` - the height of the highest ledge the character can step up.
+    /// * `skin_width`  - the gap kept between the character and the obstacles.
+    pub fn new(up: V, max_slope: N, step_height: N, skin_width: N) -> CharacterController<N, V> {
+        CharacterController::new_with_max_iterations(up, max_slope, step_height, skin_width,
+                                                     DEFAULT_MAX_ITERATIONS)
+    }
+
+    /// Creates a new kinematic character controller performing at most `max_iterations` sliding
+    /// (and depenetration) iterations by motion.
+    ///
+    /// See `CharacterController::new` for the other arguments.
+    pub fn new_with_max_iterations(up:             V,
+                                   max_slope:      N,
+                                   step_height:    N,
+                                   skin_width:     N,
+                                   max_iterations: usize)
+                                   -> CharacterController<N, V> {
+        assert!(skin_width >= na::zero(), "The skin width must be positive.");
+        assert!(step_height >= na::zero(), "The step height must be positive.");
+
+        CharacterController {
+            up:             na::normalize(&up),
+            max_slope:      max_slope,
+            step_height:    step_height,
+            skin_width:     skin_width,
+            max_iterations: max_iterations
+        }
+    }
+
+    /// The normalized up direction of this character.
+    #[inline]
+    pub fn up(&self) -> &V {
+        &self.up
+    }
+
+    /// The maximum number of sliding (and depenetration) iterations performed by each motion.
+    #[inline]
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Sets the maximum number of sliding (and depenetration) iterations performed by each motion.
+    #[inline]
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations
+    }
+
+    /// Tests if a surface with the given normal can be walked on.
+    #[inline]
+    pub fn is_walkable(&self, normal: &V) -> bool {
+        na::dot(normal, &self.up) >= self.max_slope.cos()
+    }
+
+    /// Moves a character by a desired displacement, sliding it along the obstacles.
+    ///
+    /// Only the objects of `world` which collision groups are compatible with `groups` are taken
+    /// in account. If the character is itself part of `world`, `groups` should exclude it.
+    pub fn move_and_slide<P, AV, M, T>(&self,
+                                       world:        &CollisionWorld<N, P, V, M, T>,
+                                       shape:        &Repr<N, P, V, M>,
+                                       position:     &M,
+                                       displacement: &V,
+                                       groups:       &CollisionGroups)
+                                       -> CharacterMotion<V, M>
+        where P:  Point<N, V>,
+              V:  Translate<P> + Cross<Output = AV>,
+              AV: Vect<N>,
+              M:  Isometry<N, P, V> + Rotation<AV> {
+        let mut pos       = position.clone();
+        let mut remaining = displacement.clone();
+        let mut collided  = self.depenetrate(world, shape, &mut pos, groups);
+
+        for _ in 0 .. self.max_iterations {
+            let len = na::norm(&remaining);
+
+            if len.is_zero() {
+                break;
+            }
+
+            let dir = remaining / len;
+
+            match world.sweep_shape(shape, &pos, &dir, len + self.skin_width, groups) {
+                Some(hit) => {
+                    collided = true;
+
+                    let travel = na::clamp(hit.toi - self.skin_width, na::zero(), len);
+
+                    pos       = na::append_translation(&pos, &(dir * travel));
+                    remaining = dir * (len - travel);
+
+                    // Without a normal, the obstacle is assumed to face the motion.
+                    let normal   = hit.normal.unwrap_or(-dir);
+                    let walkable = self.is_walkable(&normal);
+
+                    if !walkable {
+                        if let Some((stepped, left)) = self.step_up(world, shape, &pos, &remaining, groups) {
+                            pos       = stepped;
+                            remaining = left;
+
+                            continue;
+                        }
+                    }
+
+                    // Slide along the obstacle.
+                    remaining = remaining - normal * na::dot(&remaining, &normal);
+
+                    if !walkable {
+                        // Do not climb steep slopes.
+                        let climb = na::dot(&remaining, &self.up);
+
+                        if climb > na::zero() {
+                            remaining = remaining - self.up * climb;
+                        }
+                    }
+                },
+                None => {
+                    pos = na::append_translation(&pos, &remaining);
+
+                    break;
+                }
+            }
+        }
+
+        let probe     = self.skin_width + self.skin_width;
+        let on_ground = match world.sweep_shape(shape, &pos, &-self.up, probe, groups) {
+            Some(hit) => hit.normal.map_or(false, |n| self.is_walkable(&n)),
+            None      => false
+        };
+
+        CharacterMotion {
+            displacement: pos.translation() - position.translation(),
+            position:     pos,
+            collided:     collided,
+            on_ground:    on_ground
+        }
+    }
+
+    /// Pushes the character out of the obstacles it penetrates.
+    ///
+    /// All the penetrating contacts are resolved at once so that the character does not oscillate
+    /// between obstacles, e.g., in corners. Returns `true` if the character was penetrating at
+    /// least one obstacle.
+    fn depenetrate<P, AV, M, T>(&self,
+                                world:    &CollisionWorld<N, P, V, M, T>,
+                                shape:    &Repr<N, P, V, M>,
+                                position: &mut M,
+                                groups:   &CollisionGroups)
+                                -> bool
+        where P:  Point<N, V>,
+              V:  Translate<P> + Cross<Output = AV>,
+              AV: Vect<N>,
+              M:  Isometry<N, P, V> + Rotation<AV> {
+        let mut penetrated = false;
+
+        let mut contacts = Vec::new();
+
+        for _ in 0 .. self.max_iterations {
+            world.interferences_with_shape(&*position, shape, groups, |_, cs| {
+                for c in cs.iter() {
+                    if c.depth > na::zero() {
+                        contacts.push((c.depth, c.normal))
+                    }
+                }
+            });
+
+            if contacts.is_empty() {
+                break;
+            }
+
+            // Find the smallest correction pushing the character out of every contact, with a few
+            // projected Gauss-Seidel sweeps. The contact normals point toward the obstacles.
+            let mut correction = na::zero::<V>();
+
+            for _ in 0 .. self.max_iterations {
+                for &(depth, normal) in contacts.iter() {
+                    let missing = depth + self.skin_width + na::dot(&correction, &normal);
+
+                    if missing > na::zero() {
+                        correction = correction - normal * missing;
+                    }
+                }
+            }
+
+            *position  = na::append_translation(&*position, &correction);
+            penetrated = true;
+
+            contacts.clear();
+        }
+
+        penetrated
+    }
+
+    /// Attempts to step up an obstacle.
+    ///
+    /// Returns the new position of the character and the displacement left, if the character
+    /// landed on a walkable surface.
+    fn step_up<P, AV, M, T>(&self,
+                            world:     &CollisionWorld<N, P, V, M, T>,
+                            shape:     &Repr<N, P, V, M>,
+                            position:  &M,
+                            remaining: &V,
+                            groups:    &CollisionGroups)
+                            -> Option<(M, V)>
+        where P:  Point<N, V>,
+              V:  Translate<P> + Cross<Output = AV>,
+              AV: Vect<N>,
+              M:  Isometry<N, P, V> + Rotation<AV> {
+        let horizontal = *remaining - self.up * na::dot(remaining, &self.up);
+        let hlen       = na::norm(&horizontal);
+
+        if self.step_height.is_zero() || hlen.is_zero() {
+            return None;
+        }
+
+        // Move up.
+        let up_dist = match world.sweep_shape(shape, position, &self.up, self.step_height + self.skin_width, groups) {
+            Some(hit) => na::clamp(hit.toi - self.skin_width, na::zero(), self.step_height),
+            None      => self.step_height
+        };
+
+        if up_dist.is_zero() {
+            return None;
+        }
+
+        let raised = na::append_translation(position, &(self.up * up_dist));
+
+        // Move forward.
+        let hdir    = horizontal / hlen;
+        let forward = match world.sweep_shape(shape, &raised, &hdir, hlen + self.skin_width, groups) {
+            Some(hit) => na::clamp(hit.toi - self.skin_width, na::zero(), hlen),
+            None      => hlen
+        };
+
+        if forward.is_zero() {
+            return None;
+        }
+
+        let advanced = na::append_translation(&raised, &(hdir * forward));
+
+        // Move down to land on the step.
+        match world.sweep_shape(shape, &advanced, &-self.up, up_dist + self.skin_width, groups) {
+            Some(hit) => {
+                if hit.normal.map_or(false, |n| self.is_walkable(&n)) {
+                    let down   = na::clamp(hit.toi - self.skin_width, na::zero(), up_dist);
+                    let landed = na::append_translation(&advanced, &(self.up * -down));
+
+                    Some((landed, hdir * (hlen - forward)))
+                }
+                else {
+                    None
+                }
+            },
+            None => None // There is nothing to step on.
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::{Pnt3, Vec3, Iso3, Translation};
+    use na;
+    use entities::shape::{Ball, Cuboid};
+    use entities::inspection::Repr;
+    use world::{CollisionWorld, CollisionGroups};
+    use super::CharacterController;
+
+    type Shape = Box<Repr<f64, Pnt3<f64>, Vec3<f64>, Iso3<f64>>>;
+
+    fn add_box(world: &mut CollisionWorld<f64, Pnt3<f64>, Vec3<f64>, Iso3<f64>, ()>,
+               uid: usize, center: Vec3<f64>, half_extents: Vec3<f64>) {
+        let cuboid = Box::new(Cuboid::new(half_extents)) as Shape;
+
+        world.add(uid, Iso3::new(center, na::zero()), Arc::new(cuboid), CollisionGroups::new(), ());
+    }
+
+    #[test]
+    fn test_slide_against_wall() {
+        let mut world = CollisionWorld::new(0.02, 0.02, true);
+
+        add_box(&mut world, 0, Vec3::new(0.0, -0.5, 0.0), Vec3::new(10.0, 0.5, 10.0)); // Floor.
+        add_box(&mut world, 1, Vec3::new(3.0, 5.0, 0.0), Vec3::new(0.5, 5.0, 10.0));   // Wall.
+        world.update();
+
+        let controller = CharacterController::new(Vec3::y(), 0.8, 0.3, 0.01);
+        let ball       = Ball::new(0.5f64);
+        let start      = Iso3::new(Vec3::new(0.0, 0.51, 0.0), na::zero());
+        let motion     = controller.move_and_slide(&world, &ball, &start, &Vec3::new(5.0, 0.0, 1.0),
+                                                   &CollisionGroups::new());
+        let end        = motion.position.translation();
+
+        assert!(motion.collided);
+        assert!(motion.on_ground);
+        assert!((end.x - 1.99).abs() <= 1.0e-2);
+        assert!((end.y - 0.51).abs() <= 1.0e-2);
+        assert!(end.z > 0.5);
+    }
+
+    #[test]
+    fn test_depenetrate_corner() {
+        let mut world = CollisionWorld::new(0.02, 0.02, true);
+
+        add_box(&mut world, 0, Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.5, 5.0, 5.0));
+        add_box(&mut world, 1, Vec3::new(0.0, 0.0, 1.0), Vec3::new(5.0, 5.0, 0.5));
+        world.update();
+
+        let controller = CharacterController::new_with_max_iterations(Vec3::y(), 0.8, 0.0, 0.01, 8);
+        let ball       = Ball::new(0.5f64);
+        let start      = Iso3::new(Vec3::new(0.2, 0.0, 0.2), na::zero());
+        let motion     = controller.move_and_slide(&world, &ball, &start, &na::zero(), &CollisionGroups::new());
+        let end        = motion.position.translation();
+
+        assert!(motion.collided);
+        assert!(end.x <= 1.0e-3 && end.z <= 1.0e-3);
+        assert!(end.x >= -0.1 && end.z >= -0.1);
+    }
+}
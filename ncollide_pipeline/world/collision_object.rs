@@ -2,6 +2,15 @@ use std::sync::Arc;
 use entities::inspection::Repr;
 use world::CollisionGroups;
 
+/// The activation state of a collision object.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActivationState {
+    /// The object is taken in account by the narrow phase.
+    Active,
+    /// The contacts involving only sleeping objects are not updated by the narrow phase.
+    Sleeping
+}
+
 /// A stand-alone object that has a position and a shape.
 pub struct CollisionObject<N, P, V, M, T> {
     /// The position of the collision object.
@@ -12,6 +21,8 @@ pub struct CollisionObject<N, P, V, M, T> {
     pub collision_groups: CollisionGroups,
     /// The data associated to this object.
     pub data: T,
+    /// The activation state of this object.
+    pub activation_state: ActivationState,
     #[doc(hidden)]
     pub timestamp: usize
 }
@@ -28,7 +39,14 @@ impl<N, P, V, M, T> CollisionObject<N, P, V, M, T> {
             shape:            shape,
             collision_groups: groups,
             data:             data,
+            activation_state: ActivationState::Active,
             timestamp:        0
         }
     }
+
+    /// Whether this object is sleeping.
+    #[inline]
+    pub fn is_sleeping(&self) -> bool {
+        self.activation_state == ActivationState::Sleeping
+    }
 }
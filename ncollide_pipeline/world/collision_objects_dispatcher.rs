@@ -1,3 +1,5 @@
+use std::collections::HashMap as StdHashMap;
+use std::collections::hash_map::Entry;
 use utils::data::hash_map::HashMap;
 use utils::data::pair::{Pair, PairTWHash};
 use utils::data::uid_remap::{UidRemap, FastKey};
@@ -24,12 +26,23 @@ impl<N, P, V, M, T> CollisionObjectsDispatcher<N, P, V, M, T> {
         }
     }
 
+    /// The dispatcher used to create the collision algorithms between two shapes.
+    #[inline]
+    pub fn shape_dispatcher(&self) -> &CollisionDispatcher<N, P, V, M> {
+        &*self.shape_dispatcher
+    }
+
     /// Updates the contact pairs.
     pub fn update(&mut self, objects: &UidRemap<CollisionObject<N, P, V, M, T>>, timestamp: usize) {
         for e in self.pairs.elements_mut().iter_mut() {
             let co1 = &objects[e.key.first];
             let co2 = &objects[e.key.second];
 
+            if co1.is_sleeping() && co2.is_sleeping() {
+                continue; // Reuse the previous manifold.
+            }
+
+            // Pairs which did not move since the last update reuse their previous manifold too.
             if co1.timestamp == timestamp || co2.timestamp == timestamp {
                 let had_colls = e.value.num_colls() != 0;
 
@@ -51,6 +64,58 @@ impl<N, P, V, M, T> CollisionObjectsDispatcher<N, P, V, M, T> {
         }
     }
 
+    /// Collects the objects connected to any of `seeds` by a chain of contacts, including the
+    /// seeds themselves.
+    ///
+    /// The islands are computed all at once with a union-find structure, so this is linear wrt.
+    /// the number of contact pairs, whatever the number of seeds.
+    pub fn collect_islands(&self, seeds: &[FastKey], out: &mut Vec<FastKey>) {
+        if seeds.is_empty() {
+            return;
+        }
+
+        let mut islands = Islands::new();
+
+        for e in self.pairs.elements().iter() {
+            if e.value.num_colls() != 0 {
+                let n1 = islands.node(&e.key.first);
+                let n2 = islands.node(&e.key.second);
+
+                islands.union(n1, n2);
+            }
+        }
+
+        let mut selected = Vec::with_capacity(seeds.len());
+
+        for seed in seeds.iter() {
+            let node = islands.node(seed);
+            selected.push(node);
+        }
+
+        let mut is_selected = vec![ false; islands.len() ];
+
+        for node in selected.into_iter() {
+            let root = islands.find(node);
+            is_selected[root] = true;
+        }
+
+        for node in 0 .. islands.len() {
+            if is_selected[islands.find(node)] {
+                out.push(islands.keys[node])
+            }
+        }
+    }
+
+    /// Calls a closure on each pair of objects with at least one contact.
+    pub fn touching_pairs<F>(&self, mut f: F)
+          where F: FnMut(&FastKey, &FastKey) {
+        for e in self.pairs.elements().iter() {
+            if e.value.num_colls() != 0 {
+                f(&e.key.first, &e.key.second)
+            }
+        }
+    }
+
     /// Iterates through all the contact pairs.
     #[inline(always)]
     pub fn contact_pairs<F>(&self,
@@ -156,3 +221,60 @@ impl<N, P, V, M, T> CollisionObjectsDispatcher<N, P, V, M, T> {
         }
     }
 }
+
+/// Union-find structure grouping the objects connected by a chain of contacts.
+struct Islands {
+    ids:     StdHashMap<usize, usize>,
+    keys:    Vec<FastKey>,
+    parents: Vec<usize>
+}
+
+impl Islands {
+    fn new() -> Islands {
+        Islands {
+            ids:     StdHashMap::new(),
+            keys:    Vec::new(),
+            parents: Vec::new()
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// The node of an object, created if needed.
+    fn node(&mut self, fk: &FastKey) -> usize {
+        match self.ids.entry(fk.uid()) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e)   => {
+                let node = self.keys.len();
+
+                self.keys.push(*fk);
+                self.parents.push(node);
+
+                *e.insert(node)
+            }
+        }
+    }
+
+    /// The representative of the island of a node.
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            // Path halving.
+            self.parents[node] = self.parents[self.parents[node]];
+            node = self.parents[node];
+        }
+
+        node
+    }
+
+    fn union(&mut self, node1: usize, node2: usize) {
+        let root1 = self.find(node1);
+        let root2 = self.find(node2);
+
+        if root1 != root2 {
+            self.parents[root1] = root2;
+        }
+    }
+}
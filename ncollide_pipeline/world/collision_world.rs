@@ -1,29 +1,92 @@
 use std::sync::Arc;
-use na::{Translate, Cross, Rotation};
+use std::collections::vec_map::Values;
+use na::{Translate, Cross, Rotation, Bounded, Translation};
+use na;
 use math::{Scalar, Point, Vect, Isometry};
 use utils::data::uid_remap::{UidRemap, FastKey};
 use entities::inspection::Repr;
-use entities::bounding_volume::{AABB, HasAABB};
+use entities::bounding_volume::{AABB, HasAABB, BoundingVolume, Frustum, FrustumClassification,
+                                FrustumInterferencesCollector};
+use entities::partitioning::BVTCostFn;
 use queries::geometry::Contact;
+use queries::geometry;
+use queries::geometry::algorithms::counters;
 use queries::ray::{RayCast, Ray, RayIntersection};
-use queries::point::PointQuery;
+use queries::point::{LocalPointQuery, PointQuery};
 use narrow_phase::{BasicCollisionDispatcher, ContactSignalHandler, CollisionAlgorithm};
 use broad_phase::{BroadPhase, DBVTBroadPhase};
-use world::{CollisionObjectsDispatcher, CollisionObject, CollisionGroups};
+use world::{CollisionObjectsDispatcher, CollisionObject, CollisionGroups, PipelineStats, ActivationState};
+use time;
 
 use na::{Pnt2, Pnt3, Vec2, Vec3, Iso2, Iso3};
 
 // FIXME: be generic wrt the BV?
 /// Type of the broad phase trait-object used by the collision world.
-pub type BroadPhaseObject<P, V> = Box<BroadPhase<P, V, AABB<P>, FastKey> + 'static>;
+pub type BroadPhaseObject<N, P, V> = Box<BroadPhase<N, P, V, AABB<P>, FastKey> + 'static>;
+
+/// An object found by a nearest-object query on the collision world.
+pub struct NearestObject<'a, N, P, T: 'a> {
+    /// The data associated to the object.
+    pub data:     &'a T,
+    /// The distance between the query point and the object.
+    pub distance: N,
+    /// The projection of the query point on the object.
+    pub point:    P
+}
+
+/// The first object hit by a shape swept through the collision world.
+pub struct SweepHit<'a, N, P, V, T: 'a> {
+    /// The data associated to the object hit.
+    pub data:   &'a T,
+    /// The distance travelled by the swept shape before the impact.
+    pub toi:    N,
+    /// The contact point on the object hit.
+    pub point:  P,
+    /// The normal of the object hit at the contact point. It points toward the swept shape.
+    ///
+    /// This is `None` if the contact at the time of impact could not be computed because of
+    /// numerical errors.
+    pub normal: Option<V>
+}
+
+/// Error returned when the shape of a collision object cannot be replaced.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SetShapeError {
+    /// No collision object has the given identifier.
+    UnknownObject,
+    /// The new shape does not have the same type as the current one.
+    ShapeTypeMismatch
+}
+
+/// An iterator through the collision objects of a collision world.
+pub struct CollisionObjects<'a, N: 'a, P: 'a, V: 'a, M: 'a, T: 'a> {
+    values: Values<'a, CollisionObject<N, P, V, M, T>>
+}
+
+impl<'a, N, P, V, M, T> Iterator for CollisionObjects<'a, N, P, V, M, T> {
+    type Item = &'a CollisionObject<N, P, V, M, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a CollisionObject<N, P, V, M, T>> {
+        self.values.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
 
 /// A world that handles collision objects.
 pub struct CollisionWorld<N, P, V, M, T> {
-    objects:       UidRemap<CollisionObject<N, P, V, M, T>>,
-    broad_phase:   BroadPhaseObject<P, V>,
-    narrow_phase:  CollisionObjectsDispatcher<N, P, V, M, T>,
-    pos_to_update: Vec<(FastKey, M)>,
-    timestamp:     usize
+    objects:         UidRemap<CollisionObject<N, P, V, M, T>>,
+    broad_phase:     BroadPhaseObject<N, P, V>,
+    narrow_phase:    CollisionObjectsDispatcher<N, P, V, M, T>,
+    pos_to_update:   Vec<(FastKey, M)>,
+    shape_to_update: Vec<(FastKey, Arc<Box<Repr<N, P, V, M>>>)>,
+    prediction:      N,
+    timestamp:       usize,
+    stats:           PipelineStats
     // FIXME: allow modification of the other properties too.
 }
 
@@ -43,11 +106,14 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
         let narrow_phase = CollisionObjectsDispatcher::new(sdispatcher);
 
         CollisionWorld {
-            objects:       objects,
-            broad_phase:   broad_phase as BroadPhaseObject<P, V>,
-            narrow_phase:  narrow_phase,
-            pos_to_update: Vec::new(),
-            timestamp:     0
+            objects:         objects,
+            broad_phase:     broad_phase as BroadPhaseObject<N, P, V>,
+            narrow_phase:    narrow_phase,
+            pos_to_update:   Vec::new(),
+            shape_to_update: Vec::new(),
+            prediction:      prediction,
+            timestamp:       0,
+            stats:           PipelineStats::new()
         }
     }
 
@@ -68,7 +134,11 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
     }
 
     /// Remove a collision object from the world.
+    ///
+    /// The sleeping objects touching the removed object are woken up.
     pub fn remove(&mut self, uid: usize) {
+        self.wake_up(uid);
+
         if let Some((fk, _)) = self.objects.remove(uid) {
             self.broad_phase.defered_remove(fk.uid());
         }
@@ -84,6 +154,33 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
         self.perform_narrow_phase();
     }
 
+    /// Statistics about the last execution of each phase of the collision detection pipeline.
+    #[inline]
+    pub fn stats(&self) -> &PipelineStats {
+        &self.stats
+    }
+
+    fn update_narrow_phase_stats(&mut self) {
+        let stats = &mut self.stats;
+
+        stats.num_gjk_iterations               = counters::gjk_iterations();
+        stats.num_minkowski_sampling_fallbacks = counters::minkowski_sampling_fallbacks();
+        stats.num_contacts                     = 0;
+        stats.num_algorithms.clear();
+
+        self.narrow_phase.contact_pairs(&self.objects, |_, _, algorithm| {
+            let name = algorithm.name();
+            let id   = stats.num_algorithms.iter().position(|e| e.0 == name);
+
+            match id {
+                Some(i) => stats.num_algorithms[i].1 = stats.num_algorithms[i].1 + 1,
+                None    => stats.num_algorithms.push((name, 1))
+            }
+
+            stats.num_contacts = stats.num_contacts + algorithm.num_colls();
+        });
+    }
+
     /// Sets the position the collision object attached to the specified object will have during
     /// the next update.
     pub fn defered_set_position(&mut self, uid: usize, pos: M) {
@@ -92,6 +189,29 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
         }
     }
 
+    /// Sets the shape the collision object attached to the specified object will have during the
+    /// next update.
+    ///
+    /// This is meant for deformable shapes, e.g., a `TriMesh` which vertices have been moved with
+    /// `TriMesh::set_vertices`. The new shape must have the same type as the current one so that
+    /// the contact algorithms of the object remain valid. Otherwise, the shape is left unchanged
+    /// and an error is returned.
+    pub fn defered_set_shape(&mut self, uid: usize, shape: Arc<Box<Repr<N, P, V, M>>>)
+                             -> Result<(), SetShapeError> {
+        match self.objects.get_fast_key(uid) {
+            Some(fk) => {
+                if self.objects[fk].shape.repr().type_id() != shape.repr().type_id() {
+                    return Err(SetShapeError::ShapeTypeMismatch)
+                }
+
+                self.shape_to_update.push((fk, shape));
+
+                Ok(())
+            },
+            None => Err(SetShapeError::UnknownObject)
+        }
+    }
+
     /// Registers a handler for contact start/stop events.
     pub fn register_contact_signal_handler<H>(&mut self, name: &str, handler: H)
         where H: ContactSignalHandler<T> + 'static {
@@ -103,17 +223,114 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
         self.narrow_phase.unregister_contact_signal_handler(name)
     }
 
-    /// Executes the position updates.
+    /// Puts a collision object to sleep.
+    ///
+    /// The contacts between two sleeping objects are no longer updated by the narrow phase. The
+    /// object is woken up automatically if its position is modified or if a moving object touches
+    /// it.
+    pub fn sleep(&mut self, uid: usize) {
+        if let Some(co) = self.objects.get_mut(uid) {
+            co.activation_state = ActivationState::Sleeping
+        }
+    }
+
+    /// Puts to sleep a collision object and every object connected to it by a chain of contacts.
+    pub fn sleep_island(&mut self, uid: usize) {
+        if let Some(fk) = self.objects.get_fast_key(uid) {
+            self.set_islands_activation_state(&[ fk ], ActivationState::Sleeping)
+        }
+    }
+
+    /// Wakes up a collision object and every object connected to it by a chain of contacts.
+    pub fn wake_up(&mut self, uid: usize) {
+        if let Some(fk) = self.objects.get_fast_key(uid) {
+            self.set_islands_activation_state(&[ fk ], ActivationState::Active)
+        }
+    }
+
+    /// Whether the collision object with the given identifier is sleeping.
+    #[inline]
+    pub fn is_sleeping(&self, uid: usize) -> bool {
+        match self.objects.get(uid) {
+            Some(co) => co.is_sleeping(),
+            None     => false
+        }
+    }
+
+    fn set_islands_activation_state(&mut self, seeds: &[FastKey], state: ActivationState) {
+        let mut islands = Vec::new();
+
+        self.narrow_phase.collect_islands(seeds, &mut islands);
+
+        for fk in islands.iter() {
+            if let Some(co) = self.objects.get_fast_mut(fk) {
+                co.activation_state = state
+            }
+        }
+    }
+
+    /// Wakes up the sleeping objects touching an object that moved during the current update.
+    fn propagate_wake_up(&mut self) {
+        let mut to_wake = Vec::new();
+
+        {
+            let objects   = &self.objects;
+            let timestamp = self.timestamp;
+
+            self.narrow_phase.touching_pairs(|fk1, fk2| {
+                let co1 = &objects[*fk1];
+                let co2 = &objects[*fk2];
+
+                if co1.is_sleeping() && !co2.is_sleeping() && co2.timestamp == timestamp {
+                    to_wake.push(*fk1)
+                }
+                else if co2.is_sleeping() && !co1.is_sleeping() && co1.timestamp == timestamp {
+                    to_wake.push(*fk2)
+                }
+            });
+        }
+
+        self.set_islands_activation_state(&to_wake[..], ActivationState::Active);
+    }
+
+    /// Executes the position and shape updates.
+    ///
+    /// Moving or deforming a sleeping object wakes it up, as well as the objects touching it.
     pub fn perform_position_update(&mut self) {
+        let t0          = time::precise_time_ns();
+        let mut to_wake = Vec::new();
+
+        for &(ref fk, ref shape) in self.shape_to_update.iter() {
+            if let Some(co) = self.objects.get_fast_mut(fk) {
+                co.shape = shape.clone();
+                co.timestamp = self.timestamp;
+                self.broad_phase.defered_set_bounding_volume(fk.uid(), co.shape.aabb(&co.position));
+
+                if co.is_sleeping() {
+                    to_wake.push(*fk)
+                }
+            }
+        }
+
+        self.shape_to_update.clear();
+
         for &(ref fk, ref pos) in self.pos_to_update.iter() {
             if let Some(co) = self.objects.get_fast_mut(fk) {
                 co.position = pos.clone();
                 co.timestamp = self.timestamp;
                 self.broad_phase.defered_set_bounding_volume(fk.uid(), co.shape.aabb(pos));
+
+                if co.is_sleeping() {
+                    to_wake.push(*fk)
+                }
             }
         }
 
         self.pos_to_update.clear();
+
+        self.set_islands_activation_state(&to_wake[..], ActivationState::Active);
+
+        self.stats.position_update_time = time::precise_time_ns() - t0;
     }
 
     /// Executes the broad phase of the collision detection pipeline.
@@ -121,20 +338,59 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
     /// Not that this does not take in account the changes made to the collision updates after the
     /// last `.perform_position_update()` call.
     pub fn perform_broad_phase(&mut self) {
-        let bf = &mut self.broad_phase;
-        let nf = &mut self.narrow_phase;
-        let objs = &self.objects;
+        let t0 = time::precise_time_ns();
+
+        {
+            let bf = &mut self.broad_phase;
+            let nf = &mut self.narrow_phase;
+            let objs = &self.objects;
+
+            bf.update(
+                &mut |b1, b2| CollisionObjectsDispatcher::is_proximity_allowed(objs, b1, b2),
+                &mut |b1, b2, started| nf.handle_proximity(objs, b1, b2, started)
+            );
+        }
 
-        bf.update(
-            &mut |b1, b2| CollisionObjectsDispatcher::is_proximity_allowed(objs, b1, b2),
-            &mut |b1, b2, started| nf.handle_proximity(objs, b1, b2, started)
-        );
+        self.stats.broad_phase_time      = time::precise_time_ns() - t0;
+        self.stats.num_broad_phase_pairs = self.broad_phase.num_interferences();
     }
 
     /// Executes the narrow phase of the collision detection pipeline.
+    ///
+    /// The contacts between two sleeping objects, or between two objects that did not move since
+    /// the last update, are not recomputed. Sleeping objects touched by a moving object are woken
+    /// up.
     pub fn perform_narrow_phase(&mut self) {
+        counters::reset();
+
+        let t0 = time::precise_time_ns();
+
         self.narrow_phase.update(&self.objects, self.timestamp);
+        self.propagate_wake_up();
         self.timestamp = self.timestamp + 1;
+
+        self.stats.narrow_phase_time = time::precise_time_ns() - t0;
+        self.update_narrow_phase_stats();
+    }
+
+    /// The collision object with the given identifier.
+    #[inline]
+    pub fn collision_object(&self, uid: usize) -> Option<&CollisionObject<N, P, V, M, T>> {
+        self.objects.get(uid)
+    }
+
+    /// Iterates through all the collision objects.
+    #[inline]
+    pub fn collision_objects(&self) -> CollisionObjects<N, P, V, M, T> {
+        CollisionObjects {
+            values: self.objects.values()
+        }
+    }
+
+    /// The broad phase used by this collision world.
+    #[inline]
+    pub fn broad_phase(&self) -> &BroadPhase<N, P, V, AABB<P>, FastKey> {
+        &*self.broad_phase
     }
 
     /// Iterats through all the contact pairs.
@@ -200,9 +456,473 @@ impl<N, P, V, AV, M, T> CollisionWorld<N, P, V, M, T>
             f(&self.objects[*fk].data)
         }
     }
+
+    /// Computes the objects of this world which AABB is not outside of a frustum.
+    ///
+    /// This is conservative: an object is reported if its AABB cannot be proven to be outside of
+    /// the frustum, even if the object itself does not intersect it.
+    pub fn interferences_with_frustum<F>(&self, frustum: &Frustum<N, P, V>, mut f: F)
+          where F: FnMut(&T) {
+        let mut fks = Vec::new();
+
+        {
+            let mut visitor = FrustumInterferencesCollector::new(frustum, &mut fks);
+            self.broad_phase.visit(&mut visitor);
+        }
+
+        for fk in fks.into_iter() {
+            let co = match self.objects.get_fast(fk) {
+                Some(co) => co,
+                None     => continue // Removed but not yet updated by the broad phase.
+            };
+
+            if frustum.classify_aabb(&co.shape.aabb(&co.position)) != FrustumClassification::Outside {
+                f(&co.data)
+            }
+        }
+    }
+
+    /// Computes the interferences between every object of this world and a shape at a given
+    /// position.
+    ///
+    /// The shape is not added to the world, thus no contact signal is triggered. Only the objects
+    /// which collision groups are compatible with `groups` are taken in account. The closure is
+    /// called with the data of each object intersecting the shape, and the contacts between the
+    /// shape (first) and the object (second).
+    ///
+    /// Objects for which the narrow phase dispatcher has no collision algorithm for the pair of
+    /// shapes are silently ignored, even if they intersect the shape.
+    pub fn interferences_with_shape<F>(&self,
+                                       position: &M,
+                                       shape:    &Repr<N, P, V, M>,
+                                       groups:   &CollisionGroups,
+                                       mut f:    F)
+          where F: FnMut(&T, &[Contact<N, P, V>]) {
+        let mut fks      = Vec::new();
+        let mut contacts = Vec::new();
+        let dispatcher   = self.narrow_phase.shape_dispatcher();
+
+        self.broad_phase.interferences_with_bounding_volume(&shape.aabb(position), &mut fks);
+
+        for fk in fks.into_iter() {
+            let co = match self.objects.get_fast(fk) {
+                Some(co) => co,
+                None     => continue // Removed but not yet updated by the broad phase.
+            };
+
+            if !co.collision_groups.can_collide_with_groups(groups) {
+                continue;
+            }
+
+            let algorithm = dispatcher.get_collision_algorithm(&shape.repr(), &co.shape.repr());
+
+            if let Some(mut algorithm) = algorithm {
+                let _ = algorithm.update(dispatcher, position, shape, &co.position, &**co.shape);
+
+                algorithm.colls(&mut contacts);
+
+                // Ignore the contacts due to the prediction margin.
+                contacts.retain(|c| c.depth >= na::zero());
+
+                if !contacts.is_empty() {
+                    f(&co.data, contacts.as_slice())
+                }
+
+                contacts.clear();
+            }
+        }
+    }
+
+    /// Sweeps a shape along a direction and finds the first object it hits.
+    ///
+    /// The shape starts at `start_position` and travels at most `max_dist` along `direction`
+    /// which does not need to be normalized. Only objects which collision groups are compatible
+    /// with `groups` are taken in account. The shape is not added to the world.
+    ///
+    /// Returns `None` if nothing is hit or if `direction` is zero.
+    pub fn sweep_shape(&self,
+                       shape:          &Repr<N, P, V, M>,
+                       start_position: &M,
+                       direction:      &V,
+                       max_dist:       N,
+                       groups:         &CollisionGroups)
+                       -> Option<SweepHit<N, P, V, T>> {
+        if na::is_zero(&na::sqnorm(direction)) {
+            return None;
+        }
+
+        let dir          = na::normalize(direction);
+        let end_position = na::append_translation(start_position, &(dir * max_dist));
+        let swept_aabb   = shape.aabb(start_position).merged(&shape.aabb(&end_position));
+        let mut fks      = Vec::new();
+
+        self.broad_phase.interferences_with_bounding_volume(&swept_aabb, &mut fks);
+
+        let     null_vel = na::zero::<V>();
+        let mut best_toi = max_dist;
+        let mut best_co  = None;
+
+        for fk in fks.into_iter() {
+            let co = match self.objects.get_fast(fk) {
+                Some(co) => co,
+                None     => continue // Removed but not yet updated by the broad phase.
+            };
+
+            if !co.collision_groups.can_collide_with_groups(groups) {
+                continue;
+            }
+
+            let toi = geometry::time_of_impact(start_position, &dir, shape,
+                                               &co.position, &null_vel, &**co.shape);
+
+            if let Some(toi) = toi {
+                if toi <= best_toi {
+                    best_toi = toi;
+                    best_co  = Some(co);
+                }
+            }
+        }
+
+        best_co.map(|co| {
+            let hit_position = na::append_translation(start_position, &(dir * best_toi));
+            let contact      = geometry::contact(&hit_position, shape, &co.position, &**co.shape,
+                                                 self.prediction);
+
+            match contact {
+                Some(c) => SweepHit {
+                    data:   &co.data,
+                    toi:    best_toi,
+                    point:  c.world2,
+                    normal: Some(-c.normal)
+                },
+                None => {
+                    // Should not happen, except for numerical errors.
+                    let center = na::orig::<P>() + hit_position.translation();
+
+                    SweepHit {
+                        data:   &co.data,
+                        toi:    best_toi,
+                        point:  co.shape.project_point_with_transform(&co.position, &center, true),
+                        normal: None
+                    }
+                }
+            }
+        })
+    }
+
+    /// Finds the object closest to a given point.
+    ///
+    /// Only objects which collision groups are compatible with `groups` and which are not farther
+    /// than `max_dist` (if specified) are taken in account.
+    pub fn nearest_object(&self, point: &P, max_dist: Option<N>, groups: &CollisionGroups)
+                          -> Option<NearestObject<N, P, T>> {
+        let mut nearest = self.k_nearest_objects(point, 1, max_dist, groups);
+
+        nearest.pop()
+    }
+
+    /// Finds the `k` objects closest to a given point, sorted by increasing distance.
+    ///
+    /// Only objects which collision groups are compatible with `groups` and which are not farther
+    /// than `max_dist` (if specified) are taken in account.
+    pub fn k_nearest_objects(&self, point: &P, k: usize, max_dist: Option<N>, groups: &CollisionGroups)
+                             -> Vec<NearestObject<N, P, T>> {
+        let mut fks = Vec::new();
+
+        {
+            let mut cost_fn = PointDistanceCostFn::new(&self.objects, point, groups);
+            let max_dist    = max_dist.unwrap_or(Bounded::max_value());
+
+            self.broad_phase.k_best_first_search(k, max_dist, &mut cost_fn, &mut fks);
+        }
+
+        let mut res = Vec::with_capacity(fks.len());
+
+        for (fk, distance) in fks.into_iter() {
+            let co = &self.objects[*fk];
+
+            res.push(NearestObject {
+                data:     &co.data,
+                distance: distance,
+                point:    co.shape.project_point_with_transform(&co.position, point, true)
+            })
+        }
+
+        res
+    }
+}
+
+/// Cost function computing the distance between a point and the objects of the world.
+struct PointDistanceCostFn<'a, N: 'a, P: 'a, V: 'a, M: 'a, T: 'a> {
+    objects: &'a UidRemap<CollisionObject<N, P, V, M, T>>,
+    point:   &'a P,
+    groups:  &'a CollisionGroups
+}
+
+impl<'a, N, P, V, M, T> PointDistanceCostFn<'a, N, P, V, M, T> {
+    fn new(objects: &'a UidRemap<CollisionObject<N, P, V, M, T>>,
+           point:   &'a P,
+           groups:  &'a CollisionGroups)
+           -> PointDistanceCostFn<'a, N, P, V, M, T> {
+        PointDistanceCostFn {
+            objects: objects,
+            point:   point,
+            groups:  groups
+        }
+    }
+}
+
+impl<'a, N, P, V, M, T> BVTCostFn<N, FastKey, AABB<P>, ()> for PointDistanceCostFn<'a, N, P, V, M, T>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P>,
+          M: Isometry<N, P, V> {
+    #[inline]
+    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
+        Some(aabb.distance_to_point(self.point))
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, fk: &FastKey) -> Option<(N, ())> {
+        match self.objects.get_fast(fk) {
+            Some(co) => {
+                if co.collision_groups.can_collide_with_groups(self.groups) {
+                    Some((co.shape.distance_to_point_with_transform(&co.position, self.point), ()))
+                }
+                else {
+                    None
+                }
+            },
+            None => None
+        }
+    }
 }
 
 /// 2D collision world containing objects of type `T`.
 pub type CollisionWorld2<N, T> = CollisionWorld<N, Pnt2<N>, Vec2<N>, Iso2<N>, T>;
 /// 3D collision world containing objects of type `T`.
 pub type CollisionWorld3<N, T> = CollisionWorld<N, Pnt3<N>, Vec3<N>, Iso3<N>, T>;
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::{Pnt3, Vec3, Iso3};
+    use na;
+    use entities::shape::{Ball, Cuboid, DistanceField};
+    use entities::bounding_volume::Frustum;
+    use entities::inspection::Repr;
+    use world::{CollisionGroups, SetShapeError};
+    use super::{CollisionWorld, CollisionWorld3};
+
+    fn add_ball(world: &mut CollisionWorld3<f64, usize>, uid: usize, x: f64) {
+        let ball = Box::new(Ball::new(0.5f64)) as Box<Repr<f64, Pnt3<f64>, Vec3<f64>, Iso3<f64>>>;
+        let pos  = Iso3::new(Vec3::new(x, 0.0, 0.0), na::zero());
+
+        world.add(uid, pos, Arc::new(ball), CollisionGroups::new(), uid);
+    }
+
+    #[test]
+    fn test_k_nearest_objects() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 3.0);
+        add_ball(&mut world, 2, -6.0);
+        world.update();
+
+        let groups  = CollisionGroups::new();
+        let nearest = world.k_nearest_objects(&Pnt3::new(4.0, 0.0, 0.0), 2, None, &groups);
+
+        assert!(nearest.len() == 2);
+        assert!(*nearest[0].data == 1 && *nearest[1].data == 0);
+        assert!(na::approx_eq(&nearest[0].distance, &0.5));
+        assert!(na::approx_eq(&nearest[0].point, &Pnt3::new(3.5, 0.0, 0.0)));
+        assert!(na::approx_eq(&nearest[1].distance, &3.5));
+
+        assert!(world.nearest_object(&Pnt3::new(4.0, 0.0, 0.0), Some(0.4), &groups).is_none());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 0.9);
+        add_ball(&mut world, 2, 5.0);
+        world.update();
+
+        assert!(world.stats().num_broad_phase_pairs == 1);
+        assert!(world.stats().num_algorithms_named("BallBall") == 1);
+        assert!(world.stats().num_contacts == 1);
+
+        // The narrow phase alone resets its own counters.
+        world.perform_narrow_phase();
+
+        assert!(world.stats().num_algorithms_named("BallBall") == 1);
+        assert!(world.stats().num_contacts == 1);
+    }
+
+    #[test]
+    fn test_sleeping_islands() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 0.9);
+        add_ball(&mut world, 2, 1.8);
+        add_ball(&mut world, 3, 5.0);
+        world.update();
+
+        world.sleep_island(0);
+
+        assert!(world.is_sleeping(0) && world.is_sleeping(1) && world.is_sleeping(2));
+        assert!(!world.is_sleeping(3));
+
+        // Moving one object of the island wakes up the whole island.
+        world.defered_set_position(2, Iso3::new(Vec3::new(1.85, 0.0, 0.0), na::zero()));
+        world.update();
+
+        assert!(!world.is_sleeping(0) && !world.is_sleeping(1) && !world.is_sleeping(2));
+    }
+
+    #[test]
+    fn test_interferences_with_shape() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 3.0);
+        world.update();
+
+        let shape     = Ball::new(1.0f64);
+        let pos       = Iso3::new(Vec3::new(1.2, 0.0, 0.0), na::zero());
+        let mut found = Vec::new();
+
+        world.interferences_with_shape(&pos, &shape, &CollisionGroups::new(), |data, contacts| {
+            assert!(!contacts.is_empty());
+            found.push(*data)
+        });
+
+        assert!(found == vec![ 0 ]);
+
+        // The queried shape has not been added to the world.
+        assert!(world.collision_objects().count() == 2);
+    }
+
+    #[test]
+    fn test_sweep_shape() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 5.0);
+        add_ball(&mut world, 1, 10.0);
+        world.update();
+
+        let shape  = Ball::new(0.5f64);
+        let start  = Iso3::new(Vec3::new(0.0, 0.0, 0.0), na::zero());
+        let groups = CollisionGroups::new();
+        let hit    = world.sweep_shape(&shape, &start, &Vec3::new(2.0, 0.0, 0.0), 20.0, &groups).unwrap();
+
+        assert!(*hit.data == 0);
+        assert!(na::approx_eq(&hit.toi, &4.0));
+        assert!(na::approx_eq(&hit.point, &Pnt3::new(4.5, 0.0, 0.0)));
+        assert!(na::approx_eq(&hit.normal.unwrap(), &Vec3::new(-1.0, 0.0, 0.0)));
+
+        assert!(world.sweep_shape(&shape, &start, &Vec3::new(1.0, 0.0, 0.0), 3.0, &groups).is_none());
+        assert!(world.sweep_shape(&shape, &start, &na::zero(), 20.0, &groups).is_none());
+    }
+
+    #[test]
+    fn test_sweep_shape_against_distance_field() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        // The unit ball, sampled on a grid.
+        let mut values = Vec::new();
+
+        for k in 0usize .. 17 {
+            for j in 0usize .. 17 {
+                for i in 0usize .. 17 {
+                    let v = Vec3::new(i as f64 * 0.25 - 2.0, j as f64 * 0.25 - 2.0, k as f64 * 0.25 - 2.0);
+
+                    values.push(na::norm(&v) - 1.0)
+                }
+            }
+        }
+
+        let field = DistanceField::new(values, vec!(17, 17, 17), Pnt3::new(-2.0, -2.0, -2.0),
+                                       Vec3::new(0.25, 0.25, 0.25));
+        let field = Box::new(field) as Box<Repr<f64, Pnt3<f64>, Vec3<f64>, Iso3<f64>>>;
+
+        world.add(0, na::one(), Arc::new(field), CollisionGroups::new(), 0);
+        world.update();
+
+        let shape  = Ball::new(0.5f64);
+        let start  = Iso3::new(Vec3::new(-5.0, 0.0, 0.0), na::zero());
+        let groups = CollisionGroups::new();
+        let hit    = world.sweep_shape(&shape, &start, &Vec3::new(1.0, 0.0, 0.0), 20.0, &groups).unwrap();
+
+        assert!(*hit.data == 0);
+        assert!(na::approx_eq_eps(&hit.toi, &3.5, &1.0e-2));
+        assert!(na::approx_eq_eps(&hit.point, &Pnt3::new(-1.0, 0.0, 0.0), &1.0e-2));
+
+        assert!(world.sweep_shape(&shape, &start, &Vec3::new(-1.0, 0.0, 0.0), 20.0, &groups).is_none());
+    }
+
+    #[test]
+    fn test_defered_set_shape() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 2.0);
+        world.update();
+
+        assert!(world.stats().num_contacts == 0);
+
+        let big_ball = Box::new(Ball::new(1.6f64)) as Box<Repr<f64, Pnt3<f64>, Vec3<f64>, Iso3<f64>>>;
+        let big_ball = Arc::new(big_ball);
+        let cuboid   = Box::new(Cuboid::new(Vec3::new(1.0f64, 1.0, 1.0)));
+        let cuboid   = cuboid as Box<Repr<f64, Pnt3<f64>, Vec3<f64>, Iso3<f64>>>;
+
+        assert!(world.defered_set_shape(0, Arc::new(cuboid)) == Err(SetShapeError::ShapeTypeMismatch));
+        assert!(world.defered_set_shape(42, big_ball.clone()) == Err(SetShapeError::UnknownObject));
+        assert!(world.defered_set_shape(0, big_ball).is_ok());
+        world.update();
+
+        assert!(world.stats().num_contacts == 1);
+    }
+
+    #[test]
+    fn test_interferences_with_frustum() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 1.2);
+        add_ball(&mut world, 2, 4.0);
+        world.update();
+
+        // The cube [-1, 1]^3.
+        let frustum   = Frustum::from_view_projection(&na::one());
+        let mut found = Vec::new();
+
+        world.interferences_with_frustum(&frustum, |data| found.push(*data));
+        found.sort();
+
+        assert!(found == vec![ 0, 1 ]);
+    }
+
+    #[test]
+    fn test_interferences_with_frustum_after_removal() {
+        let mut world: CollisionWorld3<f64, usize> = CollisionWorld::new(0.02, 0.02, true);
+
+        add_ball(&mut world, 0, 0.0);
+        add_ball(&mut world, 1, 0.5);
+        world.update();
+
+        // The broad phase still knows the removed object until the next update.
+        world.remove(0);
+
+        let frustum   = Frustum::from_view_projection(&na::one());
+        let mut found = Vec::new();
+
+        world.interferences_with_frustum(&frustum, |data| found.push(*data));
+
+        assert!(found == vec![ 1 ]);
+    }
+}
@@ -1,9 +1,12 @@
 //! High level API to detect collisions in large, complex scenes.
 
-pub use self::collision_object::CollisionObject;
+pub use self::collision_object::{CollisionObject, ActivationState};
 pub use self::collision_groups::CollisionGroups;
 pub use self::collision_objects_dispatcher::CollisionObjectsDispatcher;
-pub use self::collision_world::{BroadPhaseObject, CollisionWorld};
+pub use self::collision_world::{BroadPhaseObject, CollisionWorld, CollisionObjects, NearestObject, SweepHit,
+                                SetShapeError};
+pub use self::pipeline_stats::PipelineStats;
+pub use self::character_controller::{CharacterController, CharacterMotion};
 
 use na::{Pnt3, Vec3, Iso3, Pnt2, Vec2, Iso2};
 
@@ -11,6 +14,8 @@ mod collision_object;
 mod collision_groups;
 mod collision_objects_dispatcher;
 mod collision_world;
+mod pipeline_stats;
+mod character_controller;
 
 
 /// A 3D collision world associating collision objects to user-defined data of type `T`.
@@ -0,0 +1,79 @@
+/// Statistics about the last update of a collision world.
+///
+/// Each phase of the pipeline updates its own statistics when it is executed. Times are expressed
+/// in nanoseconds. The GJK and Minkowski sampling counters are always zero unless the `profiling`
+/// feature is enabled.
+#[derive(Clone, Debug)]
+pub struct PipelineStats {
+    /// Time spent updating the positions of the collision objects.
+    pub position_update_time:             u64,
+    /// Time spent executing the broad phase.
+    pub broad_phase_time:                 u64,
+    /// Time spent executing the narrow phase.
+    pub narrow_phase_time:                u64,
+    /// Number of pairs of objects detected by the broad phase.
+    pub num_broad_phase_pairs:            usize,
+    /// Number of collision algorithms of the narrow phase, by algorithm name.
+    pub num_algorithms:                   Vec<(&'static str, usize)>,
+    /// Number of contacts detected by the narrow phase.
+    pub num_contacts:                     usize,
+    /// Number of GJK iterations performed during the last narrow phase.
+    pub num_gjk_iterations:               usize,
+    /// Number of times the Minkowski sampling fallback algorithm was executed during the last
+    /// narrow phase.
+    pub num_minkowski_sampling_fallbacks: usize
+}
+
+impl PipelineStats {
+    /// Creates a new set of statistics with all counters set to zero.
+    pub fn new() -> PipelineStats {
+        PipelineStats {
+            position_update_time:             0,
+            broad_phase_time:                 0,
+            narrow_phase_time:                0,
+            num_broad_phase_pairs:            0,
+            num_algorithms:                   Vec::new(),
+            num_contacts:                     0,
+            num_gjk_iterations:               0,
+            num_minkowski_sampling_fallbacks: 0
+        }
+    }
+
+    /// Total time spent during the last update.
+    #[inline]
+    pub fn total_time(&self) -> u64 {
+        self.position_update_time + self.broad_phase_time + self.narrow_phase_time
+    }
+
+    /// Total number of collision algorithms of the narrow phase.
+    #[inline]
+    pub fn total_num_algorithms(&self) -> usize {
+        self.num_algorithms.iter().fold(0, |sum, e| sum + e.1)
+    }
+
+    /// Number of collision algorithms of the narrow phase with the given name.
+    #[inline]
+    pub fn num_algorithms_named(&self, name: &str) -> usize {
+        match self.num_algorithms.iter().find(|e| e.0 == name) {
+            Some(e) => e.1,
+            None    => 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PipelineStats;
+
+    #[test]
+    fn test_num_algorithms() {
+        let mut stats = PipelineStats::new();
+
+        stats.num_algorithms.push(("BallBall", 3));
+        stats.num_algorithms.push(("PlaneSupportMap", 2));
+
+        assert!(stats.total_num_algorithms() == 5);
+        assert!(stats.num_algorithms_named("BallBall") == 3);
+        assert!(stats.num_algorithms_named("SupportMapSupportMap") == 0);
+    }
+}
@@ -0,0 +1,42 @@
+//! Thread-local counters of the work performed by the geometric algorithms.
+//!
+//! The counters are incremented only if the `profiling` feature is enabled. Otherwise, they
+//! always stay at zero and cost nothing.
+
+use std::cell::Cell;
+
+thread_local!(static GJK_ITERATIONS: Cell<usize> = Cell::new(0));
+thread_local!(static MINKOWSKI_SAMPLING_FALLBACKS: Cell<usize> = Cell::new(0));
+
+/// Number of GJK iterations performed by the current thread since the last reset.
+pub fn gjk_iterations() -> usize {
+    GJK_ITERATIONS.with(|c| c.get())
+}
+
+/// Number of times the Minkowski sampling fallback algorithm was executed by the current thread
+/// since the last reset.
+pub fn minkowski_sampling_fallbacks() -> usize {
+    MINKOWSKI_SAMPLING_FALLBACKS.with(|c| c.get())
+}
+
+/// Resets all the counters of the current thread to zero.
+pub fn reset() {
+    GJK_ITERATIONS.with(|c| c.set(0));
+    MINKOWSKI_SAMPLING_FALLBACKS.with(|c| c.set(0));
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn count_gjk_iteration() {
+    if cfg!(feature = "profiling") {
+        GJK_ITERATIONS.with(|c| c.set(c.get() + 1))
+    }
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn count_minkowski_sampling_fallback() {
+    if cfg!(feature = "profiling") {
+        MINKOWSKI_SAMPLING_FALLBACKS.with(|c| c.set(c.get() + 1))
+    }
+}
@@ -6,6 +6,7 @@ use na;
 use entities::shape::{AnnotatedPoint, AnnotatedMinkowskiSum, MinkowskiSum, Reflection};
 use entities::support_map::SupportMap;
 use geometry::algorithms::simplex::Simplex;
+use geometry::algorithms::counters;
 use ray::Ray;
 use ray;
 use math::{Scalar, Point, Vect};
@@ -133,6 +134,8 @@ pub fn project_origin<N, P, V, M, S, G: ?Sized>(m: &M, shape: &G, simplex: &mut
     let _dim     = na::dim::<V>();
 
     loop {
+        counters::count_gjk_iteration();
+
         if simplex.dimension() == _dim || sq_len_dir <= _eps_tol /* * simplex.max_sq_len()*/ {
             return None // point inside of the cso
         }
@@ -190,6 +193,8 @@ pub fn project_origin_with_max_dist<N, P, V, M, S, G: ?Sized>(m:        &M,
     let _dim     = na::dim::<V>();
 
     loop {
+        counters::count_gjk_iteration();
+
         if simplex.dimension() == _dim || sq_len_dir <= _eps_tol /* * simplex.max_sq_len()*/ {
             return GJKResult::Intersection // point inside of the cso
         }
@@ -255,6 +260,8 @@ pub fn cast_ray<N, P, V, M, S, G: ?Sized>(m:       &M,
     // FIXME: this converges in more than 100 iterations… something is wrong here…
     let mut niter = 0usize;
     loop {
+        counters::count_gjk_iteration();
+
         niter = niter + 1;
 
         if dir.normalize_mut().is_zero() {
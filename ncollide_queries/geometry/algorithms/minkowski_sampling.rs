@@ -6,6 +6,7 @@ use entities::shape::{MinkowskiSum, AnnotatedPoint, Reflection};
 use entities::support_map::{SupportMap, PreferedSamplingDirections};
 use geometry::algorithms::gjk;
 use geometry::algorithms::simplex::Simplex;
+use geometry::algorithms::counters;
 use math::{Scalar, Point, Vect};
 
 
@@ -20,6 +21,8 @@ pub fn closest_points<N, P, V, M, S, G1: ?Sized, G2: ?Sized>(
           S:  Simplex<N, AnnotatedPoint<P>>,
           G1: SupportMap<P, V, M> + PreferedSamplingDirections<V, M>,
           G2: SupportMap<P, V, M> + PreferedSamplingDirections<V, M> {
+    counters::count_minkowski_sampling_fallback();
+
     let _0: N = na::zero();
     let reflect2   = Reflection::new(g2);
     let cso        = MinkowskiSum::new(m1, g1, m2, &reflect2);
@@ -136,6 +139,8 @@ pub fn project_origin<N, P, V, M, S, G>(m: &M, g: &G, simplex: &mut S) -> Option
           M: Translation<V>,
           S: Simplex<N, P>,
           G: SupportMap<P, V, M> + PreferedSamplingDirections<V, M> {
+    counters::count_minkowski_sampling_fallback();
+
     // find an approximation of the smallest penetration direction
     let mut best_dir: V = na::zero();
     let mut min_dist    = Bounded::max_value();
@@ -3,3 +3,4 @@ pub mod simplex;
 pub mod johnson_simplex;
 pub mod gjk;
 pub mod minkowski_sampling;
+pub mod counters;
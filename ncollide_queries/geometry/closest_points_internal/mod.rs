@@ -0,0 +1,5 @@
+//! Implementation details of the closest points computations.
+
+pub use self::segment_against_segment::{segment_against_segment, closest_points_segment_segment};
+
+mod segment_against_segment;
@@ -0,0 +1,57 @@
+use std::num::Float;
+use na::Transform;
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::Segment;
+
+/// Closest points between two segments.
+///
+/// The first point is on the first segment, the second point is on the second segment.
+#[inline]
+pub fn segment_against_segment<N, P, V, M>(m1: &M, s1: &Segment<P>, m2: &M, s2: &Segment<P>) -> (P, P)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    closest_points_segment_segment(&m1.transform(s1.a()), &m1.transform(s1.b()),
+                                   &m2.transform(s2.a()), &m2.transform(s2.b()))
+}
+
+/// Closest points between the segments `[a1, b1]` and `[a2, b2]`.
+pub fn closest_points_segment_segment<N, P, V>(a1: &P, b1: &P, a2: &P, b2: &P) -> (P, P)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let d1 = *b1 - *a1;
+    let d2 = *b2 - *a2;
+    let r  = *a1 - *a2;
+
+    let a = na::sqnorm(&d1);
+    let e = na::sqnorm(&d2);
+    let f = na::dot(&d2, &r);
+    let c = na::dot(&d1, &r);
+    let b = na::dot(&d1, &d2);
+
+    let _eps: N = Float::epsilon();
+    let denom   = a * e - b * b;
+
+    let mut s = if denom > _eps * a * e {
+        na::clamp((b * f - c * e) / denom, na::zero(), na::one())
+    }
+    else {
+        na::zero()
+    };
+
+    let mut t = if e > _eps { (b * s + f) / e } else { na::zero() };
+
+    if t < na::zero() {
+        t = na::zero();
+        s = if a > _eps { na::clamp(-c / a, na::zero(), na::one()) } else { na::zero() };
+    }
+    else if t > na::one() {
+        t = na::one();
+        s = if a > _eps { na::clamp((b - c) / a, na::zero(), na::one()) } else { na::zero() };
+    }
+
+    (*a1 + d1 * s, *a2 + d2 * t)
+}
@@ -3,7 +3,7 @@ use na::Translate;
 use math::{Scalar, Point, Vect, Isometry};
 use entities::inspection;
 use entities::inspection::Repr;
-use entities::shape::{Ball, Plane};
+use entities::shape::{Ball, Capsule, Plane, DistanceField};
 use entities::bounding_volume::HasAABB;
 use geometry::contacts_internal;
 use geometry::contacts_internal::Contact;
@@ -30,6 +30,19 @@ pub fn any_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, g1: &G1,
 
         contacts_internal::ball_against_ball(&p1, b1, &p2, b2, prediction)
     }
+    else if let (Some(c1), Some(c2)) = (r1.downcast_ref::<Capsule<N>>(), r2.downcast_ref::<Capsule<N>>()) {
+        let mut contacts = Vec::new();
+
+        contacts_internal::capsule_against_capsule(m1, c1, m2, c2, prediction, &mut contacts);
+
+        deepest(contacts)
+    }
+    else if let (Some(c1), Some(b2)) = (r1.downcast_ref::<Capsule<N>>(), r2.downcast_ref::<Ball<N>>()) {
+        contacts_internal::capsule_against_ball(m1, c1, &m2.translate(&na::orig()), b2, prediction)
+    }
+    else if let (Some(b1), Some(c2)) = (r1.downcast_ref::<Ball<N>>(), r2.downcast_ref::<Capsule<N>>()) {
+        contacts_internal::ball_against_capsule(&m1.translate(&na::orig()), b1, m2, c2, prediction)
+    }
     else if let (Some(p1), Some(s2)) =
             (r1.downcast_ref::<Plane<V>>(), inspection::maybe_repr_desc_as_support_map::<P, V, M>(r2)) {
         contacts_internal::plane_against_support_map(m1, p1, m2, s2, prediction)
@@ -38,6 +51,14 @@ pub fn any_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, g1: &G1,
             (inspection::maybe_repr_desc_as_support_map::<P, V, M>(r1), r2.downcast_ref::<Plane<V>>()) {
         contacts_internal::support_map_against_plane(m1, s1, m2, p2, prediction)
     }
+    else if let (Some(d1), Some(s2)) =
+            (r1.downcast_ref::<DistanceField<N, P, V>>(), inspection::maybe_repr_desc_as_support_map::<P, V, M>(r2)) {
+        contacts_internal::distance_field_against_support_map(m1, d1, m2, s2, prediction)
+    }
+    else if let (Some(s1), Some(d2)) =
+            (inspection::maybe_repr_desc_as_support_map::<P, V, M>(r1), r2.downcast_ref::<DistanceField<N, P, V>>()) {
+        contacts_internal::support_map_against_distance_field(m1, s1, m2, d2, prediction)
+    }
     else if let (Some(s1), Some(s2)) =
             (inspection::maybe_repr_desc_as_support_map::<P, V, M>(r1),
              inspection::maybe_repr_desc_as_support_map::<P, V, M>(r2)) {
@@ -53,3 +74,20 @@ pub fn any_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, g1: &G1,
         panic!("No algorithm known to compute a contact point between the given pair of shapes.")
     }
 }
+
+fn deepest<N: Scalar, P, V>(contacts: Vec<Contact<N, P, V>>) -> Option<Contact<N, P, V>> {
+    let mut res: Option<Contact<N, P, V>> = None;
+
+    for c in contacts.into_iter() {
+        let is_deeper = match res {
+            Some(ref best) => c.depth > best.depth,
+            None           => true
+        };
+
+        if is_deeper {
+            res = Some(c)
+        }
+    }
+
+    res
+}
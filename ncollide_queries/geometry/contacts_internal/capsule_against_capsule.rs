@@ -0,0 +1,140 @@
+use std::num::Float;
+use na::{Transform, Rotate, Translate};
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::{Ball, Capsule, Plane};
+use geometry::Contact;
+use geometry::contacts_internal;
+use geometry::closest_points_internal;
+
+/// Contacts between capsules.
+///
+/// Two contacts are generated if the capsules are parallel and their axis overlap. Otherwise, at
+/// most one contact is generated. The contacts are appended to `out`.
+pub fn capsule_against_capsule<N, P, V, M>(m1: &M, c1: &Capsule<N>,
+                                           m2: &M, c2: &Capsule<N>,
+                                           prediction: N,
+                                           out: &mut Vec<Contact<N, P, V>>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let s1    = c1.segment();
+    let s2    = c2.segment();
+    let a1    = m1.transform(s1.a());
+    let b1    = m1.transform(s1.b());
+    let a2    = m2.transform(s2.a());
+    let b2    = m2.transform(s2.b());
+    let ball1 = Ball::new(c1.radius());
+    let ball2 = Ball::new(c2.radius());
+
+    let d1      = b1 - a1;
+    let d2      = b2 - a2;
+    let sqn1    = na::sqnorm(&d1);
+    let sqn2    = na::sqnorm(&d2);
+    let dot     = na::dot(&d1, &d2);
+    let _eps: N = Float::epsilon();
+
+    if sqn1 * sqn2 - dot * dot <= _eps.sqrt() * sqn1 * sqn2 {
+        // The capsules are parallel: find the overlapping part of their axis.
+        let t_a2 = na::dot(&(a2 - a1), &d1) / sqn1;
+        let t_b2 = na::dot(&(b2 - a1), &d1) / sqn1;
+        let tmin = na::clamp(if t_a2 < t_b2 { t_a2 } else { t_b2 }, na::zero(), na::one());
+        let tmax = na::clamp(if t_a2 < t_b2 { t_b2 } else { t_a2 }, na::zero(), na::one());
+
+        if tmax > tmin {
+            for t in [ tmin, tmax ].iter() {
+                let p1 = a1 + d1 * *t;
+                let p2 = closest_points_internal::closest_points_segment_segment(&a2, &b2, &p1, &p1).0;
+
+                if let Some(c) = contacts_internal::ball_against_ball(&p1, &ball1, &p2, &ball2, prediction) {
+                    out.push(c)
+                }
+            }
+
+            return;
+        }
+    }
+
+    let (p1, p2) = closest_points_internal::closest_points_segment_segment(&a1, &b1, &a2, &b2);
+
+    if let Some(c) = contacts_internal::ball_against_ball(&p1, &ball1, &p2, &ball2, prediction) {
+        out.push(c)
+    }
+}
+
+/// Contact between a capsule and a ball.
+pub fn capsule_against_ball<N, P, V, M>(m1: &M, c1: &Capsule<N>,
+                                        center2: &P, b2: &Ball<N>,
+                                        prediction: N)
+                                        -> Option<Contact<N, P, V>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let s  = c1.segment();
+    let a  = m1.transform(s.a());
+    let b  = m1.transform(s.b());
+    let pt = closest_points_internal::closest_points_segment_segment(&a, &b, center2, center2).0;
+
+    contacts_internal::ball_against_ball(&pt, &Ball::new(c1.radius()), center2, b2, prediction)
+}
+
+/// Contact between a ball and a capsule.
+pub fn ball_against_capsule<N, P, V, M>(center1: &P, b1: &Ball<N>,
+                                        m2: &M, c2: &Capsule<N>,
+                                        prediction: N)
+                                        -> Option<Contact<N, P, V>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    capsule_against_ball(m2, c2, center1, b1, prediction).map(|mut c| { c.flip(); c })
+}
+
+/// Contacts between a plane and a capsule.
+///
+/// One contact is generated for each extremity of the capsule axis closer to the plane than
+/// `prediction`. The contacts are appended to `out`.
+pub fn plane_against_capsule<N, P, V, M>(mplane: &M, plane: &Plane<V>,
+                                         mcapsule: &M, capsule: &Capsule<N>,
+                                         prediction: N,
+                                         out: &mut Vec<Contact<N, P, V>>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> + Rotate<V> {
+    let plane_normal = mplane.rotate(plane.normal());
+    let plane_center = mplane.translate(&na::orig());
+    let s            = capsule.segment();
+
+    for pt in [ s.a(), s.b() ].iter() {
+        let deepest = mcapsule.transform(*pt) + plane_normal * (-capsule.radius());
+        let dist    = na::dot(&plane_normal, &(plane_center - deepest));
+
+        if dist > -prediction {
+            out.push(Contact::new(deepest + plane_normal * dist, deepest, plane_normal, dist))
+        }
+    }
+}
+
+/// Contacts between a capsule and a plane.
+///
+/// One contact is generated for each extremity of the capsule axis closer to the plane than
+/// `prediction`. The contacts are appended to `out`.
+pub fn capsule_against_plane<N, P, V, M>(mcapsule: &M, capsule: &Capsule<N>,
+                                         mplane: &M, plane: &Plane<V>,
+                                         prediction: N,
+                                         out: &mut Vec<Contact<N, P, V>>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Translate<P> + Rotate<V> {
+    let start = out.len();
+
+    plane_against_capsule(mplane, plane, mcapsule, capsule, prediction, out);
+
+    for c in out[start ..].iter_mut() {
+        c.flip()
+    }
+}
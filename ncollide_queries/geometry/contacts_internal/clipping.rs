@@ -0,0 +1,137 @@
+//! Polygon clipping utilities used to build contact manifolds.
+
+use na;
+use math::{Scalar, Point, Vect};
+use geometry::Contact;
+use utils;
+
+/// Keeps the part of a convex polygon satisfying `dot(pt - center, normal) <= extent`.
+pub fn clip_polygon<N, P, V>(polygon: &[P], normal: &V, extent: N, center: &P) -> Vec<P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut res = Vec::with_capacity(polygon.len() + 1);
+
+    for i in 0 .. polygon.len() {
+        let a  = &polygon[i];
+        let b  = &polygon[(i + 1) % polygon.len()];
+        let da = na::dot(&(*a - *center), normal) - extent;
+        let db = na::dot(&(*b - *center), normal) - extent;
+
+        if da <= na::zero() {
+            res.push(a.clone());
+        }
+
+        if (da < na::zero() && db > na::zero()) || (da > na::zero() && db < na::zero()) {
+            let t = da / (da - db);
+
+            res.push(*a + (*b - *a) * t);
+        }
+    }
+
+    res
+}
+
+/// Reduces a manifold to at most four contacts, keeping the deepest one and maximizing the area
+/// covered by the others.
+pub fn reduce_manifold<N, P, V>(contacts: &mut Vec<Contact<N, P, V>>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    if contacts.len() <= 4 {
+        return;
+    }
+
+    let mut selected = Vec::with_capacity(4);
+
+    // The deepest point.
+    let mut deepest = 0;
+
+    for i in 1 .. contacts.len() {
+        if contacts[i].depth > contacts[deepest].depth {
+            deepest = i;
+        }
+    }
+
+    selected.push(deepest);
+
+    // The farthest point from the deepest one.
+    let p0       = contacts[deepest].world2;
+    let mut best = na::zero::<N>();
+    let mut id1  = deepest;
+
+    for i in 0 .. contacts.len() {
+        let sqdist = na::sqdist(&contacts[i].world2, &p0);
+
+        if sqdist > best {
+            best = sqdist;
+            id1  = i;
+        }
+    }
+
+    if id1 == deepest {
+        contacts.truncate(1);
+        return;
+    }
+
+    selected.push(id1);
+
+    // The point maximizing the area of the triangle, on each side of the segment.
+    let p1         = contacts[id1].world2;
+    let normal     = contacts[deepest].normal;
+    let mut best_p = na::zero::<N>();
+    let mut best_n = na::zero::<N>();
+    let mut id_p   = None;
+    let mut id_n   = None;
+
+    for i in 0 .. contacts.len() {
+        let area = na::dot(&utils::cross3(&(p1 - p0), &(contacts[i].world2 - p0)), &normal);
+
+        if area > best_p {
+            best_p = area;
+            id_p   = Some(i);
+        }
+        else if -area > best_n {
+            best_n = -area;
+            id_n   = Some(i);
+        }
+    }
+
+    if let Some(i) = id_p {
+        selected.push(i)
+    }
+
+    if let Some(i) = id_n {
+        selected.push(i)
+    }
+
+    let mut reduced = Vec::with_capacity(selected.len());
+
+    for i in selected.into_iter() {
+        reduced.push(contacts[i].clone());
+    }
+
+    *contacts = reduced;
+}
+
+/// Keeps the part of the segment `[a, b]` satisfying `dot(pt - center, normal) <= extent`.
+pub fn clip_segment<N, P, V>(a: &P, b: &P, normal: &V, extent: N, center: &P) -> Option<(P, P)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let da = na::dot(&(*a - *center), normal) - extent;
+    let db = na::dot(&(*b - *center), normal) - extent;
+
+    if da > na::zero() && db > na::zero() {
+        None
+    }
+    else if da > na::zero() {
+        Some((*a + (*b - *a) * (da / (da - db)), *b))
+    }
+    else if db > na::zero() {
+        Some((*a, *a + (*b - *a) * (da / (da - db))))
+    }
+    else {
+        Some((*a, *b))
+    }
+}
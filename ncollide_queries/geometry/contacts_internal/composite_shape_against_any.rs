@@ -31,7 +31,7 @@ pub fn manifold_composite_shape_against_shape<N, P, V, AV, M, G1, G2>(
 
     {
         let mut visitor = BoundingVolumeInterferencesCollector::new(&ls_aabb2, &mut interferences);
-        g1.bvt().visit(&mut visitor);
+        g1.visit(&mut visitor);
     }
 
     for i in interferences.into_iter() {
@@ -84,7 +84,7 @@ pub fn composite_shape_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(
 
     {
         let mut visitor = BoundingVolumeInterferencesCollector::new(&ls_aabb2, &mut interferences);
-        g1.bvt().visit(&mut visitor);
+        g1.visit(&mut visitor);
     }
 
     let mut res = None::<Contact<N, P, V>>;
@@ -0,0 +1,381 @@
+use std::num::Float;
+use na::{Transform, Rotate};
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::ConvexPolyhedron;
+use geometry::Contact;
+use geometry::contacts_internal::clipping;
+use geometry::closest_points_internal;
+use utils;
+
+/// Computes the contact manifold between two 2D or 3D convex polyhedra.
+///
+/// This uses the separating axis theorem on the face normals and, in 3D, on the pairs of edges
+/// forming a face of the Minkowski difference. If the axis of smallest penetration is a face
+/// normal, the incident face of the other polyhedron is clipped against the reference face.
+/// Otherwise, a single contact is generated between the two closest edges. The contacts are
+/// appended to `out`. Returns `false` if the polyhedra are separated by more than `prediction`.
+pub fn convex_polyhedron_against_convex_polyhedron<N, P, V, M>(m1: &M, p1: &ConvexPolyhedron<P, V>,
+                                                               m2: &M, p2: &ConvexPolyhedron<P, V>,
+                                                               prediction: N,
+                                                               out: &mut Vec<Contact<N, P, V>>)
+                                                               -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+    let dim = na::dim::<P>();
+
+    assert!(dim == 2 || dim == 3, "Convex polyhedra contacts are only implemented in 2D and 3D.");
+
+    if p1.faces().is_empty() || p2.faces().is_empty() {
+        return false;
+    }
+
+    let vs1: Vec<P> = p1.vertices().iter().map(|v| m1.transform(v)).collect();
+    let ns1: Vec<V> = p1.normals().iter().map(|n| m1.rotate(n)).collect();
+    let vs2: Vec<P> = p2.vertices().iter().map(|v| m2.transform(v)).collect();
+    let ns2: Vec<V> = p2.normals().iter().map(|n| m2.rotate(n)).collect();
+
+    let (sep1, face1) = max_face_separation(p1.faces(), &vs1[..], &ns1[..], &vs2[..]);
+
+    if sep1 > prediction {
+        return false;
+    }
+
+    let (sep2, face2) = max_face_separation(p2.faces(), &vs2[..], &ns2[..], &vs1[..]);
+
+    if sep2 > prediction {
+        return false;
+    }
+
+    let edges = if dim == 3 {
+        max_edge_separation(p1, &vs1[..], &ns1[..], p2, &vs2[..], &ns2[..])
+    }
+    else {
+        None
+    };
+
+    if let Some((sep, _, _, _)) = edges {
+        if sep > prediction {
+            return false;
+        }
+    }
+
+    // Prefer the faces of the first polyhedron, then the faces of the second one, and finally the
+    // edges, to avoid flip-flopping between features with almost equal separations. The absolute
+    // tolerance is relative to the polyhedra sizes so that it does not depend on the scene units.
+    let rel_tol: N = na::cast(0.95f64);
+    let abs_tol: N = (diameter(&vs1[..]) + diameter(&vs2[..])) * na::cast(0.005f64);
+
+    let use_face2 = sep2 > rel_tol * sep1 + abs_tol;
+    let face_sep  = if use_face2 { sep2 } else { sep1 };
+
+    match edges {
+        Some((sep, e1, e2, normal)) if sep > rel_tol * face_sep + abs_tol => {
+            let (a1, b1) = p1.edges()[e1];
+            let (a2, b2) = p2.edges()[e2];
+            let (pt1, pt2) = closest_points_internal::closest_points_segment_segment(
+                &vs1[a1], &vs1[b1], &vs2[a2], &vs2[b2]);
+
+            out.push(Contact::new(pt1, pt2, normal, -sep));
+        },
+        _ => {
+            if use_face2 {
+                clip_incident_face(&p2.faces()[face2][..], &vs2[..], &ns2[face2],
+                                   p1.faces(), &vs1[..], &ns1[..], prediction, true, out)
+            }
+            else {
+                clip_incident_face(&p1.faces()[face1][..], &vs1[..], &ns1[face1],
+                                   p2.faces(), &vs2[..], &ns2[..], prediction, false, out)
+            }
+        }
+    }
+
+    true
+}
+
+/// An approximation of the diameter of a set of vertices, within a factor of two.
+fn diameter<N, P, V>(vs: &[P]) -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut res = na::zero::<N>();
+
+    for v in vs.iter() {
+        let d = na::dist(&vs[0], v);
+
+        if d > res {
+            res = d;
+        }
+    }
+
+    res
+}
+
+/// The face of the first polyhedron with the largest separation from the second polyhedron.
+fn max_face_separation<N, P, V>(faces: &[Vec<usize>], vs1: &[P], ns1: &[V], vs2: &[P]) -> (N, usize)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut best_sep  = -Float::infinity();
+    let mut best_face = 0;
+
+    for (i, face) in faces.iter().enumerate() {
+        let orig    = &vs1[face[0]];
+        let mut sep = Float::infinity();
+
+        for v in vs2.iter() {
+            let d = na::dot(&ns1[i], &(*v - *orig));
+
+            if d < sep {
+                sep = d;
+            }
+        }
+
+        if sep > best_sep {
+            best_sep  = sep;
+            best_face = i;
+        }
+    }
+
+    (best_sep, best_face)
+}
+
+/// The pair of edges with the largest separation, among those forming a face of the Minkowski
+/// difference.
+///
+/// Returns the separation, the two edge indices, and the separating axis oriented from the first
+/// polyhedron toward the second one.
+fn max_edge_separation<N, P, V>(p1: &ConvexPolyhedron<P, V>, vs1: &[P], ns1: &[V],
+                                p2: &ConvexPolyhedron<P, V>, vs2: &[P], ns2: &[V])
+                                -> Option<(N, usize, usize, V)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let eps: N  = Float::epsilon();
+    let mut res = None;
+    let mut best_sep: N = -Float::infinity();
+
+    let mut center1 = na::orig::<P>();
+
+    for v in vs1.iter() {
+        center1 = center1 + *v.as_vec();
+    }
+
+    let center1 = center1 / na::cast(vs1.len() as f64);
+
+    for (i1, &(a1, b1)) in p1.edges().iter().enumerate() {
+        let (fa, fb) = p1.edge_faces()[i1];
+        let dir1     = vs1[b1] - vs1[a1];
+
+        for (i2, &(a2, b2)) in p2.edges().iter().enumerate() {
+            let (fc, fd) = p2.edge_faces()[i2];
+
+            if !is_minkowski_face(&ns1[fa], &ns1[fb], &-ns2[fc], &-ns2[fd]) {
+                continue;
+            }
+
+            let dir2   = vs2[b2] - vs2[a2];
+            let axis   = utils::cross3(&dir1, &dir2);
+            let sqnorm = na::sqnorm(&axis);
+
+            if sqnorm <= eps * na::sqnorm(&dir1) * na::sqnorm(&dir2) {
+                continue; // The edges are parallel.
+            }
+
+            let mut axis = axis / sqnorm.sqrt();
+
+            if na::dot(&axis, &(vs1[a1] - center1)) < na::zero() {
+                axis = -axis;
+            }
+
+            let sep = na::dot(&axis, &(vs2[a2] - vs1[a1]));
+
+            if sep > best_sep {
+                best_sep = sep;
+                res      = Some((sep, i1, i2, axis));
+            }
+        }
+    }
+
+    res
+}
+
+/// Tests if the arcs `ab` and `cd` of the Gauss map intersect.
+///
+/// The normals `c` and `d` must already be negated to represent the Minkowski difference.
+fn is_minkowski_face<N, V>(a: &V, b: &V, c: &V, d: &V) -> bool
+    where N: Scalar,
+          V: Vect<N> {
+    let bxa = utils::cross3(b, a);
+    let dxc = utils::cross3(d, c);
+
+    let cba = na::dot(c, &bxa);
+    let dba = na::dot(d, &bxa);
+    let adc = na::dot(a, &dxc);
+    let bdc = na::dot(b, &dxc);
+
+    cba * dba < na::zero() && adc * bdc < na::zero() && cba * bdc > na::zero()
+}
+
+/// Clips the incident face against the reference face and pushes the resulting contacts.
+///
+/// The `normal` is the outward normal of the reference face. If `swapped` is `true`, the
+/// reference face belongs to the second polyhedron.
+fn clip_incident_face<N, P, V>(ref_face:   &[usize],
+                               ref_vs:     &[P],
+                               normal:     &V,
+                               inc_faces:  &[Vec<usize>],
+                               inc_vs:     &[P],
+                               inc_ns:     &[V],
+                               prediction: N,
+                               swapped:    bool,
+                               out:        &mut Vec<Contact<N, P, V>>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    // The incident face is the one which normal is the most anti-parallel to the reference normal.
+    let mut inc_face = 0;
+    let mut best_dot = Float::infinity();
+
+    for (i, n) in inc_ns.iter().enumerate() {
+        let dot = na::dot(n, normal);
+
+        if dot < best_dot {
+            best_dot = dot;
+            inc_face = i;
+        }
+    }
+
+    let ref_orig = ref_vs[ref_face[0]];
+    let mut polygon: Vec<P> = inc_faces[inc_face].iter().map(|i| inc_vs[*i]).collect();
+
+    if na::dim::<P>() == 2 {
+        let a   = ref_vs[ref_face[0]];
+        let b   = ref_vs[ref_face[1]];
+        let dir = b - a;
+
+        let clipped = clipping::clip_segment(&polygon[0], &polygon[1], &dir, na::zero(), &b)
+                          .and_then(|(p, q)| clipping::clip_segment(&p, &q, &-dir, na::zero(), &a));
+
+        match clipped {
+            Some((p, q)) => polygon = vec![ p, q ],
+            None         => return
+        }
+    }
+    else {
+        for i in 0 .. ref_face.len() {
+            let a    = ref_vs[ref_face[i]];
+            let b    = ref_vs[ref_face[(i + 1) % ref_face.len()]];
+            let side = utils::cross3(&(b - a), normal);
+
+            polygon = clipping::clip_polygon(&polygon[..], &side, na::zero(), &a);
+
+            if polygon.is_empty() {
+                return;
+            }
+        }
+    }
+
+    let mut contacts = Vec::new();
+
+    for pt in polygon.iter() {
+        let sep = na::dot(&(*pt - ref_orig), normal);
+
+        if sep <= prediction {
+            let on_ref = *pt + *normal * (-sep);
+
+            if swapped {
+                contacts.push(Contact::new(*pt, on_ref, -*normal, -sep))
+            }
+            else {
+                contacts.push(Contact::new(on_ref, *pt, *normal, -sep))
+            }
+        }
+    }
+
+    clipping::reduce_manifold(&mut contacts);
+
+    out.extend(contacts.into_iter());
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt3, Vec3, Iso3};
+    use na;
+    use entities::shape::ConvexPolyhedron;
+
+    fn cube(half_extent: f64) -> ConvexPolyhedron<Pnt3<f64>, Vec3<f64>> {
+        let mut vertices = Vec::new();
+
+        for i in 0usize .. 8 {
+            let x = if i & 1 == 0 { -half_extent } else { half_extent };
+            let y = if i & 2 == 0 { -half_extent } else { half_extent };
+            let z = if i & 4 == 0 { -half_extent } else { half_extent };
+
+            vertices.push(Pnt3::new(x, y, z));
+        }
+
+        let faces = vec![
+            vec![ 0, 4, 6, 2 ], vec![ 1, 3, 7, 5 ],
+            vec![ 0, 1, 5, 4 ], vec![ 2, 6, 7, 3 ],
+            vec![ 0, 2, 3, 1 ], vec![ 4, 5, 7, 6 ]
+        ];
+
+        let normals = vec![
+            -Vec3::x(), Vec3::x(),
+            -Vec3::y(), Vec3::y(),
+            -Vec3::z(), Vec3::z()
+        ];
+
+        ConvexPolyhedron::new(vertices, faces, normals)
+    }
+
+    fn resting_manifold(scale: f64) -> Vec<::geometry::Contact<f64, Pnt3<f64>, Vec3<f64>>> {
+        let c  = cube(0.5 * scale);
+        let m1 = Iso3::new(na::zero(), na::zero());
+        let m2 = Iso3::new(Vec3::new(0.2f64, 0.99, 0.1) * scale, na::zero());
+        let mut out = Vec::new();
+
+        assert!(super::convex_polyhedron_against_convex_polyhedron(&m1, &c, &m2, &c, 0.0, &mut out));
+
+        out
+    }
+
+    #[test]
+    fn test_cube_resting_on_cube() {
+        let contacts = resting_manifold(1.0);
+
+        assert!(contacts.len() == 4);
+
+        for c in contacts.iter() {
+            assert!(na::approx_eq(&c.normal, &Vec3::y()));
+            assert!(na::approx_eq_eps(&c.depth, &0.01, &1.0e-7));
+        }
+    }
+
+    #[test]
+    fn test_manifold_does_not_depend_on_units() {
+        let small = resting_manifold(1.0);
+        let large = resting_manifold(1000.0);
+
+        assert!(small.len() == large.len());
+
+        for (s, l) in small.iter().zip(large.iter()) {
+            assert!(na::approx_eq(&s.normal, &l.normal));
+            assert!(na::approx_eq_eps(&(s.depth * 1000.0), &l.depth, &1.0e-4));
+        }
+    }
+
+    #[test]
+    fn test_separated_cubes() {
+        let c  = cube(0.5);
+        let m1 = Iso3::new(na::zero(), na::zero());
+        let m2 = Iso3::new(Vec3::new(0.0f64, 1.5, 0.0), na::zero());
+        let mut out = Vec::new();
+
+        assert!(!super::convex_polyhedron_against_convex_polyhedron(&m1, &c, &m2, &c, 0.1, &mut out));
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+use std::num::Float;
+use na::{Rotate, Translate};
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::Cuboid;
+use geometry::Contact;
+use utils;
+use geometry::contacts_internal::clipping;
+
+/// The feature of the cuboids defining the separating axis of smallest penetration.
+enum SeparatingFeature {
+    /// The i-th face of the first cuboid.
+    Face1(usize),
+    /// The i-th face of the second cuboid.
+    Face2(usize),
+    /// The i-th edge direction of the first cuboid and the j-th edge direction of the second.
+    Edges(usize, usize)
+}
+
+/// Computes the contact manifold between two 3D cuboids.
+///
+/// This uses the separating axis theorem to find the axis of smallest penetration. If it is a face
+/// normal, the incident face of the other cuboid is clipped against the reference face to produce
+/// up to four contacts. Otherwise, a single contact is generated between the two closest edges.
+/// The contacts are appended to `out`. Returns `false` if the cuboids are separated by more than
+/// `prediction`.
+pub fn cuboid_against_cuboid<N, P, V, M>(m1: &M, c1: &Cuboid<V>,
+                                         m2: &M, c2: &Cuboid<V>,
+                                         prediction: N,
+                                         out: &mut Vec<Contact<N, P, V>>)
+                                         -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Rotate<V> + Translate<P> {
+    assert!(na::dim::<P>() == 3, "The cuboid-cuboid contact manifold is only implemented in 3D.");
+
+    let he1     = c1.half_extents();
+    let he2     = c2.half_extents();
+    let center1 = m1.translate(&na::orig());
+    let center2 = m2.translate(&na::orig());
+    let dpos    = center2 - center1;
+    let axes1   = cuboid_axes(m1);
+    let axes2   = cuboid_axes(m2);
+
+    // The absolute tolerance is relative to the cuboids sizes so that the chosen feature does not
+    // depend on the scene units.
+    let eps: N     = Float::epsilon();
+    let rel_tol: N = na::cast(0.95f64);
+    let abs_tol: N = (max_half_extent(he1) + max_half_extent(he2)) * na::cast(0.01f64);
+
+    // Face normals of the first cuboid.
+    let mut best_sep     = -Float::infinity();
+    let mut best_normal  = na::zero::<V>();
+    let mut best_feature = SeparatingFeature::Face1(0);
+
+    for i in 0 .. 3 {
+        let (sep, normal) = separation(&axes1[i], &dpos, &axes1, he1, &axes2, he2);
+
+        if sep > prediction {
+            return false;
+        }
+
+        if sep > best_sep {
+            best_sep     = sep;
+            best_normal  = normal;
+            best_feature = SeparatingFeature::Face1(i);
+        }
+    }
+
+    // Face normals of the second cuboid. They are preferred only if they are significantly better
+    // to avoid flip-flopping between the two cuboids.
+    let face1_sep = best_sep;
+
+    for i in 0 .. 3 {
+        let (sep, normal) = separation(&axes2[i], &dpos, &axes1, he1, &axes2, he2);
+
+        if sep > prediction {
+            return false;
+        }
+
+        if sep > rel_tol * face1_sep + abs_tol && sep > best_sep {
+            best_sep     = sep;
+            best_normal  = normal;
+            best_feature = SeparatingFeature::Face2(i);
+        }
+    }
+
+    // Edge-edge cross products.
+    let face_sep = best_sep;
+
+    for i in 0 .. 3 {
+        for j in 0 .. 3 {
+            let axis   = utils::cross3(&axes1[i], &axes2[j]);
+            let sqnorm = na::sqnorm(&axis);
+
+            if sqnorm <= eps.sqrt() {
+                continue; // The edges are parallel.
+            }
+
+            let axis          = axis / sqnorm.sqrt();
+            let (sep, normal) = separation(&axis, &dpos, &axes1, he1, &axes2, he2);
+
+            if sep > prediction {
+                return false;
+            }
+
+            if sep > rel_tol * face_sep + abs_tol && sep > best_sep {
+                best_sep     = sep;
+                best_normal  = normal;
+                best_feature = SeparatingFeature::Edges(i, j);
+            }
+        }
+    }
+
+    match best_feature {
+        SeparatingFeature::Face1(i) => {
+            clip_incident_face(&center1, &axes1, he1, i, &center2, &axes2, he2, &best_normal,
+                               prediction, false, out)
+        },
+        SeparatingFeature::Face2(i) => {
+            clip_incident_face(&center2, &axes2, he2, i, &center1, &axes1, he1, &-best_normal,
+                               prediction, true, out)
+        },
+        SeparatingFeature::Edges(i, j) => {
+            let (pt1, pt2) = closest_edge_points(&center1, &axes1, he1, i,
+                                                 &center2, &axes2, he2, j,
+                                                 &best_normal);
+
+            out.push(Contact::new(pt1, pt2, best_normal, -best_sep));
+        }
+    }
+
+    true
+}
+
+/// The world-space axes of a cuboid.
+fn cuboid_axes<N, V, M>(m: &M) -> [V; 3]
+    where N: Scalar,
+          V: Vect<N>,
+          M: Rotate<V> {
+    [
+        m.rotate(&na::canonical_basis_element(0).unwrap()),
+        m.rotate(&na::canonical_basis_element(1).unwrap()),
+        m.rotate(&na::canonical_basis_element(2).unwrap())
+    ]
+}
+
+/// The largest half extent of a cuboid.
+fn max_half_extent<N: Scalar, V: Vect<N>>(he: &V) -> N {
+    let mut res = he[0];
+
+    for i in 1 .. 3 {
+        if he[i] > res {
+            res = he[i];
+        }
+    }
+
+    res
+}
+
+/// The half-length of the projection of a cuboid on `axis`.
+fn projected_radius<N: Scalar, V: Vect<N>>(axis: &V, axes: &[V; 3], he: &V) -> N {
+    na::abs(&na::dot(axis, &axes[0])) * he[0] +
+    na::abs(&na::dot(axis, &axes[1])) * he[1] +
+    na::abs(&na::dot(axis, &axes[2])) * he[2]
+}
+
+/// The signed separation of two cuboids along a normalized axis, and the axis oriented from the
+/// first cuboid toward the second one.
+fn separation<N, V>(axis: &V, dpos: &V, axes1: &[V; 3], he1: &V, axes2: &[V; 3], he2: &V) -> (N, V)
+    where N: Scalar,
+          V: Vect<N> {
+    let proj   = na::dot(dpos, axis);
+    let normal = if proj < na::zero() { -*axis } else { *axis };
+    let sep    = na::abs(&proj) - projected_radius(axis, axes1, he1) - projected_radius(axis, axes2, he2);
+
+    (sep, normal)
+}
+
+/// Clips the incident face against the reference face and pushes the resulting contacts.
+///
+/// The `normal` points from the reference cuboid toward the incident cuboid. If `swapped` is
+/// `true`, the reference cuboid is the second one.
+fn clip_incident_face<N, P, V>(ref_center: &P, ref_axes: &[V; 3], ref_he: &V, ref_face: usize,
+                               inc_center: &P, inc_axes: &[V; 3], inc_he: &V,
+                               normal:     &V,
+                               prediction: N,
+                               swapped:    bool,
+                               out:        &mut Vec<Contact<N, P, V>>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    // The incident face is the one which normal is the most anti-parallel to the reference normal.
+    let mut inc_face = 0;
+    let mut best_dot = na::zero::<N>();
+
+    for i in 0 .. 3 {
+        let dot = na::abs(&na::dot(&inc_axes[i], normal));
+
+        if dot > best_dot {
+            best_dot = dot;
+            inc_face = i;
+        }
+    }
+
+    let inc_sign: N = if na::dot(&inc_axes[inc_face], normal) > na::zero() { -na::one::<N>() } else { na::one() };
+    let inc_face_center = *inc_center + inc_axes[inc_face] * (inc_he[inc_face] * inc_sign);
+    let iu = (inc_face + 1) % 3;
+    let iv = (inc_face + 2) % 3;
+    let u  = inc_axes[iu] * inc_he[iu];
+    let v  = inc_axes[iv] * inc_he[iv];
+
+    let mut polygon = vec![
+        inc_face_center + (u + v),
+        inc_face_center + (v - u),
+        inc_face_center + (-u - v),
+        inc_face_center + (u - v)
+    ];
+
+    // Clip against the four side planes of the reference face.
+    let ref_face_center = *ref_center + *normal * ref_he[ref_face];
+    let ru = (ref_face + 1) % 3;
+    let rv = (ref_face + 2) % 3;
+
+    for &(axis, extent) in [ (ru, ref_he[ru]), (rv, ref_he[rv]) ].iter() {
+        polygon = clipping::clip_polygon(&polygon, &ref_axes[axis], extent, &ref_face_center);
+        polygon = clipping::clip_polygon(&polygon, &-ref_axes[axis], extent, &ref_face_center);
+    }
+
+    // Keep the points below the reference face.
+    let mut contacts = Vec::new();
+
+    for pt in polygon.iter() {
+        let sep = na::dot(&(*pt - ref_face_center), normal);
+
+        if sep <= prediction {
+            let on_ref = *pt + *normal * (-sep);
+
+            if swapped {
+                contacts.push(Contact::new(*pt, on_ref, -*normal, -sep))
+            }
+            else {
+                contacts.push(Contact::new(on_ref, *pt, *normal, -sep))
+            }
+        }
+    }
+
+    clipping::reduce_manifold(&mut contacts);
+
+    out.extend(contacts.into_iter());
+}
+
+/// The closest points between the two edges of the cuboids supporting the separating axis.
+fn closest_edge_points<N, P, V>(center1: &P, axes1: &[V; 3], he1: &V, i: usize,
+                                center2: &P, axes2: &[V; 3], he2: &V, j: usize,
+                                normal:  &V)
+                                -> (P, P)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut pt1 = *center1;
+    let mut pt2 = *center2;
+
+    // Support points of the edges: the first cuboid toward `normal`, the second toward `-normal`.
+    for k in 0 .. 3 {
+        if k != i {
+            let s = if na::dot(&axes1[k], normal) > na::zero() { he1[k] } else { -he1[k] };
+            pt1 = pt1 + axes1[k] * s;
+        }
+
+        if k != j {
+            let s = if na::dot(&axes2[k], normal) < na::zero() { he2[k] } else { -he2[k] };
+            pt2 = pt2 + axes2[k] * s;
+        }
+    }
+
+    // Closest points between the two lines.
+    let d1  = axes1[i];
+    let d2  = axes2[j];
+    let r   = pt1 - pt2;
+    let b   = na::dot(&d1, &d2);
+    let c   = na::dot(&d1, &r);
+    let f   = na::dot(&d2, &r);
+    let den = na::one::<N>() - b * b;
+
+    let _eps: N = Float::epsilon();
+
+    let (s, t) = if den <= _eps {
+        (na::zero(), f)
+    }
+    else {
+        let s = (b * f - c) / den;
+        (s, b * s + f)
+    };
+
+    let s = na::clamp(s, -he1[i], he1[i]);
+    let t = na::clamp(t, -he2[j], he2[j]);
+
+    (pt1 + d1 * s, pt2 + d2 * t)
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Iso3, Vec3};
+    use na;
+    use entities::shape::Cuboid;
+
+    fn resting_manifold(scale: f64) -> Vec<::geometry::Contact<f64, na::Pnt3<f64>, Vec3<f64>>> {
+        let c  = Cuboid::new(Vec3::new(0.5f64, 0.5, 0.5) * scale);
+        let m1 = Iso3::new(na::zero(), na::zero());
+        let m2 = Iso3::new(Vec3::new(0.2f64, 0.99, 0.1) * scale, na::zero());
+        let mut out = Vec::new();
+
+        assert!(super::cuboid_against_cuboid(&m1, &c, &m2, &c, 0.0, &mut out));
+
+        out
+    }
+
+    #[test]
+    fn test_cuboid_resting_on_cuboid() {
+        let contacts = resting_manifold(1.0);
+
+        assert!(contacts.len() == 4);
+
+        for c in contacts.iter() {
+            assert!(na::approx_eq(&c.normal, &Vec3::y()));
+            assert!(na::approx_eq_eps(&c.depth, &0.01, &1.0e-7));
+        }
+    }
+
+    #[test]
+    fn test_cuboid_manifold_does_not_depend_on_units() {
+        let small = resting_manifold(1.0);
+        let large = resting_manifold(1000.0);
+
+        assert!(small.len() == large.len());
+
+        for (s, l) in small.iter().zip(large.iter()) {
+            assert!(na::approx_eq(&s.normal, &l.normal));
+            assert!(na::approx_eq_eps(&(s.depth * 1000.0), &l.depth, &1.0e-4));
+        }
+    }
+
+    #[test]
+    fn test_separated_cuboids() {
+        let c  = Cuboid::new(Vec3::new(0.5f64, 0.5, 0.5));
+        let m1 = Iso3::new(na::zero(), na::zero());
+        let m2 = Iso3::new(Vec3::new(0.0f64, 1.5, 0.0), na::zero());
+        let mut out = Vec::new();
+
+        assert!(!super::cuboid_against_cuboid(&m1, &c, &m2, &c, 0.1, &mut out));
+        assert!(out.is_empty());
+    }
+}
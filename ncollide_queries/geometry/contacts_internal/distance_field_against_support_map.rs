@@ -0,0 +1,256 @@
+use na::{Transform, Rotate};
+use na;
+use geometry::Contact;
+use entities::support_map::SupportMap;
+use entities::shape::DistanceField;
+use math::{Scalar, Point, Vect, Isometry};
+
+/// Maximum number of steps of each descent toward the deepest point of the support-mapped shape.
+const MAX_DESCENT_STEPS: usize = 10;
+
+/// Contact between a distance field and a support-mapped shape (Cuboid, Convex, etc.)
+///
+/// This is the deepest contact computed by `distance_field_against_support_map_manifold`.
+pub fn distance_field_against_support_map<N, P, V, M, G: ?Sized>(mfield: &M, field: &DistanceField<N, P, V>,
+                                                                 mother: &M, other: &G,
+                                                                 prediction: N)
+                                                                 -> Option<Contact<N, P, V>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    let mut contacts = Vec::new();
+
+    if !distance_field_against_support_map_manifold(mfield, field, mother, other, prediction, &mut contacts) {
+        return None;
+    }
+
+    let mut deepest = contacts.pop().unwrap();
+
+    for c in contacts.into_iter() {
+        if c.depth > deepest.depth {
+            deepest = c;
+        }
+    }
+
+    Some(deepest)
+}
+
+/// Contacts between a distance field and a support-mapped shape (Cuboid, Convex, etc.)
+///
+/// The deepest points of the support-mapped shape are searched iteratively: starting from its
+/// center and from its support points along each coordinate axis of the distance field, the
+/// support point in the direction opposite to the distance field gradient is taken until the
+/// signed distance stops decreasing. Each local minimum closer than `prediction` yields a contact,
+/// so the contacts of a non-convex field with several parts of the shape are found as long as one
+/// of the starting points leads to each of them. Contacts closer than one sample spacing are
+/// merged. The contacts are appended to `out`. Returns `false` if no contact is found.
+pub fn distance_field_against_support_map_manifold<N, P, V, M, G: ?Sized>(mfield: &M,
+                                                                          field: &DistanceField<N, P, V>,
+                                                                          mother: &M, other: &G,
+                                                                          prediction: N,
+                                                                          out: &mut Vec<Contact<N, P, V>>)
+                                                                          -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    // Work in the local space of the distance field.
+    let ls_mother = na::inv(mfield).expect("The distance field transformation must be invertible.") *
+                    mother.clone();
+
+    let mut minima = Vec::new();
+
+    if let Some(m) = descend(field, &ls_mother, other, ls_mother.transform(&na::orig()), None) {
+        minima.push(m)
+    }
+
+    for i in 0 .. na::dim::<P>() {
+        for sign in [ na::one::<N>(), -na::one::<N>() ].iter() {
+            let mut dir = na::zero::<V>();
+            dir[i]      = *sign;
+
+            let start = other.support_point(&ls_mother, &dir);
+
+            if let Some(m) = descend(field, &ls_mother, other, start, Some((start, field.value_at(&start)))) {
+                minima.push(m)
+            }
+        }
+    }
+
+    let mut min_spacing = field.spacing()[0];
+
+    for i in 1 .. na::dim::<P>() {
+        min_spacing = min_spacing.min(field.spacing()[i]);
+    }
+
+    let mut kept: Vec<P> = Vec::new();
+    let mut found        = false;
+
+    for &(deepest, dist) in minima.iter() {
+        if dist >= prediction || kept.iter().any(|pt| na::sqdist(pt, &deepest) < min_spacing * min_spacing) {
+            continue;
+        }
+
+        let mut normal = field.gradient_at(&deepest);
+
+        if na::is_zero(&normal.normalize()) {
+            continue;
+        }
+
+        let c1 = deepest - normal * dist;
+
+        kept.push(deepest);
+        out.push(Contact::new(mfield.transform(&c1),
+                              mfield.transform(&deepest),
+                              mfield.rotate(&normal),
+                              -dist));
+        found = true;
+    }
+
+    found
+}
+
+/// Descends from `pt` toward the deepest point of the support-mapped shape.
+///
+/// Returns the deepest point found and its signed distance, starting with `best`.
+fn descend<N, P, V, M, G: ?Sized>(field: &DistanceField<N, P, V>, ls_mother: &M, other: &G, pt: P,
+                                  best: Option<(P, N)>)
+                                  -> Option<(P, N)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    let mut pt   = pt;
+    let mut best = best;
+
+    for _ in 0 .. MAX_DESCENT_STEPS {
+        let mut dir = -field.gradient_at(&pt);
+
+        if na::is_zero(&dir.normalize()) {
+            break;
+        }
+
+        let support = other.support_point(ls_mother, &dir);
+        let dist    = field.value_at(&support);
+
+        match best {
+            Some((_, best_dist)) if dist >= best_dist => break,
+            _ => best = Some((support, dist))
+        }
+
+        pt = support;
+    }
+
+    best
+}
+
+/// Contact between a support-mapped shape (Cuboid, Convex, etc.) and a distance field.
+pub fn support_map_against_distance_field<N, P, V, M, G: ?Sized>(mother: &M, other: &G,
+                                                                 mfield: &M, field: &DistanceField<N, P, V>,
+                                                                 prediction: N)
+                                                                 -> Option<Contact<N, P, V>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    distance_field_against_support_map(mfield, field, mother, other, prediction).map(|mut c| { c.flip(); c })
+}
+
+/// Contacts between a support-mapped shape (Cuboid, Convex, etc.) and a distance field.
+///
+/// See `distance_field_against_support_map_manifold` for details.
+pub fn support_map_against_distance_field_manifold<N, P, V, M, G: ?Sized>(mother: &M, other: &G,
+                                                                          mfield: &M,
+                                                                          field: &DistanceField<N, P, V>,
+                                                                          prediction: N,
+                                                                          out: &mut Vec<Contact<N, P, V>>)
+                                                                          -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    let first = out.len();
+    let found = distance_field_against_support_map_manifold(mfield, field, mother, other, prediction, out);
+
+    for c in out[first ..].iter_mut() {
+        c.flip()
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use na::{Iso2, Pnt2, Vec2};
+    use na;
+    use entities::shape::{Cuboid, DistanceField};
+
+    // The solid below the V-shaped notch `y = |x|`.
+    fn notch() -> DistanceField<f64, Pnt2<f64>, Vec2<f64>> {
+        let mut values = Vec::new();
+
+        for j in 0usize .. 33 {
+            for i in 0usize .. 33 {
+                let x = i as f64 * 0.25 - 4.0;
+                let y = j as f64 * 0.25 - 4.0;
+
+                values.push((y - x.abs()) / 2.0f64.sqrt())
+            }
+        }
+
+        DistanceField::new(values, vec!(33, 33), Pnt2::new(-4.0, -4.0), Vec2::new(0.25, 0.25))
+    }
+
+    #[test]
+    fn test_box_in_notch_touches_both_sides() {
+        let field  = notch();
+        let cuboid = Cuboid::new(Vec2::new(1.0f64, 0.5));
+        let mfield = Iso2::new(na::zero(), na::zero());
+        let mbox   = Iso2::new(Vec2::new(0.0, 1.0), na::zero());
+        let depth  = 0.5 / 2.0f64.sqrt();
+
+        let mut contacts = Vec::new();
+        assert!(super::distance_field_against_support_map_manifold(&mfield, &field, &mbox, &cuboid, 0.0,
+                                                                   &mut contacts));
+
+        // One contact per bottom corner of the box.
+        assert!(contacts.len() == 2);
+        assert!(contacts[0].world2.x * contacts[1].world2.x < 0.0);
+
+        for c in contacts.iter() {
+            assert!(na::approx_eq_eps(&c.depth, &depth, &1.0e-6));
+            assert!(na::approx_eq_eps(&c.world2.y, &0.5, &1.0e-6));
+        }
+
+        let deepest = super::distance_field_against_support_map(&mfield, &field, &mbox, &cuboid, 0.0).unwrap();
+        assert!(na::approx_eq_eps(&deepest.depth, &depth, &1.0e-6));
+
+        // The flipped version gives the same contacts.
+        let mut flipped = Vec::new();
+        assert!(super::support_map_against_distance_field_manifold(&mbox, &cuboid, &mfield, &field, 0.0,
+                                                                   &mut flipped));
+        assert!(flipped.len() == 2);
+        assert!(na::approx_eq(&flipped[0].normal, &-contacts[0].normal));
+    }
+
+    #[test]
+    fn test_box_above_notch() {
+        let field  = notch();
+        let cuboid = Cuboid::new(Vec2::new(1.0f64, 0.5));
+        let mfield = Iso2::new(na::zero(), na::zero());
+        let mbox   = Iso2::new(Vec2::new(0.0, 2.0), na::zero());
+
+        let mut contacts = Vec::new();
+        assert!(!super::distance_field_against_support_map_manifold(&mfield, &field, &mbox, &cuboid, 0.0,
+                                                                    &mut contacts));
+        assert!(contacts.is_empty());
+        assert!(super::distance_field_against_support_map(&mfield, &field, &mbox, &cuboid, 0.0).is_none());
+    }
+}
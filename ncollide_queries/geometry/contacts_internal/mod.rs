@@ -2,9 +2,17 @@
 
 pub use self::contact::Contact;
 pub use self::ball_against_ball::ball_against_ball;
+pub use self::cuboid_against_cuboid::cuboid_against_cuboid;
+pub use self::capsule_against_capsule::{capsule_against_capsule, capsule_against_ball, ball_against_capsule,
+                                        plane_against_capsule, capsule_against_plane};
+pub use self::convex_polyhedron_against_convex_polyhedron::convex_polyhedron_against_convex_polyhedron;
 pub use self::support_map_against_support_map::support_map_against_support_map;
 pub use self::support_map_against_support_map::support_map_against_support_map_with_params;
 pub use self::plane_against_support_map::{plane_against_support_map, support_map_against_plane};
+pub use self::distance_field_against_support_map::{distance_field_against_support_map,
+                                                   support_map_against_distance_field,
+                                                   distance_field_against_support_map_manifold,
+                                                   support_map_against_distance_field_manifold};
 pub use self::any_against_any::any_against_any;
 pub use self::any_against_any::any_against_any as contact_internal;
 pub use self::composite_shape_against_any::{composite_shape_against_any, any_against_composite_shape};
@@ -12,8 +20,13 @@ pub use self::composite_shape_against_any::{composite_shape_against_any, any_aga
 
 mod contact;
 mod ball_against_ball;
+mod cuboid_against_cuboid;
+mod capsule_against_capsule;
+mod convex_polyhedron_against_convex_polyhedron;
+mod clipping;
 mod support_map_against_support_map;
 mod plane_against_support_map;
+mod distance_field_against_support_map;
 mod any_against_any;
 mod composite_shape_against_any;
 // mod generate_contact_manifold;
@@ -0,0 +1,46 @@
+use na::Transform;
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::{Ball, Capsule, Segment};
+use geometry::closest_points_internal;
+
+/// Distance between segments.
+#[inline]
+pub fn segment_against_segment<N, P, V, M>(m1: &M, s1: &Segment<P>, m2: &M, s2: &Segment<P>) -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let (p1, p2) = closest_points_internal::segment_against_segment(m1, s1, m2, s2);
+
+    na::dist(&p1, &p2)
+}
+
+/// Distance between capsules.
+#[inline]
+pub fn capsule_against_capsule<N, P, V, M>(m1: &M, c1: &Capsule<N>, m2: &M, c2: &Capsule<N>) -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let dist = segment_against_segment(m1, &c1.segment(), m2, &c2.segment()) - c1.radius() - c2.radius();
+
+    if dist > na::zero() { dist } else { na::zero() }
+}
+
+/// Distance between a capsule and a ball.
+#[inline]
+pub fn capsule_against_ball<N, P, V, M>(m1: &M, c1: &Capsule<N>, center2: &P, b2: &Ball<N>) -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let s  = c1.segment();
+    let a  = m1.transform(s.a());
+    let b  = m1.transform(s.b());
+    let pt = closest_points_internal::closest_points_segment_segment(&a, &b, center2, center2).0;
+
+    let dist = na::dist(&pt, center2) - c1.radius() - b2.radius();
+
+    if dist > na::zero() { dist } else { na::zero() }
+}
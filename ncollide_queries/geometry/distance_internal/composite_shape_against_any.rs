@@ -18,7 +18,7 @@ pub fn composite_shape_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, g
           G2: Repr<N, P, V, M> + HasAABB<P, M> {
     let mut cost_fn = CompositeShapeAgainstAnyDistCostFn::new(m1, g1, m2, g2);
 
-    g1.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res).expect("The composite shape must not be empty.")
+    g1.best_first_search(&mut cost_fn).map(|(_, res)| res).expect("The composite shape must not be empty.")
 }
 
 /// Smallest distance between a shape and a composite shape.
@@ -0,0 +1,94 @@
+use na::{Transform, Translate, Bounded};
+use na;
+use entities::support_map::SupportMap;
+use entities::shape::DistanceField;
+use geometry::contacts_internal;
+use math::{Scalar, Point, Vect, Isometry};
+
+/// Distance between a distance field and a support-mapped shape (Cuboid, Convex, etc.)
+///
+/// The field is sampled at the deepest points of the support-mapped shape found by the same
+/// descent as `contacts_internal::distance_field_against_support_map`. Because the descent may
+/// stop at a local minimum, the result is only an upper bound of the exact distance for a
+/// non-convex field.
+pub fn distance_field_against_support_map<N, P, V, M, G: ?Sized>(mfield: &M, field: &DistanceField<N, P, V>,
+                                                                 mother: &M, other: &G)
+                                                                 -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    let max: N = Bounded::max_value();
+
+    let dist = match contacts_internal::distance_field_against_support_map(mfield, field, mother, other, max) {
+        Some(c) => -c.depth,
+        None    => {
+            // The field is flat around the shape: fall back to its value at the shape center.
+            let center = mfield.inv_transform(&mother.translate(&na::orig()));
+
+            field.value_at(&center)
+        }
+    };
+
+    if dist < na::zero() {
+        na::zero()
+    }
+    else {
+        dist
+    }
+}
+
+/// Distance between a support-mapped shape (Cuboid, Convex, etc.) and a distance field.
+pub fn support_map_against_distance_field<N, P, V, M, G: ?Sized>(mother: &M, other: &G,
+                                                                 mfield: &M, field: &DistanceField<N, P, V>)
+                                                                 -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    distance_field_against_support_map(mfield, field, mother, other)
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use na::{Iso2, Pnt2, Vec2};
+    use na;
+    use entities::shape::{Ball, DistanceField};
+
+    // The unit disk centered at the origin.
+    fn disk() -> DistanceField<f64, Pnt2<f64>, Vec2<f64>> {
+        let mut values = Vec::new();
+
+        for j in 0usize .. 33 {
+            for i in 0usize .. 33 {
+                let x = i as f64 * 0.25 - 4.0;
+                let y = j as f64 * 0.25 - 4.0;
+
+                values.push((x * x + y * y).sqrt() - 1.0)
+            }
+        }
+
+        DistanceField::new(values, vec!(33, 33), Pnt2::new(-4.0, -4.0), Vec2::new(0.25, 0.25))
+    }
+
+    #[test]
+    fn test_ball_distance_to_disk() {
+        let field  = disk();
+        let ball   = Ball::new(0.5f64);
+        let mfield = Iso2::new(na::zero(), na::zero());
+        let mball  = Iso2::new(Vec2::new(3.0, 0.0), na::zero());
+
+        let dist = super::distance_field_against_support_map(&mfield, &field, &mball, &ball);
+        assert!(na::approx_eq_eps(&dist, &1.5, &1.0e-2));
+
+        let dist = super::support_map_against_distance_field(&mball, &ball, &mfield, &field);
+        assert!(na::approx_eq_eps(&dist, &1.5, &1.0e-2));
+
+        // Penetrating shapes are at a zero distance.
+        let mball = Iso2::new(Vec2::new(1.2, 0.0), na::zero());
+        assert!(super::distance_field_against_support_map(&mfield, &field, &mball, &ball) == 0.0);
+    }
+}
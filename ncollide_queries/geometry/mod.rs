@@ -8,8 +8,13 @@ pub use self::contacts_internal::contact_internal as contact;
 pub use self::distance_internal::distance;
 #[doc(inline)]
 pub use self::time_of_impact_internal::time_of_impact;
+#[doc(inline)]
+pub use self::self_intersection_internal::{SelfIntersection, trimesh_self_intersections,
+                                           polyline_self_intersections};
 
 pub mod algorithms;
+pub mod closest_points_internal;
 pub mod contacts_internal;
 pub mod distance_internal;
+pub mod self_intersection_internal;
 pub mod time_of_impact_internal;
@@ -0,0 +1,9 @@
+//! Implementation details of the mesh self-intersection computations.
+
+pub use self::self_intersection::SelfIntersection;
+pub use self::trimesh_self_intersection::{trimesh_self_intersections, triangle_triangle_intersection};
+pub use self::polyline_self_intersection::{polyline_self_intersections, segment_segment_intersection};
+
+mod self_intersection;
+mod trimesh_self_intersection;
+mod polyline_self_intersection;
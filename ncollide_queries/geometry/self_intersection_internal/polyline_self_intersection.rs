@@ -0,0 +1,84 @@
+use std::num::Float;
+use na::Translate;
+use na;
+use entities::shape::{Polyline, Segment};
+use geometry::closest_points_internal;
+use geometry::self_intersection_internal::SelfIntersection;
+use geometry::self_intersection_internal::self_intersection::SelfIntersectionCandidatesCollector;
+use math::{Scalar, Point, Vect};
+
+/// Computes every pair of intersecting segments of a polyline.
+///
+/// Segments sharing a vertex are never reported. The intersection of each pair is given as a
+/// point.
+pub fn polyline_self_intersections<N, P, V>(mesh: &Polyline<N, P, V>) -> Vec<SelfIntersection<P>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P> {
+    let mut collector = SelfIntersectionCandidatesCollector::new(&mesh.indices()[..]);
+
+    mesh.bvt().visit_self_bvtt(&mut collector, &mut |_| true);
+
+    let mut res = Vec::new();
+
+    for &(i1, i2) in collector.candidates().iter() {
+        let s1 = mesh.segment_at(i1);
+        let s2 = mesh.segment_at(i2);
+
+        if let Some(pt) = segment_segment_intersection(&s1, &s2) {
+            res.push(SelfIntersection::new(i1, i2, pt))
+        }
+    }
+
+    res
+}
+
+/// Computes the intersection point between two segments.
+///
+/// Returns `None` if they do not intersect. If the segments overlap, one of their common points
+/// is returned. The tolerance is relative to the length of the segments.
+pub fn segment_segment_intersection<N, P, V>(s1: &Segment<P>, s2: &Segment<P>) -> Option<P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let _eps: N  = Float::epsilon();
+    let tol      = (na::dist(s1.a(), s1.b()) + na::dist(s2.a(), s2.b())) * _eps.sqrt();
+    let (p1, p2) = closest_points_internal::closest_points_segment_segment(s1.a(), s1.b(), s2.a(), s2.b());
+
+    if na::sqdist(&p1, &p2) <= tol * tol {
+        Some(na::center(&p1, &p2))
+    }
+    else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::{Pnt2, Vec2};
+    use na;
+    use entities::shape::Polyline;
+    use super::polyline_self_intersections;
+
+    // A closed polyline which first and third segments cross at (1, 1) * scale.
+    fn bowtie(scale: f64) -> Polyline<f64, Pnt2<f64>, Vec2<f64>> {
+        let vertices = vec!(Pnt2::new(0.0f64, 0.0), Pnt2::new(2.0, 2.0), Pnt2::new(2.0, 0.0), Pnt2::new(0.0, 2.0));
+        let vertices = vertices.into_iter().map(|v| v * scale).collect();
+        let indices  = vec!(Pnt2::new(0usize, 1), Pnt2::new(1, 2), Pnt2::new(2, 3), Pnt2::new(3, 0));
+
+        Polyline::new(Arc::new(vertices), Arc::new(indices), None, None)
+    }
+
+    #[test]
+    fn test_polyline_crossing() {
+        for scale in [ 1.0e-4f64, 1.0, 1.0e4 ].iter() {
+            let res = polyline_self_intersections(&bowtie(*scale));
+
+            // Each pair is reported once.
+            assert!(res.len() == 1);
+            assert!(res[0].element1 == 0 && res[0].element2 == 2);
+            assert!(na::approx_eq(&(res[0].intersection / *scale), &Pnt2::new(1.0, 1.0)));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::Index;
+use na::{Dim, Translate};
+use na;
+use entities::bounding_volume::{BoundingVolume, AABB, SpacializedCone};
+use entities::partitioning::BVTTVisitor;
+use math::{Scalar, Point, Vect};
+
+/// A pair of intersecting elements of a mesh.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SelfIntersection<S> {
+    /// The index of the first element. It is always smaller than `element2`.
+    pub element1:     usize,
+    /// The index of the second element.
+    pub element2:     usize,
+    /// The intersection between both elements.
+    pub intersection: S
+}
+
+impl<S> SelfIntersection<S> {
+    /// Creates a new self-intersection descriptor.
+    #[inline]
+    pub fn new(element1: usize, element2: usize, intersection: S) -> SelfIntersection<S> {
+        SelfIntersection {
+            element1:     element1,
+            element2:     element2,
+            intersection: intersection
+        }
+    }
+}
+
+/// A bounding volume usable to find the pairs of elements of a mesh which may intersect.
+pub trait SelfIntersectionBV {
+    /// Tests if the elements bounded by `self` and `other` may intersect.
+    fn may_overlap(&self, other: &Self) -> bool;
+}
+
+#[old_impl_check]
+impl<N, P, V> SelfIntersectionBV for AABB<P>
+    where N: Scalar,
+          P: Point<N, V> {
+    #[inline]
+    fn may_overlap(&self, other: &AABB<P>) -> bool {
+        self.intersects(other)
+    }
+}
+
+impl<N, P, V> SelfIntersectionBV for SpacializedCone<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P> {
+    #[inline]
+    fn may_overlap(&self, other: &SpacializedCone<N, P, V>) -> bool {
+        // The cones test is meant for contacts between opposite surfaces: use the spheres only.
+        self.sphere().intersects(other.sphere())
+    }
+}
+
+/// Collects the pairs of elements of a mesh which bounding volumes intersect.
+///
+/// This is meant to traverse the BVT of the mesh with `BVT::visit_self_bvtt`. Each pair is
+/// reported with its smallest index first, and pairs of elements sharing a vertex are ignored.
+pub struct SelfIntersectionCandidatesCollector<'a, I: 'a> {
+    indices:    &'a [I],
+    candidates: Vec<(usize, usize)>
+}
+
+impl<'a, I> SelfIntersectionCandidatesCollector<'a, I>
+    where I: Index<usize, Output = usize> + Dim {
+    /// Creates a new collector for the mesh with the given element indices.
+    pub fn new(indices: &'a [I]) -> SelfIntersectionCandidatesCollector<'a, I> {
+        SelfIntersectionCandidatesCollector {
+            indices:    indices,
+            candidates: Vec::new()
+        }
+    }
+
+    /// The pairs of elements found.
+    pub fn candidates(&self) -> &[(usize, usize)] {
+        &self.candidates[..]
+    }
+
+    fn share_vertex(&self, i1: usize, i2: usize) -> bool {
+        let e1 = &self.indices[i1];
+        let e2 = &self.indices[i2];
+
+        for i in 0 .. na::dim::<I>() {
+            for j in 0 .. na::dim::<I>() {
+                if e1[i] == e2[j] {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl<'a, I, BV> BVTTVisitor<usize, BV> for SelfIntersectionCandidatesCollector<'a, I>
+    where I:  Index<usize, Output = usize> + Dim,
+          BV: SelfIntersectionBV {
+    #[inline]
+    fn visit_internal_internal(&mut self, bv1: &BV, bv2: &BV) -> bool {
+        bv1.may_overlap(bv2)
+    }
+
+    #[inline]
+    fn visit_internal_leaf(&mut self, bv1: &BV, _: &usize, bv2: &BV) -> bool {
+        bv1.may_overlap(bv2)
+    }
+
+    #[inline]
+    fn visit_leaf_internal(&mut self, _: &usize, bv1: &BV, bv2: &BV) -> bool {
+        bv1.may_overlap(bv2)
+    }
+
+    #[inline]
+    fn visit_leaf_leaf(&mut self, b1: &usize, bv1: &BV, b2: &usize, bv2: &BV) {
+        if bv1.may_overlap(bv2) && !self.share_vertex(*b1, *b2) {
+            if *b1 < *b2 {
+                self.candidates.push((*b1, *b2))
+            }
+            else {
+                self.candidates.push((*b2, *b1))
+            }
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use std::num::Float;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use na::{Translate, Cross, Pnt3};
+use na;
+use entities::shape::{TriMesh, Triangle, Segment};
+use entities::partitioning::BVTNode;
+use geometry::closest_points_internal;
+use geometry::self_intersection_internal::SelfIntersection;
+use geometry::self_intersection_internal::self_intersection::SelfIntersectionCandidatesCollector;
+use math::{Scalar, Point, Vect};
+
+/// Computes every pair of intersecting triangles of a triangle mesh.
+///
+/// Triangles sharing a vertex are never reported. The intersection of each pair is given as a
+/// segment. For coplanar triangles, this segment joins the two furthest points of the overlap.
+///
+/// The triangles are traversed with their normal cones: the pairs of triangles of a connected
+/// patch which normal cone cannot fold back are not tested. Note that the boundary of such a patch
+/// is not checked, so a patch winding around its axis like a helical ramp is wrongly assumed free
+/// of self-intersections.
+pub fn trimesh_self_intersections<N, P, V>(mesh: &TriMesh<N, P, V>) -> Vec<SelfIntersection<Segment<P>>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Translate<P> + Cross<Output = V> {
+    let indices       = &mesh.indices()[..];
+    let bvt           = mesh.normal_cone_bvt();
+    let mut collector = SelfIntersectionCandidatesCollector::new(indices);
+
+    bvt.visit_self_bvtt(&mut collector, &mut |node| {
+        node.bounding_volume().may_fold_back() || !is_connected_patch(node, indices)
+    });
+
+    let mut res = Vec::new();
+
+    for &(i1, i2) in collector.candidates().iter() {
+        let t1 = mesh.triangle_at(i1);
+        let t2 = mesh.triangle_at(i2);
+
+        if let Some(seg) = triangle_triangle_intersection(&t1, &t2) {
+            res.push(SelfIntersection::new(i1, i2, seg))
+        }
+    }
+
+    res
+}
+
+/// Tests if the triangles of the leaves of `node` form a single patch connected by their vertices.
+fn is_connected_patch<BV>(node: &BVTNode<usize, BV>, indices: &[Pnt3<usize>]) -> bool {
+    let mut triangles = Vec::new();
+    node.leaves(&mut triangles);
+
+    // Triangles adjacent to each vertex.
+    let mut adjacency: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0 .. 3 {
+            match adjacency.entry(indices[**t][k]) {
+                Entry::Occupied(e) => e.into_mut().push(i),
+                Entry::Vacant(e)   => { let _ = e.insert(vec![ i ]); }
+            }
+        }
+    }
+
+    let mut visited  = vec![ false; triangles.len() ];
+    let mut stack    = vec![ 0usize ];
+    let mut nvisited = 0;
+
+    visited[0] = true;
+
+    while let Some(i) = stack.pop() {
+        nvisited = nvisited + 1;
+
+        for k in 0 .. 3 {
+            for j in adjacency.get(&indices[*triangles[i]][k]).unwrap().iter() {
+                if !visited[*j] {
+                    visited[*j] = true;
+                    stack.push(*j)
+                }
+            }
+        }
+    }
+
+    nvisited == triangles.len()
+}
+
+/// Computes the intersection between two triangles.
+///
+/// Returns `None` if they do not intersect. Otherwise, returns the segment of intersection. For
+/// coplanar triangles, this segment joins the two furthest points of the overlap. Tolerances are
+/// relative to the size of the triangles.
+pub fn triangle_triangle_intersection<N, P, V>(t1: &Triangle<P>, t2: &Triangle<P>) -> Option<Segment<P>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Cross<Output = V> {
+    let _eps: N = Float::epsilon();
+    let vs1     = [ *t1.a(), *t1.b(), *t1.c() ];
+    let vs2     = [ *t2.a(), *t2.b(), *t2.c() ];
+    let n1      = na::cross(&(vs1[1] - vs1[0]), &(vs1[2] - vs1[0]));
+    let n2      = na::cross(&(vs2[1] - vs2[0]), &(vs2[2] - vs2[0]));
+
+    if is_degenerate(&vs1, &n1, _eps) || is_degenerate(&vs2, &n2, _eps) {
+        return None;
+    }
+
+    let n1  = na::normalize(&n1);
+    let n2  = na::normalize(&n2);
+    let tol = (max_edge_length(&vs1) + max_edge_length(&vs2)) * _eps.sqrt();
+
+    // Early exit if one triangle lies strictly on one side of the other triangle plane.
+    if same_side(&n2, &vs2[0], &vs1, tol) || same_side(&n1, &vs1[0], &vs2, tol) {
+        return None;
+    }
+
+    let mut pts = Vec::new();
+    let dir     = na::cross(&n1, &n2);
+
+    if na::sqnorm(&dir) > _eps {
+        // Intersections between the edges of each triangle with the other triangle.
+        edges_against_triangle(&vs1, &vs2, &n2, tol, &mut pts);
+        edges_against_triangle(&vs2, &vs1, &n1, tol, &mut pts);
+
+        extremal_points(&pts[..], &dir)
+    }
+    else {
+        // Coplanar triangles.
+        for v in vs1.iter() {
+            if point_in_triangle(v, &vs2, &n2, tol) {
+                pts.push(*v)
+            }
+        }
+
+        for v in vs2.iter() {
+            if point_in_triangle(v, &vs1, &n1, tol) {
+                pts.push(*v)
+            }
+        }
+
+        for i in 0 .. 3 {
+            for j in 0 .. 3 {
+                let (p1, p2) = closest_points_internal::closest_points_segment_segment(
+                    &vs1[i], &vs1[(i + 1) % 3], &vs2[j], &vs2[(j + 1) % 3]);
+
+                if na::sqdist(&p1, &p2) <= tol * tol {
+                    pts.push(p1)
+                }
+            }
+        }
+
+        if pts.len() == 0 {
+            return None;
+        }
+
+        // Pick the two furthest points of the overlap.
+        let mut best = (pts[0], pts[0]);
+        let mut dist = na::zero();
+
+        for i in 0 .. pts.len() {
+            for j in i + 1 .. pts.len() {
+                let d = na::sqdist(&pts[i], &pts[j]);
+
+                if d > dist {
+                    dist = d;
+                    best = (pts[i], pts[j]);
+                }
+            }
+        }
+
+        Some(Segment::new(best.0, best.1))
+    }
+}
+
+/// Tests if the triangle `vs` with non-normalized normal `n` is degenerate.
+fn is_degenerate<N, P, V>(vs: &[P; 3], n: &V, eps: N) -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    // `n` is the cross product of two edges: compare it to the product of their lengths.
+    na::sqnorm(n) <= eps * na::sqdist(&vs[0], &vs[1]) * na::sqdist(&vs[0], &vs[2])
+}
+
+/// The length of the longest edge of the triangle `vs`.
+fn max_edge_length<N, P, V>(vs: &[P; 3]) -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let d01 = na::sqdist(&vs[0], &vs[1]);
+    let d12 = na::sqdist(&vs[1], &vs[2]);
+    let d20 = na::sqdist(&vs[2], &vs[0]);
+
+    d01.max(d12).max(d20).sqrt()
+}
+
+/// Tests if all the points `pts` lie further than `eps` on the same side of a plane.
+fn same_side<N, P, V>(n: &V, orig: &P, pts: &[P; 3], eps: N) -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut pos = true;
+    let mut neg = true;
+
+    for pt in pts.iter() {
+        let d = na::dot(n, &(*pt - *orig));
+
+        pos = pos && d > eps;
+        neg = neg && d < -eps;
+    }
+
+    pos || neg
+}
+
+/// Tests if a point lying on the plane of a triangle with unit normal `n` is inside of this
+/// triangle, up to the distance `tol`.
+fn point_in_triangle<N, P, V>(pt: &P, vs: &[P; 3], n: &V, tol: N) -> bool
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Cross<Output = V> {
+    for i in 0 .. 3 {
+        let edge = vs[(i + 1) % 3] - vs[i];
+
+        // This is the signed distance from `pt` to the edge, times the edge length.
+        if na::dot(&na::cross(&edge, &(*pt - vs[i])), n) < -tol * na::norm(&edge) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Collects the intersections between the edges of the triangle `vs1` and the triangle `vs2`.
+fn edges_against_triangle<N, P, V>(vs1: &[P; 3], vs2: &[P; 3], n2: &V, tol: N, out: &mut Vec<P>)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Cross<Output = V> {
+    for i in 0 .. 3 {
+        let a  = vs1[i];
+        let b  = vs1[(i + 1) % 3];
+        let da = na::dot(n2, &(a - vs2[0]));
+        let db = na::dot(n2, &(b - vs2[0]));
+
+        if (da > na::zero() && db > na::zero()) || (da < na::zero() && db < na::zero()) || da == db {
+            continue;
+        }
+
+        let t  = da / (da - db);
+        let pt = a + (b - a) * t;
+
+        if point_in_triangle(&pt, vs2, n2, tol) {
+            out.push(pt)
+        }
+    }
+}
+
+/// The segment joining the points with the smallest and largest projection on `dir`.
+fn extremal_points<N, P, V>(pts: &[P], dir: &V) -> Option<Segment<P>>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    if pts.len() == 0 {
+        return None;
+    }
+
+    let mut min  = pts[0];
+    let mut max  = pts[0];
+    let mut dmin = na::dot(pts[0].as_vec(), dir);
+    let mut dmax = dmin;
+
+    for pt in pts[1 ..].iter() {
+        let d = na::dot(pt.as_vec(), dir);
+
+        if d < dmin {
+            dmin = d;
+            min  = *pt;
+        }
+
+        if d > dmax {
+            dmax = d;
+            max  = *pt;
+        }
+    }
+
+    Some(Segment::new(min, max))
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::Pnt3;
+    use na;
+    use entities::shape::TriMesh;
+    use super::trimesh_self_intersections;
+
+    // Two disjoint triangles with close normals, crossing each other.
+    fn crossing_triangles(scale: f64) -> TriMesh<f64, Pnt3<f64>, na::Vec3<f64>> {
+        let vertices = vec!(Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(4.0, 0.0, 0.0), Pnt3::new(0.0, 4.0, 0.0),
+                            Pnt3::new(0.5, 0.5, -0.1), Pnt3::new(2.5, 0.5, 0.1), Pnt3::new(0.5, 2.5, 0.1));
+        let vertices = vertices.into_iter().map(|v| v * scale).collect();
+        let indices  = vec!(Pnt3::new(0usize, 1, 2), Pnt3::new(3, 4, 5));
+
+        TriMesh::new(Arc::new(vertices), Arc::new(indices), None, None)
+    }
+
+    fn cube(scale: f64) -> TriMesh<f64, Pnt3<f64>, na::Vec3<f64>> {
+        let mut vertices = Vec::new();
+
+        for i in 0usize .. 8 {
+            let x = (i & 1) as f64;
+            let y = ((i >> 1) & 1) as f64;
+            let z = ((i >> 2) & 1) as f64;
+
+            vertices.push(Pnt3::new(x, y, z) * scale)
+        }
+
+        let indices = vec!(Pnt3::new(0usize, 2, 1), Pnt3::new(1, 2, 3), Pnt3::new(4, 5, 6), Pnt3::new(5, 7, 6),
+                           Pnt3::new(0, 1, 4), Pnt3::new(1, 5, 4), Pnt3::new(2, 6, 3), Pnt3::new(3, 6, 7),
+                           Pnt3::new(0, 4, 2), Pnt3::new(2, 4, 6), Pnt3::new(1, 3, 5), Pnt3::new(3, 7, 5));
+
+        TriMesh::new(Arc::new(vertices), Arc::new(indices), None, None)
+    }
+
+    #[test]
+    fn test_crossing_triangles_with_close_normals() {
+        let mesh = crossing_triangles(1.0);
+        let res  = trimesh_self_intersections(&mesh);
+
+        // Each pair is reported once.
+        assert!(res.len() == 1);
+        assert!(res[0].element1 == 0 && res[0].element2 == 1);
+
+        // The triangles cross along the segment joining (1.5, 0.5, 0) and (0.5, 1.5, 0).
+        let seg = &res[0].intersection;
+        assert!(na::approx_eq(&na::dist(seg.a(), seg.b()), &na::dist(&Pnt3::new(1.5, 0.5, 0.0),
+                                                                    &Pnt3::new(0.5, 1.5, 0.0))));
+    }
+
+    #[test]
+    fn test_closed_cube() {
+        assert!(trimesh_self_intersections(&cube(1.0)).is_empty());
+    }
+
+    #[test]
+    fn test_scale_invariance() {
+        for scale in [ 1.0e-4f64, 1.0e4 ].iter() {
+            let res = trimesh_self_intersections(&crossing_triangles(*scale));
+
+            assert!(res.len() == 1);
+            assert!(res[0].element1 == 0 && res[0].element2 == 1);
+            assert!(trimesh_self_intersections(&cube(*scale)).is_empty());
+        }
+    }
+}
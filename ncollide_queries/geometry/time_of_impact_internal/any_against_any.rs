@@ -3,7 +3,7 @@ use na::Translate;
 use math::{Scalar, Point, Vect, Isometry};
 use entities::inspection;
 use entities::inspection::Repr;
-use entities::shape::{Ball, Plane};
+use entities::shape::{Ball, Capsule, Plane, DistanceField};
 use entities::bounding_volume::HasAABB;
 use geometry::time_of_impact_internal;
 
@@ -28,6 +28,15 @@ pub fn any_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, vel1: &V, g1:
 
         time_of_impact_internal::ball_against_ball(&p1, vel1, b1, &p2, vel2, b2)
     }
+    else if let (Some(c1), Some(c2)) = (r1.downcast_ref::<Capsule<N>>(), r2.downcast_ref::<Capsule<N>>()) {
+        time_of_impact_internal::capsule_against_capsule(m1, vel1, c1, m2, vel2, c2)
+    }
+    else if let (Some(c1), Some(b2)) = (r1.downcast_ref::<Capsule<N>>(), r2.downcast_ref::<Ball<N>>()) {
+        time_of_impact_internal::capsule_against_ball(m1, vel1, c1, &m2.translate(&na::orig()), vel2, b2)
+    }
+    else if let (Some(b1), Some(c2)) = (r1.downcast_ref::<Ball<N>>(), r2.downcast_ref::<Capsule<N>>()) {
+        time_of_impact_internal::ball_against_capsule(&m1.translate(&na::orig()), vel1, b1, m2, vel2, c2)
+    }
     else if let (Some(p1), Some(s2)) =
             (r1.downcast_ref::<Plane<V>>(), inspection::maybe_repr_desc_as_support_map::<P, V, M>(r2)) {
         time_of_impact_internal::plane_against_support_map(m1, vel1, p1, m2, vel2, s2)
@@ -36,6 +45,14 @@ pub fn any_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, vel1: &V, g1:
             (inspection::maybe_repr_desc_as_support_map::<P, V, M>(r1), r2.downcast_ref::<Plane<V>>()) {
         time_of_impact_internal::support_map_against_plane(m1, vel1, s1, m2, vel2, p2)
     }
+    else if let (Some(d1), Some(s2)) =
+            (r1.downcast_ref::<DistanceField<N, P, V>>(), inspection::maybe_repr_desc_as_support_map::<P, V, M>(r2)) {
+        time_of_impact_internal::distance_field_against_support_map(m1, vel1, d1, m2, vel2, s2)
+    }
+    else if let (Some(s1), Some(d2)) =
+            (inspection::maybe_repr_desc_as_support_map::<P, V, M>(r1), r2.downcast_ref::<DistanceField<N, P, V>>()) {
+        time_of_impact_internal::support_map_against_distance_field(m1, vel1, s1, m2, vel2, d2)
+    }
     else if let (Some(s1), Some(s2)) =
             (inspection::maybe_repr_desc_as_support_map::<P, V, M>(r1), inspection::maybe_repr_desc_as_support_map::<P, V, M>(r2)) {
         time_of_impact_internal::support_map_against_support_map(m1, vel1, s1, m2, vel2, s2)
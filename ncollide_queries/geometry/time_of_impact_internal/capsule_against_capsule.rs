@@ -0,0 +1,146 @@
+use std::num::Float;
+use na::Transform;
+use na;
+use math::{Scalar, Point, Vect};
+use entities::shape::{Ball, Capsule};
+use geometry::closest_points_internal;
+
+/// Time Of Impact of two capsules under translational movement.
+pub fn capsule_against_capsule<N, P, V, M>(m1: &M, vel1: &V, c1: &Capsule<N>,
+                                           m2: &M, vel2: &V, c2: &Capsule<N>)
+                                           -> Option<N>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let s1 = c1.segment();
+    let s2 = c2.segment();
+
+    segment_against_segment(&m1.transform(s1.a()), &m1.transform(s1.b()), vel1,
+                            &m2.transform(s2.a()), &m2.transform(s2.b()), vel2,
+                            c1.radius() + c2.radius())
+}
+
+/// Time Of Impact of a capsule and a ball under translational movement.
+pub fn capsule_against_ball<N, P, V, M>(m1: &M, vel1: &V, c1: &Capsule<N>,
+                                        center2: &P, vel2: &V, b2: &Ball<N>)
+                                        -> Option<N>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    let s1 = c1.segment();
+
+    segment_against_segment(&m1.transform(s1.a()), &m1.transform(s1.b()), vel1,
+                            center2, center2, vel2,
+                            c1.radius() + b2.radius())
+}
+
+/// Time Of Impact of a ball and a capsule under translational movement.
+pub fn ball_against_capsule<N, P, V, M>(center1: &P, vel1: &V, b1: &Ball<N>,
+                                        m2: &M, vel2: &V, c2: &Capsule<N>)
+                                        -> Option<N>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+    capsule_against_ball(m2, vel2, c2, center1, vel1, b1)
+}
+
+/// Time at which two translating segments get closer than `radius`.
+///
+/// The distance between two translating convex shapes is a convex function of time, so Newton
+/// iterations started at zero never step past the time of impact. Returns `None` if the distance
+/// stops decreasing before reaching `radius`.
+fn segment_against_segment<N, P, V>(a1: &P, b1: &P, vel1: &V,
+                                    a2: &P, b2: &P, vel2: &V,
+                                    radius: N)
+                                    -> Option<N>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let vel    = *vel1 - *vel2;
+    let eps: N = Float::epsilon();
+    let tol    = (radius + na::dist(a1, b1) + na::dist(a2, b2)) * eps.sqrt();
+    let mut t  = na::zero::<N>();
+
+    for _ in 0 .. 100 {
+        let shift    = vel * t;
+        let (p1, p2) = closest_points_internal::closest_points_segment_segment(&(*a1 + shift), &(*b1 + shift), a2, b2);
+        let dir      = p2 - p1;
+        let dist     = na::norm(&dir);
+        let gap      = dist - radius;
+
+        if gap <= tol {
+            return Some(t);
+        }
+
+        let speed = na::dot(&dir, &vel) / dist;
+
+        if speed <= na::zero() {
+            return None;
+        }
+
+        t = t + gap / speed;
+    }
+
+    // The iterations converge toward the time of impact from below: `t` is a conservative result.
+    Some(t)
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Iso3, Vec3, Pnt3};
+    use na;
+    use entities::shape::{Ball, Capsule};
+
+    #[test]
+    fn test_capsules_head_on() {
+        let c  = Capsule::new(1.0f64, 0.5);
+        let m1 = Iso3::new(na::zero(), na::zero());
+        let m2 = Iso3::new(Vec3::new(3.0f64, 0.0, 0.0), na::zero());
+
+        let toi = super::capsule_against_capsule(&m1, &Vec3::new(1.0, 0.0, 0.0), &c,
+                                                 &m2, &na::zero(), &c).unwrap();
+
+        assert!(na::approx_eq_eps(&toi, &2.0, &1.0e-6));
+    }
+
+    #[test]
+    fn test_capsules_crossed() {
+        // The second capsule lies along `z` and the first one moves toward it along `y`.
+        let c  = Capsule::new(1.0f64, 0.25);
+        let m1 = Iso3::new(Vec3::new(0.0f64, 4.0, 0.0), na::zero());
+        let m2 = Iso3::new(na::zero(), Vec3::new(1.57079632679f64, 0.0, 0.0));
+
+        let toi = super::capsule_against_capsule(&m1, &Vec3::new(0.0, -2.0, 0.0), &c,
+                                                 &m2, &na::zero(), &c).unwrap();
+
+        // The tip of the first capsule is at `y = 3` and must get within `0.5` of the origin.
+        assert!(na::approx_eq_eps(&toi, &1.25, &1.0e-6));
+    }
+
+    #[test]
+    fn test_capsules_missing() {
+        let c  = Capsule::new(1.0f64, 0.5);
+        let m1 = Iso3::new(na::zero(), na::zero());
+        let m2 = Iso3::new(Vec3::new(3.0f64, 0.0, 0.0), na::zero());
+
+        assert!(super::capsule_against_capsule(&m1, &Vec3::new(-1.0, 0.0, 0.0), &c,
+                                               &m2, &na::zero(), &c).is_none());
+        assert!(super::capsule_against_capsule(&m1, &Vec3::new(0.0, 0.0, 1.0), &c,
+                                               &m2, &na::zero(), &c).is_none());
+    }
+
+    #[test]
+    fn test_ball_against_capsule() {
+        let c  = Capsule::new(1.0f64, 0.5);
+        let b  = Ball::new(0.5f64);
+        let m2 = Iso3::new(na::zero(), na::zero());
+
+        let toi = super::ball_against_capsule(&Pnt3::new(0.0f64, 5.0, 0.0), &Vec3::new(0.0, -1.0, 0.0), &b,
+                                              &m2, &na::zero(), &c).unwrap();
+
+        assert!(na::approx_eq_eps(&toi, &3.0, &1.0e-6));
+    }
+}
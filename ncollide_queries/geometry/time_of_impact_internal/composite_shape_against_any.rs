@@ -20,7 +20,7 @@ pub fn composite_shape_against_any<N, P, V, M, G1: ?Sized, G2: ?Sized>(m1: &M, v
           G2: Repr<N, P, V, M> + HasAABB<P, M> {
     let mut cost_fn = CompositeShapeAgainstAnyTOICostFn::new(m1, vel1, g1, m2, vel2, g2);
 
-    g1.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+    g1.best_first_search(&mut cost_fn).map(|(_, res)| res)
 }
 
 /// Time Of Impact of any shape with a composite shape, under translational movement.
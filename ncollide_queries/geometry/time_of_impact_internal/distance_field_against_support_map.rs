@@ -0,0 +1,175 @@
+use na::{Rotate, Bounded};
+use na;
+use entities::support_map::SupportMap;
+use entities::shape::DistanceField;
+use geometry::distance_internal;
+use math::{Scalar, Point, Vect, Isometry};
+
+/// Maximum number of sphere tracing steps.
+const MAX_TRACING_STEPS: usize = 64;
+
+/// Time Of Impact of a distance field with a support-mapped shape under translational movement.
+///
+/// The support-mapped shape is sphere-traced through the field: it is repeatedly advanced by its
+/// distance to the field, computed by `distance_internal::distance_field_against_support_map`,
+/// until this distance falls below a hundredth of the smallest sample spacing. Only the time
+/// interval during which the shape overlaps the sampled domain is explored. Returns `None` if no
+/// impact is found after `MAX_TRACING_STEPS` steps.
+pub fn distance_field_against_support_map<N, P, V, M, G: ?Sized>(mfield: &M, vel_field: &V,
+                                                                 field: &DistanceField<N, P, V>,
+                                                                 mother: &M, vel_other: &V, other: &G)
+                                                                 -> Option<N>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    // Work in the local space of the distance field.
+    let ls_mother = na::inv(mfield).expect("The distance field transformation must be invertible.") *
+                    mother.clone();
+    let ls_vel    = mfield.inv_rotate(&(*vel_other - *vel_field));
+    let identity  = na::one::<M>();
+
+    let mut min_spacing = field.spacing()[0];
+
+    for i in 1 .. na::dim::<P>() {
+        min_spacing = min_spacing.min(field.spacing()[i]);
+    }
+
+    let tolerance = min_spacing * na::cast(0.01f64);
+
+    // Time interval during which the local AABB of the moving shape overlaps the sampled domain.
+    let fmins     = *field.mins();
+    let fmaxs     = field.maxs();
+    let mut start = na::zero::<N>();
+    let mut end: N = Bounded::max_value();
+
+    for i in 0 .. na::dim::<P>() {
+        let mut dir = na::zero::<V>();
+        dir[i]      = na::one();
+
+        let omaxs = other.support_point(&ls_mother, &dir)[i];
+        let omins = other.support_point(&ls_mother, &-dir)[i];
+
+        if na::is_zero(&ls_vel[i]) {
+            if omaxs < fmins[i] || omins > fmaxs[i] {
+                return None;
+            }
+        }
+        else {
+            let t1 = (fmins[i] - omaxs) / ls_vel[i];
+            let t2 = (fmaxs[i] - omins) / ls_vel[i];
+
+            start = start.max(t1.min(t2));
+            end   = end.min(t1.max(t2));
+        }
+    }
+
+    if start > end {
+        return None;
+    }
+
+    let speed = na::norm(&ls_vel);
+    let mut t = start;
+
+    for _ in 0 .. MAX_TRACING_STEPS {
+        let moved = na::append_translation(&ls_mother, &(ls_vel * t));
+        let dist  = distance_internal::distance_field_against_support_map(&identity, field, &moved, other);
+
+        if dist <= tolerance {
+            return Some(t);
+        }
+
+        if na::is_zero(&speed) {
+            return None;
+        }
+
+        t = t + dist / speed;
+
+        if t > end {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// Time Of Impact of a support-mapped shape with a distance field under translational movement.
+pub fn support_map_against_distance_field<N, P, V, M, G: ?Sized>(mother: &M, vel_other: &V, other: &G,
+                                                                 mfield: &M, vel_field: &V,
+                                                                 field: &DistanceField<N, P, V>)
+                                                                 -> Option<N>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Isometry<N, P, V>,
+          G: SupportMap<P, V, M> {
+    distance_field_against_support_map(mfield, vel_field, field, mother, vel_other, other)
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use na::{Iso2, Pnt2, Vec2};
+    use na;
+    use entities::shape::{Ball, DistanceField};
+
+    // The unit disk centered at the origin.
+    fn disk() -> DistanceField<f64, Pnt2<f64>, Vec2<f64>> {
+        let mut values = Vec::new();
+
+        for j in 0usize .. 33 {
+            for i in 0usize .. 33 {
+                let x = i as f64 * 0.25 - 4.0;
+                let y = j as f64 * 0.25 - 4.0;
+
+                values.push((x * x + y * y).sqrt() - 1.0)
+            }
+        }
+
+        DistanceField::new(values, vec!(33, 33), Pnt2::new(-4.0, -4.0), Vec2::new(0.25, 0.25))
+    }
+
+    #[test]
+    fn test_ball_against_disk() {
+        let field  = disk();
+        let ball   = Ball::new(0.5f64);
+        let mfield = Iso2::new(na::zero(), na::zero());
+        let mball  = Iso2::new(Vec2::new(3.0, 0.0), na::zero());
+        let null   = na::zero::<Vec2<f64>>();
+
+        // Head-on.
+        let toi = super::distance_field_against_support_map(&mfield, &null, &field,
+                                                            &mball, &Vec2::new(-1.0, 0.0), &ball).unwrap();
+        assert!(na::approx_eq_eps(&toi, &1.5, &1.0e-2));
+
+        // The field moves toward the ball.
+        let toi = super::support_map_against_distance_field(&mball, &null, &ball,
+                                                            &mfield, &Vec2::new(2.0, 0.0), &field).unwrap();
+        assert!(na::approx_eq_eps(&toi, &0.75, &1.0e-2));
+
+        // Moving away or passing by.
+        assert!(super::distance_field_against_support_map(&mfield, &null, &field,
+                                                          &mball, &Vec2::new(1.0, 0.0), &ball).is_none());
+        assert!(super::distance_field_against_support_map(&mfield, &null, &field,
+                                                          &mball, &Vec2::new(0.0, 1.0), &ball).is_none());
+
+        // Already penetrating.
+        let mball = Iso2::new(Vec2::new(1.2, 0.0), na::zero());
+        assert!(super::distance_field_against_support_map(&mfield, &null, &field,
+                                                          &mball, &null, &ball) == Some(0.0));
+    }
+
+    #[test]
+    fn test_ball_starting_outside_of_the_domain() {
+        let field  = disk();
+        let ball   = Ball::new(0.5f64);
+        let mfield = Iso2::new(na::zero(), na::zero());
+        let mball  = Iso2::new(Vec2::new(-10.0, 0.0), na::zero());
+        let null   = na::zero::<Vec2<f64>>();
+
+        let toi = super::distance_field_against_support_map(&mfield, &null, &field,
+                                                            &mball, &Vec2::new(1.0, 0.0), &ball).unwrap();
+        assert!(na::approx_eq_eps(&toi, &8.5, &1.0e-2));
+    }
+}
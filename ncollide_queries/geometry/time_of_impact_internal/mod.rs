@@ -2,13 +2,18 @@
 
 pub use self::ball_against_ball::ball_against_ball;
 pub use self::support_map_against_support_map::support_map_against_support_map;
+pub use self::capsule_against_capsule::{capsule_against_capsule, capsule_against_ball, ball_against_capsule};
 pub use self::plane_against_support_map::{plane_against_support_map, support_map_against_plane};
+pub use self::distance_field_against_support_map::{distance_field_against_support_map,
+                                                    support_map_against_distance_field};
 pub use self::any_against_any::any_against_any;
 pub use self::any_against_any::any_against_any as time_of_impact;
 pub use self::composite_shape_against_any::{composite_shape_against_any, any_against_composite_shape};
 
 mod ball_against_ball;
 mod support_map_against_support_map;
+mod capsule_against_capsule;
 mod plane_against_support_map;
+mod distance_field_against_support_map;
 mod any_against_any;
 mod composite_shape_against_any;
@@ -2,7 +2,9 @@
 
 #[doc(inline)]
 pub use self::point_query::{LocalPointQuery, PointQuery};
-pub use self::point_bvt::PointInterferencesCollector;
+pub use self::point_bvt::{PointInterferencesCollector, PointDistanceCostFn};
+pub use self::point_mesh::{k_closest_elements, k_closest_triangles, TriMeshFeature,
+                            trimesh_signed_projection, trimesh_signed_distance};
 
 #[doc(hidden)]
 pub mod point_query;
@@ -11,10 +13,14 @@ mod point_ball;
 mod point_cuboid;
 mod point_aabb;
 mod point_bounding_sphere;
+mod point_obb;
+mod point_kdop;
 mod point_support_map;
 mod point_segment;
 mod point_triangle;
 mod point_compound;
 mod point_mesh;
+mod point_heightfield;
+mod point_distance_field;
 mod point_repr;
 mod point_bvt;
@@ -1,7 +1,45 @@
-use entities::partitioning::BVTVisitor;
+use na;
+use entities::partitioning::{BVTVisitor, BVTCostFn};
 use point::LocalPointQuery;
+use math::{Scalar, Point, Vect};
 
-// FIXME: add a point cost fn.
+/// A search that selects the objects that are the closest to a given point.
+///
+/// The result associated to each object is the projection of the point on this object.
+pub struct PointDistanceCostFn<'a, P: 'a> {
+    point: &'a P,
+    solid: bool
+}
+
+impl<'a, P> PointDistanceCostFn<'a, P> {
+    /// Creates a new `PointDistanceCostFn`.
+    pub fn new(point: &'a P, solid: bool) -> PointDistanceCostFn<'a, P> {
+        PointDistanceCostFn {
+            point: point,
+            solid: solid
+        }
+    }
+}
+
+#[old_impl_check]
+impl<'a, N, P, V, B, BV> BVTCostFn<N, B, BV, P> for PointDistanceCostFn<'a, P>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N>,
+          B:  LocalPointQuery<N, P>,
+          BV: LocalPointQuery<N, P> {
+    #[inline]
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        Some(bv.distance_to_point(self.point))
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, b: &B) -> Option<(N, P)> {
+        let proj = b.project_point(self.point, self.solid);
+
+        Some((na::dist(self.point, &proj), proj))
+    }
+}
 
 /// Bounding Volume Tree visitor collecting nodes that may contain a given point.
 pub struct PointInterferencesCollector<'a, P: 'a, B: 'a> {
@@ -1,7 +1,6 @@
 use na::Translate;
 use na;
 use point::{LocalPointQuery, PointQuery};
-use entities::bounding_volume::AABB;
 use entities::shape::{Compound, CompositeShape};
 use entities::partitioning::{BVTCostFn, BVTVisitor};
 use math::{Scalar, Point, Vect, Isometry};
@@ -17,7 +16,10 @@ impl<N, P, V, M> LocalPointQuery<N, P> for Compound<N, P, V, M>
     fn project_point(&self, point: &P, solid: bool) -> P {
         let mut cost_fn = CompoundPointProjCostFn { compound: self, point: point, solid: solid };
 
-        self.bvt().best_first_search(&mut cost_fn).unwrap().1
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn).unwrap().1,
+            None      => self.bvt().best_first_search(&mut cost_fn).unwrap().1
+        }
     }
 
     #[inline]
@@ -29,7 +31,10 @@ impl<N, P, V, M> LocalPointQuery<N, P> for Compound<N, P, V, M>
     fn contains_point(&self, point: &P) -> bool {
         let mut test = PointContainementTest { compound: self, point: point, found: false };
 
-        self.bvt().visit(&mut test);
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.visit(&mut test),
+            None      => self.bvt().visit(&mut test)
+        }
 
         test.found
     }
@@ -52,14 +57,15 @@ struct CompoundPointProjCostFn<'a, N: 'a, P: 'a, V: 'a, M: 'a> {
     solid:    bool
 }
 
-impl<'a, N, P, V, M> BVTCostFn<N, usize, AABB<P>, P> for CompoundPointProjCostFn<'a, N, P, V, M>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N> + Translate<P>,
-          M: Isometry<N, P, V> {
+impl<'a, N, P, V, M, BV> BVTCostFn<N, usize, BV, P> for CompoundPointProjCostFn<'a, N, P, V, M>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N> + Translate<P>,
+          M:  Isometry<N, P, V>,
+          BV: LocalPointQuery<N, P> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        Some(aabb.distance_to_point(self.point))
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        Some(bv.distance_to_point(self.point))
     }
 
     #[inline]
@@ -86,18 +92,19 @@ struct PointContainementTest<'a, N: 'a, P: 'a, V: 'a, M: 'a> {
     found:    bool
 }
 
-impl<'a, N, P, V, M> BVTVisitor<usize, AABB<P>> for PointContainementTest<'a, N, P, V, M>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N> + Translate<P>,
-          M: Isometry<N, P, V> {
+impl<'a, N, P, V, M, BV> BVTVisitor<usize, BV> for PointContainementTest<'a, N, P, V, M>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N> + Translate<P>,
+          M:  Isometry<N, P, V>,
+          BV: LocalPointQuery<N, P> {
     #[inline]
-    fn visit_internal(&mut self, bv: &AABB<P>) -> bool {
+    fn visit_internal(&mut self, bv: &BV) -> bool {
         !self.found && bv.contains_point(self.point)
     }
 
     #[inline]
-    fn visit_leaf(&mut self, b: &usize, bv: &AABB<P>) {
+    fn visit_leaf(&mut self, b: &usize, bv: &BV) {
         if !self.found && bv.contains_point(self.point) {
             self.compound.map_part_at(*b, &mut |objm, obj| {
                 self.found = obj.contains_point_with_transform(objm, self.point)
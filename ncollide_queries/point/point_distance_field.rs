@@ -0,0 +1,47 @@
+use na::Transform;
+use na;
+use point::{LocalPointQuery, PointQuery};
+use entities::shape::DistanceField;
+use math::{Scalar, Point, Vect};
+
+
+impl<N, P, V> LocalPointQuery<N, P> for DistanceField<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn project_point(&self, point: &P, solid: bool) -> P {
+        let dist = self.value_at(point);
+
+        if solid && dist <= na::zero() {
+            return point.clone()
+        }
+
+        // Follow the gradient down to the zero level set.
+        let mut dir = self.gradient_at(point);
+
+        if na::is_zero(&dir.normalize()) {
+            point.clone()
+        }
+        else {
+            *point - dir * dist
+        }
+    }
+
+    #[inline]
+    fn distance_to_point(&self, point: &P) -> N {
+        self.value_at(point).max(na::zero())
+    }
+
+    #[inline]
+    fn contains_point(&self, point: &P) -> bool {
+        self.value_at(point) <= na::zero()
+    }
+}
+
+impl<N, P, V, M> PointQuery<N, P, M> for DistanceField<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+}
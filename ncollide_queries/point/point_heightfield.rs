@@ -0,0 +1,76 @@
+use na::Transform;
+use na;
+use point::{LocalPointQuery, PointQuery};
+use entities::shape::HeightField;
+use entities::bounding_volume::AABB;
+use entities::partitioning::BVTCostFn;
+use math::{Scalar, Point, Vect};
+
+
+impl<N, P, V> LocalPointQuery<N, P> for HeightField<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn project_point(&self, point: &P, solid: bool) -> P {
+        if solid && self.contains_point(point) {
+            return point.clone()
+        }
+
+        let mut cost_fn = HeightFieldPointProjCostFn { heightfield: self, point: point };
+
+        self.best_first_search(&mut cost_fn).unwrap().1
+    }
+
+    #[inline]
+    fn distance_to_point(&self, point: &P) -> N {
+        na::dist(point, &self.project_point(point, true))
+    }
+
+    #[inline]
+    fn contains_point(&self, point: &P) -> bool {
+        // The heightfield is considered solid below its surface.
+        match self.surface_height_at_point(point) {
+            Some(h) => point[1] <= h,
+            None    => false
+        }
+    }
+}
+
+impl<N, P, V, M> PointQuery<N, P, M> for HeightField<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+}
+
+
+/*
+ * Costs function.
+ */
+struct HeightFieldPointProjCostFn<'a, N: 'a, V: 'a, P: 'a> {
+    heightfield: &'a HeightField<N, V>,
+    point:       &'a P
+}
+
+impl<'a, N, P, V> BVTCostFn<N, usize, AABB<P>, P> for HeightFieldPointProjCostFn<'a, N, V, P>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
+        Some(aabb.distance_to_point(self.point))
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, b: &usize) -> Option<(N, P)> {
+        let proj = if na::dim::<P>() == 2 {
+            self.heightfield.segment_at(*b).project_point(self.point, true)
+        }
+        else {
+            self.heightfield.triangle_at(*b).project_point(self.point, true)
+        };
+
+        Some((na::dist(self.point, &proj), proj))
+    }
+}
@@ -0,0 +1,109 @@
+use std::num::Float;
+use na::{Transform, Bounded};
+use na;
+use point::{LocalPointQuery, PointQuery};
+use entities::bounding_volume::KDOP;
+use math::{Scalar, Point, Vect};
+
+/// The maximum number of iterations of the projection on the intersection of the slabs.
+const MAX_ITERATIONS: usize = 100;
+
+#[old_impl_check]
+impl<N, P, V> LocalPointQuery<N, P> for KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn project_point(&self, pt: &P, solid: bool) -> P {
+        if !self.contains_point(pt) {
+            project_outside_point(self, pt)
+        }
+        else if solid {
+            *pt
+        }
+        else {
+            // The closest boundary point lies on the closest slab plane.
+            let _max: N   = Bounded::max_value();
+            let mut best  = _max;
+            let mut shift = na::zero::<V>();
+
+            for (i, axis) in self.axes().iter().enumerate() {
+                let proj = na::dot(pt.as_vec(), axis);
+
+                if proj - self.mins()[i] < best {
+                    best  = proj - self.mins()[i];
+                    shift = *axis * (-best);
+                }
+
+                if self.maxs()[i] - proj < best {
+                    best  = self.maxs()[i] - proj;
+                    shift = *axis * best;
+                }
+            }
+
+            *pt + shift
+        }
+    }
+
+    #[inline]
+    fn distance_to_point(&self, pt: &P) -> N {
+        if self.contains_point(pt) {
+            na::zero()
+        }
+        else {
+            na::dist(pt, &project_outside_point(self, pt))
+        }
+    }
+
+    #[inline]
+    fn contains_point(&self, pt: &P) -> bool {
+        self.contains_point(pt)
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, M> PointQuery<N, P, M> for KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> {
+}
+
+/// Projects a point located outside of a k-DOP on its boundary.
+///
+/// This uses Dykstra’s alternating projection algorithm on the slabs of the k-DOP.
+fn project_outside_point<N, P, V>(kdop: &KDOP<N, V>, pt: &P) -> P
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let _eps: N        = Float::epsilon();
+    let mut res        = *pt;
+    let mut increments = vec![ na::zero::<V>(); kdop.nslabs() ];
+
+    for _ in 0 .. MAX_ITERATIONS {
+        let prev = res;
+
+        for (i, axis) in kdop.axes().iter().enumerate() {
+            let shifted = res + increments[i];
+            let proj    = na::dot(shifted.as_vec(), axis);
+
+            res = if proj < kdop.mins()[i] {
+                shifted + *axis * (kdop.mins()[i] - proj)
+            }
+            else if proj > kdop.maxs()[i] {
+                shifted + *axis * (kdop.maxs()[i] - proj)
+            }
+            else {
+                shifted
+            };
+
+            increments[i] = shifted - res;
+        }
+
+        if na::sqdist(&res, &prev) <= _eps {
+            break;
+        }
+    }
+
+    res
+}
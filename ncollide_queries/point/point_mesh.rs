@@ -1,6 +1,9 @@
-use na::Transform;
+use na::{Transform, Bounded};
 use na;
 use point::{LocalPointQuery, PointQuery};
+use point::point_triangle::{self, TriangleFeature};
+use ray::{Ray, RayInterferencesCollector};
+use ray;
 use entities::shape::{BaseMesh, BaseMeshElement, TriMesh, Polyline};
 use entities::bounding_volume::AABB;
 use entities::partitioning::{BVTCostFn, BVTVisitor};
@@ -16,7 +19,10 @@ impl<N, P, V, I, E> LocalPointQuery<N, P> for BaseMesh<N, P, V, I, E>
     fn project_point(&self, point: &P, _: bool) -> P {
         let mut cost_fn = BaseMeshPointProjCostFn { mesh: self, point: point };
 
-        self.bvt().best_first_search(&mut cost_fn).unwrap().1
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn).unwrap().1,
+            None      => self.bvt().best_first_search(&mut cost_fn).unwrap().1
+        }
     }
 
     #[inline]
@@ -28,7 +34,10 @@ impl<N, P, V, I, E> LocalPointQuery<N, P> for BaseMesh<N, P, V, I, E>
     fn contains_point(&self, point: &P) -> bool {
         let mut test = PointContainementTest { mesh: self, point: point, found: false };
 
-        self.bvt().visit(&mut test);
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.visit(&mut test),
+            None      => self.bvt().visit(&mut test)
+        }
 
         test.found
     }
@@ -42,6 +51,155 @@ impl<N, P, V, M, I, E> PointQuery<N, P, M> for BaseMesh<N, P, V, I, E>
           E: BaseMeshElement<I, P> + LocalPointQuery<N, P> {
 }
 
+/// Finds the `k` elements of a mesh that are the closest to a point.
+///
+/// Elements further than `max_dist` are ignored. The result is sorted by increasing distance and
+/// contains the element index, its distance to the point, and the projection of the point on it.
+pub fn k_closest_elements<N, P, V, I, E>(mesh:     &BaseMesh<N, P, V, I, E>,
+                                         point:    &P,
+                                         k:        usize,
+                                         max_dist: Option<N>)
+                                         -> Vec<(usize, N, P)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          E: BaseMeshElement<I, P> + LocalPointQuery<N, P> {
+    let mut cost_fn = BaseMeshPointProjCostFn { mesh: mesh, point: point };
+    let mut found   = Vec::new();
+
+    mesh.bvt().k_best_first_search(k, max_dist.unwrap_or(Bounded::max_value()), &mut cost_fn, &mut found);
+
+    found.into_iter().map(|(i, dist, proj)| (*i, dist, proj)).collect()
+}
+
+/// Finds the `k` triangles of a triangle mesh that are the closest to a point.
+///
+/// See `k_closest_elements` for details.
+#[inline]
+pub fn k_closest_triangles<N, P, V>(mesh: &TriMesh<N, P, V>, point: &P, k: usize, max_dist: Option<N>)
+                                    -> Vec<(usize, N, P)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    k_closest_elements(mesh.base_mesh(), point, k, max_dist)
+}
+
+/// The feature of a triangle mesh containing the projection of a point.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TriMeshFeature {
+    /// The vertex with the given index.
+    Vertex(usize),
+    /// The edge joining the vertices with the given indices, the smallest index first.
+    Edge(usize, usize),
+    /// The triangle with the given index.
+    Face(usize)
+}
+
+/// Computes the signed projection of a point on a closed triangle mesh.
+///
+/// Returns the projection, the signed distance between the point and the mesh, and the feature
+/// containing the projection. The distance is negative if the point is inside of the mesh, as
+/// given by the sign of `dot(point - projection, n)` where `n` is the angle-weighted pseudo-normal
+/// of the feature. Ray parity tests are used instead if the mesh is not watertight. In any case,
+/// the distance is negative iff. `contains_point` returns `true` and the point is not on the mesh.
+///
+/// Returns `None` if the mesh is empty. Fails if the mesh has no pseudo-normals, i.e., if it is not
+/// a 3d mesh flagged as closed at construction.
+pub fn trimesh_signed_projection<N, P, V>(mesh: &TriMesh<N, P, V>, point: &P) -> Option<(P, N, TriMeshFeature)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    match closed_trimesh_closest_feature(mesh, point) {
+        Some((proj, feature, inside)) => {
+            let dist = na::dist(point, &proj);
+
+            if inside {
+                Some((proj, -dist, feature))
+            }
+            else {
+                Some((proj, dist, feature))
+            }
+        },
+        None => None
+    }
+}
+
+/// Computes the signed distance between a point and a closed triangle mesh.
+///
+/// See `trimesh_signed_projection` for details.
+#[inline]
+pub fn trimesh_signed_distance<N, P, V>(mesh: &TriMesh<N, P, V>, point: &P) -> Option<(N, TriMeshFeature)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    trimesh_signed_projection(mesh, point).map(|(_, dist, feature)| (dist, feature))
+}
+
+/// Projects a point on a closed triangle mesh.
+///
+/// Returns the projection, the feature containing it, and whether the point is inside of the mesh.
+/// Points on the mesh are inside. Otherwise, this is given by the angle-weighted pseudo-normal of
+/// the feature if the mesh is watertight, and by ray parity tests if it is not.
+fn closed_trimesh_closest_feature<N, P, V>(mesh: &TriMesh<N, P, V>, point: &P) -> Option<(P, TriMeshFeature, bool)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    match trimesh_closest_feature(mesh, point) {
+        Some((proj, feature, inside)) => {
+            let inside = if na::is_zero(&na::sqdist(point, &proj)) {
+                true
+            }
+            else if mesh.is_watertight() {
+                inside
+            }
+            else {
+                ray_parity_contains_point(mesh, point).unwrap_or(inside)
+            };
+
+            Some((proj, feature, inside))
+        },
+        None => None
+    }
+}
+
+/// Projects a point on a closed triangle mesh.
+///
+/// Returns the projection, the feature containing it, and whether the point is inside of the mesh
+/// according to the angle-weighted pseudo-normal of this feature.
+fn trimesh_closest_feature<N, P, V>(mesh: &TriMesh<N, P, V>, point: &P) -> Option<(P, TriMeshFeature, bool)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let pseudo_normals = mesh.pseudo_normals().expect(
+        "Signed queries require a 3d triangle mesh flagged as closed at construction.");
+
+    let mut cost_fn        = TriMeshFeatureProjCostFn { mesh: mesh, point: point };
+    let (best, (proj, tf)) = match mesh.bvt().best_first_search(&mut cost_fn) {
+        Some(res) => res,
+        None      => return None
+    };
+
+    let idx = mesh.indices()[*best];
+
+    let (feature, normal) = match tf {
+        TriangleFeature::Vertex(i) => {
+            (TriMeshFeature::Vertex(idx[i]), pseudo_normals.vertex_normal(idx[i]))
+        },
+        TriangleFeature::Edge(i) => {
+            let a = idx[i];
+            let b = idx[(i + 1) % 3];
+
+            let feature = if a < b { TriMeshFeature::Edge(a, b) } else { TriMeshFeature::Edge(b, a) };
+
+            (feature, pseudo_normals.edge_normal(*best, i))
+        },
+        TriangleFeature::Face => {
+            (TriMeshFeature::Face(*best), pseudo_normals.face_normal(*best))
+        }
+    };
+
+    Some((proj, feature, na::dot(&(*point - proj), normal) < na::zero()))
+}
 
 /*
  * Costs function.
@@ -51,14 +209,15 @@ struct BaseMeshPointProjCostFn<'a, N: 'a, P: 'a, V: 'a, I: 'a, E: 'a> {
     point: &'a P
 }
 
-impl<'a, N, P, V, I, E> BVTCostFn<N, usize, AABB<P>, P> for BaseMeshPointProjCostFn<'a, N, P, V, I, E>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N>,
-          E: BaseMeshElement<I, P> + LocalPointQuery<N, P> {
+impl<'a, N, P, V, I, E, BV> BVTCostFn<N, usize, BV, P> for BaseMeshPointProjCostFn<'a, N, P, V, I, E>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N>,
+          E:  BaseMeshElement<I, P> + LocalPointQuery<N, P>,
+          BV: LocalPointQuery<N, P> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        Some(aabb.distance_to_point(self.point))
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        Some(bv.distance_to_point(self.point))
     }
 
     #[inline]
@@ -69,6 +228,29 @@ impl<'a, N, P, V, I, E> BVTCostFn<N, usize, AABB<P>, P> for BaseMeshPointProjCos
     }
 }
 
+struct TriMeshFeatureProjCostFn<'a, N: 'a, P: 'a, V: 'a> {
+    mesh:  &'a TriMesh<N, P, V>,
+    point: &'a P
+}
+
+impl<'a, N, P, V> BVTCostFn<N, usize, AABB<P>, (P, TriangleFeature)> for TriMeshFeatureProjCostFn<'a, N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
+        Some(aabb.distance_to_point(self.point))
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, b: &usize) -> Option<(N, (P, TriangleFeature))> {
+        let t          = self.mesh.triangle_at(*b);
+        let (proj, tf) = point_triangle::triangle_project_point_with_feature(&t, self.point);
+
+        Some((na::dist(self.point, &proj), (proj, tf)))
+    }
+}
+
 /*
  * Visitor.
  */
@@ -79,18 +261,19 @@ struct PointContainementTest<'a, N: 'a, P: 'a, V: 'a, I: 'a, E: 'a> {
     found: bool
 }
 
-impl<'a, N, P, V, I, E> BVTVisitor<usize, AABB<P>> for PointContainementTest<'a, N, P, V, I, E>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N>,
-          E: BaseMeshElement<I, P> + LocalPointQuery<N, P> {
+impl<'a, N, P, V, I, E, BV> BVTVisitor<usize, BV> for PointContainementTest<'a, N, P, V, I, E>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N>,
+          E:  BaseMeshElement<I, P> + LocalPointQuery<N, P>,
+          BV: LocalPointQuery<N, P> {
     #[inline]
-    fn visit_internal(&mut self, bv: &AABB<P>) -> bool {
+    fn visit_internal(&mut self, bv: &BV) -> bool {
         !self.found && bv.contains_point(self.point)
     }
 
     #[inline]
-    fn visit_leaf(&mut self, b: &usize, bv: &AABB<P>) {
+    fn visit_leaf(&mut self, b: &usize, bv: &BV) {
         if !self.found &&
            bv.contains_point(self.point) &&
            self.mesh.element_at(*b).contains_point(self.point) {
@@ -108,17 +291,34 @@ impl<N, P, V> LocalPointQuery<N, P> for TriMesh<N, P, V>
           V: Vect<N> {
     #[inline]
     fn project_point(&self, point: &P, solid: bool) -> P {
-        self.base_mesh().project_point(point, solid)
+        if solid && self.is_closed() && self.pseudo_normals().is_some() {
+            match closed_trimesh_closest_feature(self, point) {
+                Some((_, _, true))     => point.clone(),
+                Some((proj, _, false)) => proj,
+                None                   => self.base_mesh().project_point(point, solid)
+            }
+        }
+        else {
+            self.base_mesh().project_point(point, solid)
+        }
     }
 
     #[inline]
     fn distance_to_point(&self, point: &P) -> N {
-        self.base_mesh().distance_to_point(point)
+        na::dist(point, &self.project_point(point, true))
     }
 
     #[inline]
     fn contains_point(&self, point: &P) -> bool {
-        self.base_mesh().contains_point(point)
+        if self.is_closed() && self.pseudo_normals().is_some() {
+            match closed_trimesh_closest_feature(self, point) {
+                Some((_, _, inside)) => inside,
+                None                 => false
+            }
+        }
+        else {
+            self.base_mesh().contains_point(point)
+        }
     }
 }
 
@@ -129,6 +329,57 @@ impl<N, P, V, M> PointQuery<N, P, M> for TriMesh<N, P, V>
           M: Transform<P> {
 }
 
+/// Tests if a point is inside of the volume bounded by a 3d triangle mesh with ray parity tests.
+///
+/// Rays are cast from the point and the triangles they cross are counted. A ray passing too close
+/// to an edge or a vertex may cross several triangles at once so it is discarded in favor of the
+/// next direction. Returns `None` if every direction is discarded.
+fn ray_parity_contains_point<N, P, V>(mesh: &TriMesh<N, P, V>, point: &P) -> Option<bool>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    // Unit directions not aligned with any axis nor coordinate plane.
+    let dirs = [ [ 0.36f64, 0.48, 0.8 ], [ -0.8, 0.36, 0.48 ], [ 0.48, -0.6, 0.64 ], [ 0.6, 0.64, -0.48 ] ];
+
+    let eps: N     = na::cast(1.0e-6f64);
+    let mut buffer = Vec::new();
+
+    for d in dirs.iter() {
+        let mut dir = na::zero::<V>();
+
+        for i in 0 .. 3 {
+            dir[i] = na::cast(d[i]);
+        }
+
+        let ray = Ray::new(point.clone(), dir);
+
+        buffer.clear();
+
+        {
+            let mut collector = RayInterferencesCollector::new(&ray, &mut buffer);
+            mesh.bvt().visit(&mut collector);
+        }
+
+        let mut crossings = 0usize;
+        let mut ambiguous = false;
+
+        for i in buffer.iter() {
+            let t = mesh.triangle_at(*i);
+
+            if let Some((_, bcoords)) = ray::triangle_ray_intersection(t.a(), t.b(), t.c(), &ray) {
+                crossings = crossings + 1;
+                ambiguous = ambiguous || bcoords.x < eps || bcoords.y < eps || bcoords.z < eps;
+            }
+        }
+
+        if !ambiguous {
+            return Some(crossings % 2 == 1);
+        }
+    }
+
+    None
+}
+
 impl<N, P, V> LocalPointQuery<N, P> for Polyline<N, P, V>
     where N: Scalar,
           P: Point<N, V>,
@@ -155,3 +406,223 @@ impl<N, P, V, M> PointQuery<N, P, M> for Polyline<N, P, V>
           V: Vect<N>,
           M: Transform<P> {
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::Pnt3;
+    use na;
+    use entities::shape::{TriMesh, TriMesh3};
+    use entities::partitioning::median_partitioner;
+    use point::{self, LocalPointQuery, TriMeshFeature};
+
+    fn cube_vertices_and_indices() -> (Vec<Pnt3<f64>>, Vec<Pnt3<usize>>) {
+        let mut vertices = Vec::new();
+
+        for i in 0usize .. 8 {
+            vertices.push(Pnt3::new((i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64))
+        }
+
+        let indices = vec!(Pnt3::new(0usize, 2, 1), Pnt3::new(1, 2, 3), Pnt3::new(4, 5, 6), Pnt3::new(5, 7, 6),
+                           Pnt3::new(0, 1, 4), Pnt3::new(1, 5, 4), Pnt3::new(2, 6, 3), Pnt3::new(3, 6, 7),
+                           Pnt3::new(0, 4, 2), Pnt3::new(2, 4, 6), Pnt3::new(1, 3, 5), Pnt3::new(3, 7, 5));
+
+        (vertices, indices)
+    }
+
+    // The unit cube, with its triangles oriented counterclockwise from the outside.
+    fn cube() -> TriMesh3<f64> {
+        let (vertices, indices) = cube_vertices_and_indices();
+
+        TriMesh::new_closed(Arc::new(vertices), Arc::new(indices), None, None)
+    }
+
+    // The unit cube with an additional stray triangle far away, so it is not watertight.
+    fn leaky_cube() -> TriMesh3<f64> {
+        let (mut vertices, mut indices) = cube_vertices_and_indices();
+
+        vertices.push(Pnt3::new(10.0, 10.0, -10.0));
+        vertices.push(Pnt3::new(11.0, 10.0, -10.0));
+        vertices.push(Pnt3::new(10.0, 11.0, -10.0));
+        indices.push(Pnt3::new(8, 9, 10));
+
+        TriMesh::new_closed_lenient(Arc::new(vertices), Arc::new(indices), None, None, &mut median_partitioner)
+    }
+
+    fn in_unit_cube(pt: &Pnt3<f64>) -> bool {
+        pt.x >= 0.0 && pt.x <= 1.0 && pt.y >= 0.0 && pt.y <= 1.0 && pt.z >= 0.0 && pt.z <= 1.0
+    }
+
+    // The L-shaped polygon (0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2) extruded along `z` from 0
+    // to 1. Its edge at `x = y = 1` is concave.
+    fn l_prism() -> TriMesh3<f64> {
+        let polygon  = [ (0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0) ];
+        let n        = polygon.len();
+        let mut vertices = Vec::new();
+        let mut indices  = Vec::new();
+
+        for z in [ 0.0f64, 1.0 ].iter() {
+            for &(x, y) in polygon.iter() {
+                vertices.push(Pnt3::new(x, y, *z))
+            }
+        }
+
+        for i in 0 .. n {
+            let j = (i + 1) % n;
+
+            indices.push(Pnt3::new(i, j, j + n));
+            indices.push(Pnt3::new(i, j + n, i + n));
+        }
+
+        // Fan around the concave vertex.
+        for &(a, b) in [ (4usize, 5usize), (5, 0), (0, 1), (1, 2) ].iter() {
+            indices.push(Pnt3::new(3 + n, a + n, b + n));
+            indices.push(Pnt3::new(3, b, a));
+        }
+
+        TriMesh::new_closed(Arc::new(vertices), Arc::new(indices), None, None)
+    }
+
+    fn in_l_prism(pt: &Pnt3<f64>) -> bool {
+        pt.x >= 0.0 && pt.x <= 2.0 && pt.y >= 0.0 && pt.y <= 2.0 && pt.z >= 0.0 && pt.z <= 1.0 &&
+        (pt.x <= 1.0 || pt.y <= 1.0)
+    }
+
+    #[test]
+    fn test_closed_trimesh_contains_point() {
+        let mesh = cube();
+
+        for i in 0usize .. 7 {
+            for j in 0usize .. 7 {
+                for k in 0usize .. 7 {
+                    let pt = Pnt3::new(i as f64 * 0.25 - 0.25, j as f64 * 0.25 - 0.25, k as f64 * 0.25 - 0.25);
+
+                    assert!(mesh.contains_point(&pt) == in_unit_cube(&pt));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_leaky_trimesh_contains_point() {
+        let mesh = leaky_cube();
+
+        assert!(!mesh.is_watertight());
+
+        for i in 0usize .. 7 {
+            for j in 0usize .. 7 {
+                for k in 0usize .. 7 {
+                    let pt = Pnt3::new(i as f64 * 0.25 - 0.25, j as f64 * 0.25 - 0.25, k as f64 * 0.25 - 0.25);
+
+                    assert!(mesh.contains_point(&pt) == in_unit_cube(&pt));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_leaky_trimesh_contains_point_ray_through_edge() {
+        let mesh = leaky_cube();
+
+        // The first ray cast from this point crosses the diagonal edge of the top face.
+        assert!(mesh.contains_point(&Pnt3::new(0.2, 0.275, 0.5)));
+        // The first ray cast from those points crosses the vertex (1, 1, 1), resp. (0, 0, 0).
+        assert!(mesh.contains_point(&Pnt3::new(0.64, 0.52, 0.2)));
+        assert!(!mesh.contains_point(&Pnt3::new(-0.36, -0.48, -0.8)));
+    }
+
+    #[test]
+    fn test_closed_trimesh_solid_projection() {
+        let mesh = cube();
+        let pt   = Pnt3::new(0.5, 0.5, 0.25);
+
+        assert!(mesh.project_point(&pt, true) == pt);
+        assert!(na::approx_eq(&mesh.project_point(&pt, false), &Pnt3::new(0.5, 0.5, 0.0)));
+    }
+
+    #[test]
+    fn test_signed_distance_agrees_with_contains_point() {
+        let mesh = cube();
+
+        for i in 0usize .. 9 {
+            for j in 0usize .. 9 {
+                for k in 0usize .. 9 {
+                    let pt        = Pnt3::new(i as f64 * 0.2 - 0.3, j as f64 * 0.2 - 0.3, k as f64 * 0.2 - 0.3);
+                    let (dist, _) = point::trimesh_signed_distance(&mesh, &pt).unwrap();
+
+                    assert!((dist < 0.0) == mesh.contains_point(&pt));
+                    assert!(na::approx_eq(&dist.abs(), &na::dist(&mesh.project_point(&pt, false), &pt)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_projection_feature() {
+        let mesh = cube();
+
+        let (proj, dist, feature) = point::trimesh_signed_projection(&mesh, &Pnt3::new(2.0, 2.0, 2.0)).unwrap();
+        assert!(na::approx_eq(&proj, &Pnt3::new(1.0, 1.0, 1.0)));
+        assert!(na::approx_eq(&dist, &3.0f64.sqrt()));
+        assert!(feature == TriMeshFeature::Vertex(7));
+
+        let (dist, feature) = point::trimesh_signed_distance(&mesh, &Pnt3::new(0.3, 0.4, 0.75)).unwrap();
+        assert!(na::approx_eq(&dist, &-0.25));
+        assert!(feature == TriMeshFeature::Face(2));
+    }
+
+    #[test]
+    fn test_signed_distance_near_concave_features() {
+        let mesh = l_prism();
+        let eps  = 1.0e-3;
+
+        let mut pts = Vec::new();
+
+        // Around the concave edge, and its top vertex.
+        for dx in [ -eps, 0.0, eps ].iter() {
+            for dy in [ -eps, 0.0, eps ].iter() {
+                for z in [ 0.5, 1.0 - eps, 1.0 + eps ].iter() {
+                    pts.push(Pnt3::new(1.0 + *dx, 1.0 + *dy, *z))
+                }
+            }
+        }
+
+        // The first ray a parity test would cast from the first point goes through the concave
+        // vertex (1, 1, 1). The other ones are close to the faces adjacent to the concave edge.
+        pts.push(Pnt3::new(0.82, 0.76, 0.6));
+        pts.push(Pnt3::new(1.0 - eps, 1.5, 0.5));
+        pts.push(Pnt3::new(1.5, 1.0 - eps, 0.5));
+
+        for pt in pts.iter() {
+            let (dist, _) = point::trimesh_signed_distance(&mesh, pt).unwrap();
+            let on_mesh   = na::approx_eq(&dist, &0.0);
+
+            assert!(on_mesh || (dist < 0.0) == in_l_prism(pt));
+            assert!(on_mesh || mesh.contains_point(pt) == in_l_prism(pt));
+        }
+
+        // The projection of this point lies on the concave edge, which pseudo-normal points
+        // toward the notch.
+        let (dist, feature) = point::trimesh_signed_distance(&mesh, &Pnt3::new(1.0 - eps, 1.0 - eps, 0.5)).unwrap();
+        assert!(na::approx_eq(&dist, &-(2.0f64.sqrt() * eps)));
+        assert!(feature == TriMeshFeature::Edge(3, 9));
+    }
+
+    #[test]
+    fn test_signed_distance_to_leaky_mesh() {
+        let mesh = leaky_cube();
+
+        let (dist, _) = point::trimesh_signed_distance(&mesh, &Pnt3::new(0.5, 0.5, 0.25)).unwrap();
+        assert!(na::approx_eq(&dist, &-0.25));
+
+        let (dist, _) = point::trimesh_signed_distance(&mesh, &Pnt3::new(0.5, 0.5, 1.25)).unwrap();
+        assert!(na::approx_eq(&dist, &0.25));
+    }
+
+    #[test]
+    fn test_signed_distance_to_empty_mesh() {
+        let mesh: TriMesh3<f64> = TriMesh::new_closed(Arc::new(Vec::new()), Arc::new(Vec::new()), None, None);
+
+        assert!(point::trimesh_signed_distance(&mesh, &Pnt3::new(0.0, 0.0, 0.0)).is_none());
+    }
+}
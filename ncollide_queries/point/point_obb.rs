@@ -0,0 +1,37 @@
+use na::{Transform, Col, One};
+use point::{LocalPointQuery, PointQuery};
+use entities::bounding_volume::OBB;
+use math::{Scalar, Point, Vect};
+
+#[old_impl_check]
+impl<N, P, V, R> LocalPointQuery<N, P> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy {
+    #[inline]
+    fn project_point(&self, pt: &P, solid: bool) -> P {
+        let ls_pt = self.to_local(pt);
+
+        self.from_local(&self.local_aabb().project_point(&ls_pt, solid))
+    }
+
+    #[inline]
+    fn distance_to_point(&self, pt: &P) -> N {
+        self.local_aabb().distance_to_point(&self.to_local(pt))
+    }
+
+    #[inline]
+    fn contains_point(&self, pt: &P) -> bool {
+        self.local_aabb().contains_point(&self.to_local(pt))
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R, M> PointQuery<N, P, M> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy,
+          M: Transform<P> {
+}
@@ -1,7 +1,7 @@
 use na::Translate;
 use math::{Scalar, Point, Vect, Isometry};
-use entities::shape::{Ball, Capsule, Compound, Cone, Convex, Cuboid, Cylinder, TriMesh, Polyline, Plane,
-                      Segment, Triangle};
+use entities::shape::{Ball, Capsule, Compound, Cone, Convex, ConvexPolyhedron, Cuboid, Cylinder,
+                      HeightField, DistanceField, TriMesh, Polyline, Plane, Segment, Triangle};
 use entities::inspection::Repr;
 use point::{LocalPointQuery, PointQuery};
 
@@ -25,12 +25,21 @@ macro_rules! dispatch(
             else if let Some(c) = repr.downcast_ref::<Convex<P>>() {
                 c.$name($($argN,)*)
             }
+            else if let Some(c) = repr.downcast_ref::<ConvexPolyhedron<P, V>>() {
+                c.$name($($argN,)*)
+            }
             else if let Some(c) = repr.downcast_ref::<Cuboid<V>>() {
                 c.$name($($argN,)*)
             }
             else if let Some(c) = repr.downcast_ref::<Cylinder<N>>() {
                 c.$name($($argN,)*)
             }
+            else if let Some(h) = repr.downcast_ref::<HeightField<N, V>>() {
+                h.$name($($argN,)*)
+            }
+            else if let Some(d) = repr.downcast_ref::<DistanceField<N, P, V>>() {
+                d.$name($($argN,)*)
+            }
             else if let Some(t) = repr.downcast_ref::<TriMesh<N, P, V>>() {
                 t.$name($($argN,)*)
             }
@@ -4,7 +4,7 @@ use geometry::algorithms::gjk;
 use geometry::algorithms::minkowski_sampling;
 use geometry::algorithms::simplex::Simplex;
 use geometry::algorithms::johnson_simplex::JohnsonSimplex;
-use entities::shape::{MinkowskiSum, Cylinder, Cone, Capsule, Convex};
+use entities::shape::{MinkowskiSum, Cylinder, Cone, Capsule, Convex, ConvexPolyhedron};
 use entities::support_map::{SupportMap, PreferedSamplingDirections};
 use point::{LocalPointQuery, PointQuery};
 use math::{Scalar, Point, Vect};
@@ -160,6 +160,39 @@ impl<N, P, V, M> PointQuery<N, P, M> for Convex<P>
           M: Transform<P> + Rotate<V> {
 }
 
+#[old_impl_check]
+impl<N, P, V> LocalPointQuery<N, P> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn project_point(&self, point: &P, solid: bool) -> P {
+        support_map_point_projection(&Identity::new(), self, &mut JohnsonSimplex::<N, P, V>::new_w_tls(), point, solid)
+    }
+
+    #[inline]
+    fn distance_to_point(&self, pt: &P) -> N {
+        na::dist(pt, &self.project_point(pt, true))
+    }
+
+    #[inline]
+    fn contains_point(&self, pt: &P) -> bool {
+        let vs = self.vertices();
+
+        self.faces().iter().zip(self.normals().iter()).all(|(f, n)| {
+            na::dot(&(*pt - vs[f[0]]), n) <= na::zero()
+        })
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, M> PointQuery<N, P, M> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+}
+
 #[old_impl_check]
 impl<'a, N, P, V, M, G1, G2> LocalPointQuery<N, P> for MinkowskiSum<'a, M, G1, G2>
     where N:  Scalar,
@@ -1,4 +1,4 @@
-use na::Transform;
+use na::{Transform, Bounded};
 use na;
 use entities::shape::Triangle;
 use point::{LocalPointQuery, PointQuery};
@@ -12,118 +12,15 @@ impl<N, P, V> LocalPointQuery<N, P> for Triangle<P>
           V: Vect<N> {
     #[inline]
     fn project_point(&self, pt: &P, solid: bool) -> P {
-        /*
-         * This comes from the book `Real Time Collision Detection`.
-         * This is a trivial Voronoï region based approach, except that great care has been taken
-         * to avoid cross products (which is good for the genericity here).
-         *
-         * We keep the original (somehow, obscure) notations for future reference.
-         */
-        let a = self.a().clone();
-        let b = self.b().clone();
-        let c = self.c().clone();
-        let p = pt.clone();
-
-        let ab = b - a;
-        let ac = c - a;
-        let ap = p - a;
-
-        let d1 = na::dot(&ab, &ap);
-        let d2 = na::dot(&ac, &ap);
-
-        if d1 <= na::zero() && d2 <= na::zero() {
-            // Voronoï region of `a`.
-            return a;
-        }
-
-        let bp = p - b;
-        let d3 = na::dot(&ab, &bp);
-        let d4 = na::dot(&ac, &bp);
-
-        if d3 >= na::zero() && d4 <= d3 {
-            // Voronoï region of `b`.
-            return b;
-        }
-
-        let vc = d1 * d4 - d3 * d2;
-        if vc <= na::zero() && d1 >= na::zero() && d3 <= na::zero() {
-            // Voronoï region of `ab`.
-            let v = d1 / (d1 - d3);
-            return a + ab * v;
-        }
-
-        let cp = p - c;
-        let d5 = na::dot(&ab, &cp);
-        let d6 = na::dot(&ac, &cp);
-
-        if d6 >= na::zero() && d5 <= d6 {
-            // Voronoï region of `c`.
-            return c;
-        }
-
-        let vb = d5 * d2 - d1 * d6;
-
-        if vb <= na::zero() && d2 >= na::zero() && d6 <= na::zero() {
-            // Voronoï region of `ac`.
-            let w = d2 / (d2 - d6);
-            return a + ac * w;
-        }
+        let (proj, feature) = triangle_project_point_with_feature(self, pt);
 
-        let va = d3 * d6 - d5 * d4;
-        if va <= na::zero() && d4 - d3 >= na::zero() && d5 - d6 >= na::zero() {
-            // Voronoï region of `bc`.
-            let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
-            return b + (c - b) * w;
-        }
-
-        // Voronoï region of the face.
-        if na::dim::<P>() != 2 {
-            let denom = na::one::<N>() / (va + vb + vc);
-            let v = vb * denom;
-            let w = vc * denom;
-
-            a + ab * v + ac * w
+        if feature == TriangleFeature::Face && na::dim::<P>() == 2 && !solid {
+            // Special treatement if we work in 2d because in this case we really are inside of the
+            // object: we have to project on the closest edge.
+            project_point_on_closest_edge(self, pt)
         }
         else {
-            // Special treatement if we work in 2d because in this case we really are inside of the
-            // object.
-            if solid {
-                p
-            }
-            else {
-                // We have to project on the closest edge.
-
-                // FIXME: this might be optimizable.
-                let v = d1 / (d1 - d3);                      // proj on ab = a + ab * v
-                let w = d2 / (d2 - d6);                      // proj on ac = a + ac * w
-                let u = (d4 - d3) / ((d4 - d3) + (d5 - d6)); // proj on bc = b + bc * u
-
-                let bc = c - b;
-                let d_ab = na::sqnorm(&ap) - (na::sqnorm(&ab) * v * v);
-                let d_ac = na::sqnorm(&ap) - (na::sqnorm(&ac) * u * u);
-                let d_bc = na::sqnorm(&bp) - (na::sqnorm(&bc) * w * w);
-
-                if d_ab < d_ac {
-                    if d_ab < d_bc {
-                        // ab
-                        a + ab * v
-                    }
-                    else {
-                        // bc
-                        b + bc * u
-                    }
-                }
-                else {
-                    if d_ac < d_bc {
-                        // ac
-                        a + ac * w
-                    }
-                    else {
-                        // bc
-                        b + bc * u
-                    }
-                }
-            }
+            proj
         }
     }
 
@@ -145,3 +42,160 @@ impl<N, P, V, M> PointQuery<N, P, M> for Triangle<P>
           V: Vect<N>,
           M: Transform<P> {
 }
+
+/// The feature of a triangle a point projection lies on.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TriangleFeature {
+    /// The i-th vertex of the triangle.
+    Vertex(usize),
+    /// The i-th edge of the triangle, i.e., `ab`, `bc`, or `ca`.
+    Edge(usize),
+    /// The interior of the triangle.
+    Face
+}
+
+/// Projects a point on a triangle and returns the feature containing the projection.
+///
+/// In 2d, a point inside of the triangle is its own projection, as with a solid `project_point`.
+pub fn triangle_project_point_with_feature<N, P, V>(t: &Triangle<P>, pt: &P) -> (P, TriangleFeature)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    /*
+     * This comes from the book `Real Time Collision Detection`.
+     * This is a trivial Voronoï region based approach, except that great care has been taken
+     * to avoid cross products (which is good for the genericity here).
+     *
+     * We keep the original (somehow, obscure) notations for future reference.
+     */
+    let a = t.a().clone();
+    let b = t.b().clone();
+    let c = t.c().clone();
+    let p = pt.clone();
+
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+
+    let d1 = na::dot(&ab, &ap);
+    let d2 = na::dot(&ac, &ap);
+
+    if d1 <= na::zero() && d2 <= na::zero() {
+        // Voronoï region of `a`.
+        return (a, TriangleFeature::Vertex(0));
+    }
+
+    let bp = p - b;
+    let d3 = na::dot(&ab, &bp);
+    let d4 = na::dot(&ac, &bp);
+
+    if d3 >= na::zero() && d4 <= d3 {
+        // Voronoï region of `b`.
+        return (b, TriangleFeature::Vertex(1));
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= na::zero() && d1 >= na::zero() && d3 <= na::zero() {
+        // Voronoï region of `ab`.
+        let v = d1 / (d1 - d3);
+        return (a + ab * v, TriangleFeature::Edge(0));
+    }
+
+    let cp = p - c;
+    let d5 = na::dot(&ab, &cp);
+    let d6 = na::dot(&ac, &cp);
+
+    if d6 >= na::zero() && d5 <= d6 {
+        // Voronoï region of `c`.
+        return (c, TriangleFeature::Vertex(2));
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+
+    if vb <= na::zero() && d2 >= na::zero() && d6 <= na::zero() {
+        // Voronoï region of `ac`.
+        let w = d2 / (d2 - d6);
+        return (a + ac * w, TriangleFeature::Edge(2));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= na::zero() && d4 - d3 >= na::zero() && d5 - d6 >= na::zero() {
+        // Voronoï region of `bc`.
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, TriangleFeature::Edge(1));
+    }
+
+    // Voronoï region of the face.
+    let denom = na::one::<N>() / (va + vb + vc);
+    let v     = vb * denom;
+    let w     = vc * denom;
+
+    (a + ab * v + ac * w, TriangleFeature::Face)
+}
+
+/// Projects a point on the closest edge of a triangle.
+fn project_point_on_closest_edge<N, P, V>(t: &Triangle<P>, pt: &P) -> P
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let vs       = [ *t.a(), *t.b(), *t.c() ];
+    let mut best = vs[0];
+    let mut dist = Bounded::max_value();
+
+    for i in 0 .. 3 {
+        let a  = vs[i];
+        let ab = vs[(i + 1) % 3] - a;
+        let l  = na::sqnorm(&ab);
+
+        let proj = if na::is_zero(&l) {
+            a
+        }
+        else {
+            a + ab * na::clamp(na::dot(&ab, &(*pt - a)) / l, na::zero(), na::one())
+        };
+
+        let d = na::sqdist(pt, &proj);
+
+        if d < dist {
+            dist = d;
+            best = proj;
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Pnt2, Pnt3};
+    use na;
+    use entities::shape::Triangle;
+    use point::LocalPointQuery;
+    use super::{triangle_project_point_with_feature, TriangleFeature};
+
+    #[test]
+    fn test_project_point_matches_feature_projection() {
+        let t   = Triangle::new(Pnt3::new(0.0f64, 0.0, 0.0), Pnt3::new(2.0, 0.0, 0.0), Pnt3::new(0.0, 2.0, 0.0));
+        let pts = [ Pnt3::new(-1.0, -1.0, 1.0), Pnt3::new(3.0, -1.0, 0.0), Pnt3::new(1.0, -1.0, 2.0),
+                    Pnt3::new(2.0, 2.0, -1.0), Pnt3::new(0.5, 0.5, 3.0) ];
+        let fts = [ TriangleFeature::Vertex(0), TriangleFeature::Vertex(1), TriangleFeature::Edge(0),
+                    TriangleFeature::Edge(1), TriangleFeature::Face ];
+
+        for (pt, ft) in pts.iter().zip(fts.iter()) {
+            let (proj, feature) = triangle_project_point_with_feature(&t, pt);
+
+            assert!(feature == *ft);
+            assert!(na::approx_eq(&proj, &t.project_point(pt, true)));
+            assert!(na::approx_eq(&proj, &t.project_point(pt, false)));
+        }
+    }
+
+    #[test]
+    fn test_project_point_inside_2d_triangle() {
+        let t  = Triangle::new(Pnt2::new(0.0f64, 0.0), Pnt2::new(4.0, 0.0), Pnt2::new(0.0, 4.0));
+        let pt = Pnt2::new(1.0, 0.5);
+
+        assert!(t.project_point(&pt, true) == pt);
+        assert!(na::approx_eq(&t.project_point(&pt, false), &Pnt2::new(1.0, 0.0)));
+    }
+}
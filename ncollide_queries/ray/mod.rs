@@ -5,7 +5,7 @@ pub use ray::ray_plane::plane_toi_with_ray;
 pub use ray::ray_triangle::triangle_ray_intersection;
 pub use ray::ray_support_map::implicit_toi_and_normal_with_ray;
 pub use ray::ray_ball::ball_toi_with_ray;
-pub use ray::ray_bvt::{RayIntersectionCostFn, RayInterferencesCollector};
+pub use ray::ray_bvt::{RayIntersectionCostFn, RayToiCostFn, RayInterferencesCollector};
 
 use na::{Pnt2, Vec2, Pnt3, Vec3};
 
@@ -16,10 +16,13 @@ mod ray_ball;
 mod ray_cuboid;
 mod ray_aabb;
 mod ray_bounding_sphere;
+mod ray_obb;
+mod ray_kdop;
 mod ray_support_map;
 mod ray_triangle;
 mod ray_compound;
 mod ray_mesh;
+mod ray_heightfield;
 mod ray_repr;
 mod ray_bvt;
 
@@ -40,6 +40,39 @@ impl<'a, N, P, V, B, BV> BVTCostFn<N, B, BV, RayIntersection<N, V>> for RayInter
     }
 }
 
+/// A search that selects the objects that have the smallest time of impact with a given ray.
+///
+/// Unlike `RayIntersectionCostFn`, this does not compute the normal at the impact points.
+pub struct RayToiCostFn<'a, P: 'a, V: 'a> {
+    ray:   &'a Ray<P, V>,
+    solid: bool
+}
+
+impl<'a, P, V> RayToiCostFn<'a, P, V> {
+    /// Creates a new `RayToiCostFn`.
+    pub fn new(ray: &'a Ray<P, V>, solid: bool) -> RayToiCostFn<'a, P, V> {
+        RayToiCostFn {
+            ray:   ray,
+            solid: solid
+        }
+    }
+}
+
+impl<'a, N, P, V, B, BV> BVTCostFn<N, B, BV, N> for RayToiCostFn<'a, P, V>
+    where N:  Scalar,
+          B:  LocalRayCast<N, P, V>,
+          BV: LocalRayCast<N, P, V> {
+    #[inline]
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        bv.toi_with_ray(self.ray, true)
+    }
+
+    #[inline]
+    fn compute_b_cost(&mut self, b: &B) -> Option<(N, N)> {
+        b.toi_with_ray(self.ray, self.solid).map(|toi| (toi, toi))
+    }
+}
+
 /// Bounding Volume Tree visitor collecting interferences with a given ray.
 pub struct RayInterferencesCollector<'a, P: 'a, V: 'a, B: 'a> {
     ray:       &'a Ray<P, V>,
@@ -1,5 +1,4 @@
 use na::Translate;
-use entities::bounding_volume::AABB;
 use entities::shape::Compound;
 use entities::partitioning::BVTCostFn;
 use ray::{Ray, LocalRayCast, RayCast, RayIntersection};
@@ -15,13 +14,19 @@ impl<N, P, V, M> LocalRayCast<N, P, V> for Compound<N, P, V, M>
     fn toi_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<N> {
         let mut cost_fn = CompoundRayToiCostFn { compound: self, ray: ray, solid: solid };
 
-        self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn).map(|(_, res)| res),
+            None      => self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        }
     }
 
     fn toi_and_normal_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<RayIntersection<N, V>> {
         let mut cost_fn = CompoundRayToiAndNormalCostFn { compound: self, ray: ray, solid: solid };
 
-        self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn).map(|(_, res)| res),
+            None      => self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        }
     }
 
     // XXX: We have to implement toi_and_normal_and_uv_with_ray! Otherwise, no uv will be computed
@@ -44,14 +49,15 @@ struct CompoundRayToiCostFn<'a, N: 'a, P: 'a, V: 'a, M: 'a> {
     solid:    bool
 }
 
-impl<'a, N, P, V, M> BVTCostFn<N, usize, AABB<P>, N> for CompoundRayToiCostFn<'a, N, P, V, M>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N> + Translate<P>,
-          M: Isometry<N, P, V> {
+impl<'a, N, P, V, M, BV> BVTCostFn<N, usize, BV, N> for CompoundRayToiCostFn<'a, N, P, V, M>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N> + Translate<P>,
+          M:  Isometry<N, P, V>,
+          BV: LocalRayCast<N, P, V> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        aabb.toi_with_ray(self.ray, self.solid)
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        bv.toi_with_ray(self.ray, self.solid)
     }
 
     #[inline]
@@ -67,15 +73,16 @@ struct CompoundRayToiAndNormalCostFn<'a, N: 'a, P: 'a, V: 'a, M: 'a> {
     solid:    bool
 }
 
-impl<'a, N, P, V, M> BVTCostFn<N, usize, AABB<P>, RayIntersection<N, V>>
+impl<'a, N, P, V, M, BV> BVTCostFn<N, usize, BV, RayIntersection<N, V>>
 for CompoundRayToiAndNormalCostFn<'a, N, P, V, M>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N> + Translate<P>,
-          M: Isometry<N, P, V> {
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N> + Translate<P>,
+          M:  Isometry<N, P, V>,
+          BV: LocalRayCast<N, P, V> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        aabb.toi_with_ray(self.ray, self.solid)
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        bv.toi_with_ray(self.ray, self.solid)
     }
 
     #[inline]
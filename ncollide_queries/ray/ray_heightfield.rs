@@ -0,0 +1,110 @@
+use std::num::Float;
+use na::{Transform, Rotate};
+use na;
+use ray::{Ray, LocalRayCast, RayCast, RayIntersection};
+use entities::shape::HeightField;
+use entities::bounding_volume::AABB;
+use math::{Scalar, Point, Vect};
+
+
+impl<N, P, V> LocalRayCast<N, P, V> for HeightField<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    // The cells crossed by the horizontal projection of the ray are traversed in order using a
+    // digital differential analyzer. The first cell with a part hit by the ray gives the result.
+    fn toi_and_normal_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<RayIntersection<N, V>> {
+        let aabb: AABB<P> = self.local_aabb();
+
+        let toi = match aabb.toi_with_ray(ray, true) {
+            Some(toi) => toi,
+            None      => return None
+        };
+
+        let start          = ray.orig + ray.dir * toi;
+        let (mut i, mut j) = self.clamped_cell_at_point(&start);
+        let is_3d          = na::dim::<P>() == 3;
+
+        let (step_j, mut tmax_j, tdelta_j) = dda_params(ray.orig[0], ray.dir[0], self.cell_width(),
+                                                        self.column_coordinate(j),
+                                                        self.column_coordinate(j + 1));
+        let (step_i, mut tmax_i, tdelta_i) = if is_3d {
+            dda_params(ray.orig[2], ray.dir[2], self.cell_depth(),
+                       self.row_coordinate(i), self.row_coordinate(i + 1))
+        }
+        else {
+            (0, Float::infinity(), Float::infinity())
+        };
+
+        loop {
+            let mut best: Option<RayIntersection<N, V>> = None;
+
+            for part in self.cell_parts(i, j).into_iter() {
+                let inter = if is_3d {
+                    self.triangle_at::<P>(part).toi_and_normal_with_ray(ray, solid)
+                }
+                else {
+                    self.segment_at::<P>(part).toi_and_normal_with_ray(ray, solid)
+                };
+
+                if let Some(inter) = inter {
+                    let is_better = match best {
+                        Some(ref b) => inter.toi < b.toi,
+                        None        => true
+                    };
+
+                    if is_better {
+                        best = Some(inter)
+                    }
+                }
+            }
+
+            if best.is_some() {
+                return best;
+            }
+
+            // Move to the next cell.
+            if step_i == 0 && step_j == 0 {
+                return None;
+            }
+
+            if tmax_j < tmax_i {
+                if (step_j < 0 && j == 0) || (step_j > 0 && j + 1 == self.ncell_cols()) {
+                    return None;
+                }
+
+                j      = (j as isize + step_j) as usize;
+                tmax_j = tmax_j + tdelta_j;
+            }
+            else {
+                if (step_i < 0 && i == 0) || (step_i > 0 && i + 1 == self.ncell_rows()) {
+                    return None;
+                }
+
+                i      = (i as isize + step_i) as usize;
+                tmax_i = tmax_i + tdelta_i;
+            }
+        }
+    }
+}
+
+impl<N, P, V, M> RayCast<N, P, V, M> for HeightField<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+}
+
+/// The stepping direction, the time of impact with the first cell boundary, and the time needed to
+/// cross one cell, along one axis of the grid.
+fn dda_params<N: Scalar>(orig: N, dir: N, cell_size: N, cell_min: N, cell_max: N) -> (isize, N, N) {
+    if dir > na::zero() {
+        (1, (cell_max - orig) / dir, cell_size / dir)
+    }
+    else if dir < na::zero() {
+        (-1, (cell_min - orig) / dir, -cell_size / dir)
+    }
+    else {
+        (0, Float::infinity(), Float::infinity())
+    }
+}
@@ -0,0 +1,87 @@
+use std::mem;
+use na::{Transform, Rotate, Bounded};
+use na;
+use ray::{Ray, LocalRayCast, RayCast, RayIntersection};
+use entities::bounding_volume::KDOP;
+use math::{Scalar, Point, Vect};
+
+
+#[old_impl_check]
+impl<N, P, V> LocalRayCast<N, P, V> for KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    #[inline]
+    fn toi_and_normal_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<RayIntersection<N, V>> {
+        ray_kdop(self, ray, solid).map(|(t, n)| RayIntersection::new(t, n))
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, M> RayCast<N, P, V, M> for KDOP<N, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+}
+
+fn ray_kdop<N, P, V>(kdop: &KDOP<N, V>, ray: &Ray<P, V>, solid: bool) -> Option<(N, V)>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut tmax: N     = Bounded::max_value();
+    let mut tmin: N     = -tmax;
+    let mut near_normal = na::zero::<V>();
+    let mut far_normal  = na::zero::<V>();
+
+    for (i, axis) in kdop.axes().iter().enumerate() {
+        let orig = na::dot(ray.orig.as_vec(), axis);
+        let dir  = na::dot(&ray.dir, axis);
+
+        if na::is_zero(&dir) {
+            if orig < kdop.mins()[i] || orig > kdop.maxs()[i] {
+                return None
+            }
+        }
+        else {
+            let _1: N = na::one();
+            let denom = _1 / dir;
+            let mut inter_with_near_plane = (kdop.mins()[i] - orig) * denom;
+            let mut inter_with_far_plane  = (kdop.maxs()[i] - orig) * denom;
+
+            // The normal of both planes is opposite to the ray direction.
+            let normal = if dir > na::zero() { -*axis } else { *axis };
+
+            if inter_with_near_plane > inter_with_far_plane {
+                mem::swap(&mut inter_with_near_plane, &mut inter_with_far_plane)
+            }
+
+            if inter_with_near_plane > tmin {
+                tmin        = inter_with_near_plane;
+                near_normal = normal;
+            }
+
+            if inter_with_far_plane < tmax {
+                tmax       = inter_with_far_plane;
+                far_normal = normal;
+            }
+
+            if tmin > tmax || tmax < na::zero() {
+                return None;
+            }
+        }
+    }
+
+    if tmin < na::zero() {
+        // the ray starts inside of the k-DOP
+        if solid {
+            Some((na::zero(), na::zero()))
+        }
+        else {
+            Some((tmax, far_normal))
+        }
+    }
+    else {
+        Some((tmin, near_normal))
+    }
+}
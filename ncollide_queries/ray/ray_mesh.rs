@@ -4,7 +4,6 @@ use na;
 use ray::{Ray, LocalRayCast, RayCast, RayIntersection};
 use ray;
 use entities::shape::{BaseMesh, BaseMeshElement, TriMesh, Polyline};
-use entities::bounding_volume::AABB;
 use entities::partitioning::BVTCostFn;
 use math::{Scalar, Point, Vect};
 
@@ -19,14 +18,20 @@ impl<N, P, V, I, E> LocalRayCast<N, P, V> for BaseMesh<N, P, V, I, E>
     fn toi_with_ray(&self, ray: &Ray<P, V>, _: bool) -> Option<N> {
         let mut cost_fn = BaseMeshRayToiCostFn { mesh: self, ray: ray };
 
-        self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn).map(|(_, res)| res),
+            None      => self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        }
     }
 
     #[inline]
     fn toi_and_normal_with_ray(&self, ray: &Ray<P, V>, _: bool) -> Option<RayIntersection<N, V>> {
         let mut cost_fn = BaseMeshRayToiAndNormalCostFn { mesh: self, ray: ray };
 
-        self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn).map(|(_, res)| res),
+            None      => self.bvt().best_first_search(&mut cost_fn).map(|(_, res)| res)
+        }
     }
 
     fn toi_and_normal_and_uv_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<RayIntersection<N, V>> {
@@ -35,7 +40,10 @@ impl<N, P, V, I, E> LocalRayCast<N, P, V> for BaseMesh<N, P, V, I, E>
         }
 
         let mut cost_fn = BaseMeshRayToiAndNormalAndUVsCostFn { mesh: self, ray: ray };
-        let cast = self.bvt().best_first_search(&mut cost_fn);
+        let cast = match self.kdop_bvt() {
+            Some(bvt) => bvt.best_first_search(&mut cost_fn),
+            None      => self.bvt().best_first_search(&mut cost_fn)
+        };
 
         match cast {
             None                => None,
@@ -102,14 +110,15 @@ struct BaseMeshRayToiCostFn<'a, N: 'a, P: 'a, V: 'a, I: 'a, E: 'a> {
     ray:   &'a Ray<P, V>
 }
 
-impl<'a, N, P, V, I, E> BVTCostFn<N, usize, AABB<P>, N> for BaseMeshRayToiCostFn<'a, N, P, V, I, E>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N>,
-          E: BaseMeshElement<I, P> + LocalRayCast<N, P, V> {
+impl<'a, N, P, V, I, E, BV> BVTCostFn<N, usize, BV, N> for BaseMeshRayToiCostFn<'a, N, P, V, I, E>
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N>,
+          E:  BaseMeshElement<I, P> + LocalRayCast<N, P, V>,
+          BV: LocalRayCast<N, P, V> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        aabb.toi_with_ray(self.ray, true)
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        bv.toi_with_ray(self.ray, true)
     }
 
     #[inline]
@@ -123,15 +132,16 @@ struct BaseMeshRayToiAndNormalCostFn<'a, N: 'a, P: 'a, V: 'a, I: 'a, E: 'a> {
     ray:   &'a Ray<P, V>
 }
 
-impl<'a, N, P, V, I, E> BVTCostFn<N, usize, AABB<P>, RayIntersection<N, V>>
+impl<'a, N, P, V, I, E, BV> BVTCostFn<N, usize, BV, RayIntersection<N, V>>
 for BaseMeshRayToiAndNormalCostFn<'a, N, P, V, I, E>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N>,
-          E: BaseMeshElement<I, P> + LocalRayCast<N, P, V> {
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N>,
+          E:  BaseMeshElement<I, P> + LocalRayCast<N, P, V>,
+          BV: LocalRayCast<N, P, V> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        aabb.toi_with_ray(self.ray, true)
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        bv.toi_with_ray(self.ray, true)
     }
 
     #[inline]
@@ -145,16 +155,17 @@ struct BaseMeshRayToiAndNormalAndUVsCostFn<'a, N: 'a, P: 'a, V: 'a, I: 'a, E: 'a
     ray:   &'a Ray<P, V>
 }
 
-impl<'a, N, P, V, I, E> BVTCostFn<N, usize, AABB<P>, (RayIntersection<N, V>, Vec3<N>)>
+impl<'a, N, P, V, I, E, BV> BVTCostFn<N, usize, BV, (RayIntersection<N, V>, Vec3<N>)>
 for BaseMeshRayToiAndNormalAndUVsCostFn<'a, N, P, V, I, E>
-    where N: Scalar,
-          P: Point<N, V>,
-          V: Vect<N>,
-          I: Index<usize, Output = usize>,
-          E: BaseMeshElement<I, P> + LocalRayCast<N, P, V> {
+    where N:  Scalar,
+          P:  Point<N, V>,
+          V:  Vect<N>,
+          I:  Index<usize, Output = usize>,
+          E:  BaseMeshElement<I, P> + LocalRayCast<N, P, V>,
+          BV: LocalRayCast<N, P, V> {
     #[inline]
-    fn compute_bv_cost(&mut self, aabb: &AABB<P>) -> Option<N> {
-        aabb.toi_with_ray(self.ray, true)
+    fn compute_bv_cost(&mut self, bv: &BV) -> Option<N> {
+        bv.toi_with_ray(self.ray, true)
     }
 
     #[inline]
@@ -226,3 +237,113 @@ impl<N, P, V, M> RayCast<N, P, V, M> for Polyline<N, P, V>
           V: Vect<N>,
           M: Transform<P> + Rotate<V> {
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use na::{Pnt3, Vec3};
+    use na;
+    use entities::shape::{TriMesh, TriMesh3};
+    use entities::bounding_volume::kdop_axes;
+    use point::LocalPointQuery;
+    use ray::{Ray, LocalRayCast};
+
+    fn bumpy_grid(kdop: bool) -> TriMesh3<f64> {
+        let n            = 8;
+        let mut vertices = Vec::new();
+        let mut indices  = Vec::new();
+
+        for i in 0 .. n + 1 {
+            for j in 0 .. n + 1 {
+                let y = ((i * 7 + j * 3) % 5) as f64 * 0.1;
+                vertices.push(Pnt3::new(i as f64, y, j as f64));
+            }
+        }
+
+        for i in 0 .. n {
+            for j in 0 .. n {
+                let a = i * (n + 1) + j;
+                indices.push(Pnt3::new(a, a + 1, a + n + 1));
+                indices.push(Pnt3::new(a + 1, a + n + 2, a + n + 1));
+            }
+        }
+
+        if kdop {
+            TriMesh::new_with_kdop(Arc::new(vertices), Arc::new(indices), None, None, kdop_axes(14))
+        }
+        else {
+            TriMesh::new(Arc::new(vertices), Arc::new(indices), None, None)
+        }
+    }
+
+    #[test]
+    fn test_kdop_mesh_has_kdop_bvt() {
+        assert!(bumpy_grid(true).kdop_bvt().is_some());
+        assert!(bumpy_grid(false).kdop_bvt().is_none());
+    }
+
+    #[test]
+    fn test_kdop_mesh_ray_cast_matches_aabb_mesh() {
+        let aabb_mesh = bumpy_grid(false);
+        let kdop_mesh = bumpy_grid(true);
+
+        for i in 0 .. 20 {
+            let orig = Pnt3::new(0.3 + i as f64 * 0.37, 5.0, 7.9 - i as f64 * 0.35);
+            let dir  = na::normalize(&Vec3::new(0.1, -1.0, 0.05 * i as f64));
+            let ray  = Ray::new(orig, dir);
+
+            let expected = aabb_mesh.toi_and_normal_with_ray(&ray, true);
+            let actual   = kdop_mesh.toi_and_normal_with_ray(&ray, true);
+
+            match (expected, actual) {
+                (Some(e), Some(a)) => {
+                    assert!(na::approx_eq(&e.toi, &a.toi));
+                    assert!(na::approx_eq(&e.normal, &a.normal));
+                },
+                (None, None) => { },
+                _ => panic!("The k-DOP and AABB meshes disagree.")
+            }
+        }
+
+        let miss = Ray::new(Pnt3::new(-1.0, 5.0, -1.0), -Vec3::y());
+        assert!(kdop_mesh.toi_with_ray(&miss, true).is_none());
+    }
+
+    #[test]
+    fn test_kdop_mesh_follows_deformations() {
+        for threshold in [ None, Some(0.0) ].iter() {
+            let mut aabb_mesh = bumpy_grid(false);
+            let mut kdop_mesh = bumpy_grid(true);
+
+            let moved: Vec<Pnt3<f64>> = aabb_mesh.vertices().iter().map(|pt| {
+                Pnt3::new(pt.x, pt.y * 2.0 + 10.0, pt.z)
+            }).collect();
+            let moved = Arc::new(moved);
+
+            let _ = aabb_mesh.set_vertices(moved.clone(), None, *threshold);
+            let _ = kdop_mesh.set_vertices(moved.clone(), None, *threshold);
+
+            for i in 0 .. 20 {
+                let ray = Ray::new(Pnt3::new(0.3 + i as f64 * 0.37, 20.0, 7.9 - i as f64 * 0.35), -Vec3::y());
+
+                let expected = aabb_mesh.toi_with_ray(&ray, true).unwrap();
+                let actual   = kdop_mesh.toi_with_ray(&ray, true).unwrap();
+
+                assert!(expected < 10.0);
+                assert!(na::approx_eq(&expected, &actual));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kdop_mesh_projection_matches_aabb_mesh() {
+        let aabb_mesh = bumpy_grid(false);
+        let kdop_mesh = bumpy_grid(true);
+
+        for i in 0 .. 20 {
+            let pt = Pnt3::new(-1.0 + i as f64 * 0.5, 1.0 - i as f64 * 0.1, 3.3);
+
+            assert!(na::approx_eq(&aabb_mesh.distance_to_point(&pt), &kdop_mesh.distance_to_point(&pt)));
+        }
+    }
+}
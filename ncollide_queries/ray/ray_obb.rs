@@ -0,0 +1,39 @@
+use na::{Transform, Rotate, Col, One};
+use ray::{Ray, LocalRayCast, RayCast, RayIntersection};
+use entities::bounding_volume::OBB;
+use math::{Scalar, Point, Vect};
+
+
+#[old_impl_check]
+impl<N, P, V, R> LocalRayCast<N, P, V> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy {
+    #[inline]
+    fn toi_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<N> {
+        let ls_ray = Ray::new(self.to_local(&ray.orig), self.to_local_vec(&ray.dir));
+
+        self.local_aabb().toi_with_ray(&ls_ray, solid)
+    }
+
+    #[inline]
+    fn toi_and_normal_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<RayIntersection<N, V>> {
+        let ls_ray = Ray::new(self.to_local(&ray.orig), self.to_local_vec(&ray.dir));
+
+        self.local_aabb().toi_and_normal_with_ray(&ls_ray, solid).map(|mut inter| {
+            inter.normal = self.from_local_vec(&inter.normal);
+
+            inter
+        })
+    }
+}
+
+#[old_impl_check]
+impl<N, P, V, R, M> RayCast<N, P, V, M> for OBB<P, V, R>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          R: Col<V> + One + Copy,
+          M: Transform<P> + Rotate<V> {
+}
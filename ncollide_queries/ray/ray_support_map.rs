@@ -3,7 +3,7 @@ use na;
 use geometry::algorithms::gjk;
 use geometry::algorithms::simplex::Simplex;
 use geometry::algorithms::johnson_simplex::JohnsonSimplex;
-use entities::shape::{MinkowskiSum, Segment, Cylinder, Cone, Capsule, Convex};
+use entities::shape::{MinkowskiSum, Segment, Cylinder, Cone, Capsule, Convex, ConvexPolyhedron};
 use entities::support_map::SupportMap;
 use ray::{Ray, LocalRayCast, RayCast, RayIntersection};
 use math::{Scalar, Point, Vect};
@@ -116,6 +116,22 @@ impl<N, P, V, M> RayCast<N, P, V, M> for Convex<P>
           M: Transform<P> + Rotate<V> {
 }
 
+impl<N, P, V> LocalRayCast<N, P, V> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    fn toi_and_normal_with_ray(&self, ray: &Ray<P, V>, solid: bool) -> Option<RayIntersection<N, V>> {
+        implicit_toi_and_normal_with_ray(&Identity::new(), self, &mut JohnsonSimplex::<N, P, V>::new_w_tls(), ray, solid)
+    }
+}
+
+impl<N, P, V, M> RayCast<N, P, V, M> for ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P> + Rotate<V> {
+}
+
 impl<N, P, V> LocalRayCast<N, P, V> for Segment<P>
     where N: Scalar,
           P: Point<N, V>,
@@ -0,0 +1,241 @@
+use std::ops::{Mul, Add};
+use std::num::Float;
+use std::collections::HashMap;
+use na::{Outer, EigenQR, Zero};
+use na;
+use math::{Scalar, Point, Vect};
+use utils;
+use entities::shape::ConvexPolyhedron;
+use convex_hull::{convex_hull3, convex_hull2_idx};
+
+/// Computes the convex polyhedron of a set of 3d points.
+///
+/// The coplanar triangles of the convex hull are merged into polygonal faces.
+pub fn convex_polyhedron3<N, P, V, M>(points: &[P]) -> ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> + Outer<M>,
+          M: EigenQR<N, V> + Mul<P, Output = P> + Add<M, Output = M> + Zero + Copy {
+    let hull      = convex_hull3(points);
+    let coords    = hull.coords;
+    let triangles = hull.indices.unwrap_unified();
+    let _eps: N   = Float::epsilon();
+    let coplanar  = na::one::<N>() - _eps.sqrt();
+
+    let mut center = na::orig::<P>();
+
+    for c in coords.iter() {
+        center = center + *c.as_vec();
+    }
+
+    let center = center / na::cast(coords.len() as f64);
+
+    // Oriented triangles and their outward normals.
+    let mut tris    = Vec::new();
+    let mut normals = Vec::new();
+
+    for t in triangles.iter() {
+        let (a, b, c) = (t.x as usize, t.y as usize, t.z as usize);
+        let n         = utils::cross3(&(coords[b] - coords[a]), &(coords[c] - coords[a]));
+        let sqnorm    = na::sqnorm(&n);
+
+        if sqnorm <= _eps {
+            continue; // Degenerate triangle.
+        }
+
+        let n = n / sqnorm.sqrt();
+
+        if na::dot(&n, &(coords[a] - center)) < na::zero() {
+            tris.push([ a, c, b ]);
+            normals.push(-n);
+        }
+        else {
+            tris.push([ a, b, c ]);
+            normals.push(n);
+        }
+    }
+
+    // Group the adjacent coplanar triangles.
+    let mut edge2tri = HashMap::new();
+
+    for (i, t) in tris.iter().enumerate() {
+        for j in 0 .. 3 {
+            let _ = edge2tri.insert((t[j], t[(j + 1) % 3]), i);
+        }
+    }
+
+    let mut groups: Vec<usize> = (0 .. tris.len()).collect();
+
+    for (i, t) in tris.iter().enumerate() {
+        for j in 0 .. 3 {
+            if let Some(&adj) = edge2tri.get(&(t[(j + 1) % 3], t[j])) {
+                if na::dot(&normals[i], &normals[adj]) >= coplanar {
+                    let gi = find_group(&mut groups[..], i);
+                    let ga = find_group(&mut groups[..], adj);
+
+                    groups[gi] = ga;
+                }
+            }
+        }
+    }
+
+    // Extract the boundary loop of each group.
+    let mut group2face = HashMap::new();
+    let mut boundaries = Vec::new();
+    let mut face_normals: Vec<V> = Vec::new();
+
+    for (i, t) in tris.iter().enumerate() {
+        let g = find_group(&mut groups[..], i);
+
+        let fid = match group2face.get(&g).cloned() {
+            Some(fid) => fid,
+            None => {
+                let fid = boundaries.len();
+                let _   = group2face.insert(g, fid);
+                boundaries.push(HashMap::new());
+                face_normals.push(na::zero());
+                fid
+            }
+        };
+
+        face_normals[fid] = face_normals[fid] + normals[i];
+
+        for j in 0 .. 3 {
+            let (a, b) = (t[j], t[(j + 1) % 3]);
+
+            let is_boundary = match edge2tri.get(&(b, a)) {
+                Some(&adj) => find_group(&mut groups[..], adj) != g,
+                None       => true
+            };
+
+            if is_boundary {
+                let _ = boundaries[fid].insert(a, b);
+            }
+        }
+    }
+
+    let mut faces = Vec::with_capacity(boundaries.len());
+
+    for (fid, boundary) in boundaries.iter().enumerate() {
+        face_normals[fid] = na::normalize(&face_normals[fid]);
+
+        let start    = *boundary.keys().next().unwrap();
+        let mut face = vec![ start ];
+        let mut curr = *boundary.get(&start).unwrap();
+
+        while curr != start && face.len() <= boundary.len() {
+            face.push(curr);
+            curr = *boundary.get(&curr).unwrap();
+        }
+
+        remove_collinear_vertices(&mut face, &coords[..], &face_normals[fid]);
+        faces.push(face);
+    }
+
+    // Keep only the vertices used by the faces.
+    let mut vertices = Vec::new();
+    let mut old2new  = HashMap::new();
+
+    for face in faces.iter_mut() {
+        for id in face.iter_mut() {
+            let new_id = match old2new.get(id).cloned() {
+                Some(new_id) => new_id,
+                None => {
+                    let new_id = vertices.len();
+                    let _      = old2new.insert(*id, new_id);
+                    vertices.push(coords[*id]);
+                    new_id
+                }
+            };
+
+            *id = new_id;
+        }
+    }
+
+    ConvexPolyhedron::new(vertices, faces, face_normals)
+}
+
+/// Computes the convex polygon of a set of 2d points.
+pub fn convex_polyhedron2<N, P, V>(points: &[P]) -> ConvexPolyhedron<P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let idx          = convex_hull2_idx(points);
+    let mut vertices = Vec::with_capacity(idx.len());
+
+    for id in idx.into_iter() {
+        vertices.push(points[id].clone());
+    }
+
+    // Ensure a counterclockwise orientation.
+    let mut area = na::zero::<N>();
+
+    for i in 0 .. vertices.len() {
+        let a = &vertices[i];
+        let b = &vertices[(i + 1) % vertices.len()];
+
+        area = area + (a[0] * b[1] - a[1] * b[0]);
+    }
+
+    if area < na::zero() {
+        vertices.reverse();
+    }
+
+    let mut faces   = Vec::with_capacity(vertices.len());
+    let mut normals = Vec::with_capacity(vertices.len());
+
+    for i in 0 .. vertices.len() {
+        let j = (i + 1) % vertices.len();
+        let d = vertices[j] - vertices[i];
+
+        let mut n = na::zero::<V>();
+        n[0] = d[1];
+        n[1] = -d[0];
+
+        faces.push(vec![ i, j ]);
+        normals.push(na::normalize(&n));
+    }
+
+    ConvexPolyhedron::new(vertices, faces, normals)
+}
+
+fn find_group(groups: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+
+    while groups[root] != root {
+        root = groups[root];
+    }
+
+    // Path compression.
+    let mut curr = i;
+
+    while groups[curr] != root {
+        let next     = groups[curr];
+        groups[curr] = root;
+        curr         = next;
+    }
+
+    root
+}
+
+fn remove_collinear_vertices<N, P, V>(face: &mut Vec<usize>, coords: &[P], normal: &V)
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let _eps: N = Float::epsilon();
+    let mut i   = 0;
+
+    while face.len() > 3 && i < face.len() {
+        let prev = coords[face[(i + face.len() - 1) % face.len()]];
+        let curr = coords[face[i]];
+        let next = coords[face[(i + 1) % face.len()]];
+        let turn = na::dot(&utils::cross3(&(curr - prev), &(next - curr)), normal);
+
+        if turn <= _eps.sqrt() * na::norm(&(curr - prev)) * na::norm(&(next - curr)) {
+            let _ = face.remove(i);
+        }
+        else {
+            i = i + 1;
+        }
+    }
+}
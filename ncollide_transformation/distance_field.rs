@@ -0,0 +1,287 @@
+use std::cmp::Ordering;
+use na::{Transform, Bounded};
+use na;
+use entities::bounding_volume::{BoundingVolume, HasAABB};
+use entities::shape::DistanceField;
+use queries::point::PointQuery;
+use math::{Scalar, Point, Vect};
+
+/// Distance, in samples, between two consecutive samples of the coarse pass.
+const COARSE_STRIDE: usize = 4;
+
+/// Samples the signed distance to a shape on a regular 2D or 3D grid.
+///
+/// The sampled domain is the AABB of the shape transformed by `m`, enlarged by `padding`. Its
+/// largest side is divided into `resolution - 1` cells, and the other sides are divided into
+/// cells of the same size. The shape interior is determined by `contains_point` so only solid
+/// shapes get negative samples.
+///
+/// The exact signed distance is computed only on a coarse subset of the samples and on the
+/// samples near the shape surface. The far field is then computed by fast sweeping, i.e., by
+/// solving the eikonal equation `|grad d| = 1` with Gauss-Seidel sweeps in alternating directions.
+pub fn distance_field<N, P, V, M, G: ?Sized>(shape: &G, m: &M, resolution: usize, padding: N)
+                                              -> DistanceField<N, P, V>
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P>,
+          G: PointQuery<N, P, M> + HasAABB<P, M> {
+    assert!(resolution >= 2, "The distance field resolution must be at least 2.");
+    assert!(na::dim::<P>() == 2 || na::dim::<P>() == 3, "Distance fields must be 2D or 3D.");
+
+    let dim        = na::dim::<P>();
+    let aabb       = shape.aabb(m).loosened(padding);
+    let extents    = *aabb.maxs() - *aabb.mins();
+    let mut extent = na::zero::<N>();
+
+    for i in 0 .. dim {
+        assert!(extents[i].is_finite(), "Cannot sample the distance field of an unbounded shape.");
+        extent = extent.max(extents[i]);
+    }
+
+    assert!(extent > na::zero(), "Cannot sample the distance field of an empty domain.");
+
+    let cell        = extent / na::cast((resolution - 1) as f64);
+    let mut spacing = na::zero::<V>();
+    let mut res     = Vec::with_capacity(dim);
+    let mut total   = 1;
+
+    for i in 0 .. dim {
+        let ncells   = na::cast::<N, f64>((extents[i] / cell).ceil()) as usize;
+        let nsamples = if ncells < 1 { 2 } else { ncells + 1 };
+
+        spacing[i] = cell;
+        res.push(nsamples);
+        total = total * nsamples;
+    }
+
+    let mins       = *aabb.mins();
+    let mut values = vec![ na::zero::<N>(); total ];
+    let mut fixed  = vec![ false; total ];
+    let mut index  = [ 0usize; 3 ];
+
+    // Coarse pass: exact values on the samples which indices are all multiples of the stride.
+    for lin in 0 .. total {
+        multi_index(lin, &res[..], &mut index);
+
+        if index[.. dim].iter().all(|i| *i % COARSE_STRIDE == 0) {
+            values[lin] = signed_distance(shape, m, &sample_point(&mins, cell, &index[.. dim]));
+            fixed[lin]  = true;
+        }
+    }
+
+    // Narrow band: exact values on the samples which may lie near the surface. Since the signed
+    // distance is 1-Lipschitz, the absolute value of a sample is at least the one of its closest
+    // coarse sample minus their distance. Otherwise, the sample has the same sign as this coarse
+    // sample and its value is left to the fast sweeping.
+    let band       = cell + cell;
+    let max: N     = Bounded::max_value();
+    let mut coarse = [ 0usize; 3 ];
+
+    for lin in 0 .. total {
+        if fixed[lin] {
+            continue;
+        }
+
+        multi_index(lin, &res[..], &mut index);
+
+        let mut sqdist = na::zero::<N>();
+
+        for i in 0 .. dim {
+            let down = (index[i] / COARSE_STRIDE) * COARSE_STRIDE;
+            let up   = down + COARSE_STRIDE;
+
+            coarse[i] = if up < res[i] && up - index[i] < index[i] - down { up } else { down };
+
+            let delta = cell * na::cast((index[i] as f64 - coarse[i] as f64).abs());
+            sqdist    = sqdist + delta * delta;
+        }
+
+        let coarse_value = values[linear_index(&coarse[.. dim], &res[..])];
+
+        if coarse_value.abs() - sqdist.sqrt() <= band {
+            values[lin] = signed_distance(shape, m, &sample_point(&mins, cell, &index[.. dim]));
+            fixed[lin]  = true;
+        }
+        else if coarse_value < na::zero() {
+            values[lin] = -max;
+        }
+        else {
+            values[lin] = max;
+        }
+    }
+
+    // Far field: fast sweeping on the absolute values, the signs being already known.
+    for _ in 0 .. 2usize {
+        for sweep in 0 .. 1usize << dim {
+            for lin in 0 .. total {
+                multi_index(lin, &res[..], &mut index);
+
+                for i in 0 .. dim {
+                    if sweep & (1 << i) != 0 {
+                        index[i] = res[i] - 1 - index[i];
+                    }
+                }
+
+                let id = linear_index(&index[.. dim], &res[..]);
+
+                if fixed[id] {
+                    continue;
+                }
+
+                let new = eikonal_update(&values[..], &index[.. dim], &res[..], cell);
+
+                if new < values[id].abs() {
+                    values[id] = if values[id] < na::zero() { -new } else { new };
+                }
+            }
+        }
+    }
+
+    DistanceField::new(values, res, mins, spacing)
+}
+
+fn signed_distance<N, P, V, M, G: ?Sized>(shape: &G, m: &M, pt: &P) -> N
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N>,
+          M: Transform<P>,
+          G: PointQuery<N, P, M> {
+    let proj = shape.project_point_with_transform(m, pt, false);
+    let dist = na::dist(pt, &proj);
+
+    if shape.contains_point_with_transform(m, pt) {
+        -dist
+    }
+    else {
+        dist
+    }
+}
+
+/// Solves the discretized eikonal equation at the given sample from its neighbors.
+fn eikonal_update<N: Scalar>(values: &[N], index: &[usize], res: &[usize], cell: N) -> N {
+    let dim        = index.len();
+    let max: N     = Bounded::max_value();
+    let mut neighs = [ max; 3 ];
+    let mut nindex = [ 0usize; 3 ];
+
+    for i in 0 .. dim {
+        nindex[i] = index[i];
+    }
+
+    for i in 0 .. dim {
+        if index[i] > 0 {
+            nindex[i] = index[i] - 1;
+            neighs[i] = neighs[i].min(values[linear_index(&nindex[.. dim], res)].abs());
+        }
+
+        if index[i] + 1 < res[i] {
+            nindex[i] = index[i] + 1;
+            neighs[i] = neighs[i].min(values[linear_index(&nindex[.. dim], res)].abs());
+        }
+
+        nindex[i] = index[i];
+    }
+
+    let neighs = &mut neighs[.. dim];
+    // NaN neighbors are considered unknown, i.e., as large as `max`.
+    for n in neighs.iter_mut() {
+        if n.is_nan() {
+            *n = max;
+        }
+    }
+
+    neighs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    if neighs[0] == max {
+        return max;
+    }
+
+    let mut u = neighs[0] + cell;
+
+    if dim > 1 && u > neighs[1] {
+        let diff = neighs[0] - neighs[1];
+        u = (neighs[0] + neighs[1] + (cell * cell * na::cast(2.0f64) - diff * diff).sqrt()) * na::cast(0.5f64);
+
+        if dim > 2 && u > neighs[2] {
+            let sum   = neighs[0] + neighs[1] + neighs[2];
+            let sqsum = neighs[0] * neighs[0] + neighs[1] * neighs[1] + neighs[2] * neighs[2];
+            let three = na::cast::<f64, N>(3.0);
+
+            u = (sum + (sum * sum - three * (sqsum - cell * cell)).sqrt()) / three;
+        }
+    }
+
+    u
+}
+
+#[inline]
+fn sample_point<N, P, V>(mins: &P, cell: N, index: &[usize]) -> P
+    where N: Scalar,
+          P: Point<N, V>,
+          V: Vect<N> {
+    let mut res = *mins;
+
+    for i in 0 .. index.len() {
+        res[i] = res[i] + cell * na::cast(index[i] as f64);
+    }
+
+    res
+}
+
+/// Converts a linear sample index to a multi-index, the first index varying the fastest.
+#[inline]
+fn multi_index(mut lin: usize, res: &[usize], out: &mut [usize; 3]) {
+    for i in 0 .. res.len() {
+        out[i] = lin % res[i];
+        lin    = lin / res[i];
+    }
+}
+
+#[inline]
+fn linear_index(index: &[usize], res: &[usize]) -> usize {
+    let mut lin = 0;
+
+    for i in (0 .. res.len()).rev() {
+        lin = lin * res[i] + index[i];
+    }
+
+    lin
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use na::{Iso3, Pnt3, Vec3};
+    use na;
+    use entities::shape::{Ball, Plane};
+
+    #[test]
+    fn test_ball_distance_field() {
+        let ball  = Ball::new(1.0f64);
+        let m     = Iso3::new(na::zero(), na::zero());
+        let field = super::distance_field(&ball, &m, 17, 0.5);
+
+        assert!(na::approx_eq_eps(&field.value_at(&Pnt3::new(0.0, 0.0, 0.0)), &-1.0, &0.1));
+        assert!(na::approx_eq_eps(&field.value_at(&Pnt3::new(1.3, 0.0, 0.0)), &0.3, &0.1));
+        assert!(field.values().iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    #[should_panic(expected = "unbounded")]
+    fn test_unbounded_shape_distance_field() {
+        let plane = Plane::new(Vec3::y());
+        let m     = Iso3::new(na::zero(), na::zero());
+
+        let _ = super::distance_field(&plane, &m, 4, 0.5);
+    }
+
+    #[test]
+    fn test_eikonal_update_with_nan_neighbor() {
+        let nan: f64 = Float::nan();
+        let values   = [ 1.0, nan, 1.0, nan ];
+        let u        = super::eikonal_update(&values[..], &[ 0, 0 ], &[ 2, 2 ], 1.0);
+
+        assert!(na::approx_eq(&u, &2.0));
+    }
+}
@@ -28,10 +28,17 @@ pub use to_trimesh::ToTriMesh;
 pub use to_polyline::ToPolyline;
 pub use hacd::hacd;
 pub use convex_hull::{convex_hull3, convex_hull2, convex_hull2_idx};
+pub use convex_polyhedron::{convex_polyhedron3, convex_polyhedron2};
 pub use triangulate::triangulate;
+pub use distance_field::distance_field;
+pub use mesh_boolean::{mesh_boolean, mesh_union, mesh_intersection, mesh_difference, MeshBooleanOp,
+                       MeshBooleanReport};
 
 mod to_trimesh;
 mod to_polyline;
 mod hacd;
 mod convex_hull;
+mod convex_polyhedron;
 mod triangulate;
+mod distance_field;
+mod mesh_boolean;
@@ -0,0 +1,1241 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use na::{Pnt2, Pnt3, Vec2, Vec3, Norm, Bounded};
+use na;
+use math::Scalar;
+use entities::bounding_volume::{BoundingVolume, AABB};
+use entities::partitioning::{BVTTVisitor, median_partitioner};
+use entities::shape;
+use queries::point::LocalPointQuery;
+use queries::geometry::self_intersection_internal;
+use procedural::{TriMesh, IndexBuffer};
+
+/// A boolean operation between two solids.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MeshBooleanOp {
+    /// Keeps the points inside of any of the two solids.
+    Union,
+    /// Keeps the points inside of both solids.
+    Intersection,
+    /// Keeps the points inside of the first solid and outside of the second one.
+    Difference
+}
+
+/// Defects of the input meshes found while computing a boolean operation.
+#[derive(Clone, Debug)]
+pub struct MeshBooleanReport<N> {
+    /// Edges of the first mesh not shared by exactly two consistently oriented triangles.
+    pub non_manifold_edges1:      Vec<(Pnt3<N>, Pnt3<N>)>,
+    /// Edges of the second mesh not shared by exactly two consistently oriented triangles.
+    pub non_manifold_edges2:      Vec<(Pnt3<N>, Pnt3<N>)>,
+    /// Number of intersection curves which could not be used to split a triangle.
+    pub unresolved_intersections: usize,
+    /// Number of split triangles which could only be triangulated by cutting a vertex which is not
+    /// an ear. The resulting triangles may overlap or be inverted.
+    pub forced_ears:              usize,
+    /// Whether the texture coordinates of one of the meshes have been dropped because the other
+    /// mesh has none.
+    pub dropped_uvs:              bool
+}
+
+impl<N> MeshBooleanReport<N> {
+    fn new() -> MeshBooleanReport<N> {
+        MeshBooleanReport {
+            non_manifold_edges1:      Vec::new(),
+            non_manifold_edges2:      Vec::new(),
+            unresolved_intersections: 0,
+            forced_ears:              0,
+            dropped_uvs:              false
+        }
+    }
+
+    /// Whether both input meshes are manifold and every split triangle has been triangulated.
+    ///
+    /// The result of a boolean operation is guaranteed to be watertight only if this is `true`.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.non_manifold_edges1.is_empty() &&
+        self.non_manifold_edges2.is_empty() &&
+        self.unresolved_intersections == 0 &&
+        self.forced_ears == 0
+    }
+}
+
+/// Computes the union of two closed triangle meshes.
+///
+/// See `mesh_boolean` for details.
+#[inline]
+pub fn mesh_union<N: Scalar>(mesh1: &TriMesh<N, Pnt3<N>, Vec3<N>>, mesh2: &TriMesh<N, Pnt3<N>, Vec3<N>>)
+                             -> (TriMesh<N, Pnt3<N>, Vec3<N>>, MeshBooleanReport<N>) {
+    mesh_boolean(mesh1, mesh2, MeshBooleanOp::Union)
+}
+
+/// Computes the intersection of two closed triangle meshes.
+///
+/// See `mesh_boolean` for details.
+#[inline]
+pub fn mesh_intersection<N: Scalar>(mesh1: &TriMesh<N, Pnt3<N>, Vec3<N>>, mesh2: &TriMesh<N, Pnt3<N>, Vec3<N>>)
+                                    -> (TriMesh<N, Pnt3<N>, Vec3<N>>, MeshBooleanReport<N>) {
+    mesh_boolean(mesh1, mesh2, MeshBooleanOp::Intersection)
+}
+
+/// Computes the difference between two closed triangle meshes.
+///
+/// See `mesh_boolean` for details.
+#[inline]
+pub fn mesh_difference<N: Scalar>(mesh1: &TriMesh<N, Pnt3<N>, Vec3<N>>, mesh2: &TriMesh<N, Pnt3<N>, Vec3<N>>)
+                                  -> (TriMesh<N, Pnt3<N>, Vec3<N>>, MeshBooleanReport<N>) {
+    mesh_boolean(mesh1, mesh2, MeshBooleanOp::Difference)
+}
+
+/// Computes a boolean operation between two closed triangle meshes.
+///
+/// Both meshes must be closed and their triangles must be oriented counterclockwise when seen
+/// from the outside. Vertices closer than a small tolerance are merged, so duplicate vertices
+/// (e.g. along texture seams) are not an issue. The triangles are split along the intersection
+/// curves and kept depending on their position wrt. the other mesh.
+///
+/// The resulting mesh shares its vertices and is thus watertight if the returned report is valid.
+/// Faces of both meshes lying on the same plane are split along their overlap.
+///
+/// Normals are carried over, and interpolated on the intersection curves, if any of the meshes
+/// has them. The faces of a mesh without normals get their flat normal. Texture coordinates are
+/// carried over only if both meshes have them, otherwise `report.dropped_uvs` is set. If any
+/// attribute is carried over, the index buffer of the result is split.
+///
+/// If one of the meshes has no triangle, or if all the vertices are at the same position, the
+/// meshes are not checked and the report is empty. The result is then a copy of the other mesh
+/// or an empty mesh, depending on `op`.
+pub fn mesh_boolean<N: Scalar>(mesh1: &TriMesh<N, Pnt3<N>, Vec3<N>>,
+                               mesh2: &TriMesh<N, Pnt3<N>, Vec3<N>>,
+                               op:    MeshBooleanOp)
+                               -> (TriMesh<N, Pnt3<N>, Vec3<N>>, MeshBooleanReport<N>) {
+    let inputs = [ BooleanInput::new(mesh1), BooleanInput::new(mesh2) ];
+
+    if inputs[0].coords.is_empty() || inputs[1].coords.is_empty() {
+        return (empty_operand_result(mesh1, mesh2, op), MeshBooleanReport::new());
+    }
+
+    let mut aabb = inputs[0].aabb();
+    aabb.merge(&inputs[1].aabb());
+
+    let diag  = na::dist(aabb.mins(), aabb.maxs());
+    let eps   = diag * na::cast(1.0e-9f64);
+    let delta = diag * na::cast(1.0e-6f64);
+
+    if na::is_zero(&diag) {
+        // All the vertices are at the same position: both meshes are empty solids.
+        return (empty_mesh(), MeshBooleanReport::new());
+    }
+
+    /*
+     * Merge the duplicate vertices and check the topology.
+     */
+    let mut welder = VertexWelder::new(eps);
+    let tris1      = inputs[0].weld(&mut welder);
+    let tris2      = inputs[1].weld(&mut welder);
+
+    let mut report = MeshBooleanReport {
+        non_manifold_edges1:      non_manifold_edges(&tris1[..], welder.coords()),
+        non_manifold_edges2:      non_manifold_edges(&tris2[..], welder.coords()),
+        unresolved_intersections: 0,
+        forced_ears:              0,
+        dropped_uvs:              mesh1.uvs.is_some() != mesh2.uvs.is_some()
+    };
+
+    // Non-manifold inputs are recorded by the report instead of being rejected.
+    let vertices = Arc::new(welder.coords().to_vec());
+    let shapes   = [
+        shape::TriMesh::new_closed_lenient(vertices.clone(), Arc::new(tris1), None, None,
+                                           &mut median_partitioner),
+        shape::TriMesh::new_closed_lenient(vertices, Arc::new(tris2), None, None,
+                                           &mut median_partitioner)
+    ];
+
+    /*
+     * Intersection segments between the candidate pairs of triangles.
+     */
+    let mut collector = CandidatePairsCollector { pairs: Vec::new() };
+    shapes[0].bvt().visit_bvtt(shapes[1].bvt(), &mut collector);
+
+    let mut cuts = [ vec![ Vec::new(); shapes[0].indices().len() ],
+                     vec![ Vec::new(); shapes[1].indices().len() ] ];
+
+    for &(i, j) in collector.pairs.iter() {
+        let t1 = shapes[0].triangle_at(i);
+        let t2 = shapes[1].triangle_at(j);
+
+        let n1 = na::cross(&(*t1.b() - *t1.a()), &(*t1.c() - *t1.a()));
+        let n2 = na::cross(&(*t2.b() - *t2.a()), &(*t2.c() - *t2.a()));
+
+        if na::sqnorm(&na::cross(&n1, &n2)) <= na::sqnorm(&n1) * na::sqnorm(&n2) * na::cast(1.0e-12f64) &&
+           are_coplanar(&t1, &t2, &n1, delta) {
+            // Each triangle is cut along the edges of the other one lying inside of it so that
+            // their overlap becomes a set of sub-triangles.
+            coplanar_cuts(&t1, &n1, &t2, eps, &mut cuts[0][i]);
+            coplanar_cuts(&t2, &n2, &t1, eps, &mut cuts[1][j]);
+
+            continue;
+        }
+
+        if let Some(seg) = self_intersection_internal::triangle_triangle_intersection(&t1, &t2) {
+            if na::dist(seg.a(), seg.b()) > eps {
+                cuts[0][i].push((*seg.a(), *seg.b()));
+                cuts[1][j].push((*seg.a(), *seg.b()));
+            }
+        }
+    }
+
+    /*
+     * Split and classify the triangles.
+     */
+    let carry_normals = mesh1.normals.is_some() || mesh2.normals.is_some();
+    let carry_uvs     = mesh1.uvs.is_some() && mesh2.uvs.is_some();
+    let mut output    = BooleanOutput::new(carry_normals, carry_uvs);
+
+    for k in 0 .. 2usize {
+        let shape = &shapes[k];
+        let other = &shapes[1 - k];
+        let flip  = k == 1 && op == MeshBooleanOp::Difference;
+
+        for (t, idx) in shape.indices().iter().enumerate() {
+            if idx.x == idx.y || idx.y == idx.z || idx.z == idx.x {
+                continue;
+            }
+
+            let tri     = shape.triangle_at(t);
+            let corners = [ *tri.a(), *tri.b(), *tri.c() ];
+            let split   = split_triangle(&corners, &cuts[k][t][..], eps, &mut report);
+            let mut n   = na::cross(&(corners[1] - corners[0]), &(corners[2] - corners[0]));
+            let _       = n.normalize();
+
+            let mut ids = Vec::with_capacity(split.points.len());
+
+            for (i, pt) in split.points.iter().enumerate() {
+                if i < 3 { ids.push(idx[i]) } else { ids.push(welder.insert(pt)) }
+            }
+
+            for sub in split.triangles.iter() {
+                let center = na::center(&split.points[sub.x], &na::center(&split.points[sub.y], &split.points[sub.z]));
+                let front  = other.contains_point(&(center + n * delta));
+                let behind = other.contains_point(&(center - n * delta));
+
+                let keep = match (op, k) {
+                    (MeshBooleanOp::Union, 0)        => !front,
+                    (MeshBooleanOp::Union, _)        => !front && !behind,
+                    (MeshBooleanOp::Intersection, 0) => behind,
+                    (MeshBooleanOp::Intersection, _) => front && behind,
+                    (MeshBooleanOp::Difference, 0)   => !behind,
+                    (MeshBooleanOp::Difference, _)   => front && behind
+                };
+
+                if keep {
+                    output.push_triangle(k, &inputs[k], t, &corners, &n, &split.points[..], &ids[..], sub, flip);
+                }
+            }
+        }
+    }
+
+    (output.into_trimesh(welder.coords()), report)
+}
+
+/// The result of a boolean operation when one of the meshes has no triangle.
+fn empty_operand_result<N: Scalar>(mesh1: &TriMesh<N, Pnt3<N>, Vec3<N>>,
+                                   mesh2: &TriMesh<N, Pnt3<N>, Vec3<N>>,
+                                   op:    MeshBooleanOp)
+                                   -> TriMesh<N, Pnt3<N>, Vec3<N>> {
+    let empty1 = mesh1.num_triangles() == 0;
+
+    match op {
+        MeshBooleanOp::Union if empty1       => mesh2.clone(),
+        MeshBooleanOp::Union                 => mesh1.clone(),
+        MeshBooleanOp::Difference if !empty1 => mesh1.clone(),
+        MeshBooleanOp::Difference            => empty_mesh(),
+        MeshBooleanOp::Intersection          => empty_mesh()
+    }
+}
+
+fn empty_mesh<N: Scalar>() -> TriMesh<N, Pnt3<N>, Vec3<N>> {
+    TriMesh::new(Vec::new(), None, None, Some(IndexBuffer::Unified(Vec::new())))
+}
+
+/*
+ * Input meshes.
+ */
+struct BooleanInput<'a, N: 'a> {
+    mesh:    &'a TriMesh<N, Pnt3<N>, Vec3<N>>,
+    coords:  Vec<Pnt3<usize>>,
+    normals: Vec<Pnt3<usize>>,
+    uvs:     Vec<Pnt3<usize>>
+}
+
+impl<'a, N: Scalar> BooleanInput<'a, N> {
+    fn new(mesh: &'a TriMesh<N, Pnt3<N>, Vec3<N>>) -> BooleanInput<'a, N> {
+        let mut coords  = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs     = Vec::new();
+
+        match mesh.indices {
+            IndexBuffer::Unified(ref ids) => {
+                for t in ids.iter() {
+                    let t = Pnt3::new(t.x as usize, t.y as usize, t.z as usize);
+
+                    coords.push(t);
+                    normals.push(t);
+                    uvs.push(t);
+                }
+            },
+            IndexBuffer::Split(ref ids) => {
+                for t in ids.iter() {
+                    coords.push(Pnt3::new(t.x.x as usize, t.y.x as usize, t.z.x as usize));
+                    normals.push(Pnt3::new(t.x.y as usize, t.y.y as usize, t.z.y as usize));
+                    uvs.push(Pnt3::new(t.x.z as usize, t.y.z as usize, t.z.z as usize));
+                }
+            }
+        }
+
+        BooleanInput {
+            mesh:    mesh,
+            coords:  coords,
+            normals: normals,
+            uvs:     uvs
+        }
+    }
+
+    fn aabb(&self) -> AABB<Pnt3<N>> {
+        assert!(!self.mesh.coords.is_empty(), "Cannot compute a boolean operation on an empty mesh.");
+
+        let mut mins = self.mesh.coords[0];
+        let mut maxs = self.mesh.coords[0];
+
+        for pt in self.mesh.coords[1 ..].iter() {
+            mins = na::inf(&mins, pt);
+            maxs = na::sup(&maxs, pt);
+        }
+
+        AABB::new(mins, maxs)
+    }
+
+    /// The triangles of this mesh, indexing the merged vertices.
+    fn weld(&self, welder: &mut VertexWelder<N>) -> Vec<Pnt3<usize>> {
+        let ids: Vec<usize> = self.mesh.coords.iter().map(|pt| welder.insert(pt)).collect();
+
+        self.coords.iter().map(|t| Pnt3::new(ids[t.x], ids[t.y], ids[t.z])).collect()
+    }
+}
+
+/*
+ * Output mesh.
+ */
+/// Identifies an attribute (normal or texture coordinates) of the output mesh.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum AttributeKey {
+    /// The attribute with the given index in the k-th input mesh.
+    Input(usize, usize),
+    /// The attribute interpolated on the given welded vertex of the t-th triangle of the k-th mesh.
+    Interpolated(usize, usize, usize),
+    /// The flat normal of the t-th triangle of the k-th mesh.
+    Face(usize, usize)
+}
+
+struct BooleanOutput<N> {
+    coords:     HashMap<usize, u32>,
+    coord_ids:  Vec<usize>,
+    normals:    Option<Vec<Vec3<N>>>,
+    uvs:        Option<Vec<Pnt2<N>>>,
+    normal_ids: HashMap<AttributeKey, u32>,
+    uv_ids:     HashMap<AttributeKey, u32>,
+    indices:    Vec<Pnt3<Pnt3<u32>>>
+}
+
+impl<N: Scalar> BooleanOutput<N> {
+    fn new(carry_normals: bool, carry_uvs: bool) -> BooleanOutput<N> {
+        BooleanOutput {
+            coords:     HashMap::new(),
+            coord_ids:  Vec::new(),
+            normals:    if carry_normals { Some(Vec::new()) } else { None },
+            uvs:        if carry_uvs { Some(Vec::new()) } else { None },
+            normal_ids: HashMap::new(),
+            uv_ids:     HashMap::new(),
+            indices:    Vec::new()
+        }
+    }
+
+    fn push_triangle(&mut self,
+                     k:           usize,
+                     input:       &BooleanInput<N>,
+                     t:           usize,
+                     corners:     &[Pnt3<N>; 3],
+                     face_normal: &Vec3<N>,
+                     points:      &[Pnt3<N>],
+                     ids:         &[usize],
+                     sub:         &Pnt3<usize>,
+                     flip:        bool) {
+        let mut res = [ Pnt3::new(0, 0, 0); 3 ];
+
+        for i in 0 .. 3 {
+            let local = sub[i];
+            let coord = match self.coords.entry(ids[local]) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e)   => {
+                    let id = self.coord_ids.len() as u32;
+                    self.coord_ids.push(ids[local]);
+                    *e.insert(id)
+                }
+            };
+
+            // Original corners share their attributes with the other triangles of their input mesh.
+            // The attributes of the other vertices are shared by the sub-triangles of `t`.
+            let bcoords = if local < 3 {
+                let mut bcoords = Vec3::new(na::zero(), na::zero(), na::zero());
+                bcoords[local]  = na::one();
+
+                bcoords
+            }
+            else {
+                barycentric_coordinates(corners, &points[local])
+            };
+
+            let normal = self.push_normal(k, input, t, local, ids[local], &bcoords, face_normal, flip);
+            let uv     = self.push_uv(k, input, t, local, ids[local], &bcoords);
+
+            res[i] = Pnt3::new(coord, normal, uv);
+        }
+
+        if flip {
+            self.indices.push(Pnt3::new(res[0], res[2], res[1]));
+        }
+        else {
+            self.indices.push(Pnt3::new(res[0], res[1], res[2]));
+        }
+    }
+
+    fn push_normal(&mut self,
+                   k:           usize,
+                   input:       &BooleanInput<N>,
+                   t:           usize,
+                   local:       usize,
+                   welded:      usize,
+                   bcoords:     &Vec3<N>,
+                   face_normal: &Vec3<N>,
+                   flip:        bool)
+                   -> u32 {
+        let normals = match self.normals {
+            Some(ref mut normals) => normals,
+            None                  => return 0
+        };
+
+        let key = match input.mesh.normals {
+            None                 => AttributeKey::Face(k, t),
+            Some(_) if local < 3 => AttributeKey::Input(k, input.normals[t][local]),
+            Some(_)              => AttributeKey::Interpolated(k, t, welded)
+        };
+
+        match self.normal_ids.entry(key) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e)   => {
+                let mut n = match input.mesh.normals {
+                    Some(ref ns) => {
+                        let idx = input.normals[t];
+                        ns[idx.x] * bcoords.x + ns[idx.y] * bcoords.y + ns[idx.z] * bcoords.z
+                    },
+                    None => *face_normal
+                };
+                let _ = n.normalize();
+
+                normals.push(if flip { -n } else { n });
+
+                *e.insert((normals.len() - 1) as u32)
+            }
+        }
+    }
+
+    fn push_uv(&mut self, k: usize, input: &BooleanInput<N>, t: usize, local: usize, welded: usize,
+               bcoords: &Vec3<N>) -> u32 {
+        let uvs = match self.uvs {
+            Some(ref mut uvs) => uvs,
+            None              => return 0
+        };
+
+        let key = if local < 3 {
+            AttributeKey::Input(k, input.uvs[t][local])
+        }
+        else {
+            AttributeKey::Interpolated(k, t, welded)
+        };
+
+        match self.uv_ids.entry(key) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e)   => {
+                let us  = input.mesh.uvs.as_ref().unwrap();
+                let idx = input.uvs[t];
+                let uv  = Pnt2::new(us[idx.x].x * bcoords.x + us[idx.y].x * bcoords.y + us[idx.z].x * bcoords.z,
+                                    us[idx.x].y * bcoords.x + us[idx.y].y * bcoords.y + us[idx.z].y * bcoords.z);
+
+                uvs.push(uv);
+
+                *e.insert((uvs.len() - 1) as u32)
+            }
+        }
+    }
+
+    fn into_trimesh(self, coords: &[Pnt3<N>]) -> TriMesh<N, Pnt3<N>, Vec3<N>> {
+        let coords = self.coord_ids.iter().map(|i| coords[*i]).collect();
+
+        if self.normals.is_none() && self.uvs.is_none() {
+            let indices = self.indices.iter().map(|t| Pnt3::new(t.x.x, t.y.x, t.z.x)).collect();
+
+            TriMesh::new(coords, None, None, Some(IndexBuffer::Unified(indices)))
+        }
+        else {
+            TriMesh::new(coords, self.normals, self.uvs, Some(IndexBuffer::Split(self.indices)))
+        }
+    }
+}
+
+/*
+ * Vertex merging.
+ */
+struct VertexWelder<N> {
+    eps:    N,
+    coords: Vec<Pnt3<N>>,
+    grid:   HashMap<(i64, i64, i64), Vec<usize>>
+}
+
+impl<N: Scalar> VertexWelder<N> {
+    fn new(eps: N) -> VertexWelder<N> {
+        VertexWelder {
+            eps:    eps,
+            coords: Vec::new(),
+            grid:   HashMap::new()
+        }
+    }
+
+    fn coords(&self) -> &[Pnt3<N>] {
+        &self.coords[..]
+    }
+
+    /// The index of the vertex closer than `eps` to `pt`. Such a vertex is created if needed.
+    fn insert(&mut self, pt: &Pnt3<N>) -> usize {
+        let key = (self.cell(pt.x), self.cell(pt.y), self.cell(pt.z));
+
+        for i in -1i64 .. 2 {
+            for j in -1i64 .. 2 {
+                for k in -1i64 .. 2 {
+                    if let Some(ids) = self.grid.get(&(key.0 + i, key.1 + j, key.2 + k)) {
+                        for id in ids.iter() {
+                            if na::sqdist(&self.coords[*id], pt) <= self.eps * self.eps {
+                                return *id;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let id = self.coords.len();
+
+        self.coords.push(*pt);
+
+        match self.grid.entry(key) {
+            Entry::Occupied(e) => e.into_mut().push(id),
+            Entry::Vacant(e)   => { let _ = e.insert(vec![ id ]); }
+        }
+
+        id
+    }
+
+    #[inline]
+    fn cell(&self, x: N) -> i64 {
+        na::cast::<N, f64>((x / self.eps).floor()) as i64
+    }
+}
+
+/// The edges not shared by exactly two consistently oriented triangles.
+fn non_manifold_edges<N: Scalar>(triangles: &[Pnt3<usize>], coords: &[Pnt3<N>]) -> Vec<(Pnt3<N>, Pnt3<N>)> {
+    let mut edges = HashMap::new();
+
+    for t in triangles.iter() {
+        if t.x == t.y || t.y == t.z || t.z == t.x {
+            continue;
+        }
+
+        for i in 0 .. 3 {
+            let a = t[i];
+            let b = t[(i + 1) % 3];
+
+            let counts = match edges.entry((a.min(b), a.max(b))) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e)   => e.insert((0usize, 0usize))
+            };
+
+            if a < b { counts.0 = counts.0 + 1 } else { counts.1 = counts.1 + 1 }
+        }
+    }
+
+    edges.iter()
+         .filter(|&(_, counts)| *counts != (1, 1))
+         .map(|(&(a, b), _)| (coords[a], coords[b]))
+         .collect()
+}
+
+/*
+ * Coplanar triangles.
+ */
+/// Tests if `t2` lies on the plane of `t1`, with normal `n1`, up to `tol`.
+fn are_coplanar<N: Scalar>(t1: &shape::Triangle<Pnt3<N>>, t2: &shape::Triangle<Pnt3<N>>, n1: &Vec3<N>, tol: N)
+                           -> bool {
+    let n = na::normalize(n1);
+
+    [ t2.a(), t2.b(), t2.c() ].iter().all(|pt| na::dot(&n, &(**pt - *t1.a())).abs() <= tol)
+}
+
+/// Collects the parts of the edges of `other` lying inside of the coplanar triangle `t`, with
+/// normal `n`.
+fn coplanar_cuts<N: Scalar>(t:     &shape::Triangle<Pnt3<N>>,
+                            n:     &Vec3<N>,
+                            other: &shape::Triangle<Pnt3<N>>,
+                            eps:   N,
+                            out:   &mut Vec<(Pnt3<N>, Pnt3<N>)>) {
+    let vs = [ *t.a(), *t.b(), *t.c() ];
+    let os = [ *other.a(), *other.b(), *other.c() ];
+
+    for e in 0 .. 3usize {
+        let a          = os[e];
+        let ab         = os[(e + 1) % 3] - a;
+        let mut tmin   = na::zero::<N>();
+        let mut tmax   = na::one::<N>();
+        let mut inside = true;
+
+        // Clip the edge with the half-planes bounding `t`.
+        for i in 0 .. 3usize {
+            let inward = na::cross(n, &(vs[(i + 1) % 3] - vs[i]));
+            let da     = na::dot(&inward, &(a - vs[i]));
+            let db     = na::dot(&inward, &(a + ab - vs[i]));
+
+            if da < na::zero() && db < na::zero() {
+                inside = false;
+                break;
+            }
+
+            if da < na::zero() {
+                tmin = tmin.max(da / (da - db));
+            }
+            else if db < na::zero() {
+                tmax = tmax.min(da / (da - db));
+            }
+        }
+
+        if inside && tmin < tmax {
+            let p1 = a + ab * tmin;
+            let p2 = a + ab * tmax;
+
+            if na::dist(&p1, &p2) > eps {
+                out.push((p1, p2))
+            }
+        }
+    }
+}
+
+/*
+ * Triangle splitting.
+ */
+struct TriangleSplit<N> {
+    /// The vertices of the split triangle. The three first ones are its corners.
+    points:    Vec<Pnt3<N>>,
+    triangles: Vec<Pnt3<usize>>
+}
+
+/// Splits a triangle along intersection segments.
+fn split_triangle<N: Scalar>(corners: &[Pnt3<N>; 3],
+                             cuts:    &[(Pnt3<N>, Pnt3<N>)],
+                             eps:     N,
+                             report:  &mut MeshBooleanReport<N>)
+                             -> TriangleSplit<N> {
+    let mut points = corners.to_vec();
+
+    if cuts.is_empty() {
+        return TriangleSplit { points: points, triangles: vec![ Pnt3::new(0, 1, 2) ] };
+    }
+
+    /*
+     * Collect the vertices and segments.
+     */
+    let mut segments = Vec::new();
+
+    for &(a, b) in cuts.iter() {
+        let ia = local_point(&mut points, &a, eps);
+        let ib = local_point(&mut points, &b, eps);
+
+        if ia != ib && !segments.iter().any(|s| *s == (ia, ib) || *s == (ib, ia)) {
+            segments.push((ia, ib))
+        }
+    }
+
+    let pts2 = project_on_triangle_plane(&points[..]);
+
+    // The edge each vertex lies on (if any) and its parameter along this edge.
+    let mut edge_params = Vec::with_capacity(points.len());
+
+    for (i, pt) in points.iter().enumerate() {
+        if i < 3 {
+            edge_params.push(None);
+            continue;
+        }
+
+        let mut res = None;
+
+        for e in 0 .. 3usize {
+            let a  = corners[e];
+            let ab = corners[(e + 1) % 3] - a;
+            let t  = na::dot(&(*pt - a), &ab) / na::sqnorm(&ab);
+
+            if t > na::zero() && t < na::one() && na::dist(&(a + ab * t), pt) <= eps {
+                res = Some((e, t));
+                break;
+            }
+        }
+
+        edge_params.push(res);
+    }
+
+    let on_edge = |i: usize, e: usize| -> bool {
+        if i < 3 {
+            i == e || i == (e + 1) % 3
+        }
+        else {
+            match edge_params[i] { Some((ee, _)) => ee == e, None => false }
+        }
+    };
+
+    let on_boundary = |i: usize| i < 3 || edge_params[i].is_some();
+
+    // Segments lying on the boundary of the triangle do not split it.
+    segments.retain(|&(a, b)| !(0 .. 3usize).any(|e| on_edge(a, e) && on_edge(b, e)));
+
+    /*
+     * The boundary polygon.
+     */
+    let mut boundary = Vec::new();
+
+    for e in 0 .. 3usize {
+        let mut on_e: Vec<(usize, N)> = edge_params.iter().enumerate().filter_map(|(i, p)| {
+            match *p {
+                Some((ee, t)) if ee == e => Some((i, t)),
+                _ => None
+            }
+        }).collect();
+
+        on_e.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        boundary.push(e);
+        boundary.extend(on_e.into_iter().map(|(i, _)| i));
+    }
+
+    /*
+     * Chain the segments.
+     */
+    let mut adj = vec![ Vec::new(); points.len() ];
+
+    for (s, &(a, b)) in segments.iter().enumerate() {
+        adj[a].push(s);
+        adj[b].push(s);
+    }
+
+    let mut used   = vec![ false; segments.len() ];
+    let mut chains = Vec::new();
+    let mut loops  = Vec::new();
+
+    for start in 0 .. points.len() {
+        if !on_boundary(start) {
+            continue;
+        }
+
+        for s in adj[start].clone().into_iter() {
+            if used[s] {
+                continue;
+            }
+
+            match follow_chain(start, s, &segments[..], &adj[..], &mut used, &on_boundary) {
+                Some(chain) => chains.push(chain),
+                None        => report.unresolved_intersections = report.unresolved_intersections + 1
+            }
+        }
+    }
+
+    for s in 0 .. segments.len() {
+        if !used[s] {
+            let start = segments[s].0;
+
+            match follow_chain(start, s, &segments[..], &adj[..], &mut used, &on_boundary) {
+                Some(mut chain) => {
+                    if chain[chain.len() - 1] == start {
+                        let _ = chain.pop();
+                        loops.push(chain)
+                    }
+                    else {
+                        report.unresolved_intersections = report.unresolved_intersections + 1
+                    }
+                },
+                None => report.unresolved_intersections = report.unresolved_intersections + 1
+            }
+        }
+    }
+
+    /*
+     * Split the polygons.
+     */
+    let mut polygons = vec![ boundary ];
+
+    for chain in chains.into_iter() {
+        let first = chain[0];
+        let last  = chain[chain.len() - 1];
+        let probe = na::center(&pts2[chain[0]], &pts2[chain[1]]);
+
+        let candidates: Vec<usize> = (0 .. polygons.len()).filter(|p| {
+            polygons[*p].contains(&first) && polygons[*p].contains(&last)
+        }).collect();
+
+        let target = if candidates.len() == 1 {
+            Some(candidates[0])
+        }
+        else {
+            candidates.into_iter().find(|p| point_in_polygon(&probe, &polygons[*p][..], &pts2[..]))
+        };
+
+        match target {
+            Some(p) if first != last => {
+                let poly = polygons.swap_remove(p);
+                let n    = poly.len();
+                let i1   = poly.iter().position(|i| *i == first).unwrap();
+                let i2   = poly.iter().position(|i| *i == last).unwrap();
+
+                if chain.len() == 2 && ((i1 + 1) % n == i2 || (i2 + 1) % n == i1) {
+                    // The chain is an edge of the polygon.
+                    polygons.push(poly);
+                    continue;
+                }
+
+                let inner = &chain[1 .. chain.len() - 1];
+
+                let mut poly1: Vec<usize> = (0 .. (i2 + n - i1) % n + 1).map(|k| poly[(i1 + k) % n]).collect();
+                poly1.extend(inner.iter().rev().map(|i| *i));
+
+                let mut poly2: Vec<usize> = (0 .. (i1 + n - i2) % n + 1).map(|k| poly[(i2 + k) % n]).collect();
+                poly2.extend(inner.iter().map(|i| *i));
+
+                polygons.push(poly1);
+                polygons.push(poly2);
+            },
+            _ => report.unresolved_intersections = report.unresolved_intersections + 1
+        }
+    }
+
+    for mut hole in loops.into_iter() {
+        let target = (0 .. polygons.len()).find(|p| point_in_polygon(&pts2[hole[0]], &polygons[*p][..], &pts2[..]));
+
+        match target {
+            Some(p) => {
+                // The polygons are counterclockwise.
+                if signed_area(&hole[..], &pts2[..]) < na::zero() {
+                    hole.reverse();
+                }
+
+                // Bridge the hole with the outer polygon through their closest vertices.
+                let mut best = (0, 0, na::sqdist(&pts2[polygons[p][0]], &pts2[hole[0]]));
+
+                for (i, vo) in polygons[p].iter().enumerate() {
+                    for (j, vh) in hole.iter().enumerate() {
+                        let d = na::sqdist(&pts2[*vo], &pts2[*vh]);
+
+                        if d < best.2 {
+                            best = (i, j, d);
+                        }
+                    }
+                }
+
+                let (io, ih, _) = best;
+                let nh          = hole.len();
+                let mut outer   = polygons[p][.. io + 1].to_vec();
+
+                // Go around the hole clockwise.
+                outer.extend((0 .. nh + 1).map(|k| hole[(ih + nh - k % nh) % nh]));
+                outer.push(polygons[p][io]);
+                outer.extend(polygons[p][io + 1 ..].iter().map(|i| *i));
+
+                polygons[p] = outer;
+                polygons.push(hole);
+            },
+            None => report.unresolved_intersections = report.unresolved_intersections + 1
+        }
+    }
+
+    /*
+     * Triangulate the polygons.
+     */
+    let mut scale = na::zero::<N>();
+
+    for e in 0 .. 3usize {
+        scale = scale.max(na::dist(&corners[e], &corners[(e + 1) % 3]));
+    }
+
+    let mut triangles = Vec::new();
+
+    for poly in polygons.iter() {
+        if !ear_clipping(&poly[..], &pts2[..], eps * scale, &mut triangles) {
+            report.forced_ears = report.forced_ears + 1;
+        }
+    }
+
+    TriangleSplit {
+        points:    points,
+        triangles: triangles
+    }
+}
+
+/// Follows a chain of segments until a boundary vertex is reached.
+///
+/// Returns `None` if the chain stops at an interior vertex.
+fn follow_chain<F>(start:       usize,
+                   first:       usize,
+                   segments:    &[(usize, usize)],
+                   adj:         &[Vec<usize>],
+                   used:        &mut Vec<bool>,
+                   on_boundary: &F)
+                   -> Option<Vec<usize>>
+    where F: Fn(usize) -> bool {
+    let mut chain = vec![ start ];
+    let mut curr  = start;
+    let mut seg   = first;
+
+    loop {
+        used[seg] = true;
+
+        let (a, b) = segments[seg];
+        curr       = if a == curr { b } else { a };
+
+        chain.push(curr);
+
+        if curr == start || on_boundary(curr) {
+            return Some(chain);
+        }
+
+        if adj[curr].len() != 2 {
+            return None;
+        }
+
+        match adj[curr].iter().find(|s| !used[**s]) {
+            Some(s) => seg = *s,
+            None    => return None
+        }
+    }
+}
+
+/// The index of the vertex closer than `eps` to `pt`. Such a vertex is created if needed.
+fn local_point<N: Scalar>(points: &mut Vec<Pnt3<N>>, pt: &Pnt3<N>, eps: N) -> usize {
+    match points.iter().position(|p| na::dist(p, pt) <= eps) {
+        Some(i) => i,
+        None    => {
+            points.push(*pt);
+            points.len() - 1
+        }
+    }
+}
+
+/// Projects points on the plane of the triangle formed by the three first ones.
+///
+/// The projection preserves the orientation of the triangle, i.e., it is counterclockwise.
+fn project_on_triangle_plane<N: Scalar>(points: &[Pnt3<N>]) -> Vec<Pnt2<N>> {
+    let n = na::cross(&(points[1] - points[0]), &(points[2] - points[0]));
+
+    let mut axis = 0;
+
+    for i in 1 .. 3usize {
+        if n[i].abs() > n[axis].abs() {
+            axis = i;
+        }
+    }
+
+    let (mut u, mut v) = ((axis + 1) % 3, (axis + 2) % 3);
+
+    if n[axis] < na::zero() {
+        let tmp = u;
+        u = v;
+        v = tmp;
+    }
+
+    points.iter().map(|p| Pnt2::new(p[u], p[v])).collect()
+}
+
+/// Triangulates a weakly simple counterclockwise polygon.
+///
+/// Returns `false` if a vertex which is not an ear had to be cut.
+fn ear_clipping<N: Scalar>(polygon: &[usize], pts: &[Pnt2<N>], eps: N, out: &mut Vec<Pnt3<usize>>) -> bool {
+    let mut only_ears = true;
+    let mut poly = polygon.to_vec();
+
+    while poly.len() > 3 {
+        let n        = poly.len();
+        let mut ear  = None;
+        let max: N   = Bounded::max_value();
+        let mut best = (0, -max);
+
+        for i in 0 .. n {
+            let a = poly[(i + n - 1) % n];
+            let b = poly[i];
+            let c = poly[(i + 1) % n];
+
+            let cross = perp(&(pts[b] - pts[a]), &(pts[c] - pts[b]));
+
+            if cross > best.1 {
+                best = (i, cross);
+            }
+
+            if cross <= eps {
+                continue;
+            }
+
+            let blocked = poly.iter().any(|q| {
+                *q != a && *q != b && *q != c && point_in_triangle(&pts[*q], &pts[a], &pts[b], &pts[c], eps)
+            });
+
+            if !blocked {
+                ear = Some(i);
+                break;
+            }
+        }
+
+        // If no ear is found because of rounding errors, cut the most convex vertex anyway.
+        let i = match ear {
+            Some(i) => i,
+            None    => {
+                only_ears = false;
+                best.0
+            }
+        };
+
+        out.push(Pnt3::new(poly[(i + n - 1) % n], poly[i], poly[(i + 1) % n]));
+
+        let _ = poly.remove(i);
+    }
+
+    if poly.len() == 3 {
+        out.push(Pnt3::new(poly[0], poly[1], poly[2]));
+    }
+
+    only_ears
+}
+
+#[inline]
+fn perp<N: Scalar>(a: &Vec2<N>, b: &Vec2<N>) -> N {
+    a.x * b.y - a.y * b.x
+}
+
+/// Tests if a point is inside of (or on the boundary of) a counterclockwise triangle.
+#[inline]
+fn point_in_triangle<N: Scalar>(p: &Pnt2<N>, a: &Pnt2<N>, b: &Pnt2<N>, c: &Pnt2<N>, eps: N) -> bool {
+    perp(&(*b - *a), &(*p - *a)) >= -eps &&
+    perp(&(*c - *b), &(*p - *b)) >= -eps &&
+    perp(&(*a - *c), &(*p - *c)) >= -eps
+}
+
+fn point_in_polygon<N: Scalar>(p: &Pnt2<N>, polygon: &[usize], pts: &[Pnt2<N>]) -> bool {
+    let n          = polygon.len();
+    let mut inside = false;
+
+    for i in 0 .. n {
+        let a = pts[polygon[i]];
+        let b = pts[polygon[(i + 1) % n]];
+
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (b.x - a.x) * (p.y - a.y) / (b.y - a.y) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+fn signed_area<N: Scalar>(polygon: &[usize], pts: &[Pnt2<N>]) -> N {
+    let n       = polygon.len();
+    let mut res = na::zero::<N>();
+
+    for i in 0 .. n {
+        res = res + perp(pts[polygon[i]].as_vec(), pts[polygon[(i + 1) % n]].as_vec());
+    }
+
+    res * na::cast(0.5f64)
+}
+
+fn barycentric_coordinates<N: Scalar>(corners: &[Pnt3<N>; 3], pt: &Pnt3<N>) -> Vec3<N> {
+    let ab = corners[1] - corners[0];
+    let ac = corners[2] - corners[0];
+    let ap = *pt - corners[0];
+
+    let d00   = na::dot(&ab, &ab);
+    let d01   = na::dot(&ab, &ac);
+    let d11   = na::dot(&ac, &ac);
+    let d20   = na::dot(&ap, &ab);
+    let d21   = na::dot(&ap, &ac);
+    let denom = d00 * d11 - d01 * d01;
+
+    if na::is_zero(&denom) {
+        return Vec3::new(na::one(), na::zero(), na::zero());
+    }
+
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+
+    Vec3::new(na::one::<N>() - v - w, v, w)
+}
+
+/*
+ * Candidate pairs of triangles.
+ */
+struct CandidatePairsCollector {
+    pairs: Vec<(usize, usize)>
+}
+
+impl<N: Scalar> BVTTVisitor<usize, AABB<Pnt3<N>>> for CandidatePairsCollector {
+    #[inline]
+    fn visit_internal_internal(&mut self, bv1: &AABB<Pnt3<N>>, bv2: &AABB<Pnt3<N>>) -> bool {
+        bv1.intersects(bv2)
+    }
+
+    #[inline]
+    fn visit_internal_leaf(&mut self, bv1: &AABB<Pnt3<N>>, _: &usize, bv2: &AABB<Pnt3<N>>) -> bool {
+        bv1.intersects(bv2)
+    }
+
+    #[inline]
+    fn visit_leaf_internal(&mut self, _: &usize, bv1: &AABB<Pnt3<N>>, bv2: &AABB<Pnt3<N>>) -> bool {
+        bv1.intersects(bv2)
+    }
+
+    #[inline]
+    fn visit_leaf_leaf(&mut self, b1: &usize, bv1: &AABB<Pnt3<N>>, b2: &usize, bv2: &AABB<Pnt3<N>>) {
+        if bv1.intersects(bv2) {
+            self.pairs.push((*b1, *b2))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use na::{Pnt3, Vec3};
+    use na;
+    use procedural::{TriMesh, IndexBuffer};
+    use procedural;
+    use super::MeshBooleanReport;
+
+    fn cube(offset: Vec3<f64>) -> TriMesh<f64, Pnt3<f64>, Vec3<f64>> {
+        let mut cube = procedural::cuboid(&Vec3::new(1.0f64, 1.0, 1.0));
+        cube.translate_by(&offset);
+
+        cube
+    }
+
+    fn coord_triangles(mesh: &TriMesh<f64, Pnt3<f64>, Vec3<f64>>) -> Vec<Pnt3<usize>> {
+        match mesh.indices {
+            IndexBuffer::Unified(ref idx) => {
+                idx.iter().map(|t| Pnt3::new(t.x as usize, t.y as usize, t.z as usize)).collect()
+            },
+            IndexBuffer::Split(ref idx) => {
+                idx.iter().map(|t| Pnt3::new(t.x.x as usize, t.y.x as usize, t.z.x as usize)).collect()
+            }
+        }
+    }
+
+    fn volume(mesh: &TriMesh<f64, Pnt3<f64>, Vec3<f64>>) -> f64 {
+        let mut res = 0.0;
+
+        for t in coord_triangles(mesh).iter() {
+            let a = *mesh.coords[t.x].as_vec();
+            let b = *mesh.coords[t.y].as_vec();
+            let c = *mesh.coords[t.z].as_vec();
+
+            res = res + na::dot(&a, &na::cross(&b, &c));
+        }
+
+        res / 6.0
+    }
+
+    fn assert_watertight(mesh: &TriMesh<f64, Pnt3<f64>, Vec3<f64>>, report: &MeshBooleanReport<f64>) {
+        assert!(report.is_valid());
+        assert!(super::non_manifold_edges(&coord_triangles(mesh)[..], &mesh.coords[..]).is_empty());
+    }
+
+    fn check_volumes(offset: Vec3<f64>, union: f64, intersection: f64, difference: f64) {
+        let cube1 = cube(na::zero());
+        let cube2 = cube(offset);
+
+        let (res, report) = super::mesh_union(&cube1, &cube2);
+        assert_watertight(&res, &report);
+        assert!((volume(&res) - union).abs() < 1.0e-7);
+
+        let (res, report) = super::mesh_intersection(&cube1, &cube2);
+        assert_watertight(&res, &report);
+        assert!((volume(&res) - intersection).abs() < 1.0e-7);
+
+        let (res, report) = super::mesh_difference(&cube1, &cube2);
+        assert_watertight(&res, &report);
+        assert!((volume(&res) - difference).abs() < 1.0e-7);
+    }
+
+    #[test]
+    fn test_overlapping_cubes() {
+        check_volumes(Vec3::new(0.5, 0.5, 0.5), 1.875, 0.125, 0.875);
+    }
+
+    #[test]
+    fn test_cubes_with_coplanar_faces() {
+        check_volumes(Vec3::new(0.5, 0.0, 0.0), 1.5, 0.5, 0.5);
+    }
+
+    #[test]
+    fn test_flush_cubes() {
+        let cube1 = cube(na::zero());
+        let cube2 = cube(Vec3::new(1.0, 0.5, 0.0));
+
+        let (res, report) = super::mesh_union(&cube1, &cube2);
+        assert_watertight(&res, &report);
+        assert!((volume(&res) - 2.0).abs() < 1.0e-7);
+    }
+
+    #[test]
+    fn test_empty_operand() {
+        let cube1 = cube(na::zero());
+        let empty = TriMesh::new(Vec::new(), None, None, Some(IndexBuffer::Unified(Vec::new())));
+
+        let (res, report) = super::mesh_union(&empty, &cube1);
+        assert!(report.is_valid());
+        assert!((volume(&res) - 1.0).abs() < 1.0e-7);
+
+        let (res, _) = super::mesh_difference(&cube1, &empty);
+        assert!((volume(&res) - 1.0).abs() < 1.0e-7);
+
+        let (res, _) = super::mesh_intersection(&cube1, &empty);
+        assert!(res.coords.is_empty());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let cube1     = cube(na::zero());
+        let mut cube2 = cube(Vec3::new(0.5, 0.5, 0.5));
+
+        cube2.unify_index_buffer();
+        cube2.normals = None;
+        cube2.uvs     = None;
+
+        let (res, report) = super::mesh_union(&cube1, &cube2);
+
+        assert!(report.dropped_uvs);
+        assert!(res.uvs.is_none());
+        assert!((volume(&res) - 1.875).abs() < 1.0e-7);
+
+        // The faces of `cube2` get their flat normal.
+        let normals = res.normals.as_ref().expect("The normals should be carried over.");
+        assert!(normals.iter().all(|n| (na::norm(n) - 1.0).abs() < 1.0e-7));
+    }
+}
@@ -62,6 +62,6 @@ pub use ncollide_math as math;
 pub use ncollide_utils as utils;
 pub use ncollide_entities::{shape, inspection, bounding_volume, partitioning, support_map};
 pub use ncollide_queries::{geometry, point, ray};
-pub use ncollide_pipeline::{narrow_phase, broad_phase, world};
+pub use ncollide_pipeline::{narrow_phase, broad_phase, world, debug, spatial_index};
 pub use ncollide_procedural as procedural;
 pub use ncollide_transformation as transformation;